@@ -0,0 +1,42 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tegra_swizzle::block_height_mip0;
+use tegra_swizzle::swizzle::{AccessOrder, BlockLinearPlan};
+
+// Compare visiting GOBs in linear row order and tiled memory order for data already in memory.
+// Sequential tiled accesses only pay off when page faults for the tiled data dominate,
+// like a memory mapped file that isn't in the page cache yet.
+// That depends on the disk and page cache, so this only measures the in memory overhead.
+fn access_order_benchmark(c: &mut Criterion) {
+    let bytes_per_pixel = 4;
+
+    let mut group = c.benchmark_group("access_order");
+    for (width, height) in [(256, 256), (1000, 1000), (4096, 4096), (16384, 256)] {
+        let block_height = block_height_mip0(height);
+        let plan = BlockLinearPlan::new(width, height, 1, block_height, bytes_per_pixel);
+        let mut tiled = vec![0u8; plan.swizzled_size()];
+        let mut untiled = vec![0u8; plan.deswizzled_size()];
+        group.throughput(Throughput::Bytes(untiled.len() as u64));
+
+        for access_order in [AccessOrder::Linear, AccessOrder::Tiled] {
+            let plan = plan.clone().with_access_order(access_order);
+            let id = BenchmarkId::new(
+                format!("deswizzle_{access_order:?}"),
+                format!("{width}x{height}"),
+            );
+            group.bench_function(id, |b| {
+                b.iter(|| plan.deswizzle_into(&tiled, &mut untiled).unwrap())
+            });
+            let id = BenchmarkId::new(
+                format!("swizzle_{access_order:?}"),
+                format!("{width}x{height}"),
+            );
+            group.bench_function(id, |b| {
+                b.iter(|| plan.swizzle_into(&untiled, &mut tiled).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, access_order_benchmark);
+criterion_main!(benches);
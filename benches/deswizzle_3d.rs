@@ -0,0 +1,36 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tegra_swizzle::swizzle::{deswizzle_block_linear_into, deswizzled_mip_size, swizzled_mip_size};
+use tegra_swizzle::BlockHeight;
+
+// Volume textures like color grading LUTs stored as 3D RGBA8 surfaces.
+fn deswizzle_3d_benchmark(c: &mut Criterion) {
+    let bytes_per_pixel = 4;
+    // 3D surfaces always use a block height of 1.
+    let block_height = BlockHeight::One;
+
+    let mut group = c.benchmark_group("deswizzle_3d");
+    for size in [16, 128, 256] {
+        let source = vec![0u8; swizzled_mip_size(size, size, size, block_height, bytes_per_pixel)];
+        let mut destination = vec![0u8; deswizzled_mip_size(size, size, size, bytes_per_pixel)];
+
+        group.throughput(Throughput::Bytes(destination.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| {
+                deswizzle_block_linear_into(
+                    size,
+                    size,
+                    size,
+                    &source,
+                    &mut destination,
+                    block_height,
+                    bytes_per_pixel,
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, deswizzle_3d_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tegra_swizzle::surface::{BlockDim, SurfaceParams, SurfacePlan};
+use tegra_swizzle::swizzle::{deswizzle_block_linear_into, BlockLinearPlan};
+use tegra_swizzle::{block_height_mip0, div_round_up};
+
+// Texture arrays with hundreds of layers with the same dimensions.
+fn deswizzle_plan_benchmark(c: &mut Criterion) {
+    let layer_count = 256;
+    let bytes_per_pixel = 16;
+
+    let mut group = c.benchmark_group("deswizzle_plan");
+    for size in [16, 64, 256] {
+        let width = div_round_up(size, 4);
+        let height = div_round_up(size, 4);
+        let block_height = block_height_mip0(height);
+
+        let plan = BlockLinearPlan::new(width, height, 1, block_height, bytes_per_pixel);
+        let source = vec![0u8; plan.swizzled_size() * layer_count];
+        let mut destination = vec![0u8; plan.deswizzled_size() * layer_count];
+
+        group.throughput(Throughput::Bytes(destination.len() as u64));
+        group.bench_with_input(BenchmarkId::new("mip", size), &size, |b, _| {
+            b.iter(|| {
+                for (tiled, untiled) in source
+                    .chunks_exact(plan.swizzled_size())
+                    .zip(destination.chunks_exact_mut(plan.deswizzled_size()))
+                {
+                    deswizzle_block_linear_into(
+                        width,
+                        height,
+                        1,
+                        tiled,
+                        untiled,
+                        block_height,
+                        bytes_per_pixel,
+                    )
+                    .unwrap();
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("plan", size), &size, |b, _| {
+            b.iter(|| {
+                for (tiled, untiled) in source
+                    .chunks_exact(plan.swizzled_size())
+                    .zip(destination.chunks_exact_mut(plan.deswizzled_size()))
+                {
+                    plan.deswizzle_into(tiled, untiled).unwrap();
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+// Many small surfaces with mipmaps that have the same parameters.
+fn surface_plan_benchmark(c: &mut Criterion) {
+    let surface_count = 64;
+
+    let mut group = c.benchmark_group("surface_plan");
+    for size in [16, 64, 256] {
+        let params = SurfaceParams {
+            width: size,
+            height: size,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: size.ilog2() + 1,
+            ..Default::default()
+        };
+        let plan = SurfacePlan::new(&params).unwrap();
+        let source = vec![0u8; plan.swizzled_size() * surface_count];
+        let mut destination = vec![0u8; plan.deswizzled_size() * surface_count];
+
+        group.throughput(Throughput::Bytes(destination.len() as u64));
+        group.bench_with_input(BenchmarkId::new("params", size), &size, |b, _| {
+            b.iter(|| {
+                for (tiled, untiled) in source
+                    .chunks_exact(plan.swizzled_size())
+                    .zip(destination.chunks_exact_mut(plan.deswizzled_size()))
+                {
+                    params.deswizzle_into(tiled, untiled).unwrap();
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("plan", size), &size, |b, _| {
+            b.iter(|| {
+                for (tiled, untiled) in source
+                    .chunks_exact(plan.swizzled_size())
+                    .zip(destination.chunks_exact_mut(plan.deswizzled_size()))
+                {
+                    plan.deswizzle_into(tiled, untiled).unwrap();
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, deswizzle_plan_benchmark, surface_plan_benchmark);
+criterion_main!(benches);
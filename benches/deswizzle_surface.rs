@@ -1,7 +1,7 @@
 use criterion::black_box;
 use criterion::{criterion_group, criterion_main, Criterion};
-use tegra_swizzle::surface::deswizzle_surface;
 use tegra_swizzle::surface::BlockDim;
+use tegra_swizzle::surface::SurfaceParams;
 use tegra_swizzle::swizzle::swizzled_mip_size;
 use tegra_swizzle::BlockHeight;
 
@@ -18,22 +18,57 @@ fn deswizzle_surface_benchmark(c: &mut Criterion) {
         group.throughput(Throughput::Bytes((size * size * 6) as u64));
         group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
             b.iter(|| {
-                deswizzle_surface(
-                    size,
-                    size,
-                    1,
-                    &source,
-                    BlockDim::block_4x4(),
-                    None,
-                    black_box(16),
-                    black_box(6),
-                    black_box(6),
-                )
+                black_box(SurfaceParams {
+                    width: size,
+                    height: size,
+                    block_dim: BlockDim::block_4x4(),
+                    bytes_per_pixel: 16,
+                    mipmap_count: 6,
+                    layer_count: 6,
+                    ..Default::default()
+                })
+                .deswizzle(&source)
             });
         });
     }
     group.finish();
 }
 
-criterion_group!(benches, deswizzle_surface_benchmark);
+fn deswizzle_surface_array_benchmark(c: &mut Criterion) {
+    // Texture arrays with full mip chains spend more time on the per mip setup.
+    let layer_count = 16;
+    let mipmap_count = 12;
+    let params = SurfaceParams {
+        width: 2048,
+        height: 2048,
+        block_dim: BlockDim::block_4x4(),
+        bytes_per_pixel: 16,
+        mipmap_count,
+        layer_count,
+        ..Default::default()
+    };
+    let source = vec![0u8; params.swizzled_size()];
+
+    let mut group = c.benchmark_group("deswizzle_surface_array");
+    for size in [16, 64, 256, 2048] {
+        group.throughput(Throughput::Bytes((size * size * layer_count) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| {
+                black_box(SurfaceParams {
+                    width: size,
+                    height: size,
+                    ..params
+                })
+                .deswizzle(&source)
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    deswizzle_surface_benchmark,
+    deswizzle_surface_array_benchmark
+);
 criterion_main!(benches);
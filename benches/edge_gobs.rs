@@ -0,0 +1,46 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tegra_swizzle::block_height_mip0;
+use tegra_swizzle::swizzle::{
+    deswizzle_block_linear_into, deswizzled_mip_size, set_gob_copy, swizzled_mip_size, GobCopy,
+};
+
+// Untile surfaces with partially filled GOBs along the right and bottom edges.
+// Copying all GOBs as partial GOBs measures the edge GOB implementation on its own.
+fn edge_gobs_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("edge_gobs");
+    for bytes_per_pixel in [1, 4, 8, 16] {
+        for (width, height) in [(100, 100), (340, 340)] {
+            let block_height = block_height_mip0(height);
+            let source =
+                vec![0u8; swizzled_mip_size(width, height, 1, block_height, bytes_per_pixel)];
+            let mut destination = vec![0u8; deswizzled_mip_size(width, height, 1, bytes_per_pixel)];
+
+            for gob_copy in [GobCopy::Auto, GobCopy::Bytes] {
+                set_gob_copy(gob_copy);
+                let id = BenchmarkId::new(
+                    format!("{gob_copy:?}"),
+                    format!("{width}x{height}_bpp{bytes_per_pixel}"),
+                );
+                group.bench_function(id, |b| {
+                    b.iter(|| {
+                        deswizzle_block_linear_into(
+                            width,
+                            height,
+                            1,
+                            &source,
+                            &mut destination,
+                            block_height,
+                            bytes_per_pixel,
+                        )
+                        .unwrap()
+                    })
+                });
+            }
+        }
+    }
+    set_gob_copy(GobCopy::Auto);
+    group.finish();
+}
+
+criterion_group!(benches, edge_gobs_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tegra_swizzle::generate::SurfaceGenerator;
+
+// Surfaces generated from fixed seeds are identical on every machine,
+// so results can be compared without sharing any test files.
+const SEEDS: [u64; 3] = [1, 2, 3];
+
+fn generated_surfaces_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generated_surfaces");
+    for seed in SEEDS {
+        let mut generator = SurfaceGenerator::new(seed);
+        for (case, params) in [
+            ("best", generator.best_case(2048)),
+            ("worst", generator.worst_case(2048)),
+        ] {
+            // Only use functions that tile into existing buffers to also support the minimal feature.
+            let mut untiled = vec![0u8; params.deswizzled_size()];
+            generator.fill_bytes(&mut untiled);
+            let mut tiled = vec![0u8; params.swizzled_size()];
+            params.swizzle_into(&untiled, &mut tiled).unwrap();
+            let mut tiled_output = vec![0u8; tiled.len()];
+            let mut untiled_output = vec![0u8; untiled.len()];
+            group.throughput(Throughput::Bytes(untiled.len() as u64));
+
+            let name = format!("{case}_seed{seed}");
+            group.bench_function(BenchmarkId::new("swizzle", &name), |b| {
+                b.iter(|| params.swizzle_into(&untiled, &mut tiled_output).unwrap())
+            });
+            group.bench_function(BenchmarkId::new("deswizzle", &name), |b| {
+                b.iter(|| params.deswizzle_into(&tiled, &mut untiled_output).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, generated_surfaces_benchmark);
+criterion_main!(benches);
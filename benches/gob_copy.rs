@@ -0,0 +1,149 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tegra_swizzle::block_height_mip0;
+use tegra_swizzle::swizzle::{
+    deswizzle_block_linear_into, deswizzled_mip_size, set_gob_copy,
+    swizzle_block_linear_buffered_into, swizzle_block_linear_into, swizzled_mip_size, GobCopy,
+};
+
+// Compare the GOB copy implementations for tiny, narrow, short, and large RGBA surfaces.
+fn gob_copy_benchmark(c: &mut Criterion) {
+    let bytes_per_pixel = 4;
+
+    let mut group = c.benchmark_group("gob_copy");
+    for (width, height) in [
+        (4, 4),
+        (16, 8),
+        (17, 9),
+        (64, 64),
+        (16, 1024),
+        (17, 1024),
+        (1024, 8),
+        (256, 256),
+    ] {
+        let block_height = block_height_mip0(height);
+        let source = vec![0u8; swizzled_mip_size(width, height, 1, block_height, bytes_per_pixel)];
+        let mut destination = vec![0u8; deswizzled_mip_size(width, height, 1, bytes_per_pixel)];
+
+        for gob_copy in [GobCopy::Rows, GobCopy::Bytes] {
+            set_gob_copy(gob_copy);
+            let id = BenchmarkId::new(format!("{gob_copy:?}"), format!("{width}x{height}"));
+            group.bench_function(id, |b| {
+                b.iter(|| {
+                    deswizzle_block_linear_into(
+                        width,
+                        height,
+                        1,
+                        &source,
+                        &mut destination,
+                        block_height,
+                        bytes_per_pixel,
+                    )
+                    .unwrap()
+                })
+            });
+        }
+    }
+    set_gob_copy(GobCopy::Auto);
+    group.finish();
+}
+
+// BC7 surfaces with a width that is an exact multiple of 4 GOBs should only copy complete GOBs.
+fn gob_copy_bc7_benchmark(c: &mut Criterion) {
+    let bytes_per_pixel = 16;
+
+    let mut group = c.benchmark_group("gob_copy_bc7");
+    for (width, height) in [(512, 512), (516, 512), (1024, 1024), (2048, 2048)] {
+        // The dimensions are in blocks for compressed formats.
+        let (width_blocks, height_blocks) = (width / 4, height / 4);
+        let block_height = block_height_mip0(height_blocks);
+        let source = vec![
+            0u8;
+            swizzled_mip_size(
+                width_blocks,
+                height_blocks,
+                1,
+                block_height,
+                bytes_per_pixel
+            )
+        ];
+        let mut destination =
+            vec![0u8; deswizzled_mip_size(width_blocks, height_blocks, 1, bytes_per_pixel)];
+
+        for gob_copy in [GobCopy::Rows, GobCopy::Bytes] {
+            set_gob_copy(gob_copy);
+            let id = BenchmarkId::new(format!("{gob_copy:?}"), format!("{width}x{height}"));
+            group.bench_function(id, |b| {
+                b.iter(|| {
+                    deswizzle_block_linear_into(
+                        width_blocks,
+                        height_blocks,
+                        1,
+                        &source,
+                        &mut destination,
+                        block_height,
+                        bytes_per_pixel,
+                    )
+                    .unwrap()
+                })
+            });
+        }
+    }
+    set_gob_copy(GobCopy::Auto);
+    group.finish();
+}
+
+// Tiling into write-combined memory like a mapped GPU buffer is slow for small scattered writes.
+// This does not simulate write-combined memory and only measures the overhead of buffering each GOB
+// in ordinary memory. Write-combined mappings require a GPU driver, so compare the results
+// on the target device to see the benefit for write-combined destinations.
+fn gob_copy_write_combined_benchmark(c: &mut Criterion) {
+    let bytes_per_pixel = 4;
+
+    let mut group = c.benchmark_group("gob_copy_write_combined");
+    for (width, height) in [(17, 9), (340, 340), (256, 256), (1000, 1000)] {
+        let block_height = block_height_mip0(height);
+        let source = vec![0u8; deswizzled_mip_size(width, height, 1, bytes_per_pixel)];
+        let mut destination =
+            vec![0u8; swizzled_mip_size(width, height, 1, block_height, bytes_per_pixel)];
+
+        let id = BenchmarkId::new("Unbuffered", format!("{width}x{height}"));
+        group.bench_function(id, |b| {
+            b.iter(|| {
+                swizzle_block_linear_into(
+                    width,
+                    height,
+                    1,
+                    &source,
+                    &mut destination,
+                    block_height,
+                    bytes_per_pixel,
+                )
+                .unwrap()
+            })
+        });
+        let id = BenchmarkId::new("Buffered", format!("{width}x{height}"));
+        group.bench_function(id, |b| {
+            b.iter(|| {
+                swizzle_block_linear_buffered_into(
+                    width,
+                    height,
+                    1,
+                    &source,
+                    &mut destination,
+                    block_height,
+                    bytes_per_pixel,
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    gob_copy_benchmark,
+    gob_copy_bc7_benchmark,
+    gob_copy_write_combined_benchmark
+);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use tegra_swizzle::surface::{max_deswizzled_size, max_swizzled_size, BlockDim, SurfaceParams};
+
+// Parameters from Smash Ultimate nutexb files used in the surface tests.
+// Batch converters process many surfaces of different sizes rather than a single size.
+// (width, height, compressed, bytes per pixel, mipmaps, layers)
+const NUTEXB_SURFACES: &[(u32, u32, bool, u32, u32, u32)] = &[
+    (100, 100, true, 8, 7, 1),
+    (1028, 256, true, 16, 11, 1),
+    (128, 32, false, 4, 8, 1),
+    (1536, 1024, true, 16, 11, 1),
+    (180, 180, true, 8, 8, 1),
+    (2048, 1344, true, 16, 12, 1),
+    (256, 32, true, 16, 9, 1),
+    (320, 128, true, 16, 9, 1),
+    (340, 340, true, 8, 9, 1),
+    (400, 400, true, 8, 9, 1),
+    (4, 24, false, 4, 1, 1),
+    (512, 384, true, 16, 10, 1),
+    (640, 640, true, 8, 10, 1),
+    (64, 512, true, 8, 10, 1),
+    (800, 400, true, 8, 10, 1),
+    (8192, 2048, true, 16, 1, 1),
+    (16, 16, false, 4, 1, 6),
+    (16, 16, true, 8, 1, 6),
+    (2048, 2048, true, 16, 1, 6),
+    (256, 256, false, 4, 1, 6),
+    (64, 64, false, 4, 1, 6),
+    (64, 64, false, 16, 1, 6),
+    (128, 128, true, 16, 8, 6),
+    (16, 16, true, 16, 5, 6),
+    (256, 256, true, 16, 9, 6),
+    (288, 288, true, 16, 9, 6),
+    (512, 512, true, 16, 10, 6),
+    (64, 64, true, 16, 7, 6),
+];
+
+fn nutexb_params() -> Vec<SurfaceParams<'static>> {
+    NUTEXB_SURFACES
+        .iter()
+        .map(
+            |&(width, height, compressed, bytes_per_pixel, mipmap_count, layer_count)| {
+                SurfaceParams {
+                    width,
+                    height,
+                    block_dim: if compressed {
+                        BlockDim::block_4x4()
+                    } else {
+                        BlockDim::uncompressed()
+                    },
+                    bytes_per_pixel,
+                    mipmap_count,
+                    layer_count,
+                    ..Default::default()
+                }
+            },
+        )
+        .collect()
+}
+
+fn nutexb_surfaces_benchmark(c: &mut Criterion) {
+    let params = nutexb_params();
+
+    // Reuse the same buffers for every surface like a batch converter.
+    let mut tiled = vec![0u8; max_swizzled_size(&params)];
+    let mut untiled = vec![0u8; max_deswizzled_size(&params)];
+
+    let mut group = c.benchmark_group("nutexb_surfaces");
+    group.throughput(Throughput::Bytes(
+        params.iter().map(|p| p.deswizzled_size() as u64).sum(),
+    ));
+    group.bench_function("deswizzle", |b| {
+        b.iter(|| {
+            for p in &params {
+                p.deswizzle_into(&tiled, &mut untiled).unwrap();
+            }
+        });
+    });
+    group.bench_function("swizzle", |b| {
+        b.iter(|| {
+            for p in &params {
+                p.swizzle_into(&untiled, &mut tiled).unwrap();
+            }
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, nutexb_surfaces_benchmark);
+criterion_main!(benches);
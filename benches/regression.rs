@@ -0,0 +1,64 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+
+// Key cases checked against the stored baselines with `cargo xtask bench-compare`.
+// Changing the names or parameters requires saving new baselines with `--save`.
+fn regression_cases() -> Vec<(&'static str, SurfaceParams<'static>)> {
+    vec![
+        // A common texture size with a full mipmap chain.
+        (
+            "bc7_512x512",
+            SurfaceParams {
+                width: 512,
+                height: 512,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 10,
+                ..Default::default()
+            },
+        ),
+        // A large render target that only uses the optimized GOB copies.
+        (
+            "rgba8_7680x4320",
+            SurfaceParams {
+                width: 7680,
+                height: 4320,
+                bytes_per_pixel: 4,
+                ..Default::default()
+            },
+        ),
+        // Dimensions that are not a power of two have partially filled GOBs.
+        (
+            "rgba8_504x156",
+            SurfaceParams {
+                width: 504,
+                height: 156,
+                bytes_per_pixel: 4,
+                ..Default::default()
+            },
+        ),
+    ]
+}
+
+fn regression_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("regression");
+    // The 8K surface is slow, so use fewer samples to keep the comparison quick.
+    group.sample_size(20);
+
+    for (name, params) in regression_cases() {
+        let mut tiled = vec![0u8; params.swizzled_size()];
+        let mut untiled = vec![0u8; params.deswizzled_size()];
+        group.throughput(Throughput::Bytes(params.deswizzled_size() as u64));
+
+        group.bench_function(BenchmarkId::new("swizzle", name), |b| {
+            b.iter(|| params.swizzle_into(&untiled, &mut tiled).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("deswizzle", name), |b| {
+            b.iter(|| params.deswizzle_into(&tiled, &mut untiled).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, regression_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,124 @@
+use std::time::{Duration, Instant};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tegra_swizzle::swizzle::{
+    deswizzle_block_linear_into, deswizzled_mip_size, swizzle_block_linear_into, swizzled_mip_size,
+};
+use tegra_swizzle::BlockHeight;
+
+const SIZES: [u32; 5] = [64, 256, 512, 1024, 2048];
+const BLOCK_HEIGHT: BlockHeight = BlockHeight::Sixteen;
+const BYTES_PER_PIXEL: u32 = 4;
+
+// Copying the same number of bytes with memcpy is an upper bound for tiling performance.
+// Tiling needs to read and write every byte at least once,
+// so the memcpy throughput approximates the achievable memory bandwidth.
+fn roofline_benchmark(c: &mut Criterion) {
+    // Allocate the size needed by the largest run and tile into it with the _into functions.
+    // This avoids including the allocation time in the benchmark.
+    let max_size = *SIZES.iter().max().unwrap();
+    let source = vec![0u8; swizzled_mip_size(max_size, max_size, 1, BLOCK_HEIGHT, BYTES_PER_PIXEL)];
+    let mut destination = vec![0u8; source.len()];
+
+    let mut group = c.benchmark_group("roofline");
+    for size in SIZES {
+        let bytes = deswizzled_mip_size(size, size, 1, BYTES_PER_PIXEL);
+        group.throughput(Throughput::Bytes(bytes as u64));
+
+        group.bench_with_input(BenchmarkId::new("memcpy", size), &size, |b, _| {
+            b.iter(|| destination[..bytes].copy_from_slice(black_box(&source[..bytes])))
+        });
+        group.bench_with_input(BenchmarkId::new("swizzle", size), &size, |b, &size| {
+            b.iter(|| {
+                swizzle_block_linear_into(
+                    size,
+                    size,
+                    1,
+                    black_box(&source),
+                    &mut destination,
+                    BLOCK_HEIGHT,
+                    BYTES_PER_PIXEL,
+                )
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("deswizzle", size), &size, |b, &size| {
+            b.iter(|| {
+                deswizzle_block_linear_into(
+                    size,
+                    size,
+                    1,
+                    black_box(&source),
+                    &mut destination,
+                    BLOCK_HEIGHT,
+                    BYTES_PER_PIXEL,
+                )
+            })
+        });
+    }
+    group.finish();
+
+    print_efficiency(&source, &mut destination);
+}
+
+// Criterion reports each function separately, so summarize the relative performance here.
+// The efficiency is the memcpy time as a percentage of the tiling time for the same number of bytes.
+fn print_efficiency(source: &[u8], destination: &mut [u8]) {
+    println!("{:>8} {:>12} {:>12}", "size", "swizzle", "deswizzle");
+    for size in SIZES {
+        let bytes = deswizzled_mip_size(size, size, 1, BYTES_PER_PIXEL);
+
+        let memcpy = average_time(|| {
+            destination[..bytes].copy_from_slice(black_box(&source[..bytes]));
+        });
+        let swizzle = average_time(|| {
+            swizzle_block_linear_into(
+                size,
+                size,
+                1,
+                black_box(source),
+                destination,
+                BLOCK_HEIGHT,
+                BYTES_PER_PIXEL,
+            )
+            .unwrap();
+        });
+        let deswizzle = average_time(|| {
+            deswizzle_block_linear_into(
+                size,
+                size,
+                1,
+                black_box(source),
+                destination,
+                BLOCK_HEIGHT,
+                BYTES_PER_PIXEL,
+            )
+            .unwrap();
+        });
+
+        println!(
+            "{:>8} {:>11.1}% {:>11.1}%",
+            size,
+            efficiency(memcpy, swizzle),
+            efficiency(memcpy, deswizzle)
+        );
+    }
+}
+
+fn average_time<F: FnMut()>(mut f: F) -> Duration {
+    // Run for a fixed duration to get stable timings for small and large sizes.
+    let target = Duration::from_millis(200);
+    let start = Instant::now();
+    let mut iterations = 0u32;
+    while start.elapsed() < target {
+        f();
+        iterations += 1;
+    }
+    start.elapsed() / iterations
+}
+
+fn efficiency(memcpy: Duration, actual: Duration) -> f64 {
+    memcpy.as_secs_f64() / actual.as_secs_f64() * 100.0
+}
+
+criterion_group!(benches, roofline_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,57 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tegra_swizzle::swizzle::{
+    deswizzle_block_linear_into, deswizzled_mip_size, swizzle_block_linear_into, swizzled_mip_size,
+};
+use tegra_swizzle::{block_height_mip0, div_round_up};
+
+// Wide surfaces where each GOB row spans many blocks of tiled data.
+fn wide_surface_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wide_surface");
+    // The width and height are in blocks for BC7.
+    for (name, width, height, bytes_per_pixel) in [
+        ("rgba8", 1024, 1024, 4),
+        ("rgba8", 8192, 1024, 4),
+        ("bc7", div_round_up(4096, 4), div_round_up(4096, 4), 16),
+        ("bc7", div_round_up(16384, 4), div_round_up(2048, 4), 16),
+    ] {
+        let block_height = block_height_mip0(height);
+        let mut tiled =
+            vec![0u8; swizzled_mip_size(width, height, 1, block_height, bytes_per_pixel)];
+        let mut untiled = vec![0u8; deswizzled_mip_size(width, height, 1, bytes_per_pixel)];
+
+        let size = format!("{name}_{width}x{height}");
+        group.throughput(Throughput::Bytes(untiled.len() as u64));
+        group.bench_function(BenchmarkId::new("deswizzle", &size), |b| {
+            b.iter(|| {
+                deswizzle_block_linear_into(
+                    width,
+                    height,
+                    1,
+                    &tiled,
+                    &mut untiled,
+                    block_height,
+                    bytes_per_pixel,
+                )
+                .unwrap()
+            })
+        });
+        group.bench_function(BenchmarkId::new("swizzle", &size), |b| {
+            b.iter(|| {
+                swizzle_block_linear_into(
+                    width,
+                    height,
+                    1,
+                    &untiled,
+                    &mut tiled,
+                    block_height,
+                    bytes_per_pixel,
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, wide_surface_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,201 @@
+//! Converts a tiled nutexb texture from Smash Ultimate to an untiled DDS file.
+//!
+//! This demonstrates the steps for extracting textures from game files:
+//! read the dimensions and format from the file, calculate the surface parameters,
+//! untile the surface, and write the untiled data to a common format.
+//! Other formats like bntx only differ in how the parameters are stored.
+//!
+//! cargo run --example nutexb_to_dds input.nutexb output.dds
+use std::{convert::TryInto, io::Write};
+
+use tegra_swizzle::{
+    div_round_up,
+    surface::{BlockDim, SurfaceParams},
+};
+
+// The nutexb footer is stored at the end of the file after the image data.
+const FOOTER_SIZE: usize = 112;
+
+struct Nutexb<'a> {
+    data: &'a [u8],
+    width: u32,
+    height: u32,
+    depth: u32,
+    format: Format,
+    mipmap_count: u32,
+    layer_count: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Format {
+    dxgi_format: u32,
+    block_dim: BlockDim,
+    bytes_per_pixel: u32,
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_nutexb(bytes: &[u8]) -> Result<Nutexb<'_>, String> {
+    if bytes.len() < FOOTER_SIZE {
+        return Err("File is too small for a nutexb footer.".to_string());
+    }
+    let footer = &bytes[bytes.len() - FOOTER_SIZE..];
+    if &footer[..4] != b" XNT" || &footer[104..108] != b" XET" {
+        return Err("Invalid nutexb magic.".to_string());
+    }
+
+    // The name takes up the first 64 bytes after the magic.
+    let width = read_u32(footer, 68);
+    let height = read_u32(footer, 72);
+    let depth = read_u32(footer, 76);
+    let format = nutexb_format(footer[80])?;
+    let mipmap_count = read_u32(footer, 88);
+    let layer_count = read_u32(footer, 96);
+    let data_size = read_u32(footer, 100) as usize;
+
+    let data = bytes
+        .get(..data_size)
+        .ok_or_else(|| format!("Image data size {data_size} exceeds the file size."))?;
+
+    Ok(Nutexb {
+        data,
+        width,
+        height,
+        depth,
+        format,
+        mipmap_count,
+        layer_count,
+    })
+}
+
+fn nutexb_format(value: u8) -> Result<Format, String> {
+    // The block dimensions and bytes per pixel are all that's needed for tiling.
+    // The DXGI format is only needed for writing the DDS file.
+    let (dxgi_format, block_dim, bytes_per_pixel) = match value {
+        0x00 => (28, BlockDim::uncompressed(), 4),
+        0x05 => (29, BlockDim::uncompressed(), 4),
+        0x34 => (2, BlockDim::uncompressed(), 16),
+        0x50 => (87, BlockDim::uncompressed(), 4),
+        0x55 => (91, BlockDim::uncompressed(), 4),
+        0x80 => (71, BlockDim::block_4x4(), 8),
+        0x85 => (72, BlockDim::block_4x4(), 8),
+        0x90 => (74, BlockDim::block_4x4(), 16),
+        0x95 => (75, BlockDim::block_4x4(), 16),
+        0xa0 => (77, BlockDim::block_4x4(), 16),
+        0xa5 => (78, BlockDim::block_4x4(), 16),
+        0xb0 => (80, BlockDim::block_4x4(), 8),
+        0xb5 => (81, BlockDim::block_4x4(), 8),
+        0xc0 => (83, BlockDim::block_4x4(), 16),
+        0xc5 => (84, BlockDim::block_4x4(), 16),
+        0xd7 => (95, BlockDim::block_4x4(), 16),
+        0xd8 => (96, BlockDim::block_4x4(), 16),
+        0xe0 => (98, BlockDim::block_4x4(), 16),
+        0xe5 => (99, BlockDim::block_4x4(), 16),
+        _ => return Err(format!("Unsupported nutexb format {value:#x}.")),
+    };
+    Ok(Format {
+        dxgi_format,
+        block_dim,
+        bytes_per_pixel,
+    })
+}
+
+fn write_dds<W: Write>(
+    writer: &mut W,
+    params: &SurfaceParams,
+    format: &Format,
+    data: &[u8],
+) -> std::io::Result<()> {
+    // DDS_HEADER_FLAGS_TEXTURE | DDSD_MIPMAPCOUNT | DDSD_LINEARSIZE
+    let mut flags = 0x1 | 0x2 | 0x4 | 0x1000 | 0x20000 | 0x80000;
+    if params.depth > 1 {
+        // DDSD_DEPTH
+        flags |= 0x800000;
+    }
+    let is_cube = params.layer_count % 6 == 0 && params.depth == 1 && params.layer_count > 0;
+
+    let mut header = Vec::new();
+    header.extend_from_slice(b"DDS ");
+    for value in [
+        124,
+        flags,
+        params.height,
+        params.width,
+        div_round_up(params.width, params.block_dim.width.get())
+            * div_round_up(params.height, params.block_dim.height.get())
+            * format.bytes_per_pixel,
+        params.depth,
+        params.mipmap_count,
+    ] {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+    header.extend_from_slice(&[0u8; 44]);
+
+    // The DDS_PIXELFORMAT uses the DX10 header for the actual format.
+    for value in [32u32, 0x4] {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+    header.extend_from_slice(b"DX10");
+    header.extend_from_slice(&[0u8; 20]);
+
+    // DDSCAPS_TEXTURE | DDSCAPS_COMPLEX | DDSCAPS_MIPMAP
+    let caps: u32 = 0x1000 | 0x8 | 0x400000;
+    let caps2 = if is_cube {
+        // DDSCAPS2_CUBEMAP and all faces
+        0xfe00
+    } else if params.depth > 1 {
+        // DDSCAPS2_VOLUME
+        0x200000
+    } else {
+        0
+    };
+    for value in [caps, caps2, 0, 0, 0] {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+
+    // The DDS_HEADER_DXT10 uses a 2D or 3D texture resource.
+    let (dimension, misc_flag, array_size) = if params.depth > 1 {
+        (4, 0, 1)
+    } else if is_cube {
+        (3, 0x4, params.layer_count / 6)
+    } else {
+        (3, 0, params.layer_count)
+    };
+    for value in [format.dxgi_format, dimension, misc_flag, array_size, 0] {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+
+    writer.write_all(&header)?;
+
+    // Both formats store each array layer with all of its mipmaps.
+    writer.write_all(data)
+}
+
+fn main() {
+    let args: Vec<_> = std::env::args().collect();
+    if args.len() != 3 {
+        eprintln!("Usage: nutexb_to_dds <input.nutexb> <output.dds>");
+        std::process::exit(1);
+    }
+
+    let bytes = std::fs::read(&args[1]).unwrap();
+    let nutexb = read_nutexb(&bytes).unwrap();
+
+    // Nutexb does not store the block height, so infer it from the dimensions.
+    let params = SurfaceParams {
+        width: nutexb.width,
+        height: nutexb.height,
+        depth: nutexb.depth,
+        block_dim: nutexb.format.block_dim,
+        bytes_per_pixel: nutexb.format.bytes_per_pixel,
+        mipmap_count: nutexb.mipmap_count,
+        layer_count: nutexb.layer_count,
+        ..Default::default()
+    };
+    let untiled = params.deswizzle(nutexb.data).unwrap();
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(&args[2]).unwrap());
+    write_dds(&mut writer, &params, &nutexb.format, &untiled).unwrap();
+}
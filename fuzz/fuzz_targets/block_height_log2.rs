@@ -0,0 +1,19 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use tegra_swizzle::BlockHeight;
+
+fuzz_target!(|value: u8| {
+    // The conversions should never panic and should agree for valid values.
+    let clamped = BlockHeight::from_log2_clamped(value);
+    match BlockHeight::from_log2_checked(value) {
+        Some(block_height) => {
+            assert_eq!(block_height, clamped);
+            assert_eq!(1 << value, block_height as u32);
+        }
+        None => {
+            assert!(value > 5);
+            assert_eq!(BlockHeight::ThirtyTwo, clamped);
+        }
+    }
+});
@@ -40,19 +40,20 @@ fuzz_target!(|input: Input| {
     let swizzled = vec![0u8; input.input_size];
 
     // This should never panic even if the input size is incorrect.
-    let _ = tegra_swizzle::surface::deswizzle_surface(
-        input.width,
-        input.height,
-        input.depth,
-        &swizzled,
-        tegra_swizzle::surface::BlockDim {
+    let params = tegra_swizzle::surface::SurfaceParams {
+        width: input.width,
+        height: input.height,
+        depth: input.depth,
+        block_dim: tegra_swizzle::surface::BlockDim {
             width: input.block_width,
             height: input.block_height,
             depth: NonZeroU32::new(1).unwrap(),
         },
-        Some(input.block_height_mip0),
-        input.bytes_per_pixel,
-        input.layer_count,
-        input.mipmap_count,
-    );
+        block_height_mip0: Some(input.block_height_mip0),
+        bytes_per_pixel: input.bytes_per_pixel,
+        mipmap_count: input.mipmap_count,
+        layer_count: input.layer_count,
+        ..Default::default()
+    };
+    let _ = params.deswizzle(&swizzled);
 });
@@ -0,0 +1,132 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+extern crate arbitrary;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::num::NonZeroU32;
+
+use tegra_swizzle::generate::SurfaceGenerator;
+use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+use tegra_swizzle::swizzle::{deswizzle_block_linear, deswizzled_mip_size, swizzled_mip_size};
+use tegra_swizzle::{div_round_up, mip_block_height, BlockHeight};
+
+#[derive(Debug)]
+struct Input {
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_width: NonZeroU32,
+    block_height: NonZeroU32,
+    block_height_mip0: BlockHeight,
+    bytes_per_pixel: u32,
+    layer_count: u32,
+    mipmap_count: u32,
+}
+
+impl<'a> Arbitrary<'a> for Input {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let depth = u.int_in_range(1..=33)?;
+        Ok(Input {
+            width: u.int_in_range(1..=513)?,
+            height: u.int_in_range(1..=513)?,
+            depth,
+            block_width: NonZeroU32::new(u.int_in_range(1..=12)?).unwrap(),
+            block_height: NonZeroU32::new(u.int_in_range(1..=12)?).unwrap(),
+            block_height_mip0: u.arbitrary()?,
+            bytes_per_pixel: u.int_in_range(1..=17)?,
+            // 3D surfaces can't have array layers.
+            layer_count: if depth > 1 { 1 } else { u.int_in_range(1..=9)? },
+            // The mip functions infer the block depth from the depth of each mipmap,
+            // but surfaces use the block depth of the base level for all mipmaps.
+            mipmap_count: if depth > 1 {
+                1
+            } else {
+                u.int_in_range(1..=11)?
+            },
+        })
+    }
+}
+
+// The layer alignment from the driver code used by Ryujinx for surfaces with a depth of 1.
+fn align_layer_size(layer_size: usize, height: u32, block_height_mip0: BlockHeight) -> usize {
+    let mut gob_height = block_height_mip0 as u32;
+    while height <= (gob_height / 2) * 8 && gob_height > 1 {
+        gob_height /= 2;
+    }
+    layer_size.next_multiple_of(gob_height as usize * 512)
+}
+
+fuzz_target!(|input: Input| {
+    let params = SurfaceParams {
+        width: input.width,
+        height: input.height,
+        depth: input.depth,
+        block_dim: BlockDim {
+            width: input.block_width,
+            height: input.block_height,
+            depth: NonZeroU32::new(1).unwrap(),
+        },
+        block_height_mip0: Some(input.block_height_mip0),
+        bytes_per_pixel: input.bytes_per_pixel,
+        mipmap_count: input.mipmap_count,
+        layer_count: input.layer_count,
+        ..Default::default()
+    };
+
+    let swizzled = SurfaceGenerator::new(13).bytes(params.swizzled_size());
+
+    let expected = params.deswizzle(&swizzled).unwrap();
+
+    // Compose the surface from each mipmap using only the mip level functions.
+    // 3D surfaces always use a block height of 1.
+    let block_height_mip0 = if input.depth > 1 {
+        BlockHeight::One
+    } else {
+        input.block_height_mip0
+    };
+    let dimensions = |mip: u32| {
+        (
+            div_round_up(input.width >> mip, input.block_width.get()).max(1),
+            div_round_up(input.height >> mip, input.block_height.get()).max(1),
+            (input.depth >> mip).max(1),
+        )
+    };
+
+    let mut composed = Vec::new();
+    let mut layer_offset = 0;
+    for _ in 0..input.layer_count {
+        let mut offset = layer_offset;
+        for mip in 0..input.mipmap_count {
+            let (width, height, depth) = dimensions(mip);
+            let block_height = mip_block_height(height, block_height_mip0);
+            let size = swizzled_mip_size(width, height, depth, block_height, input.bytes_per_pixel);
+            let mip_data = deswizzle_block_linear(
+                width,
+                height,
+                depth,
+                &swizzled[offset..offset + size],
+                block_height,
+                input.bytes_per_pixel,
+            )
+            .unwrap();
+            assert_eq!(
+                deswizzled_mip_size(width, height, depth, input.bytes_per_pixel),
+                mip_data.len()
+            );
+            composed.extend_from_slice(&mip_data);
+            offset += size;
+        }
+
+        let layer_size = offset - layer_offset;
+        layer_offset += if input.layer_count > 1 {
+            align_layer_size(layer_size, dimensions(0).1, block_height_mip0)
+        } else {
+            layer_size
+        };
+    }
+
+    assert_eq!(params.swizzled_size(), layer_offset);
+    if expected != composed {
+        panic!("Surface does not match the composed mipmaps");
+    }
+});
@@ -4,10 +4,8 @@ use libfuzzer_sys::fuzz_target;
 extern crate arbitrary;
 use arbitrary::{Arbitrary, Result, Unstructured};
 
-extern crate rand;
-use rand::{rngs::StdRng, Rng, SeedableRng};
-
-use tegra_swizzle::surface::BlockDim;
+use tegra_swizzle::generate::SurfaceGenerator;
+use tegra_swizzle::surface::{BlockDim, SurfaceParams};
 
 #[derive(Debug)]
 struct Input {
@@ -22,60 +20,41 @@ struct Input {
 
 impl<'a> Arbitrary<'a> for Input {
     fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let depth = u.int_in_range(1..=33)?;
         Ok(Input {
             width: u.int_in_range(1..=257)?,
             height: u.int_in_range(1..=257)?,
-            depth: u.int_in_range(1..=33)?,
+            depth,
             block_height: u.arbitrary()?,
             bytes_per_pixel: u.int_in_range(1..=17)?,
-            layer_count: u.int_in_range(1..=17)?,
+            // 3D surfaces can't have array layers.
+            layer_count: if depth > 1 {
+                1
+            } else {
+                u.int_in_range(1..=17)?
+            },
             mipmap_count: u.int_in_range(1..=17)?,
         })
     }
 }
 
 fuzz_target!(|input: Input| {
-    let deswizzled_size = tegra_swizzle::surface::deswizzled_surface_size(
-        input.width,
-        input.height,
-        input.depth,
-        BlockDim::uncompressed(),
-        input.bytes_per_pixel,
-        input.mipmap_count,
-        input.layer_count,
-    );
-
-    let seed = [13u8; 32];
-    let mut rng: StdRng = SeedableRng::from_seed(seed);
-    let deswizzled: Vec<_> = (0..deswizzled_size)
-        .map(|_| rng.gen_range::<u8, _>(0..=255))
-        .collect();
-
-    let swizzled = tegra_swizzle::surface::swizzle_surface(
-        input.width,
-        input.height,
-        input.depth,
-        &deswizzled,
-        BlockDim::uncompressed(),
-        Some(input.block_height),
-        input.bytes_per_pixel,
-        input.mipmap_count,
-        input.layer_count,
-    )
-    .unwrap();
-
-    let new_deswizzled = tegra_swizzle::surface::deswizzle_surface(
-        input.width,
-        input.height,
-        input.depth,
-        &swizzled,
-        BlockDim::uncompressed(),
-        Some(input.block_height),
-        input.bytes_per_pixel,
-        input.mipmap_count,
-        input.layer_count,
-    )
-    .unwrap();
+    let params = SurfaceParams {
+        width: input.width,
+        height: input.height,
+        depth: input.depth,
+        block_dim: BlockDim::uncompressed(),
+        block_height_mip0: Some(input.block_height),
+        bytes_per_pixel: input.bytes_per_pixel,
+        mipmap_count: input.mipmap_count,
+        layer_count: input.layer_count,
+        ..Default::default()
+    };
+
+    let deswizzled = SurfaceGenerator::new(13).untiled_data(&params);
+
+    let swizzled = params.swizzle(&deswizzled).unwrap();
+    let new_deswizzled = params.deswizzle(&swizzled).unwrap();
 
     if deswizzled != new_deswizzled {
         panic!("Swizzle deswizzle is not 1:1");
@@ -0,0 +1,111 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+extern crate arbitrary;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::num::NonZeroU32;
+
+use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+use tegra_swizzle::SwizzleError;
+
+#[derive(Debug)]
+struct Input {
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_width: NonZeroU32,
+    block_height: NonZeroU32,
+    block_height_mip0: tegra_swizzle::BlockHeight,
+    bytes_per_pixel: u32,
+    source_size: usize,
+    destination_size: usize,
+    layer_count: u32,
+    mipmap_count: u32,
+    deswizzle: bool,
+}
+
+impl<'a> Arbitrary<'a> for Input {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Input {
+            width: u.int_in_range(0..=513)?,
+            height: u.int_in_range(0..=513)?,
+            depth: u.int_in_range(0..=33)?,
+            block_width: NonZeroU32::new(u.int_in_range(1..=16)?).unwrap(),
+            block_height: NonZeroU32::new(u.int_in_range(1..=16)?).unwrap(),
+            block_height_mip0: u.arbitrary()?,
+            bytes_per_pixel: u.int_in_range(0..=32)?,
+            source_size: u.int_in_range(0..=4194304)?,
+            destination_size: u.int_in_range(0..=4194304)?,
+            layer_count: u.int_in_range(0..=12)?,
+            mipmap_count: u.int_in_range(0..=33)?,
+            deswizzle: u.arbitrary()?,
+        })
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let params = SurfaceParams {
+        width: input.width,
+        height: input.height,
+        depth: input.depth,
+        block_dim: BlockDim {
+            width: input.block_width,
+            height: input.block_height,
+            depth: NonZeroU32::new(1).unwrap(),
+        },
+        block_height_mip0: Some(input.block_height_mip0),
+        bytes_per_pixel: input.bytes_per_pixel,
+        mipmap_count: input.mipmap_count,
+        layer_count: input.layer_count,
+        ..Default::default()
+    };
+
+    let source = vec![0u8; input.source_size];
+    let mut destination = vec![0u8; input.destination_size];
+
+    // This should never panic even if the source or destination size is incorrect.
+    let result = if input.deswizzle {
+        params.deswizzle_into(&source, &mut destination)
+    } else {
+        params.swizzle_into(&source, &mut destination)
+    };
+
+    match result {
+        Ok(()) if !is_empty(&params) => {
+            // Only calculate sizes for valid surfaces to avoid overflow.
+            let (source_size, destination_size) = if input.deswizzle {
+                (params.swizzled_size(), params.deswizzled_size())
+            } else {
+                (params.deswizzled_size(), params.swizzled_size())
+            };
+            assert!(source.len() >= source_size);
+            assert!(destination.len() >= destination_size);
+        }
+        Err(SwizzleError::NotEnoughData {
+            expected_size,
+            actual_size,
+        }) => {
+            assert!(actual_size < expected_size);
+            assert!(actual_size == source.len() || actual_size == destination.len());
+        }
+        Err(SwizzleError::NotEnoughMipData {
+            expected_size,
+            actual_size,
+            ..
+        }) => {
+            assert!(actual_size < expected_size);
+            assert_eq!(source.len(), actual_size);
+        }
+        _ => (),
+    }
+});
+
+// Empty surfaces succeed without checking any sizes.
+fn is_empty(params: &SurfaceParams) -> bool {
+    params.width == 0
+        || params.height == 0
+        || params.depth == 0
+        || params.bytes_per_pixel == 0
+        || params.mipmap_count == 0
+        || params.layer_count == 0
+}
@@ -0,0 +1,115 @@
+//! # nutexb_swizzle
+//! The original nutexb_swizzle crate has been replaced by [tegra_swizzle].
+//! This crate keeps the old function signatures for existing tools
+//! and forwards to the implementations in tegra_swizzle.
+//!
+//! New code should use tegra_swizzle directly.
+//! The functions in tegra_swizzle return errors instead of panicking
+//! and support 3D textures, array layers, and mipmaps.
+//!
+//! The old block height calculation did not match the Tegra X1 for some heights.
+//! [block_height] preserves the old values using [BlockHeightHeuristic::LegacyNutexb]
+//! so existing files are untiled the same as before.
+#![no_std]
+use tegra_swizzle::{
+    swizzle::{swizzle_block_linear_into, swizzled_mip_size},
+    BlockHeight, BlockHeightHeuristic,
+};
+
+/// Calculates the block height in GOBs for a surface with `height` many rows.
+/// The height should be in blocks for compressed formats.
+///
+/// This matches the values from older versions of nutexb_swizzle.
+/// Use [tegra_swizzle::block_height_mip0] for the block height used by the Tegra X1.
+pub fn block_height(height: usize) -> usize {
+    BlockHeightHeuristic::LegacyNutexb.block_height_mip0(height as u32) as usize
+}
+
+/// Calculates the size in bytes of the tiled data for a 2D surface.
+/// The width and height should be in blocks for compressed formats.
+///
+/// # Panics
+/// Panics if `block_height` is not one of 1, 2, 4, 8, 16, or 32.
+pub fn swizzled_surface_size(
+    width: usize,
+    height: usize,
+    block_height: usize,
+    bytes_per_pixel: usize,
+) -> usize {
+    swizzled_mip_size(
+        width as u32,
+        height as u32,
+        1,
+        to_block_height(block_height),
+        bytes_per_pixel as u32,
+    )
+}
+
+/// Tiles the bytes from `source` into `destination` for a 2D surface.
+/// The width and height should be in blocks for compressed formats.
+///
+/// # Panics
+/// Panics if `block_height` is not one of 1, 2, 4, 8, 16, or 32,
+/// if `source` is smaller than the untiled size,
+/// or if `destination` is smaller than the result of [swizzled_surface_size].
+pub fn swizzle_block_linear(
+    width: usize,
+    height: usize,
+    source: &[u8],
+    destination: &mut [u8],
+    block_height: usize,
+    bytes_per_pixel: usize,
+) {
+    swizzle_block_linear_into(
+        width as u32,
+        height as u32,
+        1,
+        source,
+        destination,
+        to_block_height(block_height),
+        bytes_per_pixel as u32,
+    )
+    .unwrap();
+}
+
+fn to_block_height(block_height: usize) -> BlockHeight {
+    BlockHeight::new(block_height as u32)
+        .unwrap_or_else(|| panic!("Unsupported block height {}", block_height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate alloc;
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn legacy_block_heights() {
+        assert_eq!(1, block_height(8));
+        assert_eq!(8, block_height(40));
+        assert_eq!(16, block_height(4096));
+    }
+
+    #[test]
+    fn swizzled_surface_size_rgba() {
+        assert_eq!(1024, swizzled_surface_size(16, 16, 2, 4));
+    }
+
+    #[test]
+    fn swizzle_matches_tegra_swizzle() {
+        let input: Vec<_> = (0..64 * 64 * 4).map(|i| i as u8).collect();
+        let mut output = vec![0u8; swizzled_surface_size(64, 64, block_height(64), 4)];
+        swizzle_block_linear(64, 64, &input, &mut output, block_height(64), 4);
+
+        let mut expected = vec![0u8; output.len()];
+        swizzle_block_linear_into(64, 64, 1, &input, &mut expected, BlockHeight::Eight, 4).unwrap();
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_block_height() {
+        swizzled_surface_size(16, 16, 3, 4);
+    }
+}
@@ -0,0 +1,166 @@
+//! Functions for calculating the tiled location of texels in a single mipmap.
+//!
+//! Software samplers and debugging tools can read texels directly from tiled memory
+//! instead of untiling the entire mipmap first.
+//! The parameters are the same as [crate::swizzle::deswizzle_block_linear].
+//!
+//! # Examples
+/*!
+```rust
+use tegra_swizzle::{addressing::scanline_offsets, swizzle::swizzle_block_linear, BlockHeight};
+
+// 32x32 R8G8B8A8 with a block height of 4.
+let linear: Vec<u8> = (0..32 * 32 * 4).map(|i| i as u8).collect();
+let tiled = swizzle_block_linear(32, 32, 1, &linear, BlockHeight::Four, 4).unwrap();
+
+// Read the third row directly from the tiled data.
+let row: Vec<u8> = scanline_offsets(2, 0, 32, 32, 1, BlockHeight::Four, 4)
+    .flat_map(|offset| tiled[offset..offset + 4].iter().copied())
+    .collect();
+assert_eq!(&linear[2 * 32 * 4..3 * 32 * 4], &row[..]);
+```
+*/
+use crate::{
+    blockdepth::block_depth,
+    swizzle::{gob_address_x, gob_address_y, gob_address_z, gob_offset, slice_size},
+    width_in_gobs, BlockHeight, GOB_HEIGHT_IN_BYTES, GOB_SIZE_IN_BYTES,
+};
+
+/// Calculates the offset in the tiled data for the first byte of the texel at `x`, `y`, and `z`.
+///
+/// The coordinates are in pixels or blocks for compressed formats.
+/// The bytes of each texel are contiguous if `bytes_per_pixel` is a power of two up to 16.
+/// Formats like R8G8B8 should calculate the offset of each byte of the texel separately
+/// using a `bytes_per_pixel` of 1 and a width multiplied by the bytes per pixel.
+///
+/// Coordinates outside the mipmap return offsets outside the tiled data or offsets of other texels.
+pub const fn texel_offset(
+    x: u32,
+    y: u32,
+    z: u32,
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> usize {
+    let row_offset = row_offset(y, z, width, height, depth, block_height, bytes_per_pixel);
+    row_offset + byte_offset(x * bytes_per_pixel, y, block_height, depth)
+}
+
+/// Calculates the offset in the tiled data for the first byte of each texel in row `y` of slice `z`.
+///
+/// The iterator is empty if `y` or `z` are outside the mipmap.
+/// See [texel_offset] for details.
+pub fn scanline_offsets(
+    y: u32,
+    z: u32,
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> impl Iterator<Item = usize> {
+    // The offsets for the row and slice are the same for each texel.
+    let row_offset = row_offset(y, z, width, height, depth, block_height, bytes_per_pixel);
+    let count = if y < height && z < depth { width } else { 0 };
+
+    (0..count).map(move |x| row_offset + byte_offset(x * bytes_per_pixel, y, block_height, depth))
+}
+
+const fn row_offset(
+    y: u32,
+    z: u32,
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> usize {
+    let block_height = block_height as u32;
+    let block_depth = block_depth(depth);
+    let width_in_gobs = width_in_gobs(width, bytes_per_pixel);
+    let slice_size = slice_size(block_height, block_depth, width_in_gobs, height);
+    let block_size_in_bytes = GOB_SIZE_IN_BYTES * block_height * block_depth;
+
+    gob_address_z(z, block_height, block_depth, slice_size as u32) as usize
+        + gob_address_y(
+            y,
+            GOB_HEIGHT_IN_BYTES * block_height,
+            block_size_in_bytes,
+            width_in_gobs,
+        ) as usize
+}
+
+// The offset of byte x in row y relative to the start of the row of blocks.
+const fn byte_offset(x: u32, y: u32, block_height: BlockHeight, depth: u32) -> usize {
+    let block_size_in_bytes = GOB_SIZE_IN_BYTES * block_height as u32 * block_depth(depth);
+    gob_address_x(x, block_size_in_bytes) as usize + gob_offset(x, y) as usize
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::swizzle::{deswizzle_block_linear, swizzled_mip_size};
+    use alloc::vec::Vec;
+
+    fn check_scanlines(
+        width: u32,
+        height: u32,
+        depth: u32,
+        block_height: BlockHeight,
+        bytes_per_pixel: u32,
+    ) {
+        let size = swizzled_mip_size(width, height, depth, block_height, bytes_per_pixel);
+        let tiled: Vec<_> = (0..size).map(|i| (i * 7 % 251) as u8).collect();
+        let linear =
+            deswizzle_block_linear(width, height, depth, &tiled, block_height, bytes_per_pixel)
+                .unwrap();
+
+        let bpp = bytes_per_pixel as usize;
+        let mut rows = linear.chunks_exact(width as usize * bpp);
+        for z in 0..depth {
+            for y in 0..height {
+                let row: Vec<_> =
+                    scanline_offsets(y, z, width, height, depth, block_height, bytes_per_pixel)
+                        .flat_map(|offset| tiled[offset..offset + bpp].iter().copied())
+                        .collect();
+                assert_eq!(rows.next().unwrap(), &row[..], "({y}, {z})");
+            }
+        }
+    }
+
+    #[test]
+    fn scanlines_match_deswizzle() {
+        check_scanlines(64, 64, 1, BlockHeight::Eight, 4);
+        check_scanlines(75, 50, 1, BlockHeight::Four, 16);
+        check_scanlines(33, 17, 1, BlockHeight::Two, 1);
+        check_scanlines(100, 30, 1, BlockHeight::ThirtyTwo, 2);
+        check_scanlines(33, 33, 33, BlockHeight::One, 4);
+        check_scanlines(16, 16, 16, BlockHeight::One, 8);
+    }
+
+    #[test]
+    fn scanline_outside_mipmap() {
+        assert_eq!(
+            0,
+            scanline_offsets(8, 0, 8, 8, 1, BlockHeight::One, 4).count()
+        );
+        assert_eq!(
+            0,
+            scanline_offsets(0, 1, 8, 8, 1, BlockHeight::One, 4).count()
+        );
+    }
+
+    #[test]
+    fn texel_offsets() {
+        // GOBs stack vertically within a block, so the next GOB to the right starts after 4 GOBs.
+        assert_eq!(0, texel_offset(0, 0, 0, 32, 32, 1, BlockHeight::Four, 4));
+        assert_eq!(16, texel_offset(0, 1, 0, 32, 32, 1, BlockHeight::Four, 4));
+        assert_eq!(
+            2048,
+            texel_offset(16, 0, 0, 32, 32, 1, BlockHeight::Four, 4)
+        );
+        assert_eq!(512, texel_offset(0, 8, 0, 32, 32, 1, BlockHeight::Four, 4));
+    }
+}
@@ -0,0 +1,583 @@
+//! Heuristics for working with surfaces with unknown parameters.
+//!
+//! These functions can be used when the exact memory layout of a surface is not known.
+//! The results are based on the surface data and may be incorrect in some cases.
+#[cfg(not(feature = "minimal"))]
+use alloc::{vec, vec::Vec};
+#[cfg(not(feature = "minimal"))]
+use core::convert::TryFrom;
+
+use crate::{block_height_mip0, swizzle::swizzled_mip_size, BlockHeight};
+#[cfg(not(feature = "minimal"))]
+use crate::{
+    surface::{mip_level, SurfaceParams},
+    swizzle::{deswizzle_block_linear_into, deswizzled_mip_size},
+    tiling::{TegraBlockLinear, TilingScheme},
+};
+
+/// The result of checking if surface data is tiled.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Likelihood {
+    /// The data is most likely tiled and should be untiled before using.
+    Tiled,
+    /// The data is most likely already untiled or linear.
+    Linear,
+    /// The data looks similar when interpreted as tiled or linear.
+    /// This can happen for very small surfaces or surfaces with a single color.
+    Unknown,
+}
+
+/// Estimates if `data` is tiled using the block linear algorithm or already in a linear layout.
+///
+/// Only the base mip level of the first array layer is checked.
+/// Images tend to be similar between adjacent rows, so the data is interpreted as both tiled and linear
+/// to see which layout has smaller differences between rows.
+/// Data that is already untiled appears scrambled when untiled again.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::analysis::{likely_tiled, Likelihood};
+use tegra_swizzle::surface::SurfaceParams;
+
+// A 64x64 R8G8B8A8 gradient.
+let data: Vec<u8> = (0..64 * 64)
+    .flat_map(|i| [(i % 64) as u8 * 4, (i / 64) as u8 * 4, 0, 255])
+    .collect();
+
+let params = SurfaceParams {
+    width: 64,
+    height: 64,
+    ..Default::default()
+};
+assert_eq!(Likelihood::Linear, likely_tiled(&data, &params));
+
+let tiled = params.swizzle(&data).unwrap();
+assert_eq!(Likelihood::Tiled, likely_tiled(&tiled, &params));
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn likely_tiled(data: &[u8], params: &SurfaceParams) -> Likelihood {
+    if params.width == 0
+        || params.height == 0
+        || params.depth == 0
+        || params.bytes_per_pixel == 0
+        || params.width.checked_mul(params.bytes_per_pixel).is_none()
+    {
+        return Likelihood::Unknown;
+    }
+
+    let level = mip_level(params, 0);
+    let linear_size = deswizzled_mip_size(
+        level.width,
+        level.height,
+        level.depth,
+        params.bytes_per_pixel,
+    );
+    let tiled_size = TegraBlockLinear.tiled_mip_size(params, &level);
+
+    // Tiled data is never smaller than linear data.
+    if data.len() < linear_size {
+        return Likelihood::Unknown;
+    }
+    if data.len() < tiled_size {
+        return Likelihood::Linear;
+    }
+
+    let mut untiled = vec![0u8; linear_size];
+    TegraBlockLinear.untile_mip(params, &level, data, &mut untiled);
+
+    let row_size = (level.width * params.bytes_per_pixel) as usize;
+    let linear_score = row_differences(&data[..linear_size], row_size);
+    let tiled_score = row_differences(&untiled, row_size);
+
+    // Require a clear difference between scores to avoid guessing.
+    if tiled_score * 10 < linear_score * 9 {
+        Likelihood::Tiled
+    } else if linear_score * 10 < tiled_score * 9 {
+        Likelihood::Linear
+    } else {
+        Likelihood::Unknown
+    }
+}
+
+// Sum the absolute differences of bytes with the byte in the same position in the next row.
+#[cfg(not(feature = "minimal"))]
+fn row_differences(data: &[u8], row_size: usize) -> u64 {
+    data.chunks_exact(row_size)
+        .zip(data.chunks_exact(row_size).skip(1))
+        .map(|(row, next_row)| {
+            row.iter()
+                .zip(next_row)
+                .map(|(a, b)| a.abs_diff(*b) as u64)
+                .sum::<u64>()
+        })
+        .sum()
+}
+
+/// Finds the block heights where the tiled size of a single mipmap with the given dimensions is `data_len`.
+///
+/// The result of [block_height_mip0] is returned first if it matches.
+/// The remaining block heights are in increasing order.
+/// Tiled sizes are padded to whole blocks, so several block heights may have the same size for some heights.
+/// The width and height should be in blocks for compressed formats.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{analysis::block_height_candidates, BlockHeight};
+
+// A 64x64 R8G8B8A8 surface is 8 GOBs tall.
+let candidates: Vec<_> = block_height_candidates(64, 64, 4, 16384).collect();
+assert_eq!(
+    vec![BlockHeight::Eight, BlockHeight::One, BlockHeight::Two, BlockHeight::Four],
+    candidates
+);
+```
+ */
+pub fn block_height_candidates(
+    width: u32,
+    height: u32,
+    bytes_per_pixel: u32,
+    data_len: usize,
+) -> impl Iterator<Item = BlockHeight> {
+    // Check dimensions to prevent overflow.
+    let is_valid = width
+        .checked_mul(height)
+        .and_then(|u| u.checked_mul(bytes_per_pixel))
+        .is_some()
+        && width.checked_mul(bytes_per_pixel).is_some()
+        && height.checked_add(height / 2).is_some();
+
+    let expected = is_valid.then(|| block_height_mip0(height));
+    let remaining = IntoIterator::into_iter([
+        BlockHeight::One,
+        BlockHeight::Two,
+        BlockHeight::Four,
+        BlockHeight::Eight,
+        BlockHeight::Sixteen,
+        BlockHeight::ThirtyTwo,
+    ])
+    .filter(move |b| is_valid && Some(*b) != expected);
+
+    expected
+        .into_iter()
+        .chain(remaining)
+        .filter(move |b| swizzled_mip_size(width, height, 1, *b, bytes_per_pixel) == data_len)
+}
+
+/// Returns the most likely block height for a single mipmap with `data_len` tiled bytes
+/// or [None] if no block height matches.
+/// This is the first result from [block_height_candidates].
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{analysis::guess_block_height, BlockHeight};
+
+// The block height is padded to 16 GOBs or 128 rows.
+assert_eq!(Some(BlockHeight::Sixteen), guess_block_height(64, 100, 4, 32768));
+assert_eq!(None, guess_block_height(64, 100, 4, 1000));
+```
+ */
+pub fn guess_block_height(
+    width: u32,
+    height: u32,
+    bytes_per_pixel: u32,
+    data_len: usize,
+) -> Option<BlockHeight> {
+    block_height_candidates(width, height, bytes_per_pixel, data_len).next()
+}
+
+/// The result of untiling a mipmap with a single block height from [rank_block_heights].
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BlockHeightScore {
+    /// The block height used to untile the data.
+    pub block_height: BlockHeight,
+    /// The sum of the differences between adjacent rows in the untiled data.
+    /// Lower scores indicate smoother and more likely correct images.
+    pub score: u64,
+}
+
+/// Untiles a single mipmap with each block height and sorts the results from most to least likely.
+///
+/// Images tend to be similar between adjacent rows, so the untiled data with the smallest
+/// differences between rows is most likely to use the correct block height.
+/// Block heights with a tiled size larger than `data` are skipped.
+/// The result of [block_height_mip0] is returned first for ties.
+/// `data` may contain additional data after the end of the mipmap like the remaining mipmaps.
+/// The width and height should be in blocks for compressed formats.
+///
+/// This helps diagnose scrambled output when the block height stored in a file
+/// does not match the block height inferred from the dimensions.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{analysis::rank_block_heights, swizzle::swizzle_block_linear, BlockHeight};
+
+// A 64x64 R8G8B8A8 gradient.
+let linear: Vec<u8> = (0..64 * 64)
+    .flat_map(|i| [(i % 64) as u8 * 4, (i / 64) as u8 * 4, 0, 255])
+    .collect();
+let tiled = swizzle_block_linear(64, 64, 1, &linear, BlockHeight::Two, 4).unwrap();
+
+let scores = rank_block_heights(&tiled, 64, 64, 4);
+assert_eq!(BlockHeight::Two, scores[0].block_height);
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn rank_block_heights(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    bytes_per_pixel: u32,
+) -> Vec<BlockHeightScore> {
+    let row_size = match width.checked_mul(bytes_per_pixel) {
+        Some(0) | None => return Vec::new(),
+        Some(row_size) => row_size as usize,
+    };
+    if height == 0 || height.checked_add(height / 2).is_none() {
+        return Vec::new();
+    }
+
+    let mut untiled = vec![0u8; deswizzled_mip_size(width, height, 1, bytes_per_pixel)];
+    let expected = block_height_mip0(height);
+    let remaining = [
+        BlockHeight::One,
+        BlockHeight::Two,
+        BlockHeight::Four,
+        BlockHeight::Eight,
+        BlockHeight::Sixteen,
+        BlockHeight::ThirtyTwo,
+    ];
+    let mut scores: Vec<_> = core::iter::once(expected)
+        .chain(IntoIterator::into_iter(remaining).filter(|b| *b != expected))
+        .filter_map(|block_height| {
+            deswizzle_block_linear_into(
+                width,
+                height,
+                1,
+                data,
+                &mut untiled,
+                block_height,
+                bytes_per_pixel,
+            )
+            .ok()?;
+            Some(BlockHeightScore {
+                block_height,
+                score: row_differences(&untiled, row_size),
+            })
+        })
+        .collect();
+
+    // The sort is stable to preserve the order for block heights with the same score.
+    scores.sort_by_key(|s| s.score);
+    scores
+}
+
+/// The maximum number of widths and heights checked by [infer_params].
+#[cfg(not(feature = "minimal"))]
+pub const MAX_INFERRED_DIMENSIONS: usize = 64;
+
+/// A possible set of parameters for a single tiled mipmap found by [infer_params].
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CandidateParams {
+    /// The width of the surface in pixels or blocks for compressed formats.
+    pub width: u32,
+    /// The height of the surface in pixels or blocks for compressed formats.
+    pub height: u32,
+    /// The block height used to untile the data.
+    pub block_height: BlockHeight,
+    /// The fraction of bytes in the linear data that match the untiled data from 0.0 to 1.0.
+    pub score: f32,
+}
+
+/// Finds the dimensions and block height that untile `tiled` to `linear`
+/// for a single mipmap with `bytes_per_pixel` bytes for each pixel or compressed block.
+///
+/// Widths that evenly divide the number of pixels in `linear` are checked with each block height.
+/// Only the [MAX_INFERRED_DIMENSIONS] dimensions closest to a square are checked,
+/// so very narrow or very wide images may not be found.
+/// Candidates are sorted from highest to lowest score and only include parameters with a score above 0.0.
+/// `tiled` may contain additional data after the end of the mipmap like the remaining mipmaps.
+/// This helps determine the parameters for new file formats from a pair of exported images.
+///
+/// Each dimension and block height untiles the entire mipmap,
+/// so this takes up to 6 times [MAX_INFERRED_DIMENSIONS] passes over the data.
+///
+/// The score only counts matching bytes, so it is a weak signal.
+/// Unrelated data still matches about 1 in 256 bytes by chance,
+/// and images with many repeated bytes match most bytes for many different parameters.
+/// Only a score at or very close to 1.0 reliably identifies the parameters.
+/// Use data with few repeated bytes like noise for more accurate results.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{analysis::infer_params, swizzle::swizzle_block_linear, BlockHeight};
+
+// 32x16 R8G8B8A8 noise.
+let linear: Vec<u8> = (0..32 * 16 * 4u32).map(|i| (i * 31 % 251) as u8).collect();
+let tiled = swizzle_block_linear(32, 16, 1, &linear, BlockHeight::Two, 4).unwrap();
+
+let candidates = infer_params(&tiled, &linear, 4);
+assert_eq!((32, 16), (candidates[0].width, candidates[0].height));
+assert_eq!(BlockHeight::Two, candidates[0].block_height);
+assert_eq!(1.0, candidates[0].score);
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn infer_params(tiled: &[u8], linear: &[u8], bytes_per_pixel: u32) -> Vec<CandidateParams> {
+    if bytes_per_pixel == 0 || linear.len() % bytes_per_pixel as usize != 0 {
+        return Vec::new();
+    }
+    let pixel_count = match u32::try_from(linear.len() / bytes_per_pixel as usize) {
+        Ok(count) => count,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut untiled = vec![0u8; linear.len()];
+    let mut candidates = Vec::new();
+    for (width, height) in inferred_dimensions(pixel_count) {
+        if width.checked_mul(bytes_per_pixel).is_none() {
+            continue;
+        }
+
+        // Check the expected block height first to rank it higher for ties.
+        let expected = block_height_mip0(height);
+        let remaining = [
+            BlockHeight::One,
+            BlockHeight::Two,
+            BlockHeight::Four,
+            BlockHeight::Eight,
+            BlockHeight::Sixteen,
+            BlockHeight::ThirtyTwo,
+        ];
+        for block_height in core::iter::once(expected)
+            .chain(IntoIterator::into_iter(remaining).filter(|b| *b != expected))
+        {
+            if deswizzle_block_linear_into(
+                width,
+                height,
+                1,
+                tiled,
+                &mut untiled,
+                block_height,
+                bytes_per_pixel,
+            )
+            .is_err()
+            {
+                continue;
+            }
+
+            let matches = untiled.iter().zip(linear).filter(|(a, b)| a == b).count();
+            if matches > 0 {
+                candidates.push(CandidateParams {
+                    width,
+                    height,
+                    block_height,
+                    score: matches as f32 / linear.len() as f32,
+                });
+            }
+        }
+    }
+
+    // The sort is stable to preserve the order for candidates with the same score.
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates
+}
+
+// The dimensions closest to a square for each width that evenly divides pixel_count.
+#[cfg(not(feature = "minimal"))]
+fn inferred_dimensions(pixel_count: u32) -> Vec<(u32, u32)> {
+    // Find divisors in pairs to avoid checking every width up to pixel_count.
+    let mut dimensions = Vec::new();
+    for small in (1..=pixel_count).take_while(|d| (*d as u64) * (*d as u64) <= pixel_count as u64) {
+        if pixel_count % small == 0 {
+            let large = pixel_count / small;
+            dimensions.push((small, large));
+            if large != small {
+                dimensions.push((large, small));
+            }
+        }
+    }
+
+    dimensions.sort_by_key(|(width, height)| (*width.max(height), *width));
+    dimensions.truncate(MAX_INFERRED_DIMENSIONS);
+    dimensions
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::surface::BlockDim;
+    use alloc::vec::Vec;
+    use BlockHeight::*;
+
+    fn gradient(width: u32, height: u32) -> Vec<u8> {
+        (0..height)
+            .flat_map(|y| (0..width).flat_map(move |x| [x as u8, y as u8, (x + y) as u8, 255]))
+            .collect()
+    }
+
+    #[test]
+    fn likely_tiled_gradient_npot() {
+        let params = SurfaceParams {
+            width: 100,
+            height: 75,
+            ..Default::default()
+        };
+        let linear = gradient(100, 75);
+        let tiled = params.swizzle(&linear).unwrap();
+
+        assert_eq!(Likelihood::Linear, likely_tiled(&linear, &params));
+        assert_eq!(Likelihood::Tiled, likely_tiled(&tiled, &params));
+    }
+
+    #[test]
+    fn rank_block_heights_bc7_504_156() {
+        // Use a smooth gradient for each 16 byte block of a 126x39 block BC7 surface.
+        let linear: Vec<_> = (0..39u32)
+            .flat_map(|y| (0..126u32).flat_map(move |x| [x as u8, y as u8, 0, 255].repeat(4)))
+            .collect();
+        for block_height in [Four, Eight] {
+            let tiled = crate::swizzle::swizzle_block_linear(126, 39, 1, &linear, block_height, 16)
+                .unwrap();
+            let scores = rank_block_heights(&tiled, 126, 39, 16);
+            assert_eq!(block_height, scores[0].block_height);
+            assert!(scores[0].score < scores[1].score);
+        }
+    }
+
+    #[test]
+    fn rank_block_heights_skips_small_data() {
+        // Block heights of 16 and 32 need more than 64x64 RGBA data.
+        let scores = rank_block_heights(&gradient(64, 64), 64, 64, 4);
+        let block_heights: Vec<_> = scores.iter().map(|s| s.block_height).collect();
+        assert_eq!(4, block_heights.len());
+        assert!(!block_heights.contains(&Sixteen));
+        assert!(rank_block_heights(&[], 0, 64, 4).is_empty());
+    }
+
+    #[test]
+    fn likely_tiled_single_color() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            ..Default::default()
+        };
+        assert_eq!(
+            Likelihood::Unknown,
+            likely_tiled(&vec![128u8; 64 * 64 * 4], &params)
+        );
+    }
+
+    #[test]
+    fn likely_tiled_too_small_for_tiled() {
+        // The tiled size is padded to 8 GOBs tall.
+        let params = SurfaceParams {
+            width: 16,
+            height: 36,
+            ..Default::default()
+        };
+        assert_eq!(
+            Likelihood::Linear,
+            likely_tiled(&vec![0u8; 16 * 36 * 4], &params)
+        );
+    }
+
+    #[test]
+    fn likely_tiled_empty() {
+        let params = SurfaceParams {
+            width: 0,
+            block_dim: BlockDim::block_4x4(),
+            ..Default::default()
+        };
+        assert_eq!(Likelihood::Unknown, likely_tiled(&[], &params));
+    }
+
+    #[test]
+    fn infer_params_bc7() {
+        // 128x96 BC7 is 32x24 blocks and uses a block height of 4.
+        let linear: Vec<_> = (0..32 * 24 * 16u32).map(|i| (i * 31 % 251) as u8).collect();
+        let params = SurfaceParams {
+            width: 128,
+            height: 96,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            ..Default::default()
+        };
+        let mut tiled = params.swizzle(&linear).unwrap();
+        // Extra data like additional mipmaps should be ignored.
+        tiled.extend_from_slice(&[0u8; 1024]);
+
+        let candidates = infer_params(&tiled, &linear, 16);
+        assert_eq!(
+            CandidateParams {
+                width: 32,
+                height: 24,
+                block_height: Four,
+                score: 1.0
+            },
+            candidates[0]
+        );
+        assert!(candidates[1..].iter().all(|c| c.score < 1.0));
+    }
+
+    #[test]
+    fn infer_params_many_divisors() {
+        // 27720 pixels has 96 divisors, so only the dimensions closest to a square are checked.
+        let linear: Vec<_> = (0..154 * 180 * 4u32)
+            .map(|i| (i * 31 % 251) as u8)
+            .collect();
+        let tiled = crate::swizzle::swizzle_block_linear(154, 180, 1, &linear, Sixteen, 4).unwrap();
+
+        let candidates = infer_params(&tiled, &linear, 4);
+        assert_eq!((154, 180), (candidates[0].width, candidates[0].height));
+        assert_eq!(1.0, candidates[0].score);
+        assert!(candidates.len() <= MAX_INFERRED_DIMENSIONS * 6);
+        assert!(candidates.iter().all(|c| c.width.max(c.height) < 27720));
+
+        let dimensions = inferred_dimensions(27720);
+        assert_eq!(MAX_INFERRED_DIMENSIONS, dimensions.len());
+        assert_eq!((165, 168), dimensions[0]);
+    }
+
+    #[test]
+    fn infer_params_invalid() {
+        assert!(infer_params(&[], &[], 4).is_empty());
+        assert!(infer_params(&[0u8; 512], &[0u8; 6], 4).is_empty());
+        assert!(infer_params(&[0u8; 512], &[0u8; 8], 0).is_empty());
+        // The tiled data is smaller than any tiled size.
+        assert!(infer_params(&[0u8; 16], &[0u8; 16], 4).is_empty());
+    }
+
+    #[test]
+    fn block_height_candidates_bc7() {
+        // 1024x1024 BC7 uses a block height of 16 but is tall enough for 32.
+        assert_eq!(
+            vec![Sixteen, One, Two, Four, Eight, ThirtyTwo],
+            block_height_candidates(256, 256, 16, 1048576).collect::<Vec<_>>()
+        );
+
+        // 300x300 BC7 is 19 GOBs wide and padded to 10 or 16 GOBs tall.
+        assert_eq!(
+            vec![One, Two],
+            block_height_candidates(75, 75, 16, 97280).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Eight, Sixteen],
+            block_height_candidates(75, 75, 16, 155648).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn block_height_candidates_invalid() {
+        assert_eq!(None, guess_block_height(u32::MAX, u32::MAX, 4, 0));
+        assert_eq!(None, guess_block_height(1, u32::MAX, 4, 0));
+        assert_eq!(None, guess_block_height(64, 64, 4, 0));
+    }
+}
@@ -3,7 +3,7 @@
 // This comes from the Ryujinx emulator: https://github.com/Ryujinx/Ryujinx/blob/master/LICENSE.txt.
 use crate::{BlockHeight, GOB_SIZE_IN_BYTES};
 
-pub fn align_layer_size(
+pub const fn align_layer_size(
     layer_size: usize,
     height: u32,
     depth: u32,
@@ -98,4 +98,23 @@ mod tests {
         assert_eq!(2113536, aligned_size(512, 512, 4, 4, 16, 10));
         assert_eq!(49152, aligned_size(64, 64, 4, 4, 16, 7));
     }
+
+    #[test]
+    fn layer_sizes_block_height_32() {
+        // Layers are aligned to blocks of 32 GOBs.
+        assert_eq!(
+            16384,
+            align_layer_size(1000, 512, 1, BlockHeight::ThirtyTwo, 1)
+        );
+        assert_eq!(
+            32768,
+            align_layer_size(16385, 512, 1, BlockHeight::ThirtyTwo, 1)
+        );
+
+        // The alignment uses smaller blocks for shorter surfaces.
+        assert_eq!(
+            8192,
+            align_layer_size(1000, 100, 1, BlockHeight::ThirtyTwo, 1)
+        );
+    }
 }
@@ -0,0 +1,16 @@
+// Format names shared by the tegra_size and probe binaries.
+use tegra_swizzle::surface::BlockDim;
+
+// The block dimensions and bytes per pixel or block for `format`.
+pub fn format_info(format: &str) -> Option<(BlockDim, u32)> {
+    match format {
+        "r8" => Some((BlockDim::uncompressed(), 1)),
+        "rg8" => Some((BlockDim::uncompressed(), 2)),
+        "rgba8" => Some((BlockDim::uncompressed(), 4)),
+        "rgbaf16" => Some((BlockDim::uncompressed(), 8)),
+        "rgbaf32" => Some((BlockDim::uncompressed(), 16)),
+        "bc1" | "bc4" => Some((BlockDim::block_4x4(), 8)),
+        "bc2" | "bc3" | "bc5" | "bc6" | "bc7" => Some((BlockDim::block_4x4(), 16)),
+        _ => None,
+    }
+}
@@ -0,0 +1,173 @@
+//! Untiles a surface with each block height to find the parameters for scrambled output.
+//!
+//! Files that don't store the block height use [block_height_mip0] by default,
+//! but some tools or games use a different block height for some sizes.
+//! This ranks each block height by how smooth the base mip level looks after untiling.
+//! Use `-o` to write the untiled data for each block height to compare the images.
+//! Please report the block height that works if it differs from the default.
+//!
+//! cargo run --features cli --bin probe -- -i data.bin -w 504 -h 156 -f bc7 [-m MIPMAPS] [-l LAYERS] [-o DIR]
+use std::path::PathBuf;
+
+mod common;
+
+use common::formats::format_info;
+use tegra_swizzle::{
+    analysis::rank_block_heights,
+    block_height_mip0, div_round_up,
+    surface::{BlockDim, SurfaceParams},
+    BlockHeightHeuristic,
+};
+
+const USAGE: &str = "Usage: probe -i <data.bin> -w <width> -h <height> -f <format> [-m <mipmaps>] [-l <layers>] [-o <dir>]
+
+Formats: r8, rg8, rgba8, rgbaf16, rgbaf32, bc1, bc2, bc3, bc4, bc5, bc6, bc7";
+
+struct Options {
+    input: PathBuf,
+    width: u32,
+    height: u32,
+    format: String,
+    block_dim: BlockDim,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    layer_count: u32,
+    output: Option<PathBuf>,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut input = None;
+    let mut width = None;
+    let mut height = None;
+    let mut format = None;
+    let mut mipmap_count = 1;
+    let mut layer_count = 1;
+    let mut output = None;
+
+    let parse_u32 = |value: Option<&String>, name: &str| {
+        value
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| format!("Expected an integer for {name}\n\n{USAGE}"))
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-i" => input = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            "-w" => width = Some(parse_u32(args.next(), "-w")?),
+            "-h" => height = Some(parse_u32(args.next(), "-h")?),
+            "-f" => format = Some(args.next().ok_or(USAGE)?.to_lowercase()),
+            "-m" => mipmap_count = parse_u32(args.next(), "-m")?,
+            "-l" => layer_count = parse_u32(args.next(), "-l")?,
+            "-o" => output = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            _ => return Err(format!("Unrecognized argument {arg}\n\n{USAGE}")),
+        }
+    }
+
+    let format = format.ok_or(USAGE)?;
+    let (block_dim, bytes_per_pixel) =
+        format_info(&format).ok_or_else(|| format!("Unsupported format {format}\n\n{USAGE}"))?;
+    Ok(Options {
+        input: input.ok_or(USAGE)?,
+        width: width.ok_or(USAGE)?,
+        height: height.ok_or(USAGE)?,
+        format,
+        block_dim,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count,
+        output,
+    })
+}
+
+fn probe(options: &Options) -> Result<(), String> {
+    let data = std::fs::read(&options.input)
+        .map_err(|e| format!("Failed to read {:?}: {e}", options.input))?;
+
+    // The rows and columns of the base mip level are blocks for compressed formats.
+    let width = div_round_up(options.width, options.block_dim.width.get());
+    let height = div_round_up(options.height, options.block_dim.height.get());
+    let scores = rank_block_heights(&data, width, height, options.bytes_per_pixel);
+    if scores.is_empty() {
+        return Err(format!(
+            "The input size {} is too small for a {}x{} {} surface with any block height.",
+            data.len(),
+            options.width,
+            options.height,
+            options.format
+        ));
+    }
+
+    let trm = block_height_mip0(height);
+    let legacy = BlockHeightHeuristic::LegacyNutexb.block_height_mip0(height);
+    println!(
+        "{:<14} {:>16} {:>14}",
+        "block height", "row difference", "tiled size"
+    );
+    for score in &scores {
+        let params = SurfaceParams {
+            width: options.width,
+            height: options.height,
+            block_dim: options.block_dim,
+            bytes_per_pixel: options.bytes_per_pixel,
+            mipmap_count: options.mipmap_count,
+            layer_count: options.layer_count,
+            block_height_mip0: Some(score.block_height),
+            ..Default::default()
+        };
+
+        let mut notes = Vec::new();
+        if score.block_height == trm {
+            notes.push("default");
+        }
+        if score.block_height == legacy {
+            notes.push("legacy nutexb");
+        }
+        let line = format!(
+            "{:<14} {:>16} {:>14} {}",
+            score.block_height as u32,
+            score.score,
+            params.swizzled_size(),
+            notes.join(", ")
+        );
+        println!("{}", line.trim_end());
+
+        if let Some(output) = &options.output {
+            // The remaining mipmaps and layers only fit for some block heights.
+            match params.deswizzle(&data) {
+                Ok(untiled) => {
+                    let path = output.join(format!(
+                        "{}_bh{}.bin",
+                        options.input.file_stem().unwrap().to_string_lossy(),
+                        score.block_height as u32
+                    ));
+                    std::fs::write(&path, untiled)
+                        .map_err(|e| format!("Failed to write {path:?}: {e}"))?;
+                }
+                Err(e) => println!("    skipped writing the full surface: {e}"),
+            }
+        }
+    }
+
+    let best = scores[0].block_height;
+    if best != trm {
+        println!(
+            "\nThe most likely block height {} differs from the default block height {}.",
+            best as u32, trm as u32
+        );
+        if best == legacy {
+            println!("Use BlockHeightHeuristic::LegacyNutexb if the output looks correct.");
+        } else {
+            println!("Set SurfaceParams::block_height_mip0 if the output looks correct.");
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    if let Err(e) = parse_options(&args).and_then(|o| probe(&o)) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
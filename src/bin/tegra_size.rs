@@ -0,0 +1,118 @@
+//! Prints the tiled and untiled sizes, mipmap offsets, and block heights of a surface as JSON.
+//!
+//! This allows build scripts for projects in other languages to calculate the surface layout
+//! without linking the C API.
+//! The mipmap dimensions are in blocks for compressed formats.
+//! The offsets and sizes for each mipmap are for the first array layer.
+//! Add a multiple of the layer size to find the mipmaps for other array layers.
+//!
+//! cargo run --features cli --bin tegra_size -- -w 256 -h 256 -f bc7 -m 9 -l 6
+mod common;
+
+use common::formats::format_info;
+use tegra_swizzle::{
+    surface::{layer_stride_linear, layer_stride_tiled, mip_layout, surface_sizes, SurfaceParams},
+    BlockHeight,
+};
+
+const USAGE: &str = "Usage: tegra_size -w <width> -h <height> [-d <depth>] -f <format> [-m <mipmaps>] [-l <layers>] [-b <block height>]
+
+Formats: r8, rg8, rgba8, rgbaf16, rgbaf32, bc1, bc2, bc3, bc4, bc5, bc6, bc7";
+
+fn parse_params(args: &[String]) -> Result<SurfaceParams<'static>, String> {
+    let mut width = None;
+    let mut height = None;
+    let mut depth = 1;
+    let mut format = None;
+    let mut mipmap_count = 1;
+    let mut layer_count = 1;
+    let mut block_height_mip0 = None;
+
+    let parse_u32 = |value: Option<&String>, name: &str| {
+        value
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| format!("Expected an integer for {name}\n\n{USAGE}"))
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-w" => width = Some(parse_u32(args.next(), "-w")?),
+            "-h" => height = Some(parse_u32(args.next(), "-h")?),
+            "-d" => depth = parse_u32(args.next(), "-d")?,
+            "-f" => format = Some(args.next().ok_or(USAGE)?.to_lowercase()),
+            "-m" => mipmap_count = parse_u32(args.next(), "-m")?,
+            "-l" => layer_count = parse_u32(args.next(), "-l")?,
+            "-b" => {
+                let value = parse_u32(args.next(), "-b")?;
+                block_height_mip0 = Some(
+                    BlockHeight::new(value)
+                        .ok_or_else(|| format!("Invalid block height {value}\n\n{USAGE}"))?,
+                );
+            }
+            _ => return Err(format!("Unrecognized argument {arg}\n\n{USAGE}")),
+        }
+    }
+
+    let format = format.ok_or(USAGE)?;
+    let (block_dim, bytes_per_pixel) =
+        format_info(&format).ok_or_else(|| format!("Unsupported format {format}\n\n{USAGE}"))?;
+    Ok(SurfaceParams {
+        width: width.ok_or(USAGE)?,
+        height: height.ok_or(USAGE)?,
+        depth,
+        block_dim,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count,
+        block_height_mip0,
+        ..Default::default()
+    })
+}
+
+fn layout_json(params: &SurfaceParams) -> Result<String, String> {
+    // Check mip 0 even without mipmaps to return an error for empty surfaces.
+    let mips = (0..params.mipmap_count.max(1))
+        .map(|mip| mip_layout(params, mip).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let sizes = surface_sizes(params);
+
+    let mip_json: Vec<_> = mips
+        .iter()
+        .map(|m| {
+            format!(
+                "    {{\"mip\": {}, \"width\": {}, \"height\": {}, \"depth\": {}, \"block_height\": {}, \"block_depth\": {}, \"swizzled_offset\": {}, \"swizzled_size\": {}, \"deswizzled_offset\": {}, \"deswizzled_size\": {}}}",
+                m.level.mip,
+                m.level.width,
+                m.level.height,
+                m.level.depth,
+                m.block_height as u32,
+                m.block_depth,
+                m.swizzled_offset,
+                m.swizzled_size,
+                m.deswizzled_offset,
+                m.deswizzled_size
+            )
+        })
+        .collect();
+
+    Ok(format!(
+        "{{\n  \"swizzled_size\": {},\n  \"deswizzled_size\": {},\n  \"swizzled_layer_size\": {},\n  \"deswizzled_layer_size\": {},\n  \"mips\": [\n{}\n  ]\n}}",
+        sizes.tiled,
+        sizes.linear,
+        layer_stride_tiled(params),
+        layer_stride_linear(params),
+        mip_json.join(",\n")
+    ))
+}
+
+fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    match parse_params(&args).and_then(|p| layout_json(&p)) {
+        Ok(json) => println!("{json}"),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
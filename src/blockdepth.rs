@@ -18,7 +18,8 @@ pub const fn block_depth(depth: u32) -> u32 {
     }
 }
 
-pub fn mip_block_depth(mip_depth: u32, gob_depth: u32) -> u32 {
+#[cfg(feature = "surface")]
+pub const fn mip_block_depth(mip_depth: u32, gob_depth: u32) -> u32 {
     let mut gob_depth = gob_depth;
     while mip_depth <= gob_depth / 2 && gob_depth > 1 {
         gob_depth /= 2;
@@ -44,4 +45,21 @@ mod tests {
         assert_eq!(8, mip_block_depth(16 / 2, 16));
         assert_eq!(16, mip_block_depth(33 / 2, 16));
     }
+
+    #[test]
+    fn mip_block_depths_match_mip_size() {
+        // The mip size uses the block depth of the mip,
+        // so the traversal with the base level's block depth needs the same padded depth.
+        for depth in 1..=128 {
+            let gob_depth = block_depth(depth);
+            for mip in 0..8 {
+                let mip_depth = core::cmp::max(depth >> mip, 1);
+                assert_eq!(
+                    mip_depth.next_multiple_of(block_depth(mip_depth)),
+                    mip_depth.next_multiple_of(mip_block_depth(mip_depth, gob_depth)),
+                    "{depth} {mip}"
+                );
+            }
+        }
+    }
 }
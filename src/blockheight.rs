@@ -26,7 +26,7 @@ let height = 300;
 let block_height_mip0 = block_height_mip0(div_round_up(height, 4));
 ```
  */
-pub fn block_height_mip0(height: u32) -> BlockHeight {
+pub const fn block_height_mip0(height: u32) -> BlockHeight {
     let height_and_half = height + (height / 2);
 
     if height_and_half >= 128 {
@@ -42,6 +42,76 @@ pub fn block_height_mip0(height: u32) -> BlockHeight {
     }
 }
 
+/// The algorithm for inferring the block height of the base mip level.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum BlockHeightHeuristic {
+    /// The heuristic used by the Tegra X1 drivers. See [block_height_mip0].
+    #[default]
+    Trm,
+    /// The heuristic from the nutexb_swizzle crate used in older versions of tools.
+    ///
+    /// The height in GOBs is rounded up to the next power of two with a maximum block height of 16.
+    /// This matches some older files that were created without using [block_height_mip0].
+    /// The heuristics only differ for some heights like 156 pixels for BC7,
+    /// so files that untile incorrectly for only a few sizes may need this heuristic.
+    /// This is the suspected cause of reports like 504x156 BC7 untiling incorrectly
+    /// while 504x64 works, but it has not been confirmed with a captured file.
+    LegacyNutexb,
+    /// The same as [BlockHeightHeuristic::Trm] but allows a block height of 32 for very tall textures.
+    ///
+    /// The Tegra X1 supports a block height of 32,
+    /// but [block_height_mip0] never selects it.
+    TrmExtended,
+}
+
+impl BlockHeightHeuristic {
+    /// Calculates the block height parameter to use for the first mip level.
+    /// The height should be in blocks for compressed formats.
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::{BlockHeight, BlockHeightHeuristic};
+
+    assert_eq!(BlockHeight::Four, BlockHeightHeuristic::Trm.block_height_mip0(24));
+    assert_eq!(BlockHeight::Four, BlockHeightHeuristic::LegacyNutexb.block_height_mip0(24));
+    assert_eq!(BlockHeight::ThirtyTwo, BlockHeightHeuristic::TrmExtended.block_height_mip0(256));
+    ```
+    */
+    pub const fn block_height_mip0(&self, height: u32) -> BlockHeight {
+        match self {
+            BlockHeightHeuristic::Trm => block_height_mip0(height),
+            BlockHeightHeuristic::LegacyNutexb => legacy_nutexb_block_height(height),
+            BlockHeightHeuristic::TrmExtended => trm_extended_block_height(height),
+        }
+    }
+}
+
+const fn trm_extended_block_height(height: u32) -> BlockHeight {
+    // Continue the pattern from block_height_mip0 for the next block height.
+    if height + (height / 2) >= 256 {
+        BlockHeight::ThirtyTwo
+    } else {
+        block_height_mip0(height)
+    }
+}
+
+const fn legacy_nutexb_block_height(height: u32) -> BlockHeight {
+    let height_in_gobs = height.div_ceil(crate::GOB_HEIGHT_IN_BYTES);
+    if height_in_gobs > 8 {
+        BlockHeight::Sixteen
+    } else if height_in_gobs > 4 {
+        BlockHeight::Eight
+    } else if height_in_gobs > 2 {
+        BlockHeight::Four
+    } else if height_in_gobs > 1 {
+        BlockHeight::Two
+    } else {
+        BlockHeight::One
+    }
+}
+
 /// Calculates the block height parameter for the given mip level.
 ///
 /// # Examples
@@ -68,13 +138,110 @@ for mip in 0..mipmap_count {
 }
 ```
  */
-pub fn mip_block_height(mip_height: u32, block_height_mip0: BlockHeight) -> BlockHeight {
+pub const fn mip_block_height(mip_height: u32, block_height_mip0: BlockHeight) -> BlockHeight {
     let mut block_height = block_height_mip0 as u32;
     while mip_height <= (block_height / 2) * 8 && block_height > 1 {
         block_height /= 2;
     }
 
-    BlockHeight::new(block_height).unwrap()
+    // Option::unwrap is not const on the minimum supported Rust version.
+    match BlockHeight::new(block_height) {
+        Some(block_height) => block_height,
+        None => panic!("block height should be a power of two"),
+    }
+}
+
+/// A range of heights and the block heights that can be used for surfaces in that range.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BlockHeightRange {
+    /// The smallest height in the range.
+    pub min_height: u32,
+    /// The largest height in the range.
+    pub max_height: u32,
+    /// The allowed block heights from smallest to largest.
+    pub block_heights: &'static [BlockHeight],
+}
+
+/// The allowed block heights for each range of heights.
+/// The heights should be in blocks for compressed formats.
+///
+/// The values of [BlockHeight] come from the Tegra TRM page 1189 table 79.
+/// A block height is allowed if the block is less than twice as tall as the surface.
+/// Larger block heights are reduced by [mip_block_height], so they are never used.
+/// The ranges cover all heights without overlapping.
+pub const VALID_BLOCK_HEIGHTS: [BlockHeightRange; 6] = [
+    BlockHeightRange {
+        min_height: 0,
+        max_height: 8,
+        block_heights: &[BlockHeight::One],
+    },
+    BlockHeightRange {
+        min_height: 9,
+        max_height: 16,
+        block_heights: &[BlockHeight::One, BlockHeight::Two],
+    },
+    BlockHeightRange {
+        min_height: 17,
+        max_height: 32,
+        block_heights: &[BlockHeight::One, BlockHeight::Two, BlockHeight::Four],
+    },
+    BlockHeightRange {
+        min_height: 33,
+        max_height: 64,
+        block_heights: &[
+            BlockHeight::One,
+            BlockHeight::Two,
+            BlockHeight::Four,
+            BlockHeight::Eight,
+        ],
+    },
+    BlockHeightRange {
+        min_height: 65,
+        max_height: 128,
+        block_heights: &[
+            BlockHeight::One,
+            BlockHeight::Two,
+            BlockHeight::Four,
+            BlockHeight::Eight,
+            BlockHeight::Sixteen,
+        ],
+    },
+    BlockHeightRange {
+        min_height: 129,
+        max_height: u32::MAX,
+        block_heights: &[
+            BlockHeight::One,
+            BlockHeight::Two,
+            BlockHeight::Four,
+            BlockHeight::Eight,
+            BlockHeight::Sixteen,
+            BlockHeight::ThirtyTwo,
+        ],
+    },
+];
+
+/// Returns the allowed block heights for a surface with `height` many rows from [VALID_BLOCK_HEIGHTS].
+/// The height should be in blocks for compressed formats.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{valid_block_heights, BlockHeight};
+
+assert_eq!(&[BlockHeight::One], valid_block_heights(8));
+assert_eq!(Some(&BlockHeight::Sixteen), valid_block_heights(100).last());
+```
+ */
+pub const fn valid_block_heights(height: u32) -> &'static [BlockHeight] {
+    let mut i = 0;
+    while i < VALID_BLOCK_HEIGHTS.len() {
+        if height <= VALID_BLOCK_HEIGHTS[i].max_height {
+            return VALID_BLOCK_HEIGHTS[i].block_heights;
+        }
+        i += 1;
+    }
+    // The last range includes u32::MAX.
+    VALID_BLOCK_HEIGHTS[VALID_BLOCK_HEIGHTS.len() - 1].block_heights
 }
 
 #[cfg(test)]
@@ -794,4 +961,87 @@ mod tests {
             mip_block_height(div_round_up(20, 4), block_height_mip0(div_round_up(640, 4)))
         );
     }
+
+    #[test]
+    fn legacy_nutexb_block_heights() {
+        let legacy = BlockHeightHeuristic::LegacyNutexb;
+        assert_eq!(BlockHeight::One, legacy.block_height_mip0(8));
+        assert_eq!(BlockHeight::Two, legacy.block_height_mip0(9));
+        assert_eq!(BlockHeight::Four, legacy.block_height_mip0(32));
+        assert_eq!(BlockHeight::Eight, legacy.block_height_mip0(48));
+        assert_eq!(BlockHeight::Sixteen, legacy.block_height_mip0(128));
+        assert_eq!(BlockHeight::Sixteen, legacy.block_height_mip0(4096));
+
+        // The heuristics only differ for some heights.
+        assert_eq!(BlockHeight::Eight, legacy.block_height_mip0(40));
+        assert_eq!(BlockHeight::Four, block_height_mip0(40));
+
+        // BC7 504x156 is 39 blocks or 5 GOBs tall, so files tiled with the legacy
+        // block height don't untile correctly using the default heuristic.
+        // TODO: Add a captured 504x156 file to confirm the legacy block height.
+        assert_eq!(BlockHeight::Eight, legacy.block_height_mip0(156 / 4));
+        assert_eq!(BlockHeight::Four, block_height_mip0(156 / 4));
+        assert_eq!(BlockHeight::Two, legacy.block_height_mip0(64 / 4));
+        assert_eq!(BlockHeight::Two, block_height_mip0(64 / 4));
+    }
+
+    #[test]
+    fn trm_extended_block_heights() {
+        let extended = BlockHeightHeuristic::TrmExtended;
+        for height in 0..170 {
+            assert_eq!(
+                block_height_mip0(height),
+                extended.block_height_mip0(height)
+            );
+        }
+        assert_eq!(BlockHeight::Sixteen, extended.block_height_mip0(170));
+        assert_eq!(BlockHeight::ThirtyTwo, extended.block_height_mip0(171));
+        assert_eq!(BlockHeight::ThirtyTwo, extended.block_height_mip0(4096));
+    }
+
+    #[test]
+    fn mip_block_heights_thirty_two() {
+        let block_height_mip0 = BlockHeightHeuristic::TrmExtended.block_height_mip0(1024);
+        assert_eq!(BlockHeight::ThirtyTwo, block_height_mip0);
+        assert_eq!(
+            BlockHeight::ThirtyTwo,
+            mip_block_height(1024, block_height_mip0)
+        );
+        assert_eq!(
+            BlockHeight::ThirtyTwo,
+            mip_block_height(129, block_height_mip0)
+        );
+        assert_eq!(
+            BlockHeight::Sixteen,
+            mip_block_height(128, block_height_mip0)
+        );
+        assert_eq!(BlockHeight::One, mip_block_height(8, block_height_mip0));
+    }
+
+    #[test]
+    fn valid_block_heights_ranges() {
+        for (range, next) in VALID_BLOCK_HEIGHTS
+            .iter()
+            .zip(VALID_BLOCK_HEIGHTS.iter().skip(1))
+        {
+            assert_eq!(range.max_height + 1, next.min_height);
+        }
+        assert_eq!(0, VALID_BLOCK_HEIGHTS[0].min_height);
+        assert_eq!(u32::MAX, VALID_BLOCK_HEIGHTS[5].max_height);
+    }
+
+    #[test]
+    fn valid_block_heights_match_block_height_calculations() {
+        for height in 0..1024 {
+            let block_heights = valid_block_heights(height);
+            assert_eq!(
+                Some(&mip_block_height(height, BlockHeight::ThirtyTwo)),
+                block_heights.last()
+            );
+            assert!(block_heights.contains(&block_height_mip0(height)));
+            assert!(block_heights
+                .contains(&BlockHeightHeuristic::TrmExtended.block_height_mip0(height)));
+        }
+        assert_eq!(6, valid_block_heights(u32::MAX).len());
+    }
 }
@@ -0,0 +1,379 @@
+//! Output containers for writing tiled and untiled surfaces.
+//!
+//! [crate::surface::SurfaceParams::swizzle_to] and [crate::surface::SurfaceParams::deswizzle_to]
+//! write to any [DestBuffer].
+//! This allows writing directly into buffers from custom allocators like arenas
+//! instead of copying the `Vec` returned by [crate::surface::SurfaceParams::deswizzle].
+//!
+//! Implementations are provided for byte slices, `Vec<u8>`,
+//! `bumpalo::collections::Vec` with the `bumpalo` feature,
+//! `smallvec::SmallVec` with the `smallvec` feature,
+//! and `allocator_api2::vec::Vec` for any allocator with the `allocator-api2` feature.
+//!
+//! Surface parameters from untrusted files can describe valid surfaces that are many gigabytes in size.
+//! Wrap the destination in [Limited] to return [SwizzleError::AllocationTooLarge]
+//! instead of attempting the allocation.
+//! Set [crate::surface::SurfaceParams::max_surface_size] to apply the same limit
+//! to functions that allocate their own output like [crate::surface::SurfaceParams::deswizzle].
+//! There is no limit by default.
+use crate::{Result, SwizzleError};
+
+/// A container for the output of tiling or untiling a surface.
+pub trait DestBuffer {
+    /// Returns a buffer with space for at least `size` bytes.
+    ///
+    /// Growable containers should resize to exactly `size` bytes.
+    /// Fixed size buffers should return [SwizzleError::NotEnoughData] if they have fewer than `size` bytes.
+    /// The contents of the buffer do not need to be initialized.
+    fn buffer(&mut self, size: usize) -> Result<&mut [u8]>;
+}
+
+/// Only the first `size` bytes are modified.
+impl DestBuffer for [u8] {
+    fn buffer(&mut self, size: usize) -> Result<&mut [u8]> {
+        let actual_size = self.len();
+        self.get_mut(..size).ok_or(SwizzleError::NotEnoughData {
+            expected_size: size,
+            actual_size,
+        })
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+impl DestBuffer for alloc::vec::Vec<u8> {
+    fn buffer(&mut self, size: usize) -> Result<&mut [u8]> {
+        self.resize(size, 0);
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "bumpalo")]
+impl DestBuffer for bumpalo::collections::Vec<'_, u8> {
+    fn buffer(&mut self, size: usize) -> Result<&mut [u8]> {
+        self.resize(size, 0);
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array<Item = u8>> DestBuffer for smallvec::SmallVec<A> {
+    fn buffer(&mut self, size: usize) -> Result<&mut [u8]> {
+        self.resize(size, 0);
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl<A: allocator_api2::alloc::Allocator> DestBuffer for allocator_api2::vec::Vec<u8, A> {
+    fn buffer(&mut self, size: usize) -> Result<&mut [u8]> {
+        // Custom allocators are often fixed size pools, so avoid growing by more than needed.
+        self.reserve_exact(size.saturating_sub(self.len()));
+        self.resize(size, 0);
+        Ok(self)
+    }
+}
+
+/// A destination that returns [SwizzleError::AllocationTooLarge]
+/// if the output is larger than `max_size` bytes.
+///
+/// The size is checked before calling [DestBuffer::buffer] on the wrapped destination,
+/// so growable containers are never resized past the limit.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::buffer::Limited;
+use tegra_swizzle::surface::SurfaceParams;
+use tegra_swizzle::SwizzleError;
+
+// A valid surface with a very large alignment for each array layer.
+let params = SurfaceParams {
+    width: 4,
+    height: 4,
+    layer_count: 16,
+    layer_alignment: core::num::NonZeroUsize::new(1 << 30),
+    ..Default::default()
+};
+let input = vec![0u8; params.deswizzled_size()];
+
+let mut output = Vec::new();
+let result = params.swizzle_to(&input, &mut Limited::new(&mut output, 64 << 20));
+assert!(matches!(result, Err(SwizzleError::AllocationTooLarge { .. })));
+assert!(output.is_empty());
+```
+ */
+#[derive(Debug)]
+pub struct Limited<'a, D: ?Sized> {
+    /// The destination for the output.
+    pub destination: &'a mut D,
+    /// The maximum size in bytes for the output.
+    pub max_size: usize,
+}
+
+impl<'a, D: DestBuffer + ?Sized> Limited<'a, D> {
+    /// Limits the output written to `destination` to at most `max_size` bytes.
+    pub fn new(destination: &'a mut D, max_size: usize) -> Self {
+        Self {
+            destination,
+            max_size,
+        }
+    }
+}
+
+impl<D: DestBuffer + ?Sized> DestBuffer for Limited<'_, D> {
+    fn buffer(&mut self, size: usize) -> Result<&mut [u8]> {
+        if size > self.max_size {
+            return Err(SwizzleError::AllocationTooLarge {
+                size,
+                max_size: self.max_size,
+            });
+        }
+        self.destination.buffer(size)
+    }
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::surface::{BlockDim, SurfaceParams};
+    use alloc::{vec, vec::Vec};
+
+    fn params() -> SurfaceParams<'static> {
+        SurfaceParams {
+            width: 64,
+            height: 64,
+            block_dim: BlockDim::uncompressed(),
+            bytes_per_pixel: 4,
+            mipmap_count: 3,
+            layer_count: 2,
+            ..Default::default()
+        }
+    }
+
+    fn input(size: usize) -> Vec<u8> {
+        (0..size).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn vec_matches_swizzle_deswizzle() {
+        let params = params();
+        let input = input(params.deswizzled_size());
+
+        // Existing contents and sizes should not affect the output.
+        let mut tiled = vec![1u8; 3];
+        params.swizzle_to(&input, &mut tiled).unwrap();
+        assert_eq!(params.swizzle(&input).unwrap(), tiled);
+
+        let mut untiled = vec![1u8; params.deswizzled_size() * 2];
+        params.deswizzle_to(&tiled, &mut untiled).unwrap();
+        assert_eq!(input, untiled);
+    }
+
+    #[test]
+    fn slice_modifies_only_surface_size() {
+        let params = params();
+        let input = input(params.deswizzled_size());
+
+        let mut tiled = vec![1u8; params.swizzled_size() + 4];
+        params.swizzle_to(&input, &mut tiled[..]).unwrap();
+        assert_eq!(
+            params.swizzle(&input).unwrap(),
+            tiled[..params.swizzled_size()]
+        );
+        assert_eq!(&[1u8; 4], &tiled[params.swizzled_size()..]);
+    }
+
+    #[test]
+    fn slice_not_enough_data() {
+        let params = params();
+        let input = input(params.swizzled_size());
+
+        let mut untiled = [0u8; 4];
+        assert_eq!(
+            Err(SwizzleError::NotEnoughData {
+                expected_size: params.deswizzled_size(),
+                actual_size: 4
+            }),
+            params.deswizzle_to(&input, &mut untiled[..])
+        );
+    }
+
+    #[test]
+    fn vec_empty_surface() {
+        let params = SurfaceParams {
+            width: 0,
+            ..params()
+        };
+        let mut output = vec![1u8; 4];
+        params.deswizzle_to(&[], &mut output).unwrap();
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn limited_allows_max_size() {
+        let params = params();
+        let input = input(params.deswizzled_size());
+
+        let mut tiled = Vec::new();
+        params
+            .swizzle_to(
+                &input,
+                &mut Limited::new(&mut tiled, params.swizzled_size()),
+            )
+            .unwrap();
+        assert_eq!(params.swizzle(&input).unwrap(), tiled);
+    }
+
+    #[test]
+    fn limited_too_large() {
+        let params = params();
+        let input = input(params.swizzled_size());
+
+        let mut untiled = vec![1u8; 4];
+        let max_size = params.deswizzled_size() - 1;
+        assert_eq!(
+            Err(SwizzleError::AllocationTooLarge {
+                size: params.deswizzled_size(),
+                max_size
+            }),
+            params.deswizzle_to(&input, &mut Limited::new(&mut untiled, max_size))
+        );
+        assert_eq!(vec![1u8; 4], untiled);
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn limited_huge_valid_surface() {
+        // The size calculations don't overflow, but the output would be 16 TiB.
+        let params = SurfaceParams {
+            width: 1,
+            height: 1,
+            layer_count: 16,
+            layer_alignment: core::num::NonZeroUsize::new(1 << 40),
+            ..Default::default()
+        };
+        let input = input(params.deswizzled_size());
+        assert_eq!(1 << 44, params.swizzled_size());
+
+        let mut tiled = Vec::new();
+        assert_eq!(
+            Err(SwizzleError::AllocationTooLarge {
+                size: 1 << 44,
+                max_size: 1 << 30
+            }),
+            params.swizzle_to(&input, &mut Limited::new(&mut tiled, 1 << 30))
+        );
+        assert!(tiled.is_empty());
+    }
+
+    #[test]
+    fn limited_alignment_overflow() {
+        // The aligned size overflows before the limit is checked.
+        let params = SurfaceParams {
+            width: 4,
+            height: 4,
+            layer_count: 2,
+            layer_alignment: core::num::NonZeroUsize::new(1 << (usize::BITS - 1)),
+            ..Default::default()
+        };
+        let input = input(params.deswizzled_size());
+
+        let mut tiled = Vec::new();
+        assert!(matches!(
+            params.swizzle_to(&input, &mut Limited::new(&mut tiled, 1 << 30)),
+            Err(SwizzleError::InvalidSurface { .. })
+        ));
+        assert!(tiled.is_empty());
+    }
+
+    #[cfg(feature = "bumpalo")]
+    #[test]
+    fn bumpalo_vec() {
+        let params = params();
+        let input = input(params.swizzled_size());
+
+        let bump = bumpalo::Bump::new();
+        let mut untiled = bumpalo::collections::Vec::new_in(&bump);
+        params.deswizzle_to(&input, &mut untiled).unwrap();
+        assert_eq!(params.deswizzle(&input).unwrap(), &untiled[..]);
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn smallvec() {
+        let params = SurfaceParams {
+            width: 4,
+            height: 4,
+            mipmap_count: 1,
+            layer_count: 1,
+            ..params()
+        };
+        let input = input(params.swizzled_size());
+
+        let mut untiled = smallvec::SmallVec::<[u8; 64]>::new();
+        params.deswizzle_to(&input, &mut untiled).unwrap();
+        assert!(!untiled.spilled());
+        assert_eq!(params.deswizzle(&input).unwrap(), &untiled[..]);
+    }
+
+    #[cfg(feature = "allocator-api2")]
+    #[test]
+    fn allocator_api2_vec() {
+        let params = params();
+        let input = input(params.deswizzled_size());
+
+        let tiled = params
+            .swizzle_in(&input, allocator_api2::alloc::Global)
+            .unwrap();
+        assert_eq!(params.swizzle(&input).unwrap(), &tiled[..]);
+        assert_eq!(params.swizzled_size(), tiled.capacity());
+
+        let untiled = params
+            .deswizzle_in(&tiled, allocator_api2::alloc::Global)
+            .unwrap();
+        assert_eq!(input, &untiled[..]);
+        assert_eq!(params.deswizzled_size(), untiled.capacity());
+    }
+
+    #[cfg(feature = "allocator-api2")]
+    #[test]
+    fn allocator_api2_invalid_params() {
+        // Invalid parameters should return the same errors as the methods returning Vec.
+        let too_many_mips = SurfaceParams {
+            mipmap_count: 40,
+            ..params()
+        };
+        let input = input(16);
+        assert_eq!(
+            too_many_mips.swizzle(&input),
+            too_many_mips
+                .swizzle_in(&input, allocator_api2::alloc::Global)
+                .map(|v| v.to_vec())
+        );
+        assert_eq!(
+            too_many_mips.deswizzle(&input),
+            too_many_mips
+                .deswizzle_in(&input, allocator_api2::alloc::Global)
+                .map(|v| v.to_vec())
+        );
+    }
+
+    #[cfg(all(feature = "allocator-api2", target_pointer_width = "64"))]
+    #[test]
+    fn allocator_api2_huge_surface_small_source() {
+        // Check the source length before attempting to allocate 16 TiB.
+        let params = SurfaceParams {
+            width: 1 << 20,
+            height: 1 << 20,
+            bytes_per_pixel: 16,
+            ..Default::default()
+        };
+        let input = input(16);
+        assert!(params
+            .swizzle_in(&input, allocator_api2::alloc::Global)
+            .is_err());
+        assert!(params
+            .deswizzle_in(&input, allocator_api2::alloc::Global)
+            .is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! The functions from earlier versions that take each surface parameter as a separate argument.
+//!
+//! These functions are deprecated in favor of the methods on [SurfaceParams]
+//! and will be removed in a future major release.
+//! The functions continue to work the same as before,
+//! so existing code can migrate to [SurfaceParams] one call at a time.
+//!
+//! Each function creates a [SurfaceParams] with the [BlockHeightHeuristic::Trm] block heights
+//! and the default values for the fields not listed in its arguments.
+//!
+//! | Function | Replacement |
+//! | --- | --- |
+//! | [swizzle_surface] | [SurfaceParams::swizzle] |
+//! | [deswizzle_surface] | [SurfaceParams::deswizzle] |
+//! | [swizzle_surface_from_mips] | [SurfaceParams::swizzle_from_mips] |
+//! | [swizzle_surface_with_block_heights] | [SurfaceParams::swizzle] with [SurfaceParams::mip_block_heights] |
+//! | [deswizzle_surface_with_block_heights] | [SurfaceParams::deswizzle] with [SurfaceParams::mip_block_heights] |
+//! | [swizzled_surface_size] | [SurfaceParams::swizzled_size] |
+//! | [swizzled_surface_size_with_block_heights] | [SurfaceParams::swizzled_size] with [SurfaceParams::mip_block_heights] |
+//! | [deswizzled_surface_size] | [SurfaceParams::deswizzled_size] |
+//!
+//! [SurfaceParams]: crate::surface::SurfaceParams
+//! [SurfaceParams::swizzle]: crate::surface::SurfaceParams::swizzle
+//! [SurfaceParams::deswizzle]: crate::surface::SurfaceParams::deswizzle
+//! [SurfaceParams::swizzle_from_mips]: crate::surface::SurfaceParams::swizzle_from_mips
+//! [SurfaceParams::mip_block_heights]: crate::surface::SurfaceParams::mip_block_heights
+//! [SurfaceParams::swizzled_size]: crate::surface::SurfaceParams::swizzled_size
+//! [SurfaceParams::deswizzled_size]: crate::surface::SurfaceParams::deswizzled_size
+//! [BlockHeightHeuristic::Trm]: crate::BlockHeightHeuristic::Trm
+//!
+//! # Examples
+//! The positional arguments map directly to the fields of [SurfaceParams].
+/*!
+```rust
+use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+# let input = vec![0u8; 2208];
+
+// 16x16 BC7 cube map with 5 mipmaps.
+// Previously swizzle_surface(16, 16, 1, &input, BlockDim::block_4x4(), None, 16, 5, 6).
+let params = SurfaceParams {
+    width: 16,
+    height: 16,
+    depth: 1,
+    block_dim: BlockDim::block_4x4(),
+    block_height_mip0: None,
+    bytes_per_pixel: 16,
+    mipmap_count: 5,
+    layer_count: 6,
+    ..Default::default()
+};
+let tiled = params.swizzle(&input)?;
+assert_eq!(15360, tiled.len());
+# Ok::<(), tegra_swizzle::SwizzleError>(())
+```
+*/
+#![allow(deprecated)]
+
+#[cfg(not(feature = "minimal"))]
+pub use crate::surface::{
+    deswizzle_surface, deswizzle_surface_with_block_heights, swizzle_surface,
+    swizzle_surface_from_mips, swizzle_surface_with_block_heights,
+};
+pub use crate::surface::{
+    deswizzled_surface_size, swizzled_surface_size, swizzled_surface_size_with_block_heights,
+};
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::{
+        surface::{BlockDim, SurfaceParams},
+        BlockHeight,
+    };
+    use alloc::vec::Vec;
+
+    #[test]
+    fn compat_functions_match_params() {
+        let params = SurfaceParams {
+            width: 33,
+            height: 65,
+            depth: 1,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 8,
+            mipmap_count: 4,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let tiled = params.swizzle(&input).unwrap();
+
+        assert_eq!(
+            params.swizzled_size(),
+            swizzled_surface_size(33, 65, 1, BlockDim::block_4x4(), None, 8, 4, 2)
+        );
+        assert_eq!(
+            params.deswizzled_size(),
+            deswizzled_surface_size(33, 65, 1, BlockDim::block_4x4(), 8, 4, 2)
+        );
+        assert_eq!(
+            Ok(tiled.clone()),
+            swizzle_surface(33, 65, 1, &input, BlockDim::block_4x4(), None, 8, 4, 2)
+        );
+        assert_eq!(
+            Ok(input),
+            deswizzle_surface(33, 65, 1, &tiled, BlockDim::block_4x4(), None, 8, 4, 2)
+        );
+    }
+
+    #[test]
+    fn compat_block_heights_match_params() {
+        let mip_block_heights = [BlockHeight::Four, BlockHeight::Two, BlockHeight::One];
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            bytes_per_pixel: 4,
+            mipmap_count: 3,
+            mip_block_heights: Some(&mip_block_heights),
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let tiled = params.swizzle(&input).unwrap();
+
+        assert_eq!(
+            params.swizzled_size(),
+            swizzled_surface_size_with_block_heights(
+                64,
+                64,
+                1,
+                BlockDim::uncompressed(),
+                &mip_block_heights,
+                4,
+                3,
+                1
+            )
+        );
+        assert_eq!(
+            Ok(tiled.clone()),
+            swizzle_surface_with_block_heights(
+                64,
+                64,
+                1,
+                &input,
+                BlockDim::uncompressed(),
+                &mip_block_heights,
+                4,
+                3,
+                1
+            )
+        );
+        assert_eq!(
+            Ok(input),
+            deswizzle_surface_with_block_heights(
+                64,
+                64,
+                1,
+                &tiled,
+                BlockDim::uncompressed(),
+                &mip_block_heights,
+                4,
+                3,
+                1
+            )
+        );
+    }
+}
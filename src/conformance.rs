@@ -0,0 +1,348 @@
+//! Cross checks against a port of the block linear layout from the Ryujinx emulator.
+//!
+//! The reference functions follow `BlockLinearLayout.cs` and `SizeCalculator.cs`
+//! from Ryujinx.Graphics.Texture with the same variable names where possible.
+//! Sparse textures with more than one GOB block in x are not supported by tegra_swizzle,
+//! so the port always uses a `gobBlocksInTileX` of 1.
+//! Ryujinx only supports formats with a power of two bytes per pixel.
+//!
+//! The tests check every byte of many randomized surfaces and take a while to run,
+//! so they are ignored by default.
+//! Run them with `cargo test --release conformance -- --ignored`.
+use alloc::{format, vec, vec::Vec};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    blockdepth::block_depth,
+    div_round_up,
+    surface::{BlockDim, SurfaceParams},
+    swizzle::{deswizzle_block_linear, swizzled_mip_size},
+    BlockHeight,
+};
+
+const GOB_STRIDE: usize = 64;
+const GOB_HEIGHT: usize = 8;
+const GOB_SIZE: usize = GOB_STRIDE * GOB_HEIGHT;
+
+struct BlockLinearLayout {
+    bh_mask: usize,
+    bd_mask: usize,
+    bh_shift: u32,
+    bd_shift: u32,
+    x_shift: u32,
+    rob_size: usize,
+    slice_size: usize,
+}
+
+impl BlockLinearLayout {
+    fn new(
+        width: usize,
+        height: usize,
+        gob_blocks_in_y: usize,
+        gob_blocks_in_z: usize,
+        bpp: usize,
+    ) -> Self {
+        let width_in_gobs = div_round_up_usize(width * bpp, GOB_STRIDE);
+        let rob_size = GOB_SIZE * gob_blocks_in_y * gob_blocks_in_z * width_in_gobs;
+        let slice_size = div_round_up_usize(height, gob_blocks_in_y * GOB_HEIGHT) * rob_size;
+
+        Self {
+            bh_mask: gob_blocks_in_y - 1,
+            bd_mask: gob_blocks_in_z - 1,
+            bh_shift: gob_blocks_in_y.trailing_zeros(),
+            bd_shift: gob_blocks_in_z.trailing_zeros(),
+            x_shift: (GOB_SIZE * gob_blocks_in_y * gob_blocks_in_z).trailing_zeros(),
+            rob_size,
+            slice_size,
+        }
+    }
+
+    // x is in bytes rather than pixels.
+    fn get_offset_with_line_offset(&self, x: usize, y: usize, z: usize) -> usize {
+        let yh = y / GOB_HEIGHT;
+        let mut offset =
+            (z >> self.bd_shift) * self.slice_size + (yh >> self.bh_shift) * self.rob_size;
+
+        offset += (x / GOB_STRIDE) << self.x_shift;
+        offset += (yh & self.bh_mask) * GOB_SIZE;
+        offset += ((z & self.bd_mask) * GOB_SIZE) << self.bh_shift;
+
+        offset += ((x & 0x3f) >> 5) << 8;
+        offset += ((y & 0x07) >> 1) << 6;
+        offset += ((x & 0x1f) >> 4) << 5;
+        offset += (y & 0x01) << 4;
+        offset += x & 0x0f;
+
+        offset
+    }
+}
+
+// The layout of a single mipmap within an array layer.
+struct LevelInfo {
+    offset: usize,
+    width: usize,
+    height: usize,
+    depth: usize,
+    gob_blocks_in_y: usize,
+    gob_blocks_in_z: usize,
+}
+
+// The mipmap offsets and aligned layer size from GetBlockLinearTextureSize.
+fn get_block_linear_texture_size(
+    width: usize,
+    height: usize,
+    depth: usize,
+    levels: usize,
+    layers: usize,
+    block_width: usize,
+    block_height: usize,
+    bytes_per_pixel: usize,
+    gob_blocks_in_y: usize,
+    gob_blocks_in_z: usize,
+) -> (Vec<LevelInfo>, usize) {
+    let mut layer_size = 0;
+    let mut mip_gob_blocks_in_y = gob_blocks_in_y;
+    let mut mip_gob_blocks_in_z = gob_blocks_in_z;
+
+    let mut level_infos = Vec::new();
+    for level in 0..levels {
+        let w = div_round_up_usize((width >> level).max(1), block_width);
+        let h = div_round_up_usize((height >> level).max(1), block_height);
+        let d = (depth >> level).max(1);
+
+        while h <= (mip_gob_blocks_in_y >> 1) * GOB_HEIGHT && mip_gob_blocks_in_y != 1 {
+            mip_gob_blocks_in_y >>= 1;
+        }
+        while d <= (mip_gob_blocks_in_z >> 1) && mip_gob_blocks_in_z != 1 {
+            mip_gob_blocks_in_z >>= 1;
+        }
+
+        let width_in_gobs = div_round_up_usize(w * bytes_per_pixel, GOB_STRIDE);
+
+        let total_blocks_of_gobs_in_z = div_round_up_usize(d, mip_gob_blocks_in_z);
+        let total_blocks_of_gobs_in_y =
+            div_round_up_usize(div_round_up_usize(h, GOB_HEIGHT), mip_gob_blocks_in_y);
+
+        let rob_size = width_in_gobs * mip_gob_blocks_in_y * mip_gob_blocks_in_z * GOB_SIZE;
+        let slice_size = total_blocks_of_gobs_in_y * rob_size;
+
+        level_infos.push(LevelInfo {
+            offset: layer_size,
+            width: w,
+            height: h,
+            depth: d,
+            gob_blocks_in_y: mip_gob_blocks_in_y,
+            gob_blocks_in_z: mip_gob_blocks_in_z,
+        });
+        layer_size += total_blocks_of_gobs_in_z * slice_size;
+    }
+
+    if layers > 1 {
+        layer_size = align_layer_size(
+            layer_size,
+            height,
+            depth,
+            block_height,
+            gob_blocks_in_y,
+            gob_blocks_in_z,
+        );
+    }
+
+    (level_infos, layer_size)
+}
+
+fn align_layer_size(
+    size: usize,
+    height: usize,
+    depth: usize,
+    block_height: usize,
+    gob_blocks_in_y: usize,
+    gob_blocks_in_z: usize,
+) -> usize {
+    let height = div_round_up_usize(height, block_height);
+
+    let mut gob_blocks_in_y = gob_blocks_in_y;
+    let mut gob_blocks_in_z = gob_blocks_in_z;
+    while height <= (gob_blocks_in_y >> 1) * GOB_HEIGHT && gob_blocks_in_y != 1 {
+        gob_blocks_in_y >>= 1;
+    }
+    while depth <= (gob_blocks_in_z >> 1) && gob_blocks_in_z != 1 {
+        gob_blocks_in_z >>= 1;
+    }
+
+    let block_of_gobs_size = gob_blocks_in_y * gob_blocks_in_z * GOB_SIZE;
+    size.next_multiple_of(block_of_gobs_size)
+}
+
+fn div_round_up_usize(x: usize, d: usize) -> usize {
+    x.div_ceil(d)
+}
+
+const BLOCK_HEIGHTS: [BlockHeight; 6] = [
+    BlockHeight::One,
+    BlockHeight::Two,
+    BlockHeight::Four,
+    BlockHeight::Eight,
+    BlockHeight::Sixteen,
+    BlockHeight::ThirtyTwo,
+];
+
+fn random_bytes(rng: &mut StdRng, size: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; size];
+    rng.fill(&mut bytes[..]);
+    bytes
+}
+
+#[test]
+#[ignore]
+fn mipmaps_match_ryujinx() {
+    let mut rng = StdRng::seed_from_u64(447);
+    for _ in 0..500 {
+        let width = rng.gen_range(1..300);
+        let height = rng.gen_range(1..300);
+        let depth = if rng.gen_bool(0.25) {
+            rng.gen_range(2..40)
+        } else {
+            1
+        };
+        let bytes_per_pixel = 1 << rng.gen_range(0..5);
+        let block_height = BLOCK_HEIGHTS[rng.gen_range(0..BLOCK_HEIGHTS.len())];
+        let gob_blocks_in_z = block_depth(depth) as usize;
+
+        let description = format!(
+            "{width}x{height}x{depth} bpp: {bytes_per_pixel}, block height: {block_height:?}"
+        );
+
+        let layout = BlockLinearLayout::new(
+            width as usize,
+            height as usize,
+            block_height as usize,
+            gob_blocks_in_z,
+            bytes_per_pixel as usize,
+        );
+        // The size calculations for surfaces reduce the block height for small mipmaps,
+        // but the mipmap functions always use the specified block height.
+        let expected_size = layout.slice_size * div_round_up_usize(depth as usize, gob_blocks_in_z);
+        let size = swizzled_mip_size(width, height, depth, block_height, bytes_per_pixel);
+        assert_eq!(expected_size, size, "{description}");
+
+        let tiled = random_bytes(&mut rng, size);
+        let untiled =
+            deswizzle_block_linear(width, height, depth, &tiled, block_height, bytes_per_pixel)
+                .unwrap();
+
+        let row_size = width as usize * bytes_per_pixel as usize;
+        for z in 0..depth as usize {
+            for y in 0..height as usize {
+                for x in 0..row_size {
+                    let linear = (z * height as usize + y) * row_size + x;
+                    let offset = layout.get_offset_with_line_offset(x, y, z);
+                    assert_eq!(
+                        tiled[offset], untiled[linear],
+                        "{description} ({x}, {y}, {z})"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+#[ignore]
+fn surfaces_match_ryujinx() {
+    let mut rng = StdRng::seed_from_u64(4470);
+    for _ in 0..200 {
+        // Ryujinx uses the block depth from the texture descriptor for 3D textures.
+        // tegra_swizzle infers the block depth and uses a block height of 1.
+        let is_3d = rng.gen_bool(0.25);
+        let (depth, layer_count, block_dim) = if is_3d {
+            (rng.gen_range(2..40), 1, BlockDim::uncompressed())
+        } else if rng.gen_bool(0.5) {
+            (1, rng.gen_range(1..8), BlockDim::block_4x4())
+        } else {
+            (1, rng.gen_range(1..8), BlockDim::uncompressed())
+        };
+        let width: u32 = rng.gen_range(1..300);
+        let height: u32 = rng.gen_range(1..300);
+        let bytes_per_pixel = 1 << rng.gen_range(0..5);
+        let mipmap_count = rng.gen_range(1..=u32::BITS - width.max(height).leading_zeros());
+        let block_height_mip0 = if !is_3d && rng.gen_bool(0.5) {
+            Some(BLOCK_HEIGHTS[rng.gen_range(0..BLOCK_HEIGHTS.len())])
+        } else {
+            None
+        };
+
+        let params = SurfaceParams {
+            width,
+            height,
+            depth,
+            block_dim,
+            block_height_mip0,
+            bytes_per_pixel,
+            mipmap_count,
+            layer_count,
+            ..Default::default()
+        };
+        let description = format!("{params:?}");
+
+        let gob_blocks_in_y = if is_3d {
+            1
+        } else {
+            block_height_mip0.unwrap_or_else(|| {
+                crate::block_height_mip0(div_round_up(height, block_dim.height.get()))
+            }) as usize
+        };
+        let gob_blocks_in_z = block_depth(depth) as usize;
+
+        let (levels, layer_size) = get_block_linear_texture_size(
+            width as usize,
+            height as usize,
+            depth as usize,
+            mipmap_count as usize,
+            layer_count as usize,
+            block_dim.width.get() as usize,
+            block_dim.height.get() as usize,
+            bytes_per_pixel as usize,
+            gob_blocks_in_y,
+            gob_blocks_in_z,
+        );
+        assert_eq!(
+            layer_size * layer_count as usize,
+            params.swizzled_size(),
+            "{description}"
+        );
+
+        let tiled = random_bytes(&mut rng, params.swizzled_size());
+        let untiled = params.deswizzle(&tiled).unwrap();
+
+        let mut linear = 0;
+        for layer in 0..layer_count as usize {
+            for level in &levels {
+                let layout = BlockLinearLayout::new(
+                    level.width,
+                    level.height,
+                    level.gob_blocks_in_y,
+                    level.gob_blocks_in_z,
+                    bytes_per_pixel as usize,
+                );
+                let row_size = level.width * bytes_per_pixel as usize;
+                for z in 0..level.depth {
+                    for y in 0..level.height {
+                        for x in 0..row_size {
+                            let offset = layer * layer_size
+                                + level.offset
+                                + layout.get_offset_with_line_offset(x, y, z);
+                            assert_eq!(
+                                tiled[offset], untiled[linear],
+                                "{description} layer {layer} ({x}, {y}, {z})"
+                            );
+                            linear += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(untiled.len(), linear);
+    }
+}
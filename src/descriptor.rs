@@ -0,0 +1,230 @@
+//! Surface parameters with room for hardware features that are not supported yet.
+//!
+//! A [SurfaceDescriptor] wraps [SurfaceParams] with additional fields for
+//! multisampling, the tiling mode, and the GOB height.
+//! Only single sampled block linear surfaces with 8 byte tall GOBs are currently supported.
+//! Other values return [SwizzleError::Unsupported] instead of producing incorrect data.
+//!
+//! New fields may be added in future versions without breaking existing code,
+//! so descriptors are created with [SurfaceDescriptor::new] and then modified.
+//!
+//! # Examples
+/*!
+```rust
+use tegra_swizzle::descriptor::{SurfaceDescriptor, TileMode};
+use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+use tegra_swizzle::SwizzleError;
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 9,
+    ..Default::default()
+};
+# let input = vec![0u8; params.swizzled_size()];
+let mut descriptor = SurfaceDescriptor::new(params);
+assert_eq!(Ok(params.deswizzle(&input)?), descriptor.deswizzle(&input));
+
+descriptor.sample_count = 4;
+assert!(matches!(
+    descriptor.deswizzle(&input),
+    Err(SwizzleError::Unsupported { .. })
+));
+# Ok::<(), SwizzleError>(())
+```
+*/
+#[cfg(not(feature = "minimal"))]
+use alloc::vec::Vec;
+
+use crate::{
+    buffer::DestBuffer, surface::SurfaceParams, Result, SwizzleError, GOB_HEIGHT_IN_BYTES,
+};
+
+/// The memory layout for the pixels of each mipmap.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TileMode {
+    /// The Tegra X1 block linear layout used by [SurfaceParams::swizzle].
+    #[default]
+    BlockLinear,
+    /// Rows of pixels aligned to a fixed pitch.
+    /// This is not supported yet.
+    Pitch,
+}
+
+/// The parameters describing a surface with optional hardware features.
+/// See the [module level documentation](crate::descriptor) for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SurfaceDescriptor<'a> {
+    /// The dimensions, format, and layout options for the surface.
+    pub params: SurfaceParams<'a>,
+    /// The number of samples for each pixel or 1 for surfaces without multisampling.
+    /// Only a sample count of 1 is supported.
+    pub sample_count: u32,
+    /// The memory layout for each mipmap.
+    /// Only [TileMode::BlockLinear] is supported.
+    pub tile_mode: TileMode,
+    /// The height of each GOB in rows of bytes.
+    /// Only a GOB height of 8 is supported.
+    pub gob_height: u32,
+}
+
+impl<'a> SurfaceDescriptor<'a> {
+    /// Creates a single sampled block linear descriptor for `params`.
+    pub const fn new(params: SurfaceParams<'a>) -> Self {
+        Self {
+            params,
+            sample_count: 1,
+            tile_mode: TileMode::BlockLinear,
+            gob_height: GOB_HEIGHT_IN_BYTES,
+        }
+    }
+
+    /// Returns the [SurfaceParams] for the descriptor
+    /// or [SwizzleError::Unsupported] if the descriptor uses any unsupported features.
+    pub const fn supported_params(&self) -> Result<&SurfaceParams<'a>> {
+        if self.sample_count != 1 {
+            Err(SwizzleError::Unsupported {
+                feature: "multisampled surfaces",
+            })
+        } else if !matches!(self.tile_mode, TileMode::BlockLinear) {
+            Err(SwizzleError::Unsupported {
+                feature: "pitch linear tiling",
+            })
+        } else if self.gob_height != GOB_HEIGHT_IN_BYTES {
+            Err(SwizzleError::Unsupported {
+                feature: "GOB heights other than 8",
+            })
+        } else {
+            Ok(&self.params)
+        }
+    }
+
+    /// Tiles all the array layers and mipmaps in `source`.
+    /// See [SurfaceParams::swizzle].
+    #[cfg(not(feature = "minimal"))]
+    pub fn swizzle(&self, source: &[u8]) -> Result<Vec<u8>> {
+        self.supported_params()?.swizzle(source)
+    }
+
+    /// Untiles all the array layers and mipmaps in `source`.
+    /// See [SurfaceParams::deswizzle].
+    #[cfg(not(feature = "minimal"))]
+    pub fn deswizzle(&self, source: &[u8]) -> Result<Vec<u8>> {
+        self.supported_params()?.deswizzle(source)
+    }
+
+    /// Tiles all the array layers and mipmaps in `source` into the container `destination`.
+    /// See [SurfaceParams::swizzle_to].
+    pub fn swizzle_to<D: DestBuffer + ?Sized>(
+        &self,
+        source: &[u8],
+        destination: &mut D,
+    ) -> Result<()> {
+        self.supported_params()?.swizzle_to(source, destination)
+    }
+
+    /// Untiles all the array layers and mipmaps in `source` into the container `destination`.
+    /// See [SurfaceParams::deswizzle_to].
+    pub fn deswizzle_to<D: DestBuffer + ?Sized>(
+        &self,
+        source: &[u8],
+        destination: &mut D,
+    ) -> Result<()> {
+        self.supported_params()?.deswizzle_to(source, destination)
+    }
+
+    /// Calculates the size in bytes for the tiled data.
+    /// See [SurfaceParams::swizzled_size].
+    pub const fn swizzled_size(&self) -> Result<usize> {
+        match self.supported_params() {
+            Ok(params) => Ok(params.swizzled_size()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Calculates the size in bytes for the untiled or linear data.
+    /// See [SurfaceParams::deswizzled_size].
+    pub const fn deswizzled_size(&self) -> Result<usize> {
+        match self.supported_params() {
+            Ok(params) => Ok(params.deswizzled_size()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<'a> From<SurfaceParams<'a>> for SurfaceDescriptor<'a> {
+    fn from(params: SurfaceParams<'a>) -> Self {
+        Self::new(params)
+    }
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::surface::BlockDim;
+    use alloc::vec;
+
+    fn params() -> SurfaceParams<'static> {
+        SurfaceParams {
+            width: 64,
+            height: 64,
+            block_dim: BlockDim::uncompressed(),
+            bytes_per_pixel: 4,
+            mipmap_count: 3,
+            layer_count: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_descriptor_matches_params() {
+        let params = params();
+        let descriptor = SurfaceDescriptor::from(params);
+        assert_eq!(Ok(params.swizzled_size()), descriptor.swizzled_size());
+        assert_eq!(Ok(params.deswizzled_size()), descriptor.deswizzled_size());
+
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let tiled = descriptor.swizzle(&input).unwrap();
+        assert_eq!(params.swizzle(&input).unwrap(), tiled);
+        assert_eq!(input, descriptor.deswizzle(&tiled).unwrap());
+    }
+
+    #[test]
+    fn unsupported_features() {
+        let multisampled = SurfaceDescriptor {
+            sample_count: 4,
+            ..SurfaceDescriptor::new(params())
+        };
+        let pitch = SurfaceDescriptor {
+            tile_mode: TileMode::Pitch,
+            ..SurfaceDescriptor::new(params())
+        };
+        let gob_height = SurfaceDescriptor {
+            gob_height: 16,
+            ..SurfaceDescriptor::new(params())
+        };
+
+        for (descriptor, feature) in [
+            (multisampled, "multisampled surfaces"),
+            (pitch, "pitch linear tiling"),
+            (gob_height, "GOB heights other than 8"),
+        ] {
+            let error = SwizzleError::Unsupported { feature };
+            assert_eq!(Err(error.clone()), descriptor.swizzled_size());
+            assert_eq!(Err(error.clone()), descriptor.deswizzled_size());
+
+            // The destination should not be modified.
+            let mut output = vec![1u8; 4];
+            assert_eq!(
+                Err(error.clone()),
+                descriptor.deswizzle_to(&[0u8; 4], &mut output)
+            );
+            assert_eq!(vec![1u8; 4], output);
+            assert_eq!(Err(error), descriptor.swizzle(&[0u8; 4]));
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Annotated hex dumps of tiled data for debugging.
+//!
+//! The dump lists the tiled bytes in memory order and labels each block and GOB.
+//! Each line contains a 16 byte sector of a GOB and the untiled byte coordinates of that sector.
+//! This is mainly useful for reverse engineering the tiling of unknown formats.
+use core::fmt::Write;
+
+use crate::{
+    blockdepth::block_depth, height_in_blocks, swizzle::swizzled_mip_size, width_in_gobs,
+    BlockHeight, GOB_HEIGHT_IN_BYTES, GOB_SIZE_IN_BYTES, GOB_WIDTH_IN_BYTES,
+};
+
+const SECTOR_SIZE_IN_BYTES: usize = 16;
+
+/// Writes an annotated hex dump of the tiled `data` for a mipmap with the given dimensions to `writer`.
+/// The parameters are the same as [crate::swizzle::deswizzle_block_linear].
+///
+/// Coordinates are in bytes with the origin at the top left of the untiled data.
+/// Bytes outside the untiled data are labeled as padding.
+/// Only the available bytes are written if `data` is smaller than the tiled size.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{dump::write_tiled_dump, BlockHeight};
+
+let data = vec![0u8; 512];
+let mut dump = String::new();
+write_tiled_dump(&mut dump, &data, 16, 8, 1, BlockHeight::One, 4).unwrap();
+assert!(dump.starts_with("block 0 (x: 0, y: 0, z: 0)"));
+```
+ */
+pub fn write_tiled_dump<W: Write>(
+    writer: &mut W,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> core::fmt::Result {
+    let size = swizzled_mip_size(width, height, depth, block_height, bytes_per_pixel);
+    let data = &data[..size.min(data.len())];
+
+    let block_height = block_height as u32;
+    let block_depth = block_depth(depth);
+    let width_in_gobs = width_in_gobs(width, bytes_per_pixel);
+    let blocks_per_slice = (width_in_gobs * height_in_blocks(height, block_height)) as usize;
+    let gobs_per_block = (block_height * block_depth) as usize;
+    let row_size = width * bytes_per_pixel;
+
+    for (i, gob) in data.chunks(GOB_SIZE_IN_BYTES as usize).enumerate() {
+        let block = i / gobs_per_block;
+        let block_x = (block % blocks_per_slice) as u32 % width_in_gobs;
+        let block_y = (block % blocks_per_slice) as u32 / width_in_gobs;
+        let block_z = (block / blocks_per_slice) as u32;
+
+        // Blocks stack GOBs vertically and then in depth.
+        let gob_index = (i % gobs_per_block) as u32;
+        let gob_x = block_x * GOB_WIDTH_IN_BYTES;
+        let gob_y = (block_y * block_height + gob_index % block_height) * GOB_HEIGHT_IN_BYTES;
+        let gob_z = block_z * block_depth + gob_index / block_height;
+
+        if gob_index == 0 {
+            writeln!(writer, "block {block} (x: {gob_x}, y: {gob_y}, z: {gob_z})")?;
+        }
+        writeln!(writer, "  gob {i} (x: {gob_x}, y: {gob_y}, z: {gob_z})")?;
+
+        for (j, sector) in gob.chunks(SECTOR_SIZE_IN_BYTES).enumerate() {
+            let offset = i * GOB_SIZE_IN_BYTES as usize + j * SECTOR_SIZE_IN_BYTES;
+            write!(writer, "    {offset:08x}:")?;
+            for b in sector {
+                write!(writer, " {b:02x}")?;
+            }
+
+            let (sector_x, sector_y) = sector_position(j * SECTOR_SIZE_IN_BYTES);
+            let x = gob_x + sector_x;
+            let y = gob_y + sector_y;
+            if x >= row_size || y >= height || gob_z >= depth {
+                writeln!(writer, " | padding")?;
+            } else {
+                let end_x = (x + SECTOR_SIZE_IN_BYTES as u32).min(row_size);
+                writeln!(writer, " | x: {x}..{end_x}, y: {y}, z: {gob_z}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Find the byte coordinates within the GOB for a sector.
+// This is the inverse of the gob_offset calculation for multiples of 16 bytes.
+fn sector_position(offset: usize) -> (u32, u32) {
+    let offset = offset as u32;
+    let x = ((offset >> 8) & 1) * 32 + ((offset >> 5) & 1) * 16;
+    let y = ((offset >> 6) & 3) * 2 + ((offset >> 4) & 1);
+    (x, y)
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec, vec::Vec};
+
+    #[test]
+    fn dump_single_gob() {
+        // Label each byte with its linear position.
+        let linear: Vec<_> = (0..64 * 8).map(|i| (i / 16) as u8).collect();
+        let tiled =
+            crate::swizzle::swizzle_block_linear(64, 8, 1, &linear, BlockHeight::One, 1).unwrap();
+
+        let mut dump = String::new();
+        write_tiled_dump(&mut dump, &tiled, 64, 8, 1, BlockHeight::One, 1).unwrap();
+
+        let lines: Vec<_> = dump.lines().collect();
+        assert_eq!(34, lines.len());
+        assert_eq!("block 0 (x: 0, y: 0, z: 0)", lines[0]);
+        assert_eq!("  gob 0 (x: 0, y: 0, z: 0)", lines[1]);
+        assert_eq!(
+            "    00000000: 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 | x: 0..16, y: 0, z: 0",
+            lines[2]
+        );
+        assert_eq!(
+            "    00000010: 04 04 04 04 04 04 04 04 04 04 04 04 04 04 04 04 | x: 0..16, y: 1, z: 0",
+            lines[3]
+        );
+        assert_eq!(
+            "    000001f0: 1f 1f 1f 1f 1f 1f 1f 1f 1f 1f 1f 1f 1f 1f 1f 1f | x: 48..64, y: 7, z: 0",
+            lines[33]
+        );
+    }
+
+    #[test]
+    fn dump_sector_positions() {
+        // Each sector should map back to the bytes tiled to that offset.
+        for sector in 0..32 {
+            let (x, y) = sector_position(sector * 16);
+            assert_eq!(
+                sector as u32 * 16,
+                crate::swizzle::gob_offset(x, y),
+                "{sector}"
+            );
+        }
+    }
+
+    #[test]
+    fn dump_blocks_padding() {
+        // 2 GOBs wide and 2 GOBs tall with padding to the right and below.
+        let tiled = vec![0u8; swizzled_mip_size(20, 9, 1, BlockHeight::Two, 4)];
+
+        let mut dump = String::new();
+        write_tiled_dump(&mut dump, &tiled, 20, 9, 1, BlockHeight::Two, 4).unwrap();
+
+        let headers: Vec<_> = dump.lines().filter(|l| !l.starts_with("    ")).collect();
+        assert_eq!(
+            vec![
+                "block 0 (x: 0, y: 0, z: 0)",
+                "  gob 0 (x: 0, y: 0, z: 0)",
+                "  gob 1 (x: 0, y: 8, z: 0)",
+                "block 1 (x: 64, y: 0, z: 0)",
+                "  gob 2 (x: 64, y: 0, z: 0)",
+                "  gob 3 (x: 64, y: 8, z: 0)",
+            ],
+            headers
+        );
+        assert!(dump.lines().any(|l| l.ends_with("| x: 64..80, y: 0, z: 0")));
+        assert!(dump.lines().any(|l| l.ends_with("| x: 0..16, y: 8, z: 0")));
+        assert_eq!(
+            128 - 9 * 5,
+            dump.lines().filter(|l| l.ends_with("padding")).count()
+        );
+    }
+
+    #[test]
+    fn dump_not_enough_data() {
+        let mut dump = String::new();
+        write_tiled_dump(&mut dump, &[0u8; 20], 64, 64, 1, BlockHeight::Eight, 4).unwrap();
+        assert_eq!(4, dump.lines().count());
+    }
+}
@@ -5,11 +5,63 @@
 //! the appropriate amount of memory for the destination array
 //! by calling functions like [swizzled_surface_size] or [deswizzled_surface_size].
 //!
+//! Functions like [swizzle_surface_with_block_dims] take the block dimensions as separate integers
+//! for languages where passing the [BlockDim] struct is inconvenient.
+//!
+//! Functions like [swizzle_surface] panic for invalid inputs and are intended for callers that already validated the inputs.
+//! Functions like [try_swizzle_surface] return a [SwizzleStatus] instead of panicking for invalid inputs.
+//! Functions like [swizzle_surface_checked] also take the block dimensions as integers,
+//! so the only requirement is that the pointers and lengths describe valid arrays.
+//! Functions like [try_swizzle_surface_with_info] also write a [SwizzleErrorInfo]
+//! with details like which array layer and mipmap did not have enough data.
+//!
+//! Pointers to arrays may be null if the corresponding length is 0.
+//! Mipmaps and surfaces with a width, height, depth, or bytes per pixel of 0 are empty
+//! and have a size of 0, so tiling or untiling them does not access either array.
+//!
 //! For block height parameters, always use the result of [block_height_mip0]
 //! or [mip_block_height] unless the format explicitly specifies a block height.
-use crate::{surface::BlockDim, BlockHeight};
+//!
+//! Only the functions for the enabled `surface`, `mip`, and `heuristics` features are included.
+#[cfg(any(feature = "mip", feature = "heuristics"))]
+use crate::BlockHeight;
+#[cfg(feature = "surface")]
+use crate::{
+    surface::{BlockDim, SurfaceParams},
+    BlockHeightHeuristic, SwizzleError,
+};
+
+// Slices can't be created from null pointers even if the length is 0.
+#[cfg(feature = "mip")]
+unsafe fn slice_from_raw_parts<'a>(data: *const u8, len: usize) -> &'a [u8] {
+    if data.is_null() || len == 0 {
+        &[]
+    } else {
+        core::slice::from_raw_parts(data, len)
+    }
+}
+
+#[cfg(feature = "mip")]
+unsafe fn slice_from_raw_parts_mut<'a>(data: *mut u8, len: usize) -> &'a mut [u8] {
+    if data.is_null() || len == 0 {
+        &mut []
+    } else {
+        core::slice::from_raw_parts_mut(data, len)
+    }
+}
+
+// Plain integers are easier to pass from other languages than a struct with NonZeroU32 fields.
+#[cfg(feature = "surface")]
+fn block_dim(block_width_px: u32, block_height_px: u32, block_depth_px: u32) -> BlockDim {
+    BlockDim::new(block_width_px, block_height_px, block_depth_px).unwrap_or_else(|| {
+        panic!(
+            "Invalid block dimensions {}x{}x{}",
+            block_width_px, block_height_px, block_depth_px
+        )
+    })
+}
 
-/// See [crate::surface::swizzle_surface].
+/// See [SurfaceParams::swizzle].
 ///
 /// # Safety
 /// `source` and `source_len` should refer to an array with at least as many bytes as the result of [deswizzled_surface_size].
@@ -18,6 +70,7 @@ use crate::{surface::BlockDim, BlockHeight};
 /// All the fields of `block_dim` must be non zero.
 ///
 /// `block_height` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "surface")]
 #[no_mangle]
 pub unsafe extern "C" fn swizzle_surface(
     width: u32,
@@ -33,25 +86,28 @@ pub unsafe extern "C" fn swizzle_surface(
     mipmap_count: u32,
     array_count: u32,
 ) {
-    let source = std::slice::from_raw_parts(source, source_len);
-    let mut destination = std::slice::from_raw_parts_mut(destination, destination_len);
+    let source = slice_from_raw_parts(source, source_len);
+    let destination = slice_from_raw_parts_mut(destination, destination_len);
 
-    crate::surface::swizzle_surface_inner::<false>(
+    let params = SurfaceParams {
         width,
         height,
         depth,
-        source,
-        &mut destination,
         block_dim,
-        Some(BlockHeight::new(block_height_mip0).unwrap()),
+        block_height_mip0: Some(BlockHeight::new(block_height_mip0).unwrap()),
+        block_height_heuristic: BlockHeightHeuristic::Trm,
         bytes_per_pixel,
         mipmap_count,
-        array_count,
-    )
-    .unwrap();
+        layer_count: array_count,
+        packed_mip_tail: false,
+        mip_block_heights: None,
+        layer_alignment: None,
+        max_surface_size: None,
+    };
+    params.swizzle_into(source, destination).unwrap();
 }
 
-/// See [crate::surface::deswizzle_surface].
+/// See [SurfaceParams::deswizzle].
 ///
 /// # Safety
 /// `source` and `source_len` should refer to an array with at least as many bytes as the result of [swizzled_surface_size].
@@ -60,6 +116,7 @@ pub unsafe extern "C" fn swizzle_surface(
 /// All the fields of `block_dim` must be non zero.
 ///
 /// `block_height` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "surface")]
 #[no_mangle]
 pub unsafe extern "C" fn deswizzle_surface(
     width: u32,
@@ -75,75 +132,790 @@ pub unsafe extern "C" fn deswizzle_surface(
     mipmap_count: u32,
     array_count: u32,
 ) {
-    let source = std::slice::from_raw_parts(source, source_len);
-    let mut destination = std::slice::from_raw_parts_mut(destination, destination_len);
+    let source = slice_from_raw_parts(source, source_len);
+    let destination = slice_from_raw_parts_mut(destination, destination_len);
 
-    crate::surface::swizzle_surface_inner::<true>(
+    let params = SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0: Some(BlockHeight::new(block_height_mip0).unwrap()),
+        block_height_heuristic: BlockHeightHeuristic::Trm,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count: array_count,
+        packed_mip_tail: false,
+        mip_block_heights: None,
+        layer_alignment: None,
+        max_surface_size: None,
+    };
+    params.deswizzle_into(source, destination).unwrap();
+}
+
+/// See [SurfaceParams::swizzled_size].
+///
+/// # Safety
+/// All the fields of `block_dim` must be non zero.
+/// `block_height_mip0` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn swizzled_surface_size(
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_dim: BlockDim,
+    block_height_mip0: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+) -> usize {
+    SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0: Some(BlockHeight::new(block_height_mip0).unwrap()),
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count: array_count,
+        ..Default::default()
+    }
+    .swizzled_size()
+}
+
+/// See [SurfaceParams::deswizzled_size].
+///
+/// # Safety
+/// All the fields of `block_dim` must be non zero.
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn deswizzled_surface_size(
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_dim: BlockDim,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+) -> usize {
+    SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count: array_count,
+        ..Default::default()
+    }
+    .deswizzled_size()
+}
+
+/// See [crate::surface::layer_stride_tiled].
+///
+/// # Safety
+/// All the fields of `block_dim` must be non zero.
+/// `block_height_mip0` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn layer_stride_tiled(
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_dim: BlockDim,
+    block_height_mip0: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+) -> usize {
+    crate::surface::layer_stride_tiled(&SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0: Some(BlockHeight::new(block_height_mip0).unwrap()),
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count: array_count,
+        ..Default::default()
+    })
+}
+
+/// See [crate::surface::layer_stride_linear].
+///
+/// # Safety
+/// All the fields of `block_dim` must be non zero.
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn layer_stride_linear(
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_dim: BlockDim,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+) -> usize {
+    crate::surface::layer_stride_linear(&SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count: array_count,
+        ..Default::default()
+    })
+}
+
+/// The same as [swizzle_surface] but with the block dimensions in pixels as separate values.
+///
+/// # Safety
+/// `source` and `source_len` should refer to an array with at least as many bytes as the result of [deswizzled_surface_size_with_block_dims].
+/// Similarly, `destination` and `destination_len` should refer to an array with at least as many bytes as as the result of [swizzled_surface_size_with_block_dims].
+///
+/// `block_width_px`, `block_height_px`, and `block_depth_px` must be between 1 and 16.
+///
+/// `block_height` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn swizzle_surface_with_block_dims(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: *const u8,
+    source_len: usize,
+    destination: *mut u8,
+    destination_len: usize,
+    block_width_px: u32,
+    block_height_px: u32,
+    block_depth_px: u32,
+    block_height_mip0: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+) {
+    swizzle_surface(
+        width,
+        height,
+        depth,
+        source,
+        source_len,
+        destination,
+        destination_len,
+        block_dim(block_width_px, block_height_px, block_depth_px),
+        block_height_mip0,
+        bytes_per_pixel,
+        mipmap_count,
+        array_count,
+    )
+}
+
+/// The same as [deswizzle_surface] but with the block dimensions in pixels as separate values.
+///
+/// # Safety
+/// `source` and `source_len` should refer to an array with at least as many bytes as the result of [swizzled_surface_size_with_block_dims].
+/// Similarly, `destination` and `destination_len` should refer to an array with at least as many bytes as as the result of [deswizzled_surface_size_with_block_dims].
+///
+/// `block_width_px`, `block_height_px`, and `block_depth_px` must be between 1 and 16.
+///
+/// `block_height` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn deswizzle_surface_with_block_dims(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: *const u8,
+    source_len: usize,
+    destination: *mut u8,
+    destination_len: usize,
+    block_width_px: u32,
+    block_height_px: u32,
+    block_depth_px: u32,
+    block_height_mip0: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+) {
+    deswizzle_surface(
         width,
         height,
         depth,
         source,
-        &mut destination,
+        source_len,
+        destination,
+        destination_len,
+        block_dim(block_width_px, block_height_px, block_depth_px),
+        block_height_mip0,
+        bytes_per_pixel,
+        mipmap_count,
+        array_count,
+    )
+}
+
+/// The same as [swizzled_surface_size] but with the block dimensions in pixels as separate values.
+///
+/// # Safety
+/// `block_width_px`, `block_height_px`, and `block_depth_px` must be between 1 and 16.
+/// `block_height_mip0` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn swizzled_surface_size_with_block_dims(
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_width_px: u32,
+    block_height_px: u32,
+    block_depth_px: u32,
+    block_height_mip0: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+) -> usize {
+    swizzled_surface_size(
+        width,
+        height,
+        depth,
+        block_dim(block_width_px, block_height_px, block_depth_px),
+        block_height_mip0,
+        bytes_per_pixel,
+        mipmap_count,
+        array_count,
+    )
+}
+
+/// The same as [deswizzled_surface_size] but with the block dimensions in pixels as separate values.
+///
+/// # Safety
+/// `block_width_px`, `block_height_px`, and `block_depth_px` must be between 1 and 16.
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn deswizzled_surface_size_with_block_dims(
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_width_px: u32,
+    block_height_px: u32,
+    block_depth_px: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+) -> usize {
+    deswizzled_surface_size(
+        width,
+        height,
+        depth,
+        block_dim(block_width_px, block_height_px, block_depth_px),
+        bytes_per_pixel,
+        mipmap_count,
+        array_count,
+    )
+}
+
+/// The result of the fallible C API functions like [try_swizzle_surface].
+///
+/// Each error corresponds to a variant of [SwizzleError].
+#[cfg(feature = "surface")]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwizzleStatus {
+    Success = 0,
+    NotEnoughData = 1,
+    InvalidSurface = 2,
+    NotEnoughBlockHeights = 3,
+    InvalidRegion = 4,
+    InvalidRowPitch = 5,
+    InvalidBlockDim = 6,
+    InvalidLayerRange = 7,
+    NotEnoughMipData = 8,
+    Cancelled = 9,
+    VerificationFailed = 10,
+    /// The block height is not one of the supported values in [BlockHeight].
+    InvalidBlockHeight = 11,
+    Unsupported = 12,
+    InvalidBitsPerPixel = 13,
+    NotEnoughMipSizes = 14,
+    /// An internal consistency check failed. This indicates a bug in tegra_swizzle.
+    Internal = 15,
+    InvalidElementSize = 16,
+    AllocationTooLarge = 17,
+    InvalidGobOffsets = 18,
+    InvalidChromaOffset = 19,
+    InvalidCoordinate = 20,
+}
+
+#[cfg(feature = "surface")]
+impl From<&SwizzleError> for SwizzleStatus {
+    fn from(e: &SwizzleError) -> Self {
+        match e {
+            SwizzleError::NotEnoughData { .. } => Self::NotEnoughData,
+            SwizzleError::InvalidSurface { .. } => Self::InvalidSurface,
+            SwizzleError::NotEnoughBlockHeights { .. } => Self::NotEnoughBlockHeights,
+            SwizzleError::InvalidRegion { .. } => Self::InvalidRegion,
+            SwizzleError::InvalidRowPitch { .. } => Self::InvalidRowPitch,
+            SwizzleError::InvalidBlockDim { .. } => Self::InvalidBlockDim,
+            SwizzleError::InvalidLayerRange { .. } => Self::InvalidLayerRange,
+            SwizzleError::NotEnoughMipData { .. } => Self::NotEnoughMipData,
+            SwizzleError::Cancelled => Self::Cancelled,
+            SwizzleError::VerificationFailed { .. } => Self::VerificationFailed,
+            SwizzleError::Unsupported { .. } => Self::Unsupported,
+            SwizzleError::InvalidBitsPerPixel { .. } => Self::InvalidBitsPerPixel,
+            SwizzleError::NotEnoughMipSizes { .. } => Self::NotEnoughMipSizes,
+            SwizzleError::Internal { .. } => Self::Internal,
+            SwizzleError::InvalidElementSize { .. } => Self::InvalidElementSize,
+            SwizzleError::AllocationTooLarge { .. } => Self::AllocationTooLarge,
+            SwizzleError::InvalidGobOffsets { .. } => Self::InvalidGobOffsets,
+            SwizzleError::InvalidChromaOffset { .. } => Self::InvalidChromaOffset,
+            SwizzleError::InvalidCoordinate { .. } => Self::InvalidCoordinate,
+        }
+    }
+}
+
+/// Additional details for the result of the fallible C API functions like [try_swizzle_surface_with_info].
+///
+/// Fields that do not apply to the error are set to 0.
+/// - [SwizzleStatus::NotEnoughData] sets `expected` and `actual` to the sizes in bytes.
+/// - [SwizzleStatus::NotEnoughMipData] sets `layer` and `mip` to the first array layer and mipmap
+///   without enough data and `expected` and `actual` to the sizes in bytes.
+/// - [SwizzleStatus::NotEnoughBlockHeights] and [SwizzleStatus::NotEnoughMipSizes]
+///   set `expected` and `actual` to the number of block heights or mipmap sizes.
+/// - [SwizzleStatus::InvalidRowPitch] sets `expected` to the minimum row pitch and `actual` to the row pitch.
+/// - [SwizzleStatus::AllocationTooLarge] sets `expected` to the maximum size and `actual` to the output size.
+#[cfg(feature = "surface")]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwizzleErrorInfo {
+    pub code: SwizzleStatus,
+    pub layer: u32,
+    pub mip: u32,
+    pub expected: usize,
+    pub actual: usize,
+}
+
+#[cfg(feature = "surface")]
+impl SwizzleErrorInfo {
+    fn new(code: SwizzleStatus) -> Self {
+        Self {
+            code,
+            layer: 0,
+            mip: 0,
+            expected: 0,
+            actual: 0,
+        }
+    }
+}
+
+#[cfg(feature = "surface")]
+impl From<&SwizzleError> for SwizzleErrorInfo {
+    fn from(e: &SwizzleError) -> Self {
+        let info = Self::new(SwizzleStatus::from(e));
+        match *e {
+            SwizzleError::NotEnoughData {
+                expected_size,
+                actual_size,
+            } => Self {
+                expected: expected_size,
+                actual: actual_size,
+                ..info
+            },
+            SwizzleError::NotEnoughMipData {
+                layer,
+                mip,
+                expected_size,
+                actual_size,
+            } => Self {
+                layer,
+                mip,
+                expected: expected_size,
+                actual: actual_size,
+                ..info
+            },
+            SwizzleError::NotEnoughBlockHeights {
+                expected_count,
+                actual_count,
+            }
+            | SwizzleError::NotEnoughMipSizes {
+                expected_count,
+                actual_count,
+            } => Self {
+                expected: expected_count as usize,
+                actual: actual_count,
+                ..info
+            },
+            SwizzleError::InvalidRowPitch {
+                row_pitch,
+                min_row_pitch,
+            } => Self {
+                expected: min_row_pitch,
+                actual: row_pitch,
+                ..info
+            },
+            SwizzleError::AllocationTooLarge { size, max_size } => Self {
+                expected: max_size,
+                actual: size,
+                ..info
+            },
+            _ => info,
+        }
+    }
+}
+
+/// The same as [swizzle_surface] but returns a status code instead of panicking for invalid inputs.
+///
+/// All inputs are checked before writing to `destination`,
+/// so `destination` is not modified if an error occurs.
+/// Set `zero_destination_on_error` to fill all `destination_len` bytes of `destination` with zeros on error instead.
+///
+/// # Safety
+/// `source` and `source_len` should refer to a valid array of `source_len` bytes.
+/// Similarly, `destination` and `destination_len` should refer to a valid array of `destination_len` bytes.
+///
+/// All the fields of `block_dim` must be non zero.
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn try_swizzle_surface(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: *const u8,
+    source_len: usize,
+    destination: *mut u8,
+    destination_len: usize,
+    block_dim: BlockDim,
+    block_height_mip0: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+    zero_destination_on_error: bool,
+) -> SwizzleStatus {
+    let source = slice_from_raw_parts(source, source_len);
+    let destination = slice_from_raw_parts_mut(destination, destination_len);
+
+    try_swizzle_surface_inner::<false>(
+        width,
+        height,
+        depth,
+        source,
+        destination,
         block_dim,
-        Some(BlockHeight::new(block_height_mip0).unwrap()),
+        block_height_mip0,
         bytes_per_pixel,
         mipmap_count,
         array_count,
+        zero_destination_on_error,
     )
-    .unwrap();
+    .code
+}
+
+/// The same as [deswizzle_surface] but returns a status code instead of panicking for invalid inputs.
+///
+/// All inputs are checked before writing to `destination`,
+/// so `destination` is not modified if an error occurs.
+/// Set `zero_destination_on_error` to fill all `destination_len` bytes of `destination` with zeros on error instead.
+///
+/// # Safety
+/// `source` and `source_len` should refer to a valid array of `source_len` bytes.
+/// Similarly, `destination` and `destination_len` should refer to a valid array of `destination_len` bytes.
+///
+/// All the fields of `block_dim` must be non zero.
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn try_deswizzle_surface(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: *const u8,
+    source_len: usize,
+    destination: *mut u8,
+    destination_len: usize,
+    block_dim: BlockDim,
+    block_height_mip0: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+    zero_destination_on_error: bool,
+) -> SwizzleStatus {
+    let source = slice_from_raw_parts(source, source_len);
+    let destination = slice_from_raw_parts_mut(destination, destination_len);
+
+    try_swizzle_surface_inner::<true>(
+        width,
+        height,
+        depth,
+        source,
+        destination,
+        block_dim,
+        block_height_mip0,
+        bytes_per_pixel,
+        mipmap_count,
+        array_count,
+        zero_destination_on_error,
+    )
+    .code
+}
+
+/// The same as [try_swizzle_surface] but also writes the details of the result to `error_info`.
+///
+/// `error_info` is written for both successful and failed calls.
+///
+/// # Safety
+/// `source` and `source_len` should refer to a valid array of `source_len` bytes.
+/// Similarly, `destination` and `destination_len` should refer to a valid array of `destination_len` bytes.
+///
+/// All the fields of `block_dim` must be non zero.
+/// `error_info` may be null or should point to a valid [SwizzleErrorInfo].
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn try_swizzle_surface_with_info(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: *const u8,
+    source_len: usize,
+    destination: *mut u8,
+    destination_len: usize,
+    block_dim: BlockDim,
+    block_height_mip0: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+    zero_destination_on_error: bool,
+    error_info: *mut SwizzleErrorInfo,
+) -> SwizzleStatus {
+    let source = slice_from_raw_parts(source, source_len);
+    let destination = slice_from_raw_parts_mut(destination, destination_len);
+
+    let info = try_swizzle_surface_inner::<false>(
+        width,
+        height,
+        depth,
+        source,
+        destination,
+        block_dim,
+        block_height_mip0,
+        bytes_per_pixel,
+        mipmap_count,
+        array_count,
+        zero_destination_on_error,
+    );
+    if !error_info.is_null() {
+        *error_info = info;
+    }
+    info.code
+}
+
+/// The same as [try_deswizzle_surface] but also writes the details of the result to `error_info`.
+///
+/// `error_info` is written for both successful and failed calls.
+///
+/// # Safety
+/// `source` and `source_len` should refer to a valid array of `source_len` bytes.
+/// Similarly, `destination` and `destination_len` should refer to a valid array of `destination_len` bytes.
+///
+/// All the fields of `block_dim` must be non zero.
+/// `error_info` may be null or should point to a valid [SwizzleErrorInfo].
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn try_deswizzle_surface_with_info(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: *const u8,
+    source_len: usize,
+    destination: *mut u8,
+    destination_len: usize,
+    block_dim: BlockDim,
+    block_height_mip0: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+    zero_destination_on_error: bool,
+    error_info: *mut SwizzleErrorInfo,
+) -> SwizzleStatus {
+    let source = slice_from_raw_parts(source, source_len);
+    let destination = slice_from_raw_parts_mut(destination, destination_len);
+
+    let info = try_swizzle_surface_inner::<true>(
+        width,
+        height,
+        depth,
+        source,
+        destination,
+        block_dim,
+        block_height_mip0,
+        bytes_per_pixel,
+        mipmap_count,
+        array_count,
+        zero_destination_on_error,
+    );
+    if !error_info.is_null() {
+        *error_info = info;
+    }
+    info.code
+}
+
+/// The same as [swizzle_surface] but checks all inputs and returns a status code instead of panicking.
+///
+/// The block dimensions are passed as integers, so there are no requirements on the parameters.
+/// The dimensions and buffer lengths are checked against [swizzled_surface_size_with_block_dims]
+/// and [deswizzled_surface_size_with_block_dims] before writing to `destination`,
+/// so `destination` is not modified if an error occurs.
+/// Block dimensions of 0 return [SwizzleStatus::InvalidBlockDim].
+///
+/// This avoids repeating the size checks in bindings.
+/// Use [swizzle_surface] to skip the status code if the inputs are already known to be valid.
+///
+/// # Safety
+/// `source` and `source_len` should refer to a valid array of `source_len` bytes.
+/// Similarly, `destination` and `destination_len` should refer to a valid array of `destination_len` bytes.
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn swizzle_surface_checked(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: *const u8,
+    source_len: usize,
+    destination: *mut u8,
+    destination_len: usize,
+    block_width_px: u32,
+    block_height_px: u32,
+    block_depth_px: u32,
+    block_height_mip0: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    array_count: u32,
+) -> SwizzleStatus {
+    let source = slice_from_raw_parts(source, source_len);
+    let destination = slice_from_raw_parts_mut(destination, destination_len);
+
+    match BlockDim::new(block_width_px, block_height_px, block_depth_px) {
+        Some(block_dim) => {
+            try_swizzle_surface_inner::<false>(
+                width,
+                height,
+                depth,
+                source,
+                destination,
+                block_dim,
+                block_height_mip0,
+                bytes_per_pixel,
+                mipmap_count,
+                array_count,
+                false,
+            )
+            .code
+        }
+        None => SwizzleStatus::InvalidBlockDim,
+    }
 }
 
-/// See [crate::surface::swizzle_surface].
+/// The same as [deswizzle_surface] but checks all inputs and returns a status code instead of panicking.
+///
+/// The block dimensions are passed as integers, so there are no requirements on the parameters.
+/// The dimensions and buffer lengths are checked against [swizzled_surface_size_with_block_dims]
+/// and [deswizzled_surface_size_with_block_dims] before writing to `destination`,
+/// so `destination` is not modified if an error occurs.
+/// Block dimensions of 0 return [SwizzleStatus::InvalidBlockDim].
+///
+/// This avoids repeating the size checks in bindings.
+/// Use [deswizzle_surface] to skip the status code if the inputs are already known to be valid.
 ///
 /// # Safety
-/// All the fields of `block_dim` must be non zero.
-/// `block_height_mip0` must be one of the supported values in [BlockHeight].
+/// `source` and `source_len` should refer to a valid array of `source_len` bytes.
+/// Similarly, `destination` and `destination_len` should refer to a valid array of `destination_len` bytes.
+#[cfg(feature = "surface")]
 #[no_mangle]
-pub unsafe extern "C" fn swizzled_surface_size(
+pub unsafe extern "C" fn deswizzle_surface_checked(
     width: u32,
     height: u32,
     depth: u32,
-    block_dim: BlockDim,
+    source: *const u8,
+    source_len: usize,
+    destination: *mut u8,
+    destination_len: usize,
+    block_width_px: u32,
+    block_height_px: u32,
+    block_depth_px: u32,
     block_height_mip0: u32,
     bytes_per_pixel: u32,
     mipmap_count: u32,
     array_count: u32,
-) -> usize {
-    crate::surface::swizzled_surface_size(
-        width,
-        height,
-        depth,
-        block_dim,
-        Some(BlockHeight::new(block_height_mip0).unwrap()),
-        bytes_per_pixel,
-        mipmap_count,
-        array_count,
-    )
+) -> SwizzleStatus {
+    let source = slice_from_raw_parts(source, source_len);
+    let destination = slice_from_raw_parts_mut(destination, destination_len);
+
+    match BlockDim::new(block_width_px, block_height_px, block_depth_px) {
+        Some(block_dim) => {
+            try_swizzle_surface_inner::<true>(
+                width,
+                height,
+                depth,
+                source,
+                destination,
+                block_dim,
+                block_height_mip0,
+                bytes_per_pixel,
+                mipmap_count,
+                array_count,
+                false,
+            )
+            .code
+        }
+        None => SwizzleStatus::InvalidBlockDim,
+    }
 }
 
-/// See [crate::surface::swizzle_surface].
-///
-/// # Safety
-/// All the fields of `block_dim` must be non zero.
-#[no_mangle]
-pub unsafe extern "C" fn deswizzled_surface_size(
+#[cfg(feature = "surface")]
+fn try_swizzle_surface_inner<const DESWIZZLE: bool>(
     width: u32,
     height: u32,
     depth: u32,
+    source: &[u8],
+    destination: &mut [u8],
     block_dim: BlockDim,
+    block_height_mip0: u32,
     bytes_per_pixel: u32,
     mipmap_count: u32,
     array_count: u32,
-) -> usize {
-    crate::surface::deswizzled_surface_size(
-        width,
-        height,
-        depth,
-        block_dim,
-        bytes_per_pixel,
-        mipmap_count,
-        array_count,
-    )
+    zero_destination_on_error: bool,
+) -> SwizzleErrorInfo {
+    let info = match BlockHeight::new(block_height_mip0) {
+        Some(block_height_mip0) => {
+            let params = SurfaceParams {
+                width,
+                height,
+                depth,
+                block_dim,
+                block_height_mip0: Some(block_height_mip0),
+                block_height_heuristic: BlockHeightHeuristic::Trm,
+                bytes_per_pixel,
+                mipmap_count,
+                layer_count: array_count,
+                packed_mip_tail: false,
+                mip_block_heights: None,
+                layer_alignment: None,
+                max_surface_size: None,
+            };
+            let result = if DESWIZZLE {
+                params.deswizzle_into(source, destination)
+            } else {
+                params.swizzle_into(source, destination)
+            };
+            match result {
+                Ok(()) => SwizzleErrorInfo::new(SwizzleStatus::Success),
+                Err(e) => SwizzleErrorInfo::from(&e),
+            }
+        }
+        None => SwizzleErrorInfo::new(SwizzleStatus::InvalidBlockHeight),
+    };
+
+    if info.code != SwizzleStatus::Success && zero_destination_on_error {
+        destination.fill(0);
+    }
+    info
 }
 
 /// See [crate::swizzle::swizzle_block_linear].
@@ -153,6 +925,7 @@ pub unsafe extern "C" fn deswizzled_surface_size(
 /// Similarly, `destination` and `destination_len` should refer to an array with at least as many bytes as as the result of [swizzled_mip_size].
 ///
 /// `block_height` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "mip")]
 #[no_mangle]
 pub unsafe extern "C" fn swizzle_block_linear(
     width: u32,
@@ -165,19 +938,19 @@ pub unsafe extern "C" fn swizzle_block_linear(
     block_height: u32,
     bytes_per_pixel: u32,
 ) {
-    let source = std::slice::from_raw_parts(source, source_len);
-    let destination = std::slice::from_raw_parts_mut(destination, destination_len);
+    let source = slice_from_raw_parts(source, source_len);
+    let destination = slice_from_raw_parts_mut(destination, destination_len);
 
-    crate::swizzle::swizzle_inner::<false>(
+    crate::swizzle::swizzle_block_linear_into(
         width,
         height,
         depth,
         source,
         destination,
         BlockHeight::new(block_height).unwrap(),
-        depth,
         bytes_per_pixel,
     )
+    .unwrap();
 }
 
 /// See [crate::swizzle::deswizzle_block_linear].
@@ -187,6 +960,7 @@ pub unsafe extern "C" fn swizzle_block_linear(
 /// Similarly, `destination` and `destination_len` should refer to an array with at least as many bytes as as the result of [deswizzled_mip_size].
 ///
 /// `block_height` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "mip")]
 #[no_mangle]
 pub unsafe extern "C" fn deswizzle_block_linear(
     width: u32,
@@ -199,25 +973,26 @@ pub unsafe extern "C" fn deswizzle_block_linear(
     block_height: u32,
     bytes_per_pixel: u32,
 ) {
-    let source = std::slice::from_raw_parts(source, source_len);
-    let destination = std::slice::from_raw_parts_mut(destination, destination_len);
+    let source = slice_from_raw_parts(source, source_len);
+    let destination = slice_from_raw_parts_mut(destination, destination_len);
 
-    crate::swizzle::swizzle_inner::<true>(
+    crate::swizzle::deswizzle_block_linear_into(
         width,
         height,
         depth,
         source,
         destination,
         BlockHeight::new(block_height).unwrap(),
-        depth,
         bytes_per_pixel,
     )
+    .unwrap();
 }
 
 /// See [crate::swizzle::swizzled_mip_size].
 ///
 /// # Safety
 /// `block_height` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "mip")]
 #[no_mangle]
 pub unsafe extern "C" fn swizzled_mip_size(
     width: u32,
@@ -236,6 +1011,7 @@ pub unsafe extern "C" fn swizzled_mip_size(
 }
 
 /// See [crate::swizzle::deswizzled_mip_size].
+#[cfg(feature = "mip")]
 #[no_mangle]
 pub extern "C" fn deswizzled_mip_size(
     width: u32,
@@ -247,6 +1023,7 @@ pub extern "C" fn deswizzled_mip_size(
 }
 
 /// See [crate::block_height_mip0].
+#[cfg(feature = "heuristics")]
 #[no_mangle]
 pub extern "C" fn block_height_mip0(height: u32) -> u32 {
     super::block_height_mip0(height) as u32
@@ -256,21 +1033,62 @@ pub extern "C" fn block_height_mip0(height: u32) -> u32 {
 ///
 /// # Safety
 /// `block_height_mip0` must be one of the supported values in [BlockHeight].
+#[cfg(feature = "heuristics")]
 #[no_mangle]
 pub unsafe extern "C" fn mip_block_height(mip_height: u32, block_height_mip0: u32) -> u32 {
     super::mip_block_height(mip_height, BlockHeight::new(block_height_mip0).unwrap()) as u32
 }
 
-#[cfg(test)]
+/// See [crate::analysis::block_height_candidates].
+///
+/// Writes up to `cap` candidate block heights to `out_candidates`
+/// and returns the total number of candidates.
+/// The first candidate is the most likely block height.
+/// A result larger than `cap` means not all candidates were written.
+///
+/// # Safety
+/// `out_candidates` and `cap` should refer to an array with at least `cap` elements.
+/// A capacity of 6 is enough for all possible block heights.
+#[cfg(feature = "surface")]
+#[no_mangle]
+pub unsafe extern "C" fn block_height_guess(
+    width: u32,
+    height: u32,
+    bytes_per_pixel: u32,
+    data_len: usize,
+    out_candidates: *mut u32,
+    cap: usize,
+) -> usize {
+    let out_candidates = if out_candidates.is_null() || cap == 0 {
+        &mut []
+    } else {
+        core::slice::from_raw_parts_mut(out_candidates, cap)
+    };
+
+    let mut count = 0;
+    for block_height in
+        crate::analysis::block_height_candidates(width, height, bytes_per_pixel, data_len)
+    {
+        if let Some(out) = out_candidates.get_mut(count) {
+            *out = block_height as u32;
+        }
+        count += 1;
+    }
+    count
+}
+
+#[cfg(all(test, feature = "surface", not(feature = "minimal")))]
 mod tests {
     use super::*;
 
-    use alloc::vec;
+    use crate::fixtures::read_fixture;
+    use alloc::{vec, vec::Vec};
 
     #[test]
+    #[cfg_attr(miri, ignore)]
     fn swizzle_surface_rgba_16_16_16() {
-        let input = include_bytes!("../block_linear/16_16_16_rgba.bin");
-        let expected = include_bytes!("../block_linear/16_16_16_rgba_tiled.bin");
+        let input = &read_fixture("16_16_16_rgba.bin");
+        let expected = &read_fixture("16_16_16_rgba_tiled.bin");
 
         let block_height = block_height_mip0(16);
         let size =
@@ -296,9 +1114,10 @@ mod tests {
     }
 
     #[test]
+    #[cfg_attr(miri, ignore)]
     fn deswizzle_surface_rgba_16_16_16() {
-        let input = include_bytes!("../block_linear/16_16_16_rgba_tiled.bin");
-        let expected = include_bytes!("../block_linear/16_16_16_rgba.bin");
+        let input = &read_fixture("16_16_16_rgba_tiled.bin");
+        let expected = &read_fixture("16_16_16_rgba.bin");
 
         let block_height = block_height_mip0(16);
         let size = unsafe {
@@ -325,9 +1144,10 @@ mod tests {
     }
 
     #[test]
+    #[cfg_attr(miri, ignore)]
     fn swizzle_rgba_16_16_16() {
-        let input = include_bytes!("../block_linear/16_16_16_rgba.bin");
-        let expected = include_bytes!("../block_linear/16_16_16_rgba_tiled.bin");
+        let input = &read_fixture("16_16_16_rgba.bin");
+        let expected = &read_fixture("16_16_16_rgba_tiled.bin");
 
         let size = unsafe { swizzled_mip_size(16, 16, 16, 1, 4) };
         let mut actual = vec![0u8; size];
@@ -349,9 +1169,10 @@ mod tests {
     }
 
     #[test]
+    #[cfg_attr(miri, ignore)]
     fn deswizzle_rgba_16_16_16() {
-        let input = include_bytes!("../block_linear/16_16_16_rgba_tiled.bin");
-        let expected = include_bytes!("../block_linear/16_16_16_rgba.bin");
+        let input = &read_fixture("16_16_16_rgba_tiled.bin");
+        let expected = &read_fixture("16_16_16_rgba.bin");
 
         let size = deswizzled_mip_size(16, 16, 16, 4);
         let mut actual = vec![0u8; size];
@@ -372,10 +1193,460 @@ mod tests {
         assert_eq!(expected, &actual[..]);
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn deswizzle_rgba_33_33_33() {
+        let input = &read_fixture("33_33_33_rgba_tiled.bin");
+        let expected = &read_fixture("33_33_33_rgba.bin");
+
+        let size = deswizzled_mip_size(33, 33, 33, 4);
+        let mut actual = vec![0u8; size];
+        unsafe {
+            deswizzle_block_linear(
+                33,
+                33,
+                33,
+                input.as_ptr(),
+                input.len(),
+                actual.as_mut_ptr(),
+                actual.len(),
+                1,
+                4,
+            );
+        }
+
+        assert_eq!(expected, &actual[..]);
+    }
+
     #[test]
     fn mip_block_height_bcn() {
         assert_eq!(4, unsafe {
             mip_block_height(128 / 4, block_height_mip0(128 / 4))
         });
     }
+
+    #[test]
+    fn swizzle_surface_empty_null() {
+        unsafe {
+            swizzle_surface(
+                0,
+                16,
+                1,
+                core::ptr::null(),
+                0,
+                core::ptr::null_mut(),
+                0,
+                BlockDim::uncompressed(),
+                1,
+                4,
+                1,
+                1,
+            );
+        }
+    }
+
+    #[test]
+    fn block_linear_empty_null() {
+        for (width, height, depth, bytes_per_pixel) in
+            [(0, 16, 1, 4), (16, 0, 1, 4), (16, 16, 0, 4), (16, 16, 1, 0)]
+        {
+            assert_eq!(0, unsafe {
+                swizzled_mip_size(width, height, depth, 1, bytes_per_pixel)
+            });
+            assert_eq!(
+                0,
+                deswizzled_mip_size(width, height, depth, bytes_per_pixel)
+            );
+            unsafe {
+                swizzle_block_linear(
+                    width,
+                    height,
+                    depth,
+                    core::ptr::null(),
+                    0,
+                    core::ptr::null_mut(),
+                    0,
+                    1,
+                    bytes_per_pixel,
+                );
+                deswizzle_block_linear(
+                    width,
+                    height,
+                    depth,
+                    core::ptr::null(),
+                    0,
+                    core::ptr::null_mut(),
+                    0,
+                    1,
+                    bytes_per_pixel,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn swizzle_block_linear_clears_padding() {
+        let input = [255u8; 4];
+        let mut actual = vec![1u8; 512];
+        unsafe {
+            swizzle_block_linear(
+                1,
+                1,
+                1,
+                input.as_ptr(),
+                input.len(),
+                actual.as_mut_ptr(),
+                actual.len(),
+                1,
+                4,
+            );
+        }
+        assert_eq!(&[255u8; 4], &actual[..4]);
+        assert!(actual[4..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn surface_with_block_dims_bc7() {
+        let input = &read_fixture("64_bc7.bin");
+        let expected = &read_fixture("64_bc7_tiled.bin");
+
+        let swizzled_size =
+            unsafe { swizzled_surface_size_with_block_dims(64, 64, 1, 4, 4, 1, 2, 16, 1, 1) };
+        let deswizzled_size =
+            unsafe { deswizzled_surface_size_with_block_dims(64, 64, 1, 4, 4, 1, 16, 1, 1) };
+        assert_eq!(
+            unsafe { swizzled_surface_size(64, 64, 1, BlockDim::block_4x4(), 2, 16, 1, 1) },
+            swizzled_size
+        );
+        assert_eq!(input.len(), deswizzled_size);
+
+        let mut tiled = vec![0u8; swizzled_size];
+        unsafe {
+            swizzle_surface_with_block_dims(
+                64,
+                64,
+                1,
+                input.as_ptr(),
+                input.len(),
+                tiled.as_mut_ptr(),
+                tiled.len(),
+                4,
+                4,
+                1,
+                2,
+                16,
+                1,
+                1,
+            );
+        }
+        assert_eq!(expected, &tiled[..]);
+
+        let mut untiled = vec![0u8; deswizzled_size];
+        unsafe {
+            deswizzle_surface_with_block_dims(
+                64,
+                64,
+                1,
+                tiled.as_ptr(),
+                tiled.len(),
+                untiled.as_mut_ptr(),
+                untiled.len(),
+                4,
+                4,
+                1,
+                2,
+                16,
+                1,
+                1,
+            );
+        }
+        assert_eq!(input, &untiled[..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn block_dim_zero() {
+        block_dim(4, 0, 1);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn try_swizzle_surface_rgba_16_16_16() {
+        let input = &read_fixture("16_16_16_rgba.bin");
+        let expected = &read_fixture("16_16_16_rgba_tiled.bin");
+
+        let mut actual = vec![0u8; expected.len()];
+        let status = unsafe {
+            try_swizzle_surface(
+                16,
+                16,
+                16,
+                input.as_ptr(),
+                input.len(),
+                actual.as_mut_ptr(),
+                actual.len(),
+                BlockDim::uncompressed(),
+                1,
+                4,
+                1,
+                1,
+                false,
+            )
+        };
+        assert_eq!(SwizzleStatus::Success, status);
+        assert_eq!(expected, &actual[..]);
+    }
+
+    #[test]
+    fn try_deswizzle_surface_not_enough_data() {
+        let input = [0u8; 16];
+        for (zero_destination_on_error, expected) in [(false, 1u8), (true, 0u8)] {
+            let mut destination = vec![1u8; 64 * 64 * 4];
+            let status = unsafe {
+                try_deswizzle_surface(
+                    64,
+                    64,
+                    1,
+                    input.as_ptr(),
+                    input.len(),
+                    destination.as_mut_ptr(),
+                    destination.len(),
+                    BlockDim::uncompressed(),
+                    8,
+                    4,
+                    1,
+                    1,
+                    zero_destination_on_error,
+                )
+            };
+            assert_eq!(SwizzleStatus::NotEnoughMipData, status);
+            assert!(destination.iter().all(|b| *b == expected));
+        }
+    }
+
+    #[test]
+    fn try_deswizzle_surface_with_info_not_enough_data() {
+        // The second array layer is truncated.
+        let input = [0u8; 64 * 64 * 4 + 16];
+        let mut destination = vec![0u8; 64 * 64 * 4 * 2];
+        let mut error_info = SwizzleErrorInfo::new(SwizzleStatus::Success);
+        let status = unsafe {
+            try_deswizzle_surface_with_info(
+                64,
+                64,
+                1,
+                input.as_ptr(),
+                input.len(),
+                destination.as_mut_ptr(),
+                destination.len(),
+                BlockDim::uncompressed(),
+                8,
+                4,
+                1,
+                2,
+                false,
+                &mut error_info,
+            )
+        };
+        assert_eq!(SwizzleStatus::NotEnoughMipData, status);
+        assert_eq!(
+            SwizzleErrorInfo {
+                code: SwizzleStatus::NotEnoughMipData,
+                layer: 1,
+                mip: 0,
+                expected: 64 * 64 * 4 * 2,
+                actual: 64 * 64 * 4 + 16,
+            },
+            error_info
+        );
+    }
+
+    #[test]
+    fn try_swizzle_surface_with_info_null() {
+        let input = [0u8; 16 * 16 * 4];
+        let mut destination = vec![0u8; 1024];
+        let status = unsafe {
+            try_swizzle_surface_with_info(
+                16,
+                16,
+                1,
+                input.as_ptr(),
+                input.len(),
+                destination.as_mut_ptr(),
+                destination.len(),
+                BlockDim::uncompressed(),
+                2,
+                4,
+                1,
+                1,
+                false,
+                core::ptr::null_mut(),
+            )
+        };
+        assert_eq!(SwizzleStatus::Success, status);
+    }
+
+    #[test]
+    fn try_swizzle_surface_invalid_block_height() {
+        let input = [0u8; 16 * 16 * 4];
+        let mut destination = vec![1u8; 1024];
+        let status = unsafe {
+            try_swizzle_surface(
+                16,
+                16,
+                1,
+                input.as_ptr(),
+                input.len(),
+                destination.as_mut_ptr(),
+                destination.len(),
+                BlockDim::uncompressed(),
+                3,
+                4,
+                1,
+                1,
+                true,
+            )
+        };
+        assert_eq!(SwizzleStatus::InvalidBlockHeight, status);
+        assert_eq!(vec![0u8; 1024], destination);
+    }
+
+    #[test]
+    fn swizzle_deswizzle_surface_checked() {
+        let input: Vec<_> = (0..64 * 64).map(|i| i as u8).collect();
+        let mut tiled = vec![0u8; 4096];
+        let mut untiled = vec![0u8; input.len()];
+        unsafe {
+            assert_eq!(
+                SwizzleStatus::Success,
+                swizzle_surface_checked(
+                    64,
+                    64,
+                    1,
+                    input.as_ptr(),
+                    input.len(),
+                    tiled.as_mut_ptr(),
+                    tiled.len(),
+                    4,
+                    4,
+                    1,
+                    2,
+                    16,
+                    1,
+                    1,
+                )
+            );
+            assert_eq!(
+                SwizzleStatus::Success,
+                deswizzle_surface_checked(
+                    64,
+                    64,
+                    1,
+                    tiled.as_ptr(),
+                    tiled.len(),
+                    untiled.as_mut_ptr(),
+                    untiled.len(),
+                    4,
+                    4,
+                    1,
+                    2,
+                    16,
+                    1,
+                    1,
+                )
+            );
+        }
+        assert_eq!(input, untiled);
+    }
+
+    #[test]
+    fn swizzle_surface_checked_invalid() {
+        let input = [0u8; 16 * 16 * 4];
+        let mut destination = vec![1u8; 1024];
+        let mut swizzle = |block_width_px, block_height_mip0, destination_len| unsafe {
+            swizzle_surface_checked(
+                16,
+                16,
+                1,
+                input.as_ptr(),
+                input.len(),
+                destination.as_mut_ptr(),
+                destination_len,
+                block_width_px,
+                1,
+                1,
+                block_height_mip0,
+                4,
+                1,
+                1,
+            )
+        };
+        assert_eq!(SwizzleStatus::InvalidBlockDim, swizzle(0, 2, 1024));
+        assert_eq!(SwizzleStatus::InvalidBlockHeight, swizzle(1, 3, 1024));
+        assert_eq!(SwizzleStatus::NotEnoughData, swizzle(1, 2, 1023));
+        assert_eq!(vec![1u8; 1024], destination);
+    }
+
+    #[test]
+    fn deswizzle_surface_checked_null() {
+        let mut destination = vec![1u8; 16 * 16 * 4];
+        let status = unsafe {
+            deswizzle_surface_checked(
+                16,
+                16,
+                1,
+                core::ptr::null(),
+                1024,
+                destination.as_mut_ptr(),
+                destination.len(),
+                1,
+                1,
+                1,
+                2,
+                4,
+                1,
+                1,
+            )
+        };
+        assert_eq!(SwizzleStatus::NotEnoughMipData, status);
+        assert_eq!(vec![1u8; 16 * 16 * 4], destination);
+    }
+
+    #[test]
+    fn layer_strides_cube_map() {
+        let tiled = unsafe { layer_stride_tiled(16, 16, 1, BlockDim::block_4x4(), 1, 16, 5, 6) };
+        let linear = unsafe { layer_stride_linear(16, 16, 1, BlockDim::block_4x4(), 16, 5, 6) };
+        assert_eq!(2560, tiled);
+        assert_eq!(368, linear);
+        assert_eq!(
+            unsafe { swizzled_surface_size(16, 16, 1, BlockDim::block_4x4(), 1, 16, 5, 6) },
+            tiled * 6
+        );
+    }
+
+    #[test]
+    fn block_height_guess_candidates() {
+        let mut candidates = [0u32; 6];
+        let count = unsafe {
+            block_height_guess(64, 64, 4, 16384, candidates.as_mut_ptr(), candidates.len())
+        };
+        assert_eq!(4, count);
+        assert_eq!([8, 1, 2, 4, 0, 0], candidates);
+    }
+
+    #[test]
+    fn block_height_guess_truncated() {
+        let mut candidates = [0u32; 1];
+        let count = unsafe {
+            block_height_guess(64, 64, 4, 16384, candidates.as_mut_ptr(), candidates.len())
+        };
+        assert_eq!(4, count);
+        assert_eq!([8], candidates);
+
+        // Only count the candidates.
+        assert_eq!(4, unsafe {
+            block_height_guess(64, 64, 4, 16384, core::ptr::null_mut(), 0)
+        });
+    }
 }
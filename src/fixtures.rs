@@ -0,0 +1,58 @@
+//! Access to the binary fixtures in `block_linear` for tests.
+//!
+//! Fixtures are compressed with zstd to keep the crate download small.
+//! Each file `NAME` is stored as `NAME.zst`.
+//! Miri isolates tests from the file system, so tests reading fixtures use `#[cfg_attr(miri, ignore)]`.
+use alloc::vec::Vec;
+
+use crate::{div_round_up, BlockHeight};
+
+pub(crate) fn fixtures_dir() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("block_linear")
+}
+
+pub(crate) fn read_fixture(name: &str) -> Vec<u8> {
+    let path = fixtures_dir().join(std::format!("{name}.zst"));
+    let compressed = std::fs::read(&path).unwrap_or_else(|e| panic!("{:?}: {}", path, e));
+    zstd::decode_all(&compressed[..]).unwrap()
+}
+
+pub(crate) fn write_fixture(name: &str, data: &[u8]) {
+    let compressed = zstd::encode_all(data, 19).unwrap();
+    std::fs::write(fixtures_dir().join(std::format!("{name}.zst")), compressed).unwrap();
+}
+
+#[derive(serde::Deserialize)]
+struct Manifest {
+    fixtures: Vec<Fixture>,
+}
+
+// The dimensions are in pixels like the values shown in an emulator or graphics debugger.
+#[derive(serde::Deserialize)]
+pub(crate) struct Fixture {
+    pub(crate) name: std::string::String,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) depth: u32,
+    pub(crate) block_dim: [u32; 3],
+    pub(crate) bytes_per_pixel: u32,
+    pub(crate) block_height: u32,
+}
+
+impl Fixture {
+    // The dimensions in blocks and the block height for the block linear functions.
+    pub(crate) fn params(&self) -> (u32, u32, u32, BlockHeight) {
+        (
+            div_round_up(self.width, self.block_dim[0]),
+            div_round_up(self.height, self.block_dim[1]),
+            div_round_up(self.depth, self.block_dim[2]),
+            BlockHeight::new(self.block_height).unwrap(),
+        )
+    }
+}
+
+// The entries in block_linear/fixtures.json.
+pub(crate) fn load_fixtures() -> Vec<Fixture> {
+    let json = std::fs::read_to_string(fixtures_dir().join("fixtures.json")).unwrap();
+    serde_json::from_str::<Manifest>(&json).unwrap().fixtures
+}
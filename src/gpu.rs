@@ -0,0 +1,312 @@
+//! Tiling constants for implementing block linear tiling in GPU compute shaders.
+//!
+//! [TilingUniforms] contains the values derived from [SurfaceParams] for a single mipmap
+//! like the block height, GOB counts, and offsets within the tiled and untiled surface.
+//! Uploading these values instead of calculating them again in the shader
+//! keeps GPU implementations in sync with the CPU implementation and any fixes to tegra_swizzle.
+//!
+//! The struct only contains `u32` fields, so it has the same layout
+//! as a uniform or storage buffer struct with the same fields in WGSL, GLSL, or HLSL.
+/*!
+```wgsl
+struct TilingUniforms {
+    width: u32,
+    height: u32,
+    depth: u32,
+    bytes_per_pixel: u32,
+    block_height: u32,
+    block_depth: u32,
+    width_in_gobs: u32,
+    slice_size: u32,
+    block_size: u32,
+    row_size: u32,
+    tiled_offset: u32,
+    linear_offset: u32,
+    tiled_size: u32,
+    linear_size: u32,
+    tiled_layer_stride: u32,
+    linear_layer_stride: u32,
+}
+```
+*/
+//!
+//! The tiled offset for byte `x` of row `y` in depth slice `z` of array layer `layer`
+//! uses the GOB address and the offset within the 64x8 byte GOB.
+/*!
+```wgsl
+fn tiled_offset(u: TilingUniforms, x: u32, y: u32, z: u32, layer: u32) -> u32 {
+    let block_rows = u.block_height * 8u;
+    let gob_address = (z / u.block_depth) * u.slice_size
+        + (z % u.block_depth) * 512u * u.block_height
+        + (y / block_rows) * u.block_size * u.width_in_gobs
+        + (y % block_rows / 8u) * 512u
+        + (x / 64u) * u.block_size;
+    let gob_offset = (x % 64u / 32u) * 256u + (y % 8u / 2u) * 64u
+        + (x % 32u / 16u) * 32u + (y % 2u) * 16u + x % 16u;
+    return u.tiled_offset + layer * u.tiled_layer_stride + gob_address + gob_offset;
+}
+```
+*/
+//!
+//! The untiled offset is `linear_offset + layer * linear_layer_stride + (z * height + y) * row_size + x`.
+//!
+//! # Examples
+/*!
+```rust
+use tegra_swizzle::gpu::TilingUniforms;
+use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 9,
+    layer_count: 6,
+    ..Default::default()
+};
+
+// Dispatch a compute shader for each mipmap with the uniforms for that mipmap.
+for mip in 0..params.mipmap_count {
+    let uniforms = TilingUniforms::new(&params, mip)?;
+    assert_eq!(uniforms.row_size, uniforms.width * 16);
+}
+# Ok::<(), tegra_swizzle::SwizzleError>(())
+```
+*/
+use core::convert::TryFrom;
+
+use crate::{
+    blockdepth::{block_depth, mip_block_depth},
+    surface::{is_empty, surface_mip_block_height, validate_params, SurfaceLayout, SurfaceParams},
+    swizzle::slice_size,
+    tiling::TegraBlockLinear,
+    width_in_gobs, Result, SwizzleError, GOB_SIZE_IN_BYTES,
+};
+
+/// The derived tiling constants for a single mipmap of a surface.
+/// See the [module level documentation](crate::gpu) for how shaders use each field.
+///
+/// Dimensions are in pixels or blocks for compressed formats.
+/// Sizes and offsets are in bytes.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TilingUniforms {
+    /// The width of the mipmap.
+    pub width: u32,
+    /// The height of the mipmap.
+    pub height: u32,
+    /// The depth of the mipmap.
+    pub depth: u32,
+    /// The size of each pixel or compressed block.
+    pub bytes_per_pixel: u32,
+    /// The height of each block of GOBs in GOBs.
+    pub block_height: u32,
+    /// The depth of each block of GOBs in GOBs.
+    pub block_depth: u32,
+    /// The number of GOBs in each row of blocks.
+    pub width_in_gobs: u32,
+    /// The size of each slice of blocks with `block_depth` many GOBs in depth.
+    pub slice_size: u32,
+    /// The size of each block of GOBs.
+    pub block_size: u32,
+    /// The size of each untiled row.
+    pub row_size: u32,
+    /// The start of the mipmap in the first array layer of the tiled surface.
+    pub tiled_offset: u32,
+    /// The start of the mipmap in the first array layer of the untiled surface.
+    pub linear_offset: u32,
+    /// The tiled size of the mipmap for a single array layer.
+    pub tiled_size: u32,
+    /// The untiled size of the mipmap for a single array layer.
+    pub linear_size: u32,
+    /// The distance between array layers in the tiled surface.
+    pub tiled_layer_stride: u32,
+    /// The distance between array layers in the untiled surface.
+    pub linear_layer_stride: u32,
+}
+
+impl TilingUniforms {
+    /// Calculates the tiling constants for mipmap `mip` of the surface described by `params`.
+    ///
+    /// Returns [SwizzleError::InvalidSurface] if `mip` is not less than [SurfaceParams::mipmap_count]
+    /// or the parameters are invalid.
+    /// Returns [SwizzleError::Unsupported] if the mipmap is part of a packed mip tail
+    /// or the tiled surface is larger than 4 GiB and doesn't fit in 32-bit offsets.
+    pub fn new(params: &SurfaceParams, mip: u32) -> Result<Self> {
+        if is_empty(params) || mip >= params.mipmap_count {
+            return Err(SwizzleError::InvalidSurface {
+                width: params.width,
+                height: params.height,
+                depth: params.depth,
+                bytes_per_pixel: params.bytes_per_pixel,
+                mipmap_count: params.mipmap_count,
+            });
+        }
+
+        validate_params(params)?;
+
+        let layout = SurfaceLayout::new(&TegraBlockLinear, params);
+        let sizes = layout
+            .mips()
+            .get(mip as usize)
+            .ok_or(SwizzleError::Unsupported {
+                feature: "packed mip tails for GPU tiling",
+            })?;
+
+        let level = &sizes.level;
+        let block_height = surface_mip_block_height(params, mip, level.height) as u32;
+        let block_depth = mip_block_depth(level.depth, block_depth(params.depth));
+        let width_in_gobs = width_in_gobs(level.width, params.bytes_per_pixel);
+
+        let to_u32 = |value: usize| {
+            u32::try_from(value).map_err(|_| SwizzleError::Unsupported {
+                feature: "surfaces larger than 4 GiB for GPU tiling",
+            })
+        };
+        // The tiled surface is always at least as large as the untiled surface.
+        to_u32(layout.swizzled_layer_size * params.layer_count as usize)?;
+
+        Ok(Self {
+            width: level.width,
+            height: level.height,
+            depth: level.depth,
+            bytes_per_pixel: params.bytes_per_pixel,
+            block_height,
+            block_depth,
+            width_in_gobs,
+            slice_size: to_u32(slice_size(
+                block_height,
+                block_depth,
+                width_in_gobs,
+                level.height,
+            ))?,
+            block_size: GOB_SIZE_IN_BYTES * block_height * block_depth,
+            row_size: level.width * params.bytes_per_pixel,
+            tiled_offset: to_u32(sizes.swizzled_offset)?,
+            linear_offset: to_u32(sizes.deswizzled_offset)?,
+            tiled_size: to_u32(sizes.swizzled_size)?,
+            linear_size: to_u32(sizes.deswizzled_size)?,
+            tiled_layer_stride: to_u32(layout.swizzled_layer_size)?,
+            linear_layer_stride: to_u32(layout.deswizzled_layer_size)?,
+        })
+    }
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::surface::BlockDim;
+    use alloc::{vec, vec::Vec};
+
+    // The WGSL address calculation from the module documentation.
+    fn tiled_offset(u: &TilingUniforms, x: u32, y: u32, z: u32, layer: u32) -> u32 {
+        let block_rows = u.block_height * 8;
+        let gob_address = (z / u.block_depth) * u.slice_size
+            + (z % u.block_depth) * 512 * u.block_height
+            + (y / block_rows) * u.block_size * u.width_in_gobs
+            + (y % block_rows / 8) * 512
+            + (x / 64) * u.block_size;
+        let gob_offset =
+            (x % 64 / 32) * 256 + (y % 8 / 2) * 64 + (x % 32 / 16) * 32 + (y % 2) * 16 + x % 16;
+        u.tiled_offset + layer * u.tiled_layer_stride + gob_address + gob_offset
+    }
+
+    fn linear_offset(u: &TilingUniforms, x: u32, y: u32, z: u32, layer: u32) -> u32 {
+        u.linear_offset + layer * u.linear_layer_stride + (z * u.height + y) * u.row_size + x
+    }
+
+    fn swizzle_with_uniforms(params: &SurfaceParams, source: &[u8]) -> Vec<u8> {
+        let mut tiled = vec![0u8; params.swizzled_size()];
+        for mip in 0..params.mipmap_count {
+            let u = TilingUniforms::new(params, mip).unwrap();
+            for layer in 0..params.layer_count {
+                for z in 0..u.depth {
+                    for y in 0..u.height {
+                        for x in 0..u.row_size {
+                            let src = linear_offset(&u, x, y, z, layer) as usize;
+                            let dst = tiled_offset(&u, x, y, z, layer) as usize;
+                            tiled[dst] = source[src];
+                        }
+                    }
+                }
+            }
+        }
+        tiled
+    }
+
+    #[test]
+    fn shader_offsets_match_swizzle() {
+        for params in [
+            SurfaceParams {
+                width: 128,
+                height: 96,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 4,
+                layer_count: 6,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 300,
+                height: 200,
+                bytes_per_pixel: 4,
+                mipmap_count: 5,
+                layer_count: 2,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 33,
+                height: 17,
+                depth: 16,
+                bytes_per_pixel: 4,
+                mipmap_count: 3,
+                ..Default::default()
+            },
+        ] {
+            let source: Vec<_> = (0..params.deswizzled_size())
+                .map(|i| (i % 251) as u8)
+                .collect();
+            assert_eq!(
+                params.swizzle(&source).unwrap(),
+                swizzle_with_uniforms(&params, &source)
+            );
+        }
+    }
+
+    #[test]
+    fn uniforms_invalid_mip() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            bytes_per_pixel: 4,
+            mipmap_count: 2,
+            ..Default::default()
+        };
+        assert!(TilingUniforms::new(&params, 1).is_ok());
+        assert!(matches!(
+            TilingUniforms::new(&params, 2),
+            Err(SwizzleError::InvalidSurface { .. })
+        ));
+    }
+
+    #[test]
+    fn uniforms_packed_mip_tail() {
+        let params = SurfaceParams {
+            width: 256,
+            height: 256,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 9,
+            packed_mip_tail: true,
+            ..Default::default()
+        };
+        assert!(TilingUniforms::new(&params, 0).is_ok());
+        assert_eq!(
+            Err(SwizzleError::Unsupported {
+                feature: "packed mip tails for GPU tiling"
+            }),
+            TilingUniforms::new(&params, 8)
+        );
+    }
+}
@@ -4,10 +4,12 @@
 //!
 //! ## Getting Started
 //! Tiled texture data in binary files is often stored in a single buffer containing all arrays and mipmaps.
-//! This memory layout can be untiled all at once using [surface::deswizzle_surface].
+//! This memory layout can be untiled all at once using [surface::SurfaceParams::deswizzle].
+//! The commonly used items can be imported with `use tegra_swizzle::prelude::*;`.
+//! See the [compat] module for migrating from the deprecated functions like `surface::deswizzle_surface`.
 //!
 //! # Block Linear Memory Tiling
-//! The [surface::swizzle_surface] and [surface::deswizzle_surface] functions
+//! The [surface::SurfaceParams::swizzle] and [surface::SurfaceParams::deswizzle] methods
 //! implement safe and efficient tiling and untiling for the Tegra X1's block linear format.
 //!
 //! Block linear arranges bytes of a texture surface into a 2D grid of blocks
@@ -17,26 +19,90 @@
 //!
 //! Groups of 512 bytes form GOBs ("group of bytes") where each GOB is 64x8 bytes.
 //! The `block_height` parameter determines how many GOBs stack vertically to form a block.
+//!
+//! # Deterministic Output
+//! Tiling always sets the padding bytes in the output to zero.
+//! This includes the `_into` and `_to` variants that write to an existing buffer,
+//! so reusing buffers produces byte for byte identical files to allocating a new buffer.
+//! Asset pipelines can rely on this when caching converted files by their contents.
+//!
+//! Functions that copy tiled data without untiling it like [surface::retile],
+//! [surface::resize_surface], and [surface::extract_layers] keep the padding bytes from their source.
+//! See [surface::padded_ranges] for finding the padding in tiled data.
+//!
+//! # Safety
+//! The library does not use any unsafe code outside of the C API in the optional `ffi` module.
+//! Unsafe code is forbidden entirely when the `ffi` feature is disabled.
+//! The tests for the optimized GOB copies and the slices created by the C API
+//! are checked for undefined behavior using [Miri](https://github.com/rust-lang/miri).
 #![no_std]
+#![cfg_attr(not(feature = "ffi"), forbid(unsafe_code))]
+#![cfg_attr(feature = "ffi", deny(unsafe_code))]
+#![allow(clippy::too_many_arguments)]
+#[cfg(not(feature = "minimal"))]
 extern crate alloc;
 
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "surface")]
 mod arrays;
+#[cfg(feature = "mip")]
 mod blockdepth;
+#[cfg(feature = "heuristics")]
 mod blockheight;
+#[cfg(all(test, feature = "surface", not(feature = "minimal")))]
+mod conformance;
+#[cfg(all(test, not(feature = "minimal")))]
+mod fixtures;
+#[cfg(feature = "surface")]
+mod polyfill;
 
+#[cfg(feature = "mip")]
+pub mod addressing;
+#[cfg(feature = "surface")]
+pub mod analysis;
+#[cfg(feature = "surface")]
+pub mod buffer;
+#[cfg(feature = "surface")]
+pub mod compat;
+#[cfg(feature = "surface")]
+pub mod descriptor;
+#[cfg(feature = "dump")]
+pub mod dump;
+#[cfg(feature = "generate")]
+pub mod generate;
+#[cfg(feature = "surface")]
+pub mod gpu;
+#[cfg(feature = "surface")]
+pub mod planar;
+#[cfg(feature = "surface")]
+pub mod prelude;
+#[cfg(feature = "surface")]
+pub mod profile;
+#[cfg(feature = "surface")]
 pub mod surface;
+#[cfg(feature = "mip")]
 pub mod swizzle;
+#[cfg(feature = "surface")]
+pub mod tiling;
+#[cfg(all(feature = "wgpu", not(feature = "minimal")))]
+pub mod wgpu;
 
+// The C API is the only module allowed to contain unsafe code.
 #[cfg(feature = "ffi")]
+#[allow(unsafe_code)]
 pub mod ffi;
 
+#[cfg(feature = "heuristics")]
 pub use blockheight::*;
 
+// Builds without the mip or heuristics features only include the shared types.
+#[cfg_attr(not(feature = "mip"), allow(dead_code))]
 const GOB_WIDTH_IN_BYTES: u32 = 64;
+#[cfg_attr(not(any(feature = "mip", feature = "heuristics")), allow(dead_code))]
 const GOB_HEIGHT_IN_BYTES: u32 = 8;
+#[cfg_attr(not(feature = "mip"), allow(dead_code))]
 const GOB_SIZE_IN_BYTES: u32 = GOB_WIDTH_IN_BYTES * GOB_HEIGHT_IN_BYTES;
 
 // Block height can only have certain values based on the Tegra TRM page 1189 table 79.
@@ -58,17 +124,22 @@ pub enum BlockHeight {
 }
 
 /// Errors than can occur while tiling or untiling.
-#[derive(Debug, PartialEq, Eq)]
+///
+/// New variants may be added in future versions,
+/// so matches on this type should include a wildcard arm.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
 pub enum SwizzleError {
     /// The source data does not contain enough bytes.
-    /// See the documentation for functions like [surface::swizzle_surface] and [surface::deswizzle_surface]
-    /// for how to calculate the expected size.
+    /// See the documentation for methods like [surface::SurfaceParams::swizzled_size]
+    /// and [surface::SurfaceParams::deswizzled_size] for how to calculate the expected size.
     NotEnoughData {
         expected_size: usize,
         actual_size: usize,
     },
 
-    /// The surface dimensions would overflow in size calculations.
+    /// The surface dimensions would overflow in size calculations,
+    /// or the surface has a depth greater than 1 and more than one array layer.
     InvalidSurface {
         width: u32,
         height: u32,
@@ -76,18 +147,125 @@ pub enum SwizzleError {
         bytes_per_pixel: u32,
         mipmap_count: u32,
     },
+
+    /// The explicit block heights do not contain a block height for every mipmap.
+    NotEnoughBlockHeights {
+        /// The number of mipmaps in the surface.
+        expected_count: u32,
+        /// The number of explicit block heights.
+        actual_count: usize,
+    },
+
+    /// The region is not fully contained in the surface.
+    InvalidRegion {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+
+    /// The row pitch is smaller than the size in bytes of a row.
+    InvalidRowPitch {
+        row_pitch: usize,
+        min_row_pitch: usize,
+    },
+
+    /// The block dimensions are 0 or larger than any supported format
+    /// or the block depth for a surface is not 1.
+    InvalidBlockDim { width: u32, height: u32, depth: u32 },
+
+    /// The range of array layers is not contained in the surface.
+    InvalidLayerRange {
+        start: u32,
+        end: u32,
+        layer_count: u32,
+    },
+
+    /// The source data for a surface ends before the end of an array layer and mipmap.
+    /// Mipmaps in a packed mip tail use the first mipmap of the tail.
+    ///
+    /// For a single source slice, the expected size includes all of the data up to and including the mipmap.
+    /// For functions with a separate slice for each mipmap like [surface::swizzle_surface_from_mips],
+    /// the sizes are for the slice of that mipmap.
+    NotEnoughMipData {
+        layer: u32,
+        mip: u32,
+        expected_size: usize,
+        actual_size: usize,
+    },
+
+    /// The operation was cancelled before completing.
+    Cancelled,
+
+    /// Tiling the untiled data again did not reproduce the source data.
+    /// The offset is the first byte in the tiled data that differs.
+    VerificationFailed { offset: usize },
+
+    /// The surface uses a feature like multisampling that is not supported yet.
+    /// See [descriptor::SurfaceDescriptor].
+    Unsupported { feature: &'static str },
+
+    /// The number of bits per pixel is not 1, 2, 4, or a whole number of bytes.
+    /// See [surface::BlockDim::from_bits_per_pixel].
+    InvalidBitsPerPixel { bits_per_pixel: u32 },
+
+    /// The explicit mipmap sizes do not contain a size for every mipmap.
+    /// See [surface::deswizzle_with_mip_sizes].
+    NotEnoughMipSizes {
+        expected_count: u32,
+        actual_count: usize,
+    },
+
+    /// An internal consistency check failed like a mipmap outside the calculated surface size.
+    /// This indicates a bug in tegra_swizzle or a custom [tiling::TilingScheme] rather than invalid input.
+    ///
+    /// The `panic-on-internal-error` feature panics with the failed check instead.
+    Internal { check: &'static str },
+
+    /// The bytes per pixel is not a multiple of the size of the requested element type.
+    /// See `SurfaceParams::deswizzle_as` with the `bytemuck` feature.
+    InvalidElementSize {
+        bytes_per_pixel: u32,
+        element_size: usize,
+    },
+
+    /// The output would be larger than the maximum size in bytes allowed by the destination or surface.
+    /// See [buffer::Limited] and [surface::SurfaceParams::max_surface_size].
+    AllocationTooLarge { size: usize, max_size: usize },
+
+    /// The GOB offset table does not contain each offset from 0 to 511 exactly once.
+    /// The `offset` at `index` is out of range or already used by an earlier index.
+    /// See [tiling::RemappedBlockLinear].
+    InvalidGobOffsets { index: usize, offset: u16 },
+
+    /// The tiled chroma plane starts before the end of the tiled luma plane
+    /// or ends past the largest possible size in bytes.
+    /// See [planar::PlanarParams::chroma_offset].
+    InvalidChromaOffset { offset: usize, min_offset: usize },
+
+    /// The texel coordinate is outside the dimensions of the mipmap.
+    /// See [surface::read_texels].
+    InvalidCoordinate {
+        x: u32,
+        y: u32,
+        z: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+    },
 }
 
-#[cfg(feature = "std")]
-impl std::fmt::Display for SwizzleError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for SwizzleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             SwizzleError::NotEnoughData {
                 expected_size,
                 actual_size,
             } => write!(
                 f,
-                "Expected at least {expected_size} bytes but found {actual_size} bytes"
+                "Expected at least {expected_size} bytes but found {actual_size} bytes. \
+                Check that the dimensions for compressed formats like BC7 account for the block size \
+                using BlockDim or div_round_up."
             ),
             SwizzleError::InvalidSurface {
                 width,
@@ -95,13 +273,146 @@ impl std::fmt::Display for SwizzleError {
                 depth,
                 bytes_per_pixel,
                 mipmap_count,
-            } => write!(f, "Invalid surface dimensions {width}x{height}x{depth} with {bytes_per_pixel} bytes per pixel and {mipmap_count} mipmaps"),
+            } => write!(
+                f,
+                "Invalid surface dimensions {width}x{height}x{depth} with {bytes_per_pixel} bytes per pixel and {mipmap_count} mipmaps. \
+                The surface size in bytes must not overflow, there can be at most 32 mipmaps, \
+                and 3D surfaces must have a single array layer."
+            ),
+            SwizzleError::NotEnoughBlockHeights {
+                expected_count,
+                actual_count,
+            } => write!(
+                f,
+                "Expected a block height for each of the {expected_count} mipmaps but found {actual_count} block heights."
+            ),
+            SwizzleError::InvalidRowPitch {
+                row_pitch,
+                min_row_pitch,
+            } => write!(
+                f,
+                "The row pitch {row_pitch} is smaller than the row size of {min_row_pitch} bytes."
+            ),
+            SwizzleError::InvalidRegion {
+                x,
+                y,
+                width,
+                height,
+            } => write!(
+                f,
+                "The region at ({x}, {y}) with dimensions {width}x{height} is not contained in the surface."
+            ),
+            SwizzleError::InvalidBlockDim {
+                width,
+                height,
+                depth,
+            } => write!(
+                f,
+                "Invalid block dimensions {width}x{height}x{depth}. \
+                Each dimension must be in the range 1 to 16, and surfaces must use a block depth of 1."
+            ),
+            SwizzleError::InvalidLayerRange {
+                start,
+                end,
+                layer_count,
+            } => write!(
+                f,
+                "The layer range {start}..{end} is not contained in the {layer_count} array layers."
+            ),
+            SwizzleError::NotEnoughMipData {
+                layer,
+                mip,
+                expected_size,
+                actual_size,
+            } => write!(
+                f,
+                "Expected at least {expected_size} bytes for layer {layer} mip {mip} but found {actual_size} bytes. \
+                Check that the mipmap count, layer count, and block dimensions match the data."
+            ),
+            SwizzleError::Cancelled => write!(f, "The operation was cancelled before completing."),
+            SwizzleError::VerificationFailed { offset } => write!(
+                f,
+                "Tiling the untiled data did not reproduce the source data at offset {offset}."
+            ),
+            SwizzleError::Unsupported { feature } => {
+                write!(f, "Unsupported surface feature: {feature}.")
+            }
+            SwizzleError::InvalidBitsPerPixel { bits_per_pixel } => write!(
+                f,
+                "Invalid bits per pixel {bits_per_pixel}. \
+                Formats must use 1, 2, or 4 bits per pixel or a whole number of bytes per pixel."
+            ),
+            SwizzleError::NotEnoughMipSizes {
+                expected_count,
+                actual_count,
+            } => write!(
+                f,
+                "Expected a size for each of the {expected_count} mipmaps but found {actual_count} sizes."
+            ),
+            SwizzleError::Internal { check } => {
+                write!(f, "Internal consistency check failed: {check}.")
+            }
+            SwizzleError::InvalidElementSize {
+                bytes_per_pixel,
+                element_size,
+            } => write!(
+                f,
+                "The {bytes_per_pixel} bytes per pixel are not a multiple of the element size {element_size}. \
+                Each pixel or block must contain a whole number of elements."
+            ),
+            SwizzleError::AllocationTooLarge { size, max_size } => write!(
+                f,
+                "The output size {size} exceeds the maximum allocation size {max_size}."
+            ),
+            SwizzleError::InvalidGobOffsets { index, offset } => write!(
+                f,
+                "The GOB offset {offset} at index {index} is out of range or used more than once. \
+                The table must contain each offset from 0 to 511 exactly once."
+            ),
+            SwizzleError::InvalidChromaOffset { offset, min_offset } => write!(
+                f,
+                "The chroma offset {offset} must be at least the luma size of {min_offset} bytes \
+                and must not overflow the tiled size."
+            ),
+            SwizzleError::InvalidCoordinate {
+                x,
+                y,
+                z,
+                width,
+                height,
+                depth,
+            } => write!(
+                f,
+                "The coordinate ({x}, {y}, {z}) is outside the mipmap with dimensions {width}x{height}x{depth}."
+            ),
         }
     }
 }
 
+// Implement the core trait to support error handling with or without std.
+impl core::error::Error for SwizzleError {}
+
+/// The result type for operations that can fail with a [SwizzleError].
+///
+/// With the `std` feature, errors also convert to [std::io::Error] using `?`
+/// for functions that read or write files.
+pub type Result<T> = core::result::Result<T, SwizzleError>;
+
+// Allow using ? for tiling errors in functions that also read or write files.
 #[cfg(feature = "std")]
-impl std::error::Error for SwizzleError {}
+impl From<SwizzleError> for std::io::Error {
+    fn from(e: SwizzleError) -> Self {
+        let kind = match e {
+            SwizzleError::Cancelled => std::io::ErrorKind::Interrupted,
+            SwizzleError::VerificationFailed { .. } => std::io::ErrorKind::InvalidData,
+            SwizzleError::Unsupported { .. } => std::io::ErrorKind::Unsupported,
+            SwizzleError::AllocationTooLarge { .. } => std::io::ErrorKind::OutOfMemory,
+            SwizzleError::Internal { .. } => std::io::ErrorKind::Other,
+            _ => std::io::ErrorKind::InvalidInput,
+        };
+        std::io::Error::new(kind, e)
+    }
+}
 
 impl BlockHeight {
     /// Attempts to construct a block height from `value`.
@@ -115,7 +426,7 @@ impl BlockHeight {
     assert_eq!(None, BlockHeight::new(5));
     ```
     */
-    pub fn new(value: u32) -> Option<Self> {
+    pub const fn new(value: u32) -> Option<Self> {
         match value {
             1 => Some(BlockHeight::One),
             2 => Some(BlockHeight::Two),
@@ -126,8 +437,54 @@ impl BlockHeight {
             _ => None,
         }
     }
+
+    /// Attempts to construct a block height from the base 2 logarithm `value`.
+    /// Returns [None] if `value` is larger than 5.
+    ///
+    /// Many formats store the block height as a 3 bit field with the log2 of the block height.
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::BlockHeight;
+
+    assert_eq!(Some(BlockHeight::Eight), BlockHeight::from_log2_checked(3));
+    assert_eq!(None, BlockHeight::from_log2_checked(6));
+    ```
+    */
+    pub const fn from_log2_checked(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(BlockHeight::One),
+            1 => Some(BlockHeight::Two),
+            2 => Some(BlockHeight::Four),
+            3 => Some(BlockHeight::Eight),
+            4 => Some(BlockHeight::Sixteen),
+            5 => Some(BlockHeight::ThirtyTwo),
+            _ => None,
+        }
+    }
+
+    /// Constructs a block height from the base 2 logarithm `value`.
+    /// Values larger than 5 use the largest block height of [BlockHeight::ThirtyTwo].
+    ///
+    /// Use this for parsers that should still accept the invalid values 6 and 7 in a 3 bit field.
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::BlockHeight;
+
+    assert_eq!(BlockHeight::Eight, BlockHeight::from_log2_clamped(3));
+    assert_eq!(BlockHeight::ThirtyTwo, BlockHeight::from_log2_clamped(7));
+    ```
+    */
+    pub const fn from_log2_clamped(value: u8) -> Self {
+        match Self::from_log2_checked(value) {
+            Some(block_height) => block_height,
+            None => BlockHeight::ThirtyTwo,
+        }
+    }
 }
 
+#[cfg(feature = "mip")]
 const fn height_in_blocks(height: u32, block_height: u32) -> u32 {
     // Each block is block_height many GOBs tall.
     div_round_up(height, block_height * GOB_HEIGHT_IN_BYTES)
@@ -155,18 +512,120 @@ assert_eq!(n, div_round_up(n, 1));
  */
 #[inline]
 pub const fn div_round_up(x: u32, d: u32) -> u32 {
-    (x + d - 1) / d
+    x.div_ceil(d)
+}
+
+// Checks for bugs rather than invalid inputs.
+// Returning an error avoids crashing applications like emulators,
+// but tools for debugging and reverse engineering may prefer a panic with a backtrace.
+#[cfg(feature = "surface")]
+#[track_caller]
+fn check_internal(condition: bool, check: &'static str) -> Result<()> {
+    if condition {
+        Ok(())
+    } else if cfg!(feature = "panic-on-internal-error") {
+        panic!("Internal consistency check failed: {}", check)
+    } else {
+        Err(SwizzleError::Internal { check })
+    }
 }
 
+#[cfg(feature = "mip")]
 const fn width_in_gobs(width: u32, bytes_per_pixel: u32) -> u32 {
     div_round_up(width * bytes_per_pixel, GOB_WIDTH_IN_BYTES)
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "minimal")))]
 mod tests {
     use super::*;
     use crate::swizzle::{deswizzled_mip_size, swizzled_mip_size};
 
+    #[test]
+    fn display_not_enough_data() {
+        use alloc::string::ToString;
+
+        let error = SwizzleError::NotEnoughData {
+            expected_size: 16,
+            actual_size: 4,
+        };
+        assert!(error
+            .to_string()
+            .starts_with("Expected at least 16 bytes but found 4 bytes."));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn into_io_error() {
+        fn read_and_untile(source: &[u8]) -> std::io::Result<alloc::vec::Vec<u8>> {
+            let params = surface::SurfaceParams {
+                width: 64,
+                height: 64,
+                bytes_per_pixel: 4,
+                ..Default::default()
+            };
+            Ok(params.deswizzle(source)?)
+        }
+
+        let error = read_and_untile(&[0u8; 16]).unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidInput, error.kind());
+        assert_eq!(
+            Some(&SwizzleError::NotEnoughMipData {
+                layer: 0,
+                mip: 0,
+                expected_size: 16384,
+                actual_size: 16
+            }),
+            error.get_ref().and_then(|e| e.downcast_ref())
+        );
+
+        let error = std::io::Error::from(SwizzleError::AllocationTooLarge {
+            size: 32,
+            max_size: 16,
+        });
+        assert_eq!(std::io::ErrorKind::OutOfMemory, error.kind());
+    }
+
+    #[test]
+    fn display_not_enough_mip_data() {
+        use alloc::string::ToString;
+
+        let error = SwizzleError::NotEnoughMipData {
+            layer: 1,
+            mip: 2,
+            expected_size: 16,
+            actual_size: 4,
+        };
+        assert!(error
+            .to_string()
+            .starts_with("Expected at least 16 bytes for layer 1 mip 2 but found 4 bytes."));
+    }
+
+    #[test]
+    fn display_verification_failed() {
+        use alloc::string::ToString;
+
+        let error = SwizzleError::VerificationFailed { offset: 512 };
+        assert_eq!(
+            "Tiling the untiled data did not reproduce the source data at offset 512.",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn block_height_from_log2() {
+        for value in 0..=u8::MAX {
+            let checked = BlockHeight::from_log2_checked(value);
+            let clamped = BlockHeight::from_log2_clamped(value);
+            if value <= 5 {
+                assert_eq!(BlockHeight::new(1 << value), checked);
+                assert_eq!(checked, Some(clamped));
+            } else {
+                assert_eq!(None, checked);
+                assert_eq!(BlockHeight::ThirtyTwo, clamped);
+            }
+        }
+    }
+
     #[test]
     fn width_in_gobs_block16() {
         assert_eq!(20, width_in_gobs(320 / 4, 16));
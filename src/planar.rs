@@ -0,0 +1,318 @@
+//! Functions for surfaces with separate luma and chroma planes like NV12 video frames.
+//!
+//! Video frames on the Tegra X1 store each plane as its own block linear surface.
+//! The planes have different dimensions and bytes per pixel due to chroma subsampling.
+//! The tiled chroma plane starts at an offset after the tiled luma plane.
+//!
+//! The untiled layout stores the luma plane followed immediately by the chroma plane.
+//! This matches the layout of NV12 frames used by most video and capture APIs.
+//!
+//! # Examples
+/*!
+```rust
+use tegra_swizzle::planar::PlanarParams;
+
+// A 1280x720 NV12 frame with 8-bit luma and interleaved 8-bit UV chroma.
+let params = PlanarParams::nv12(1280, 720);
+# let tiled = vec![0u8; params.swizzled_size()];
+let frame = params.deswizzle(&tiled).unwrap();
+assert_eq!(1280 * 720 * 3 / 2, frame.len());
+```
+*/
+#[cfg(not(feature = "minimal"))]
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    div_round_up,
+    surface::{validate_params, BlockDim, SurfaceParams},
+    BlockHeightHeuristic, Result, SwizzleError,
+};
+
+/// The parameters for a surface with a luma plane and a chroma plane.
+///
+/// Each plane uses the settings from its [SurfaceParams] like the block height.
+/// Planes must have a single mipmap and array layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlanarParams<'a> {
+    /// The luma or Y plane.
+    pub luma: SurfaceParams<'a>,
+    /// The chroma or UV plane.
+    pub chroma: SurfaceParams<'a>,
+    /// The offset in bytes of the tiled chroma plane
+    /// or [None] to start immediately after the tiled luma plane.
+    ///
+    /// The offset must not be smaller than the tiled size of the luma plane.
+    pub chroma_offset: Option<usize>,
+}
+
+impl PlanarParams<'_> {
+    /// A 4:2:0 frame with 8-bit luma and interleaved 8-bit U and V chroma.
+    ///
+    /// The chroma plane is half the width and height of the luma plane rounded up.
+    pub const fn nv12(width: u32, height: u32) -> Self {
+        Self::yuv420(width, height, 1)
+    }
+
+    /// A 4:2:0 frame with 16-bit luma and interleaved 16-bit U and V chroma.
+    /// This includes formats like P010 and P016.
+    pub const fn p016(width: u32, height: u32) -> Self {
+        Self::yuv420(width, height, 2)
+    }
+
+    const fn yuv420(width: u32, height: u32, bytes_per_sample: u32) -> Self {
+        Self {
+            luma: SurfaceParams {
+                width,
+                height,
+                bytes_per_pixel: bytes_per_sample,
+                ..Self::plane()
+            },
+            chroma: SurfaceParams {
+                width: div_round_up(width, 2),
+                height: div_round_up(height, 2),
+                bytes_per_pixel: bytes_per_sample * 2,
+                ..Self::plane()
+            },
+            chroma_offset: None,
+        }
+    }
+
+    // Default::default is not const, so initialize the remaining fields here.
+    const fn plane() -> SurfaceParams<'static> {
+        SurfaceParams {
+            width: 1,
+            height: 1,
+            depth: 1,
+            block_dim: BlockDim::uncompressed(),
+            block_height_mip0: None,
+            block_height_heuristic: BlockHeightHeuristic::Trm,
+            bytes_per_pixel: 1,
+            mipmap_count: 1,
+            layer_count: 1,
+            packed_mip_tail: false,
+            mip_block_heights: None,
+            layer_alignment: None,
+            max_surface_size: None,
+        }
+    }
+
+    /// The offset in bytes of the chroma plane in the tiled data.
+    pub const fn swizzled_chroma_offset(&self) -> usize {
+        match self.chroma_offset {
+            Some(offset) => offset,
+            None => self.luma.swizzled_size(),
+        }
+    }
+
+    /// Calculates the size in bytes for the tiled data for both planes.
+    ///
+    /// The size saturates at [usize::MAX] for a [PlanarParams::chroma_offset] that would overflow.
+    /// The tiling functions return an error for these offsets instead.
+    pub const fn swizzled_size(&self) -> usize {
+        self.swizzled_chroma_offset()
+            .saturating_add(self.chroma.swizzled_size())
+    }
+
+    /// Calculates the size in bytes for the untiled data for both planes.
+    pub const fn deswizzled_size(&self) -> usize {
+        self.luma.deswizzled_size() + self.chroma.deswizzled_size()
+    }
+
+    /// Tiles both planes in `source` to a new vector.
+    ///
+    /// Returns [SwizzleError::NotEnoughData] if `source` does not have
+    /// at least as many bytes as [PlanarParams::deswizzled_size].
+    /// See [PlanarParams::swizzle_into] for the other errors.
+    #[cfg(not(feature = "minimal"))]
+    pub fn swizzle(&self, source: &[u8]) -> Result<Vec<u8>> {
+        let mut destination = vec![0u8; self.checked_swizzled_size()?];
+        self.swizzle_into(source, &mut destination)?;
+        Ok(destination)
+    }
+
+    /// Untiles both planes in `source` to a new vector.
+    ///
+    /// Returns [SwizzleError::NotEnoughData] if `source` does not have
+    /// at least as many bytes as [PlanarParams::swizzled_size].
+    /// See [PlanarParams::deswizzle_into] for the other errors.
+    #[cfg(not(feature = "minimal"))]
+    pub fn deswizzle(&self, source: &[u8]) -> Result<Vec<u8>> {
+        self.checked_swizzled_size()?;
+        let mut destination = vec![0u8; self.deswizzled_size()];
+        self.deswizzle_into(source, &mut destination)?;
+        Ok(destination)
+    }
+
+    /// Tiles both planes in `source` into `destination`.
+    ///
+    /// Returns [SwizzleError::NotEnoughData] if `source` or `destination` are too small.
+    /// Returns [SwizzleError::InvalidChromaOffset] if the chroma plane overlaps the luma plane.
+    /// Returns [SwizzleError::Unsupported] if either plane has mipmaps or array layers.
+    /// Bytes between the end of the luma plane and [PlanarParams::swizzled_chroma_offset] are set to zero.
+    pub fn swizzle_into(&self, source: &[u8], destination: &mut [u8]) -> Result<()> {
+        let swizzled_size = self.checked_swizzled_size()?;
+        check_size(source, self.deswizzled_size())?;
+        check_size(destination, swizzled_size)?;
+
+        let (luma, chroma) = source.split_at(self.luma.deswizzled_size());
+        self.luma.swizzle_into(luma, destination)?;
+        destination[self.luma.swizzled_size()..self.swizzled_chroma_offset()].fill(0);
+        self.chroma
+            .swizzle_into(chroma, &mut destination[self.swizzled_chroma_offset()..])
+    }
+
+    /// Untiles both planes in `source` into `destination`.
+    ///
+    /// Returns [SwizzleError::NotEnoughData] if `source` or `destination` are too small.
+    /// See [PlanarParams::swizzle_into] for the other errors.
+    pub fn deswizzle_into(&self, source: &[u8], destination: &mut [u8]) -> Result<()> {
+        check_size(source, self.checked_swizzled_size()?)?;
+        check_size(destination, self.deswizzled_size())?;
+
+        let (luma, chroma) = destination.split_at_mut(self.luma.deswizzled_size());
+        self.luma.deswizzle_into(source, luma)?;
+        self.chroma
+            .deswizzle_into(&source[self.swizzled_chroma_offset()..], chroma)
+    }
+
+    // Validate the planes before calculating sizes to avoid overflow.
+    fn checked_swizzled_size(&self) -> Result<usize> {
+        for plane in [&self.luma, &self.chroma] {
+            validate_params(plane)?;
+            if plane.mipmap_count > 1 || plane.layer_count > 1 {
+                return Err(SwizzleError::Unsupported {
+                    feature: "planes with mipmaps or array layers",
+                });
+            }
+        }
+
+        let offset = self.swizzled_chroma_offset();
+        let min_offset = self.luma.swizzled_size();
+        if offset < min_offset {
+            return Err(SwizzleError::InvalidChromaOffset { offset, min_offset });
+        }
+        offset
+            .checked_add(self.chroma.swizzled_size())
+            .ok_or(SwizzleError::InvalidChromaOffset { offset, min_offset })
+    }
+}
+
+fn check_size(data: &[u8], expected_size: usize) -> Result<()> {
+    if data.len() < expected_size {
+        Err(SwizzleError::NotEnoughData {
+            expected_size,
+            actual_size: data.len(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::BlockHeight;
+
+    #[test]
+    fn nv12_planes() {
+        // The planes are both 1 GOB wide but use different block heights.
+        // The luma plane uses 4 GOBs for 30 rows, and the chroma plane uses 2 GOBs for 15 rows.
+        let params = PlanarParams::nv12(64, 30);
+        assert_eq!(64 * 30 + 32 * 15 * 2, params.deswizzled_size());
+        assert_eq!(2048, params.swizzled_chroma_offset());
+        assert_eq!(3072, params.swizzled_size());
+
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let tiled = params.swizzle(&input).unwrap();
+
+        let (luma, chroma) = input.split_at(64 * 30);
+        assert_eq!(params.luma.swizzle(luma).unwrap(), tiled[..2048]);
+        assert_eq!(params.chroma.swizzle(chroma).unwrap(), tiled[2048..]);
+
+        assert_eq!(input, params.deswizzle(&tiled).unwrap());
+    }
+
+    #[test]
+    fn planar_chroma_offset_block_height() {
+        let params = PlanarParams {
+            luma: SurfaceParams {
+                block_height_mip0: Some(BlockHeight::Two),
+                ..PlanarParams::p016(33, 17).luma
+            },
+            chroma_offset: Some(0x1000),
+            ..PlanarParams::p016(33, 17)
+        };
+        assert_eq!(0x1000 + 2048, params.swizzled_size());
+
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let tiled = params.swizzle(&input).unwrap();
+        assert_eq!(
+            params.chroma.swizzle(&input[33 * 17 * 2..]).unwrap(),
+            tiled[0x1000..]
+        );
+        assert_eq!(input, params.deswizzle(&tiled).unwrap());
+    }
+
+    #[test]
+    fn planar_invalid_chroma_offset() {
+        let params = PlanarParams {
+            chroma_offset: Some(1024),
+            ..PlanarParams::nv12(64, 30)
+        };
+        let error = SwizzleError::InvalidChromaOffset {
+            offset: 1024,
+            min_offset: 2048,
+        };
+        assert_eq!(Err(error.clone()), params.swizzle(&[0u8; 2880]));
+        assert_eq!(Err(error), params.deswizzle(&[0u8; 3072]));
+
+        let params = PlanarParams {
+            chroma_offset: Some(usize::MAX - 8),
+            ..PlanarParams::nv12(64, 30)
+        };
+        assert_eq!(usize::MAX, params.swizzled_size());
+        assert_eq!(
+            Err(SwizzleError::InvalidChromaOffset {
+                offset: usize::MAX - 8,
+                min_offset: 2048
+            }),
+            params.swizzle_into(&[0u8; 2880], &mut [0u8; 4096])
+        );
+    }
+
+    #[test]
+    fn planar_mipmaps_layers() {
+        let mut params = PlanarParams::nv12(64, 30);
+        params.luma.mipmap_count = 2;
+        assert!(matches!(
+            params.swizzle(&[0u8; 2880]),
+            Err(SwizzleError::Unsupported { .. })
+        ));
+
+        let mut params = PlanarParams::nv12(64, 30);
+        params.chroma.layer_count = 2;
+        assert!(matches!(
+            params.deswizzle_into(&[0u8; 4096], &mut [0u8; 4096]),
+            Err(SwizzleError::Unsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn planar_not_enough_data() {
+        let params = PlanarParams::nv12(64, 30);
+        assert_eq!(
+            Err(SwizzleError::NotEnoughData {
+                expected_size: 3072,
+                actual_size: 2048
+            }),
+            params.deswizzle(&[0u8; 2048])
+        );
+        assert_eq!(
+            Err(SwizzleError::NotEnoughData {
+                expected_size: 3072,
+                actual_size: 8
+            }),
+            params.swizzle_into(&[0u8; 2880], &mut [0u8; 8])
+        );
+    }
+}
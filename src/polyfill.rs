@@ -0,0 +1,22 @@
+// Replacements for standard library functions that are newer than the rust-version in Cargo.toml
+// or that can't be used in const functions on that version.
+// Clippy's incompatible_msrv lint reports any remaining uses of newer functions.
+use core::num::NonZeroU32;
+
+// Option::unwrap is const since 1.83.
+pub const fn non_zero_u32(value: u32) -> NonZeroU32 {
+    match NonZeroU32::new(value) {
+        Some(value) => value,
+        None => panic!("value should be non zero"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polyfills_match_std() {
+        assert_eq!(4, non_zero_u32(4).get());
+    }
+}
@@ -0,0 +1,33 @@
+//! Commonly used items for tiling and untiling surfaces.
+//!
+//! The items in the prelude will continue to be exported from this module
+//! even if the modules that define them are reorganized in future versions.
+//!
+//! # Examples
+//! ```rust
+//! use tegra_swizzle::prelude::*;
+//!
+//! let params = SurfaceParams {
+//!     width: 256,
+//!     height: 256,
+//!     block_dim: BlockDim::block_4x4(),
+//!     bytes_per_pixel: 16,
+//!     mipmap_count: 9,
+//!     ..Default::default()
+//! };
+//! # let input = vec![0u8; params.deswizzled_size()];
+//! let tiled = params.swizzle(&input)?;
+//! assert_eq!(params.swizzled_size(), tiled.len());
+//! # Ok::<(), SwizzleError>(())
+//! ```
+// The deprecated functions are still exported for existing code.
+#[allow(deprecated)]
+#[cfg(not(feature = "minimal"))]
+pub use crate::surface::{deswizzle_surface, swizzle_surface};
+#[allow(deprecated)]
+pub use crate::surface::{deswizzled_surface_size, swizzled_surface_size};
+pub use crate::{
+    block_height_mip0, div_round_up, mip_block_height,
+    surface::{BlockDim, SurfaceParams},
+    BlockHeight, BlockHeightHeuristic, SwizzleError,
+};
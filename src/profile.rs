@@ -0,0 +1,204 @@
+//! Surface parameter presets for the texture files of specific games and tools.
+//!
+//! Files from the same game or tool use the same block height heuristic,
+//! alignment, and mipmap ordering for every texture.
+//! A [Profile] bundles these choices, so only the dimensions and format need to be read from each file.
+//! Using the profile avoids trying different combinations of parameters
+//! until the output looks correct.
+//!
+//! | Profile | Block heights | Layer alignment | Packed mip tail | Input ordering |
+//! | --- | --- | --- | --- | --- |
+//! | [Profile::GenericNvn] | [BlockHeightHeuristic::Trm] | calculated | no | [InputOrdering::LayerMajor] |
+//! | [Profile::LegacyNutexb] | [BlockHeightHeuristic::LegacyNutexb] | calculated | no | [InputOrdering::LayerMajor] |
+//!
+//! # Examples
+/*!
+```rust
+use tegra_swizzle::profile::Profile;
+use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+
+// 320x320 BC7 nutexb with a single mipmap.
+let params = SurfaceParams {
+    width: 320,
+    height: 320,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    ..Profile::GenericNvn.params()
+};
+# let input = vec![0u8; params.swizzled_size()];
+let output = params.deswizzle(&input)?;
+
+// Older tools used a larger block height for some sizes.
+let params = Profile::LegacyNutexb.apply(params);
+let output = params.deswizzle(&input)?;
+# Ok::<(), tegra_swizzle::SwizzleError>(())
+```
+*/
+use crate::{
+    surface::{InputOrdering, SurfaceParams},
+    BlockHeightHeuristic,
+};
+
+/// The parameters for the texture files of a specific game or tool.
+/// See the [module level documentation](crate::profile) for the values for each profile.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Profile {
+    /// The default parameters used by the Tegra X1 drivers through NVN.
+    /// This matches most games that don't store the block height
+    /// including nutexb files from Super Smash Bros. Ultimate.
+    #[default]
+    GenericNvn,
+    /// Nutexb files created by older tools using the nutexb_swizzle crate.
+    ///
+    /// These files round the height in GOBs up to the next power of two for the base block height.
+    /// See [BlockHeightHeuristic::LegacyNutexb].
+    ///
+    /// Unlike [Profile::GenericNvn], this profile has not been checked against files created by these tools.
+    LegacyNutexb,
+}
+
+impl Profile {
+    /// The algorithm for inferring the block height of the base mip level.
+    pub const fn block_height_heuristic(self) -> BlockHeightHeuristic {
+        match self {
+            Profile::GenericNvn => BlockHeightHeuristic::Trm,
+            Profile::LegacyNutexb => BlockHeightHeuristic::LegacyNutexb,
+        }
+    }
+
+    /// The order of the array layers and mipmaps in the untiled data.
+    /// Use this with [SurfaceParams::swizzle_ordered].
+    pub const fn input_ordering(self) -> InputOrdering {
+        match self {
+            Profile::GenericNvn | Profile::LegacyNutexb => InputOrdering::LayerMajor,
+        }
+    }
+
+    /// Sets the layout options in `params` to the values for this profile.
+    ///
+    /// The dimensions, format, and explicit block heights are not modified.
+    /// The block heights are only inferred using the profile if
+    /// [SurfaceParams::block_height_mip0] is [None].
+    pub const fn apply<'a>(self, params: SurfaceParams<'a>) -> SurfaceParams<'a> {
+        SurfaceParams {
+            block_height_heuristic: self.block_height_heuristic(),
+            packed_mip_tail: false,
+            layer_alignment: None,
+            ..params
+        }
+    }
+
+    /// The default [SurfaceParams] with the layout options for this profile.
+    /// Use struct update syntax to set the dimensions and format.
+    pub fn params(self) -> SurfaceParams<'static> {
+        self.apply(SurfaceParams::default())
+    }
+}
+
+impl<'a> From<Profile> for SurfaceParams<'a> {
+    fn from(profile: Profile) -> Self {
+        profile.params()
+    }
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{load_fixtures, read_fixture, Fixture},
+        surface::{mip_layout, padded_ranges, BlockDim},
+        BlockHeight,
+    };
+    use core::convert::TryFrom;
+
+    fn fixture_params(fixture: &Fixture, profile: Profile) -> SurfaceParams<'static> {
+        let [width, height, depth] = fixture.block_dim;
+        SurfaceParams {
+            width: fixture.width,
+            height: fixture.height,
+            depth: fixture.depth,
+            block_dim: BlockDim::try_from((width, height, depth)).unwrap(),
+            bytes_per_pixel: fixture.bytes_per_pixel,
+            ..profile.params()
+        }
+    }
+
+    fn check_reproduces_fixture(fixture: &Fixture, profile: Profile) {
+        let linear = read_fixture(&std::format!("{}.bin", fixture.name));
+        let mut tiled = read_fixture(&std::format!("{}_tiled.bin", fixture.name));
+        let params = fixture_params(fixture, profile);
+
+        // Tiled dumps may contain extra bytes after the tiled data
+        // and arbitrary values in the padding that tiling sets to zero.
+        tiled.truncate(params.swizzled_size());
+        for range in padded_ranges(&params) {
+            tiled[range].fill(0);
+        }
+        assert!(
+            params.swizzle(&linear).unwrap() == tiled,
+            "{} does not match the tiled data",
+            fixture.name
+        );
+        assert!(
+            params.deswizzle(&tiled).unwrap() == linear,
+            "{} does not match the untiled data",
+            fixture.name
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn generic_nvn_reproduces_fixtures() {
+        for fixture in load_fixtures() {
+            check_reproduces_fixture(&fixture, Profile::GenericNvn);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn legacy_nutexb_block_height() {
+        // 320x320 BC7 is 10 GOBs tall, so older tools used a block height of 16 instead of 8.
+        // TODO: Test against output captured from nutexb_swizzle once there is a file to add to the fixtures.
+        // This only checks the block height selected by each profile.
+        let fixture = load_fixtures()
+            .into_iter()
+            .find(|f| f.name == "320_bc7")
+            .unwrap();
+        let generic = fixture_params(&fixture, Profile::GenericNvn);
+        let legacy = fixture_params(&fixture, Profile::LegacyNutexb);
+        assert_eq!(
+            BlockHeight::Eight,
+            mip_layout(&generic, 0).unwrap().block_height
+        );
+        assert_eq!(
+            BlockHeight::Sixteen,
+            mip_layout(&legacy, 0).unwrap().block_height
+        );
+    }
+
+    #[test]
+    fn apply_keeps_dimensions() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 32,
+            mipmap_count: 4,
+            layer_count: 6,
+            block_height_mip0: Some(BlockHeight::Two),
+            packed_mip_tail: true,
+            layer_alignment: core::num::NonZeroUsize::new(0x800),
+            ..Default::default()
+        };
+        let legacy = Profile::LegacyNutexb.apply(params);
+        assert_eq!(
+            SurfaceParams {
+                block_height_heuristic: BlockHeightHeuristic::LegacyNutexb,
+                packed_mip_tail: false,
+                layer_alignment: None,
+                ..params
+            },
+            legacy
+        );
+        assert_eq!(SurfaceParams::default(), Profile::default().into());
+    }
+}
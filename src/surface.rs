@@ -4,13 +4,13 @@
 //! as a single allocated region of memory that contains all array layers and mipmaps.
 //! This also applies to the tiled surfaces used for most textures on the Tegra X1.
 //!
-//! Use [deswizzle_surface] for untiling surfaces into a single `Vec<u8>`.
+//! Use [SurfaceParams::deswizzle] for untiling surfaces into a single `Vec<u8>`.
 //! This output can be used as is for creating DDS files.
 //! Modern graphics APIs like Vulkan also support this dense layout for initializing all
 //! array layers and mipmaps for a texture in a single API call.
 //!
-//! Use [swizzle_surface] for tiling a surface from a combined buffer like the result of [deswizzle_surface] or a DDS file.
-//! The result of [swizzle_surface] is the layout expected for many texture file formats for console games targeting the Tegra X1.
+//! Use [SurfaceParams::swizzle] for tiling a surface from a combined buffer like the result of [SurfaceParams::deswizzle] or a DDS file.
+//! The result of [SurfaceParams::swizzle] is the layout expected for many texture file formats for console games targeting the Tegra X1.
 //!
 //! # Examples
 //! Array layers and mipmaps are ordered by layer and then mipmap.
@@ -29,15 +29,34 @@ Layer L-1 Mip M-1
 */
 //! The convention is for the untiled or linear layout to be tightly packed.
 //! Tiled surfaces add additional padding and alignment between layers and mipmaps.
+#[cfg(not(feature = "minimal"))]
 use alloc::{vec, vec::Vec};
-use core::{cmp::max, num::NonZeroU32};
+use core::{
+    convert::TryFrom,
+    num::{NonZeroU32, NonZeroUsize},
+    sync::atomic::{AtomicBool, Ordering},
+};
 
+#[cfg(not(feature = "minimal"))]
+use crate::{
+    addressing::texel_offset,
+    swizzle::{is_fast_path, retile_inner, AccessOrder, BlockLinearPlan},
+};
 use crate::{
     arrays::align_layer_size,
-    blockdepth::mip_block_depth,
-    div_round_up, mip_block_height,
-    swizzle::{deswizzled_mip_size, swizzle_inner, swizzled_mip_size},
-    BlockHeight, SwizzleError,
+    blockdepth::{block_depth, mip_block_depth},
+    buffer::DestBuffer,
+    check_internal, div_round_up, mip_block_height,
+    polyfill::non_zero_u32,
+    swizzle::{
+        deswizzled_mip_size, gob_address_x, gob_address_y, gob_address_z, gob_offset, slice_size,
+        swizzled_mip_size,
+    },
+    tiling::{
+        CancellableBlockLinear, MipLevel, ProgressBlockLinear, TegraBlockLinear, TilingScheme,
+    },
+    BlockHeight, BlockHeightHeuristic, Result, SwizzleError, GOB_HEIGHT_IN_BYTES,
+    GOB_SIZE_IN_BYTES, GOB_WIDTH_IN_BYTES,
 };
 
 /// The dimensions of a compressed block. Compressed block sizes are usually 4x4 pixels.
@@ -49,957 +68,6993 @@ pub struct BlockDim {
     /// The height of the block in pixels.
     pub height: NonZeroU32,
     /// The depth of the block in pixels.
+    ///
+    /// Surfaces must use a depth of 1 since the Tegra X1 does not support 3D compressed blocks.
+    /// The surface functions return [SwizzleError::InvalidBlockDim] for other values.
     pub depth: NonZeroU32,
 }
 
 impl BlockDim {
     /// A 1x1x1 block for formats that do not use block compression like R8G8B8A8.
-    pub fn uncompressed() -> Self {
+    pub const fn uncompressed() -> Self {
         BlockDim {
-            width: NonZeroU32::new(1).unwrap(),
-            height: NonZeroU32::new(1).unwrap(),
-            depth: NonZeroU32::new(1).unwrap(),
+            width: non_zero_u32(1),
+            height: non_zero_u32(1),
+            depth: non_zero_u32(1),
         }
     }
 
     /// A 4x4x1 compressed block. This includes any of the BCN formats like BC1, BC3, or BC7.
     /// This also includes DXT1, DXT3, and DXT5.
-    pub fn block_4x4() -> Self {
+    pub const fn block_4x4() -> Self {
         BlockDim {
-            width: NonZeroU32::new(4).unwrap(),
-            height: NonZeroU32::new(4).unwrap(),
-            depth: NonZeroU32::new(1).unwrap(),
+            width: non_zero_u32(4),
+            height: non_zero_u32(4),
+            depth: non_zero_u32(1),
         }
     }
-}
 
-/// Tiles all the array layers and mipmaps in `source` using the block linear algorithm
-/// to a combined vector with appropriate mipmap and layer alignment.
-///
-/// The `width`, `height`, and `depth` are in terms of blocks with the pixels per block defined by `block_dim`.
-/// Use a `block_height_mip0` of [None] to infer the block height from the specified dimensions.
-///
-/// Returns [SwizzleError::NotEnoughData] if `source` does not have
-/// at least as many bytes as the result of [deswizzled_surface_size].
-///
-/// # Examples
-///
-/// Compressed formats should still use pixel dimensions and set the appropriate block dimensions.
-///
-/// ```rust no_run
-/// use tegra_swizzle::surface::{BlockDim, swizzle_surface};
-/// # let deswizzled_surface = vec![0u8; 10];
-///
-/// // 16x16 BC7 cube map with 5 mipmaps.
-/// let surface = swizzle_surface(
-///     16,
-///     16,
-///     1,
-///     &deswizzled_surface,
-///     BlockDim::block_4x4(),
-///     None,
-///     16,
-///     5,
-///     6,
-/// );
-/// ```
-///
-/// Uncompressed formats use a 1x1x1 pixel block.
-///
-/// ```rust no_run
-/// // 128x128 R8G8B8A8 2D texture with no mipmaps.
-/// use tegra_swizzle::surface::{BlockDim, swizzle_surface};
-/// # let deswizzled_surface = vec![0u8; 10];
-/// let surface = swizzle_surface(
-///     128,
-///     128,
-///     1,
-///     &deswizzled_surface,
-///     BlockDim::uncompressed(),
-///     None,
-///     4,
-///     1,
-///     1,
-/// );
-///
-/// // 16x16x16 R8G8B8A8 3D texture with no mipmaps.
-/// let surface = swizzle_surface(
-///     16,
-///     16,
-///     16,
-///     &deswizzled_surface,
-///     BlockDim::uncompressed(),
-///     None,
-///     4,
-///     1,
-///     1,
-/// );
-/// ```
-pub fn swizzle_surface(
-    width: u32,
-    height: u32,
-    depth: u32,
-    source: &[u8],
-    block_dim: BlockDim, // TODO: Use None to indicate uncompressed?
-    block_height_mip0: Option<BlockHeight>, // TODO: Make this optional in other functions as well?
-    bytes_per_pixel: u32,
-    mipmap_count: u32,
-    layer_count: u32,
-) -> Result<Vec<u8>, SwizzleError> {
-    // Check for empty surfaces first to more reliably handle overflow.
-    if width == 0
-        || height == 0
-        || depth == 0
-        || bytes_per_pixel == 0
-        || mipmap_count == 0
-        || layer_count == 0
-    {
-        return Ok(Vec::new());
+    /// Attempts to construct a block with the given dimensions in pixels.
+    /// Returns [None] if any dimension is 0 or larger than 16.
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::surface::BlockDim;
+
+    assert_eq!(Some(BlockDim::block_4x4()), BlockDim::new(4, 4, 1));
+    assert_eq!(None, BlockDim::new(4, 0, 1));
+    ```
+    */
+    pub const fn new(width: u32, height: u32, depth: u32) -> Option<Self> {
+        match (
+            checked_block_size(width),
+            checked_block_size(height),
+            checked_block_size(depth),
+        ) {
+            (Some(width), Some(height), Some(depth)) => Some(BlockDim {
+                width,
+                height,
+                depth,
+            }),
+            _ => None,
+        }
     }
 
-    validate_surface(width, height, depth, bytes_per_pixel, mipmap_count)?;
+    /// Calculates the block dimensions and the size in bytes of each block
+    /// for uncompressed formats with `bits_per_pixel` bits for each pixel.
+    ///
+    /// The surface functions work with whole bytes for each pixel or compressed block.
+    /// Formats with fewer than 8 bits per pixel like 4-bit palette formats
+    /// group horizontally adjacent pixels into 1 byte blocks.
+    /// Rows are padded to whole bytes, so a 4-bit surface with an odd width has 4 bits of padding for each row.
+    ///
+    /// Returns [SwizzleError::InvalidBitsPerPixel] if `bits_per_pixel` is not 1, 2, 4, or a multiple of 8.
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::surface::{BlockDim, SurfaceParams};
 
-    let mut result = surface_destination::<false>(
-        width,
-        height,
-        depth,
-        block_dim,
-        block_height_mip0,
-        bytes_per_pixel,
-        mipmap_count,
-        layer_count,
-        source,
-    )?;
+    // 64x64 with 4 bits per pixel uses 2x1 pixel blocks with 1 byte per block.
+    let (block_dim, bytes_per_pixel) = BlockDim::from_bits_per_pixel(4).unwrap();
+    assert_eq!(BlockDim::new(2, 1, 1), Some(block_dim));
+    assert_eq!(1, bytes_per_pixel);
 
-    swizzle_surface_inner::<false>(
-        width,
-        height,
-        depth,
-        source,
-        &mut result,
+    let params = SurfaceParams {
+        width: 64,
+        height: 64,
         block_dim,
-        block_height_mip0,
         bytes_per_pixel,
-        mipmap_count,
-        layer_count,
-    )?;
+        ..Default::default()
+    };
+    assert_eq!(64 * 64 / 2, params.deswizzled_size());
 
-    Ok(result)
+    assert_eq!((BlockDim::uncompressed(), 2), BlockDim::from_bits_per_pixel(16).unwrap());
+    assert!(BlockDim::from_bits_per_pixel(12).is_err());
+    ```
+    */
+    pub const fn from_bits_per_pixel(bits_per_pixel: u32) -> Result<(Self, u32)> {
+        match bits_per_pixel {
+            1 | 2 | 4 => Ok((
+                BlockDim {
+                    width: non_zero_u32(8 / bits_per_pixel),
+                    height: non_zero_u32(1),
+                    depth: non_zero_u32(1),
+                },
+                1,
+            )),
+            _ if bits_per_pixel > 0 && bits_per_pixel % 8 == 0 => {
+                Ok((Self::uncompressed(), bits_per_pixel / 8))
+            }
+            _ => Err(SwizzleError::InvalidBitsPerPixel { bits_per_pixel }),
+        }
+    }
 }
 
-// TODO: Find a way to simplify the parameters.
-/// Untiles all the array layers and mipmaps in `source` using the block linear algorithm
-/// to a new vector without any padding between layers or mipmaps.
-///
-/// The `width`, `height`, and `depth` are in terms of blocks with the pixels per block defined by `block_dim`.
-/// Use a `block_height_mip0` of [None] to infer the block height from the specified dimensions.
-///
-/// Returns [SwizzleError::NotEnoughData] if `source` does not have
-/// at least as many bytes as the result of [swizzled_surface_size].
+// The largest blocks for formats like ASTC are 12x12 pixels.
+const MAX_BLOCK_SIZE: u32 = 16;
+
+const fn checked_block_size(value: u32) -> Option<NonZeroU32> {
+    if value <= MAX_BLOCK_SIZE {
+        NonZeroU32::new(value)
+    } else {
+        None
+    }
+}
+
+/// Converts the width and height in pixels from format tables with a depth of 1.
 ///
 /// # Examples
-///
-/// Compressed formats should still use pixel dimensions and set the appropriate block dimensions.
-///
-/// ```rust no_run
-/// use tegra_swizzle::surface::{BlockDim, deswizzle_surface};
-/// # let swizzled_surface = vec![0u8; 10];
-///
-/// // 16x16 BC7 cube map with 5 mipmaps.
-/// let surface = deswizzle_surface(
-///     16,
-///     16,
-///     1,
-///     &swizzled_surface,
-///     BlockDim::block_4x4(),
-///     None,
-///     16,
-///     5,
-///     6,
-/// );
-/// ```
-///
-/// Uncompressed formats use a 1x1x1 pixel block.
-///
-/// ```rust no_run
-/// use tegra_swizzle::surface::{BlockDim, deswizzle_surface};
-/// # let swizzled_surface = vec![0u8; 10];
-/// // 128x128 R8G8B8A8 2D texture with no mipmaps.
-/// let surface = deswizzle_surface(
-///     128,
-///     128,
-///     1,
-///     &swizzled_surface,
-///     BlockDim::uncompressed(),
-///     None,
-///     4,
-///     1,
-///     1,
-/// );
-///
-/// // 16x16x16 R8G8B8A8 3D texture with no mipmaps.
-/// let surface = deswizzle_surface(
-///     16,
-///     16,
-///     16,
-///     &swizzled_surface,
-///     BlockDim::uncompressed(),
-///     None,
-///     4,
-///     1,
-///     1,
-/// );
-/// ```
-pub fn deswizzle_surface(
-    width: u32,
-    height: u32,
-    depth: u32,
-    source: &[u8],
-    block_dim: BlockDim,
-    block_height_mip0: Option<BlockHeight>, // TODO: Make this optional in other functions as well?
-    bytes_per_pixel: u32,
-    mipmap_count: u32,
-    layer_count: u32,
-) -> Result<Vec<u8>, SwizzleError> {
-    // Check for empty surfaces first to more reliably handle overflow.
-    if width == 0
-        || height == 0
-        || depth == 0
-        || bytes_per_pixel == 0
-        || mipmap_count == 0
-        || layer_count == 0
-    {
-        return Ok(Vec::new());
+/**
+```rust
+use std::convert::TryFrom;
+use tegra_swizzle::surface::BlockDim;
+
+assert_eq!(Ok(BlockDim::block_4x4()), BlockDim::try_from((4, 4)));
+assert!(BlockDim::try_from((4, 32)).is_err());
+```
+ */
+impl TryFrom<(u32, u32)> for BlockDim {
+    type Error = SwizzleError;
+
+    fn try_from((width, height): (u32, u32)) -> core::result::Result<Self, Self::Error> {
+        BlockDim::try_from((width, height, 1))
     }
+}
 
-    validate_surface(width, height, depth, bytes_per_pixel, mipmap_count)?;
+/// Converts the width, height, and depth in pixels from format tables.
+impl TryFrom<(u32, u32, u32)> for BlockDim {
+    type Error = SwizzleError;
 
-    let mut result = surface_destination::<true>(
-        width,
-        height,
-        depth,
-        block_dim,
-        block_height_mip0,
-        bytes_per_pixel,
-        mipmap_count,
-        layer_count,
-        source,
-    )?;
+    fn try_from(
+        (width, height, depth): (u32, u32, u32),
+    ) -> core::result::Result<Self, Self::Error> {
+        BlockDim::new(width, height, depth).ok_or(SwizzleError::InvalidBlockDim {
+            width,
+            height,
+            depth,
+        })
+    }
+}
 
-    swizzle_surface_inner::<true>(
-        width,
-        height,
-        depth,
-        source,
-        &mut result,
-        block_dim,
-        block_height_mip0,
-        bytes_per_pixel,
-        mipmap_count,
-        layer_count,
-    )?;
+/// The parameters describing a surface with all of its array layers and mipmaps.
+///
+/// Use [Default::default] for fields that don't need to be changed.
+/// The deprecated functions like [swizzle_surface] use the default values
+/// for the options that are rarely needed in practice.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+# let deswizzled_surface = vec![0u8; 2208 * 6];
 
-    Ok(result)
+// 16x16 BC7 cube map with 5 mipmaps.
+let params = SurfaceParams {
+    width: 16,
+    height: 16,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 5,
+    layer_count: 6,
+    ..Default::default()
+};
+let surface = params.swizzle(&deswizzled_surface).unwrap();
+assert_eq!(params.swizzled_size(), surface.len());
+```
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurfaceParams<'a> {
+    /// The width of the surface in pixels.
+    pub width: u32,
+    /// The height of the surface in pixels.
+    pub height: u32,
+    /// The depth of the surface in pixels.
+    pub depth: u32,
+    /// The dimensions of the compressed block or [BlockDim::uncompressed].
+    pub block_dim: BlockDim,
+    /// The block height for the base mip level or [None] to infer the block height.
+    pub block_height_mip0: Option<BlockHeight>,
+    /// The algorithm for inferring the block height if `block_height_mip0` is [None].
+    pub block_height_heuristic: BlockHeightHeuristic,
+    /// The size in bytes of each pixel or compressed block.
+    /// See [BlockDim::from_bits_per_pixel] for formats with less than 1 byte per pixel.
+    pub bytes_per_pixel: u32,
+    /// The number of mipmaps in each array layer.
+    pub mipmap_count: u32,
+    /// The number of array layers or 6 for cube maps.
+    pub layer_count: u32,
+    /// Store the smallest mipmaps together in the packed mip tail.
+    ///
+    /// Mipmaps that fit within a single GOB are placed side by side in as few GOBs as possible
+    /// instead of using at least one GOB for each mipmap.
+    /// This only applies to 2D surfaces and has no effect if `depth` is greater than 1.
+    pub packed_mip_tail: bool,
+    /// The block height for each mipmap or [None] to calculate the block heights from the base mip level.
+    ///
+    /// Some formats store the block height for each mipmap instead of only the base level.
+    /// Setting the block heights explicitly allows recreating these files exactly.
+    /// Size calculations use [crate::mip_block_height] for mipmaps without a block height.
+    pub mip_block_heights: Option<&'a [BlockHeight]>,
+    /// The alignment in bytes for each array layer or [None] to calculate the alignment.
+    ///
+    /// Most games match the alignment calculated from the height and block height.
+    /// Some engines instead align layers to a fixed boundary like `0x400` or `0x800` bytes.
+    /// This only applies to surfaces with more than one array layer.
+    pub layer_alignment: Option<NonZeroUsize>,
+    /// The maximum value for [SurfaceParams::swizzled_size] or [None] for no limit.
+    ///
+    /// Functions that validate the parameters return [SwizzleError::AllocationTooLarge]
+    /// for larger surfaces before allocating any memory.
+    /// The untiled size is never larger than the tiled size, so this also limits
+    /// the output of [SurfaceParams::deswizzle] and the other untiling functions.
+    /// Use [crate::buffer::Limited] to limit the size of a specific destination instead.
+    pub max_surface_size: Option<usize>,
 }
 
-pub(crate) fn swizzle_surface_inner<const DESWIZZLE: bool>(
-    width: u32,
-    height: u32,
-    depth: u32,
-    source: &[u8],
-    result: &mut [u8],
-    block_dim: BlockDim,
-    block_height_mip0: Option<BlockHeight>, // TODO: Make this optional in other functions as well?
-    bytes_per_pixel: u32,
-    mipmap_count: u32,
-    layer_count: u32,
-) -> Result<(), SwizzleError> {
-    let block_width = block_dim.width.get();
-    let block_height = block_dim.height.get();
-    let block_depth = block_dim.depth.get();
+impl Default for SurfaceParams<'_> {
+    /// A 1x1 R8G8B8A8 2D surface with a single mipmap and array layer.
+    fn default() -> Self {
+        Self {
+            width: 1,
+            height: 1,
+            depth: 1,
+            block_dim: BlockDim::uncompressed(),
+            block_height_mip0: None,
+            block_height_heuristic: BlockHeightHeuristic::Trm,
+            bytes_per_pixel: 4,
+            mipmap_count: 1,
+            layer_count: 1,
+            packed_mip_tail: false,
+            mip_block_heights: None,
+            layer_alignment: None,
+            max_surface_size: None,
+        }
+    }
+}
 
-    // The block height can be inferred if not specified.
-    // TODO: Enforce a block height of 1 for depth textures elsewhere?
-    let block_height_mip0 = if depth == 1 {
-        block_height_mip0
-            .unwrap_or_else(|| crate::block_height_mip0(div_round_up(height, block_height)))
-    } else {
-        BlockHeight::One
-    };
+/// The order of the array layers and mipmaps in the untiled data for [SurfaceParams::swizzle_ordered].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InputOrdering {
+    /// All the mipmaps of the first array layer followed by the mipmaps of the next layer.
+    /// This is the layout of DDS files and the untiled data for [SurfaceParams::swizzle].
+    #[default]
+    LayerMajor,
+    /// The base mipmap of every array layer followed by the next mipmap of every layer.
+    /// This is the layout of KTX files.
+    MipMajor,
+}
 
-    // TODO: Don't assume block_depth is 1?
-    let block_depth_mip0 = crate::blockdepth::block_depth(depth);
+impl<'a> SurfaceParams<'a> {
+    /// Tiles all the array layers and mipmaps in `source` using the block linear algorithm
+    /// to a new vector with appropriate mipmap and layer alignment.
+    ///
+    /// Returns [SwizzleError::NotEnoughMipData] if `source` does not have
+    /// at least as many bytes as [SurfaceParams::deswizzled_size].
+    #[cfg(not(feature = "minimal"))]
+    pub fn swizzle(&self, source: &[u8]) -> Result<Vec<u8>> {
+        swizzle_surface_params::<_, false>(&TegraBlockLinear, self, source)
+    }
 
-    let mut src_offset = 0;
-    let mut dst_offset = 0;
-    for _ in 0..layer_count {
-        for mip in 0..mipmap_count {
-            let mip_width = max(div_round_up(width >> mip, block_width), 1);
-            let mip_height = max(div_round_up(height >> mip, block_height), 1);
-            let mip_depth = max(div_round_up(depth >> mip, block_depth), 1);
+    /// Untiles all the array layers and mipmaps in `source` using the block linear algorithm
+    /// to a new vector without any padding between layers or mipmaps.
+    ///
+    /// Returns [SwizzleError::NotEnoughMipData] if `source` does not have
+    /// at least as many bytes as [SurfaceParams::swizzled_size].
+    #[cfg(not(feature = "minimal"))]
+    pub fn deswizzle(&self, source: &[u8]) -> Result<Vec<u8>> {
+        swizzle_surface_params::<_, true>(&TegraBlockLinear, self, source)
+    }
 
-            let mip_block_height = mip_block_height(mip_height, block_height_mip0);
-            let mip_block_depth = mip_block_depth(mip_depth, block_depth_mip0);
+    /// Tiles all the array layers and mipmaps in `source` into `destination`.
+    /// This avoids allocating a new buffer when tiling many surfaces of the same size.
+    ///
+    /// Returns [SwizzleError::NotEnoughData] if `destination` does not have
+    /// at least as many bytes as [SurfaceParams::swizzled_size].
+    /// Only the first [SurfaceParams::swizzled_size] many bytes of `destination` are modified.
+    /// Padding bytes are set to zero, so reusing `destination` produces the same output as [SurfaceParams::swizzle].
+    pub fn swizzle_into(&self, source: &[u8], destination: &mut [u8]) -> Result<()> {
+        swizzle_surface_params_into::<_, _, false>(&TegraBlockLinear, self, source, destination)
+    }
 
-            swizzle_mipmap::<DESWIZZLE>(
-                mip_width,
-                mip_height,
-                mip_depth,
-                mip_block_height,
-                mip_block_depth,
-                bytes_per_pixel,
-                source,
-                &mut src_offset,
-                result,
-                &mut dst_offset,
-            )?;
-        }
+    /// Untiles all the array layers and mipmaps in `source` into `destination`.
+    /// This avoids allocating a new buffer when untiling many surfaces of the same size.
+    ///
+    /// Returns [SwizzleError::NotEnoughData] if `destination` does not have
+    /// at least as many bytes as [SurfaceParams::deswizzled_size].
+    /// Only the first [SurfaceParams::deswizzled_size] many bytes of `destination` are modified.
+    pub fn deswizzle_into(&self, source: &[u8], destination: &mut [u8]) -> Result<()> {
+        swizzle_surface_params_into::<_, _, true>(&TegraBlockLinear, self, source, destination)
+    }
 
-        // Align offsets between array layers.
-        if layer_count > 1 {
-            if DESWIZZLE {
-                src_offset = align_layer_size(src_offset, height, depth, block_height_mip0, 1);
-            } else {
-                dst_offset = align_layer_size(dst_offset, height, depth, block_height_mip0, 1);
-            }
-        }
+    /// Tiles all the array layers and mipmaps in `source` into the container `destination`.
+    /// Growable containers are resized to [SurfaceParams::swizzled_size] bytes.
+    /// See [crate::buffer] for the supported containers.
+    ///
+    /// Returns [SwizzleError::NotEnoughData] if `destination` is a slice with
+    /// fewer bytes than [SurfaceParams::swizzled_size].
+    /// Padding bytes are set to zero like [SurfaceParams::swizzle_into].
+    pub fn swizzle_to<D: DestBuffer + ?Sized>(
+        &self,
+        source: &[u8],
+        destination: &mut D,
+    ) -> Result<()> {
+        swizzle_surface_params_into::<_, _, false>(&TegraBlockLinear, self, source, destination)
     }
 
-    Ok(())
-}
+    /// Untiles all the array layers and mipmaps in `source` into the container `destination`.
+    /// Growable containers are resized to [SurfaceParams::deswizzled_size] bytes.
+    /// See [crate::buffer] for the supported containers.
+    ///
+    /// Returns [SwizzleError::NotEnoughData] if `destination` is a slice with
+    /// fewer bytes than [SurfaceParams::deswizzled_size].
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::surface::{BlockDim, SurfaceParams};
 
-fn surface_destination<const DESWIZZLE: bool>(
-    width: u32,
-    height: u32,
-    depth: u32,
-    block_dim: BlockDim,
-    block_height_mip0: Option<BlockHeight>,
-    bytes_per_pixel: u32,
-    mipmap_count: u32,
-    layer_count: u32,
-    source: &[u8],
-) -> Result<Vec<u8>, SwizzleError> {
-    let swizzled_size = swizzled_surface_size(
-        width,
-        height,
-        depth,
-        block_dim,
-        block_height_mip0,
-        bytes_per_pixel,
-        mipmap_count,
-        layer_count,
-    );
-    let deswizzled_size = deswizzled_surface_size(
-        width,
-        height,
-        depth,
-        block_dim,
-        bytes_per_pixel,
-        mipmap_count,
-        layer_count,
-    );
-    let (surface_size, expected_size) = if DESWIZZLE {
-        (deswizzled_size, swizzled_size)
-    } else {
-        (swizzled_size, deswizzled_size)
+    let params = SurfaceParams {
+        width: 256,
+        height: 256,
+        block_dim: BlockDim::block_4x4(),
+        bytes_per_pixel: 16,
+        mipmap_count: 9,
+        ..Default::default()
     };
+    # let input = vec![0u8; params.swizzled_size()];
+    // Reuse the same allocation for each surface.
+    let mut output = Vec::new();
+    params.deswizzle_to(&input, &mut output).unwrap();
+    assert_eq!(params.deswizzled_size(), output.len());
+    ```
+     */
+    pub fn deswizzle_to<D: DestBuffer + ?Sized>(
+        &self,
+        source: &[u8],
+        destination: &mut D,
+    ) -> Result<()> {
+        swizzle_surface_params_into::<_, _, true>(&TegraBlockLinear, self, source, destination)
+    }
 
-    // Validate the source length before attempting to allocate.
-    // This reduces potential out of memory panics.
-    if source.len() < expected_size {
-        return Err(SwizzleError::NotEnoughData {
-            actual_size: source.len(),
-            expected_size,
-        });
+    /// Tiles all the array layers and mipmaps in `source` to a new vector allocated with `alloc`.
+    /// The vector is allocated once with a capacity of exactly [SurfaceParams::swizzled_size] bytes
+    /// after checking the parameters and the length of `source`.
+    /// See [SurfaceParams::swizzle].
+    #[cfg(all(feature = "allocator-api2", not(feature = "minimal")))]
+    pub fn swizzle_in<A: allocator_api2::alloc::Allocator>(
+        &self,
+        source: &[u8],
+        alloc: A,
+    ) -> Result<allocator_api2::vec::Vec<u8, A>> {
+        // Only allocate after validating the parameters and source length.
+        let mut destination = allocator_api2::vec::Vec::new_in(alloc);
+        self.swizzle_to(source, &mut destination)?;
+        Ok(destination)
     }
 
-    // Assume the calculated size is accurate, so don't reallocate later.
-    Ok(vec![0u8; surface_size])
-}
+    /// Untiles all the array layers and mipmaps in `source` to a new vector allocated with `alloc`.
+    /// The vector is allocated once with a capacity of exactly [SurfaceParams::deswizzled_size] bytes
+    /// after checking the parameters and the length of `source`.
+    /// See [SurfaceParams::deswizzle].
+    #[cfg(all(feature = "allocator-api2", not(feature = "minimal")))]
+    pub fn deswizzle_in<A: allocator_api2::alloc::Allocator>(
+        &self,
+        source: &[u8],
+        alloc: A,
+    ) -> Result<allocator_api2::vec::Vec<u8, A>> {
+        // Only allocate after validating the parameters and source length.
+        let mut destination = allocator_api2::vec::Vec::new_in(alloc);
+        self.deswizzle_to(source, &mut destination)?;
+        Ok(destination)
+    }
 
-fn validate_surface(
-    width: u32,
-    height: u32,
-    depth: u32,
-    bytes_per_pixel: u32,
-    mipmap_count: u32,
-) -> Result<(), SwizzleError> {
-    // Check dimensions to prevent overflow.
-    if width
-        .checked_mul(height)
-        .and_then(|u| u.checked_mul(depth))
-        .and_then(|u| u.checked_mul(bytes_per_pixel))
-        .is_none()
-        || width.checked_mul(bytes_per_pixel).is_none()
-        || depth.checked_add(depth / 2).is_none()
-        || mipmap_count > u32::BITS
-    {
-        Err(SwizzleError::InvalidSurface {
-            width,
-            height,
-            depth,
-            bytes_per_pixel,
-            mipmap_count,
-        })
-    } else {
+    /// Untiles all the array layers and mipmaps in `source` to a new vector of `T`
+    /// like `u32` or `[u8; 4]` for RGBA8 pixels.
+    ///
+    /// The elements contain the same bytes as the output of [SurfaceParams::deswizzle].
+    /// This avoids unsafe casts of the untiled bytes for processing pixels.
+    /// Tiling typed data only needs a safe cast with `bytemuck::cast_slice`.
+    ///
+    /// Returns [SwizzleError::InvalidElementSize] if [SurfaceParams::bytes_per_pixel]
+    /// is not a multiple of the size of `T`.
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::surface::SurfaceParams;
+
+    let params = SurfaceParams {
+        width: 256,
+        height: 256,
+        bytes_per_pixel: 4,
+        ..Default::default()
+    };
+    # let tiled = vec![0u8; params.swizzled_size()];
+    let pixels: Vec<[u8; 4]> = params.deswizzle_as(&tiled)?;
+    assert_eq!(256 * 256, pixels.len());
+
+    let tiled = params.swizzle(bytemuck::cast_slice(&pixels))?;
+    # Ok::<(), tegra_swizzle::SwizzleError>(())
+    ```
+     */
+    #[cfg(all(feature = "bytemuck", not(feature = "minimal")))]
+    pub fn deswizzle_as<T: bytemuck::Pod>(&self, source: &[u8]) -> Result<Vec<T>> {
+        let element_size = core::mem::size_of::<T>();
+        if element_size == 0 || self.bytes_per_pixel as usize % element_size != 0 {
+            return Err(SwizzleError::InvalidElementSize {
+                bytes_per_pixel: self.bytes_per_pixel,
+                element_size,
+            });
+        }
+
+        if is_empty(self) {
+            return Ok(Vec::new());
+        }
+
+        // Validate the parameters and source length before allocating.
+        validate_params(self)?;
+        let layout = SurfaceLayout::new(&TegraBlockLinear, self);
+        let size = surface_destination_size::<true>(&layout, source)?;
+
+        let mut destination = vec![T::zeroed(); size / element_size];
+        self.deswizzle_into(source, bytemuck::cast_slice_mut(&mut destination))?;
+        Ok(destination)
+    }
+
+    /// Tiles all the array layers and mipmaps in `source` with the layers and mipmaps in the order `ordering`.
+    ///
+    /// The mipmaps are tiled directly from their location in `source`,
+    /// so data from other formats doesn't need to be rearranged first.
+    /// The output is the same as tiling the data rearranged to [InputOrdering::LayerMajor] with [SurfaceParams::swizzle].
+    ///
+    /// Returns [SwizzleError::NotEnoughMipData] with the first layer and mipmap in `ordering` that ends after `source`
+    /// if `source` does not have at least as many bytes as [SurfaceParams::deswizzled_size].
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::surface::{BlockDim, InputOrdering, SurfaceParams};
+
+    // A BC7 array texture with the base mipmap of each layer first.
+    let params = SurfaceParams {
+        width: 64,
+        height: 64,
+        block_dim: BlockDim::block_4x4(),
+        bytes_per_pixel: 16,
+        mipmap_count: 7,
+        layer_count: 4,
+        ..Default::default()
+    };
+    # let ktx_data = vec![0u8; params.deswizzled_size()];
+    let tiled = params.swizzle_ordered(&ktx_data, InputOrdering::MipMajor)?;
+    assert_eq!(params.swizzled_size(), tiled.len());
+    # Ok::<(), tegra_swizzle::SwizzleError>(())
+    ```
+     */
+    #[cfg(not(feature = "minimal"))]
+    pub fn swizzle_ordered(&self, source: &[u8], ordering: InputOrdering) -> Result<Vec<u8>> {
+        match ordering {
+            InputOrdering::LayerMajor => self.swizzle(source),
+            InputOrdering::MipMajor => swizzle_mip_major(&TegraBlockLinear, self, source),
+        }
+    }
+
+    /// Tiles the array layers and mipmaps stored in separate slices.
+    /// See [swizzle_surface_from_mips].
+    #[cfg(not(feature = "minimal"))]
+    pub fn swizzle_from_mips(&self, mips: &[&[u8]]) -> Result<Vec<u8>> {
+        swizzle_from_mips_params(&TegraBlockLinear, self, mips)
+    }
+
+    /// Tiles the array layers and mipmaps written on demand by `fill`
+    /// without allocating the entire untiled surface.
+    ///
+    /// `fill` is called with the layer, the mipmap, and a zeroed buffer for the untiled data
+    /// of that mipmap in the same order as the untiled surface.
+    /// Each mipmap is tiled after `fill` returns, so the buffer can be reused for the next mipmap.
+    /// This allows procedural generators and block compression encoders to write directly
+    /// to a staging buffer the size of the largest mipmap.
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+
+    let params = SurfaceParams {
+        width: 256,
+        height: 256,
+        bytes_per_pixel: 4,
+        mipmap_count: 9,
+        layer_count: 6,
+        ..Default::default()
+    };
+    // Fill each mipmap with a solid color for debugging.
+    let tiled = params.swizzle_from_fn(|layer, mip, untiled| {
+        for pixel in untiled.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[layer as u8 * 40, mip as u8 * 28, 0, 255]);
+        }
+    })?;
+    assert_eq!(params.swizzled_size(), tiled.len());
+    # Ok::<(), tegra_swizzle::SwizzleError>(())
+    ```
+     */
+    #[cfg(not(feature = "minimal"))]
+    pub fn swizzle_from_fn<F: FnMut(u32, u32, &mut [u8])>(&self, fill: F) -> Result<Vec<u8>> {
+        let mut destination = Vec::new();
+        swizzle_from_fn_params(&TegraBlockLinear, self, fill, &mut destination)?;
+        Ok(destination)
+    }
+
+    /// Tiles the array layers and mipmaps written on demand by `fill` into the container `destination`.
+    /// See [SurfaceParams::swizzle_from_fn] and [SurfaceParams::swizzle_to].
+    #[cfg(not(feature = "minimal"))]
+    pub fn swizzle_from_fn_to<F: FnMut(u32, u32, &mut [u8]), D: DestBuffer + ?Sized>(
+        &self,
+        fill: F,
+        destination: &mut D,
+    ) -> Result<()> {
+        swizzle_from_fn_params(&TegraBlockLinear, self, fill, destination)
+    }
+
+    /// Tiles all the array layers and mipmaps in `source` using the tiling algorithm `scheme`.
+    #[cfg(not(feature = "minimal"))]
+    pub fn swizzle_with<S: TilingScheme>(&self, scheme: &S, source: &[u8]) -> Result<Vec<u8>> {
+        swizzle_surface_params::<_, false>(scheme, self, source)
+    }
+
+    /// Untiles all the array layers and mipmaps in `source` using the tiling algorithm `scheme`.
+    #[cfg(not(feature = "minimal"))]
+    pub fn deswizzle_with<S: TilingScheme>(&self, scheme: &S, source: &[u8]) -> Result<Vec<u8>> {
+        swizzle_surface_params::<_, true>(scheme, self, source)
+    }
+
+    /// Untiles all the array layers and mipmaps in `source`
+    /// and checks that tiling the result again reproduces `source`.
+    ///
+    /// This catches any bytes that would not survive converting the surface back to its tiled layout.
+    /// In addition to the work of [SurfaceParams::deswizzle], the surface is tiled twice
+    /// to compare the result and to find the padding bytes.
+    /// This makes three passes over the surface and allocates two tiled buffers
+    /// and one untiled buffer in addition to the returned data.
+    /// Padding bytes in `source` that do not contain surface data are not compared.
+    /// Returns [SwizzleError::VerificationFailed] with the offset of the first difference.
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+
+    let params = SurfaceParams {
+        width: 300,
+        height: 300,
+        block_dim: BlockDim::block_4x4(),
+        bytes_per_pixel: 16,
+        mipmap_count: 5,
+        ..Default::default()
+    };
+    # let input = vec![0u8; params.swizzled_size()];
+    let untiled = params.deswizzle_verified(&input).unwrap();
+    assert_eq!(params.deswizzle(&input).unwrap(), untiled);
+    ```
+     */
+    #[cfg(not(feature = "minimal"))]
+    pub fn deswizzle_verified(&self, source: &[u8]) -> Result<Vec<u8>> {
+        self.deswizzle_verified_with(&TegraBlockLinear, source)
+    }
+
+    /// Untiles all the array layers and mipmaps in `source` using the tiling algorithm `scheme`
+    /// and checks that tiling the result again reproduces `source`.
+    /// See [SurfaceParams::deswizzle_verified].
+    #[cfg(not(feature = "minimal"))]
+    pub fn deswizzle_verified_with<S: TilingScheme>(
+        &self,
+        scheme: &S,
+        source: &[u8],
+    ) -> Result<Vec<u8>> {
+        let untiled = self.deswizzle_with(scheme, source)?;
+        let tiled = self.swizzle_with(scheme, &untiled)?;
+
+        // Padding bytes are never written while tiling.
+        // Tiling a surface without any zero bytes finds the bytes with surface data.
+        let mask = self.swizzle_with(scheme, &vec![0xFF; untiled.len()])?;
+
+        match tiled
+            .iter()
+            .zip(source)
+            .zip(&mask)
+            .position(|((a, b), m)| *m != 0 && a != b)
+        {
+            Some(offset) => Err(SwizzleError::VerificationFailed { offset }),
+            None => Ok(untiled),
+        }
+    }
+
+    /// Tiles all the array layers and mipmaps in `source` and stops early once `cancel` is set.
+    /// See [SurfaceParams::swizzle_into_cancellable].
+    #[cfg(not(feature = "minimal"))]
+    pub fn swizzle_cancellable(&self, source: &[u8], cancel: &AtomicBool) -> Result<Vec<u8>> {
+        let result =
+            swizzle_surface_params::<_, false>(&CancellableBlockLinear(cancel), self, source);
+        check_cancelled(cancel, result)
+    }
+
+    /// Untiles all the array layers and mipmaps in `source` and stops early once `cancel` is set.
+    /// See [SurfaceParams::deswizzle_into_cancellable].
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use std::sync::atomic::AtomicBool;
+    use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+
+    let params = SurfaceParams {
+        width: 512,
+        height: 512,
+        block_dim: BlockDim::block_4x4(),
+        bytes_per_pixel: 16,
+        mipmap_count: 10,
+        ..Default::default()
+    };
+    # let input_file = vec![0u8; params.swizzled_size()];
+    // Another thread like a UI thread can set the flag to stop untiling.
+    let cancel = AtomicBool::new(false);
+    let output = params.deswizzle_cancellable(&input_file, &cancel).unwrap();
+    ```
+     */
+    #[cfg(not(feature = "minimal"))]
+    pub fn deswizzle_cancellable(&self, source: &[u8], cancel: &AtomicBool) -> Result<Vec<u8>> {
+        let result =
+            swizzle_surface_params::<_, true>(&CancellableBlockLinear(cancel), self, source);
+        check_cancelled(cancel, result)
+    }
+
+    /// Tiles all the array layers and mipmaps in `source` into `destination` and stops early once `cancel` is set.
+    /// This works the same as [SurfaceParams::swizzle_into] but checks `cancel`
+    /// before each row of blocks for 2D mipmaps and before each 3D mipmap.
+    ///
+    /// Returns [SwizzleError::Cancelled] if `cancel` was set before tiling finished.
+    /// The contents of `destination` are unspecified after cancellation.
+    pub fn swizzle_into_cancellable(
+        &self,
+        source: &[u8],
+        destination: &mut [u8],
+        cancel: &AtomicBool,
+    ) -> Result<()> {
+        let result = swizzle_surface_params_into::<_, _, false>(
+            &CancellableBlockLinear(cancel),
+            self,
+            source,
+            destination,
+        );
+        check_cancelled(cancel, result)
+    }
+
+    /// Untiles all the array layers and mipmaps in `source` into `destination` and stops early once `cancel` is set.
+    /// This works the same as [SurfaceParams::deswizzle_into] but checks `cancel`
+    /// before each row of blocks for 2D mipmaps and before each 3D mipmap.
+    ///
+    /// Returns [SwizzleError::Cancelled] if `cancel` was set before untiling finished.
+    /// The contents of `destination` are unspecified after cancellation.
+    pub fn deswizzle_into_cancellable(
+        &self,
+        source: &[u8],
+        destination: &mut [u8],
+        cancel: &AtomicBool,
+    ) -> Result<()> {
+        let result = swizzle_surface_params_into::<_, _, true>(
+            &CancellableBlockLinear(cancel),
+            self,
+            source,
+            destination,
+        );
+        check_cancelled(cancel, result)
+    }
+
+    /// Tiles all the array layers and mipmaps in `source` and reports the progress to `progress`.
+    /// See [SurfaceParams::deswizzle_with_progress].
+    #[cfg(not(feature = "minimal"))]
+    pub fn swizzle_with_progress<F: FnMut(usize, usize)>(
+        &self,
+        source: &[u8],
+        progress: F,
+    ) -> Result<Vec<u8>> {
+        let scheme = ProgressBlockLinear::new(progress, progress_total(self));
+        let result = swizzle_surface_params::<_, false>(&scheme, self, source)?;
+        scheme.finish();
+        Ok(result)
+    }
+
+    /// Untiles all the array layers and mipmaps in `source` and reports the progress to `progress`.
+    ///
+    /// `progress` is called with the number of untiled bytes finished so far
+    /// and the total [SurfaceParams::deswizzled_size] after each row of blocks for 2D mipmaps
+    /// and after each 3D mipmap.
+    /// The finished bytes increase with each call, and the last call always reports the total.
+    /// The callback is not called if the parameters or `source` are invalid.
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+
+    let params = SurfaceParams {
+        width: 4096,
+        height: 4096,
+        block_dim: BlockDim::block_4x4(),
+        bytes_per_pixel: 16,
+        mipmap_count: 13,
+        ..Default::default()
+    };
+    # let input_file = vec![0u8; params.swizzled_size()];
+    let output = params.deswizzle_with_progress(&input_file, |done, total| {
+        // Update a progress bar in a GUI.
+        let percent = done as f64 / total as f64 * 100.0;
+    })?;
+    # Ok::<(), tegra_swizzle::SwizzleError>(())
+    ```
+     */
+    #[cfg(not(feature = "minimal"))]
+    pub fn deswizzle_with_progress<F: FnMut(usize, usize)>(
+        &self,
+        source: &[u8],
+        progress: F,
+    ) -> Result<Vec<u8>> {
+        let scheme = ProgressBlockLinear::new(progress, progress_total(self));
+        let result = swizzle_surface_params::<_, true>(&scheme, self, source)?;
+        scheme.finish();
+        Ok(result)
+    }
+
+    /// Tiles all the array layers and mipmaps in `source` into `destination`
+    /// and reports the progress to `progress`.
+    /// This works the same as [SurfaceParams::swizzle_into].
+    /// See [SurfaceParams::deswizzle_with_progress] for when `progress` is called.
+    pub fn swizzle_into_with_progress<F: FnMut(usize, usize)>(
+        &self,
+        source: &[u8],
+        destination: &mut [u8],
+        progress: F,
+    ) -> Result<()> {
+        let scheme = ProgressBlockLinear::new(progress, progress_total(self));
+        swizzle_surface_params_into::<_, _, false>(&scheme, self, source, destination)?;
+        scheme.finish();
         Ok(())
     }
+
+    /// Untiles all the array layers and mipmaps in `source` into `destination`
+    /// and reports the progress to `progress`.
+    /// This works the same as [SurfaceParams::deswizzle_into].
+    /// See [SurfaceParams::deswizzle_with_progress] for when `progress` is called.
+    pub fn deswizzle_into_with_progress<F: FnMut(usize, usize)>(
+        &self,
+        source: &[u8],
+        destination: &mut [u8],
+        progress: F,
+    ) -> Result<()> {
+        let scheme = ProgressBlockLinear::new(progress, progress_total(self));
+        swizzle_surface_params_into::<_, _, true>(&scheme, self, source, destination)?;
+        scheme.finish();
+        Ok(())
+    }
+
+    /// Calculates the size in bytes for the tiled data.
+    /// This can be calculated at compile time for known surface parameters.
+    pub const fn swizzled_size(&self) -> usize {
+        layer_stride_tiled(self) * self.layer_count as usize
+    }
+
+    /// Calculates the size in bytes for the tiled data using the tiling algorithm `scheme`.
+    ///
+    /// This is equivalent to [SurfaceParams::swizzled_size] for [TegraBlockLinear].
+    pub fn swizzled_size_with<S: TilingScheme>(&self, scheme: &S) -> usize {
+        SurfaceLayout::new(scheme, self).swizzled_size()
+    }
+
+    /// Calculates the size in bytes for the untiled or linear data.
+    /// This can be calculated at compile time for known surface parameters.
+    pub const fn deswizzled_size(&self) -> usize {
+        layer_stride_linear(self) * self.layer_count as usize
+    }
+
+    /// Returns the parameters for a partial mipmap chain with only the mipmaps starting from `first_mip`.
+    ///
+    /// Streaming systems often only store the smaller mipmaps of large textures.
+    /// The block heights for the stored mipmaps are calculated from the base mip level of `self`
+    /// to match the layout of the full mipmap chain.
+    /// The returned parameters use the dimensions of `first_mip` with an explicit block height.
+    /// Layers are aligned using the first stored mipmap unless `layer_alignment` is set.
+    ///
+    /// The returned surface is empty if `first_mip` is not less than `mipmap_count`.
+    /// Returns [SwizzleError::Unsupported] for 3D surfaces with a `first_mip` greater than 0.
+    ///
+    /// # Examples
+    /**
+    ```rust
+    use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+
+    // A 1024x1024 BC7 texture with only mipmaps 2 to 10 stored in the file.
+    let params = SurfaceParams {
+        width: 1024,
+        height: 1024,
+        block_dim: BlockDim::block_4x4(),
+        bytes_per_pixel: 16,
+        mipmap_count: 11,
+        ..Default::default()
+    };
+    let resident = params.partial_mip_chain(2)?;
+    assert_eq!((256, 256, 9), (resident.width, resident.height, resident.mipmap_count));
+
+    # let input = vec![0u8; resident.swizzled_size()];
+    let output = resident.deswizzle(&input)?;
+    # Ok::<(), tegra_swizzle::SwizzleError>(())
+    ```
+     */
+    pub const fn partial_mip_chain(&self, first_mip: u32) -> Result<Self> {
+        if first_mip == 0 {
+            return Ok(*self);
+        }
+        if self.depth > 1 {
+            // The block depth is calculated from the depth of the first stored mipmap.
+            return Err(SwizzleError::Unsupported {
+                feature: "partial mipmap chains for 3D surfaces",
+            });
+        }
+        if first_mip >= self.mipmap_count || first_mip >= u32::BITS {
+            return Ok(Self {
+                mipmap_count: 0,
+                ..*self
+            });
+        }
+
+        let (_, mip_height, _) = mip_dimensions(self, first_mip);
+        let block_height = surface_mip_block_height(self, first_mip, mip_height);
+
+        // Missing block heights are still reported as NotEnoughBlockHeights.
+        let mip_block_heights = match self.mip_block_heights {
+            Some(block_heights) if block_heights.len() >= first_mip as usize => {
+                Some(block_heights.split_at(first_mip as usize).1)
+            }
+            Some(block_heights) => Some(block_heights.split_at(0).0),
+            None => None,
+        };
+
+        Ok(Self {
+            width: max_u32(self.width >> first_mip, 1),
+            height: max_u32(self.height >> first_mip, 1),
+            block_height_mip0: Some(block_height),
+            mipmap_count: self.mipmap_count - first_mip,
+            mip_block_heights,
+            ..*self
+        })
+    }
 }
 
-// TODO: Add examples.
-/// Calculates the size in bytes for the tiled data for the given surface.
-/// Compare with [deswizzled_surface_size].
+/// Calculates the offset in bytes between the start of consecutive array layers in the tiled data.
 ///
-/// Dimensions should be in pixels.
-///
-/// Use a `block_height_mip0` of [None] to infer the block height from the specified dimensions.
-pub fn swizzled_surface_size(
-    width: u32,
-    height: u32,
-    depth: u32,
-    block_dim: BlockDim, // TODO: Use None to indicate uncompressed?
-    block_height_mip0: Option<BlockHeight>, // TODO: Make this optional in other functions as well?
-    bytes_per_pixel: u32,
-    mipmap_count: u32,
-    layer_count: u32,
-) -> usize {
-    let block_width = block_dim.width.get();
-    let block_height = block_dim.height.get();
-    let block_depth = block_dim.depth.get();
+/// This includes any padding for aligning the start of each layer.
+/// The layers for surfaces with only a single layer are not aligned,
+/// so the stride is always [SurfaceParams::swizzled_size] divided by the layer count.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{layer_stride_tiled, BlockDim, SurfaceParams};
 
-    // The block height can be inferred if not specified.
-    // TODO: Enforce a block height of 1 for depth textures elsewhere?
-    let block_height_mip0 = if depth == 1 {
-        block_height_mip0
-            .unwrap_or_else(|| crate::block_height_mip0(div_round_up(height, block_height)))
-    } else {
-        BlockHeight::One
-    };
+// 16x16 BC7 cube map with 5 mipmaps.
+let params = SurfaceParams {
+    width: 16,
+    height: 16,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 5,
+    layer_count: 6,
+    ..Default::default()
+};
+assert_eq!(2560, layer_stride_tiled(&params));
+assert_eq!(params.swizzled_size(), 6 * layer_stride_tiled(&params));
+```
+ */
+pub const fn layer_stride_tiled(params: &SurfaceParams) -> usize {
+    let tail_start = mip_tail_start(params);
 
-    let mut mip_size = 0;
-    for mip in 0..mipmap_count {
-        let mip_width = max(div_round_up(width >> mip, block_width), 1);
-        let mip_height = max(div_round_up(height >> mip, block_height), 1);
-        let mip_depth = max(div_round_up(depth >> mip, block_depth), 1);
-        let mip_block_height = mip_block_height(mip_height, block_height_mip0);
+    let mut layer_size = 0;
+    let mut mip = 0;
+    while mip < tail_start {
+        let (mip_width, mip_height, mip_depth) = mip_dimensions(params, mip);
+        let mip_block_height = surface_mip_block_height(params, mip, mip_height);
 
-        mip_size += swizzled_mip_size(
+        layer_size += swizzled_mip_size(
             mip_width,
             mip_height,
             mip_depth,
             mip_block_height,
-            bytes_per_pixel,
-        )
+            params.bytes_per_pixel,
+        );
+        mip += 1;
     }
+    layer_size += mip_tail_gob_count(params, tail_start) * GOB_SIZE_IN_BYTES as usize;
 
-    if layer_count > 1 {
+    if params.layer_count > 1 {
         // We only need alignment between layers.
-        let layer_size = align_layer_size(mip_size, height, depth, block_height_mip0, 1);
-        layer_size * layer_count as usize
+        surface_layer_size(params, layer_size)
     } else {
-        mip_size
+        layer_size
     }
 }
 
-// TODO: Add examples.
-/// Calculates the size in bytes for the untiled or linear data for the given surface.
-/// Compare with [swizzled_surface_size].
+/// Calculates the offset in bytes between the start of consecutive array layers in the untiled data.
 ///
-/// Dimensions should be in pixels.
-pub fn deswizzled_surface_size(
-    width: u32,
-    height: u32,
-    depth: u32,
-    block_dim: BlockDim,
-    bytes_per_pixel: u32,
-    mipmap_count: u32,
-    layer_count: u32,
+/// The untiled data is tightly packed, so this is the size of all the mipmaps for a single layer.
+/// The stride is always [SurfaceParams::deswizzled_size] divided by the layer count.
+pub const fn layer_stride_linear(params: &SurfaceParams) -> usize {
+    let mut layer_size = 0;
+    let mut mip = 0;
+    while mip < params.mipmap_count {
+        let (mip_width, mip_height, mip_depth) = mip_dimensions(params, mip);
+        layer_size += deswizzled_mip_size(mip_width, mip_height, mip_depth, params.bytes_per_pixel);
+        mip += 1;
+    }
+    layer_size
+}
+
+/// Calculates the largest [SurfaceParams::swizzled_size] for all the surfaces in `params`.
+///
+/// A buffer with this size can be reused as the destination for [SurfaceParams::swizzle_into]
+/// or the source for [SurfaceParams::deswizzle_into] for any of the surfaces.
+/// Empty and invalid surfaces do not affect the result since they never read or write any data.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{max_swizzled_size, BlockDim, SurfaceParams};
+
+let surfaces = [
+    SurfaceParams {
+        width: 64,
+        height: 64,
+        ..Default::default()
+    },
+    SurfaceParams {
+        width: 16,
+        height: 16,
+        block_dim: BlockDim::block_4x4(),
+        bytes_per_pixel: 16,
+        mipmap_count: 5,
+        layer_count: 6,
+        ..Default::default()
+    },
+];
+let mut buffer = vec![0u8; max_swizzled_size(&surfaces)];
+```
+ */
+pub fn max_swizzled_size<'a, 'b: 'a>(
+    params: impl IntoIterator<Item = &'a SurfaceParams<'b>>,
 ) -> usize {
-    // TODO: Avoid duplicating this code.
-    let block_width = block_dim.width.get();
-    let block_height = block_dim.height.get();
-    let block_depth = block_dim.depth.get();
+    params
+        .into_iter()
+        .filter(|p| is_valid(p))
+        .map(|p| p.swizzled_size())
+        .max()
+        .unwrap_or(0)
+}
 
-    let mut layer_size = 0;
-    for mip in 0..mipmap_count {
-        let mip_width = max(div_round_up(width >> mip, block_width), 1);
-        let mip_height = max(div_round_up(height >> mip, block_height), 1);
-        let mip_depth = max(div_round_up(depth >> mip, block_depth), 1);
-        layer_size += deswizzled_mip_size(mip_width, mip_height, mip_depth, bytes_per_pixel)
+/// Calculates the largest [SurfaceParams::deswizzled_size] for all the surfaces in `params`.
+///
+/// A buffer with this size can be reused as the destination for [SurfaceParams::deswizzle_into]
+/// or the source for [SurfaceParams::swizzle_into] for any of the surfaces.
+/// Empty and invalid surfaces do not affect the result since they never read or write any data.
+pub fn max_deswizzled_size<'a, 'b: 'a>(
+    params: impl IntoIterator<Item = &'a SurfaceParams<'b>>,
+) -> usize {
+    params
+        .into_iter()
+        .filter(|p| is_valid(p))
+        .map(|p| p.deswizzled_size())
+        .max()
+        .unwrap_or(0)
+}
+
+/// The sizes in bytes for the tiled and untiled data of a surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurfaceSizes {
+    /// The size of the tiled data. See [SurfaceParams::swizzled_size].
+    pub tiled: usize,
+    /// The size of the untiled or linear data. See [SurfaceParams::deswizzled_size].
+    pub linear: usize,
+}
+
+/// Calculates the tiled and untiled sizes for `params`.
+///
+/// This only iterates over the mipmaps once and is faster than calling
+/// [SurfaceParams::swizzled_size] and [SurfaceParams::deswizzled_size] separately.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{surface_sizes, BlockDim, SurfaceParams, SurfaceSizes};
+
+// 16x16 BC7 cube map with 5 mipmaps.
+const SIZES: SurfaceSizes = surface_sizes(&SurfaceParams {
+    width: 16,
+    height: 16,
+    depth: 1,
+    block_dim: BlockDim::block_4x4(),
+    block_height_mip0: None,
+    block_height_heuristic: tegra_swizzle::BlockHeightHeuristic::Trm,
+    bytes_per_pixel: 16,
+    mipmap_count: 5,
+    layer_count: 6,
+    packed_mip_tail: false,
+    mip_block_heights: None,
+    layer_alignment: None,
+    max_surface_size: None,
+});
+assert_eq!(15360, SIZES.tiled);
+assert_eq!(2208, SIZES.linear);
+```
+ */
+pub const fn surface_sizes(params: &SurfaceParams) -> SurfaceSizes {
+    let tail_start = mip_tail_start(params);
+
+    let mut tiled = 0;
+    let mut linear = 0;
+    let mut mip = 0;
+    while mip < params.mipmap_count {
+        let (mip_width, mip_height, mip_depth) = mip_dimensions(params, mip);
+        linear += deswizzled_mip_size(mip_width, mip_height, mip_depth, params.bytes_per_pixel);
+
+        if mip < tail_start {
+            tiled += swizzled_mip_size(
+                mip_width,
+                mip_height,
+                mip_depth,
+                surface_mip_block_height(params, mip, mip_height),
+                params.bytes_per_pixel,
+            );
+        }
+        mip += 1;
+    }
+    tiled += mip_tail_gob_count(params, tail_start) * GOB_SIZE_IN_BYTES as usize;
+
+    if params.layer_count > 1 {
+        // We only need alignment between layers.
+        tiled = surface_layer_size(params, tiled);
+    }
+
+    SurfaceSizes {
+        tiled: tiled * params.layer_count as usize,
+        linear: linear * params.layer_count as usize,
+    }
+}
+
+/// The storage cost of the padding in the tiled data of a surface.
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaddingInfo {
+    /// The difference between the tiled and untiled sizes in bytes.
+    pub padded_bytes: usize,
+    /// The padding as a percentage of the tiled size from `0.0` to `100.0`.
+    pub percent: f32,
+    /// The padding in bytes for each mipmap summed over all array layers.
+    ///
+    /// Mipmaps in a packed mip tail share GOBs,
+    /// so the padding for the tail is assigned to the first mipmap in the tail.
+    /// The alignment between array layers is only included in [PaddingInfo::padded_bytes].
+    pub per_mip: Vec<usize>,
+}
+
+/// Calculates how many bytes of the tiled data for `params` are used for padding.
+///
+/// The tiled data is padded to whole GOBs and blocks of GOBs for each mipmap.
+/// Larger block heights reduce the number of address calculations
+/// but may waste more space for surfaces with a small height.
+///
+/// Returns [SwizzleError::InvalidSurface] or other errors for invalid parameters
+/// like [SurfaceParams::swizzle] instead of calculating sizes that could overflow.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{padding_overhead, SurfaceParams};
+
+// The 1x1 mipmap still takes up an entire GOB.
+let params = SurfaceParams {
+    width: 1,
+    height: 1,
+    ..Default::default()
+};
+let padding = padding_overhead(&params)?;
+assert_eq!(508, padding.padded_bytes);
+assert_eq!(vec![508], padding.per_mip);
+# Ok::<(), tegra_swizzle::SwizzleError>(())
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn padding_overhead(params: &SurfaceParams) -> Result<PaddingInfo> {
+    // Validate first to also limit the mipmap count for empty surfaces.
+    validate_params(params)?;
+
+    if is_empty(params) {
+        return Ok(PaddingInfo {
+            padded_bytes: 0,
+            percent: 0.0,
+            per_mip: vec![0; params.mipmap_count as usize],
+        });
+    }
+
+    let tail_start = mip_tail_start(params);
+    let layer_count = params.layer_count as usize;
+
+    let per_mip = (0..params.mipmap_count)
+        .map(|mip| {
+            let (mip_width, mip_height, mip_depth) = mip_dimensions(params, mip);
+            let (tiled, linear) = match mip.cmp(&tail_start) {
+                core::cmp::Ordering::Less => (
+                    swizzled_mip_size(
+                        mip_width,
+                        mip_height,
+                        mip_depth,
+                        surface_mip_block_height(params, mip, mip_height),
+                        params.bytes_per_pixel,
+                    ),
+                    deswizzled_mip_size(mip_width, mip_height, mip_depth, params.bytes_per_pixel),
+                ),
+                core::cmp::Ordering::Equal => (
+                    mip_tail_gob_count(params, tail_start) * GOB_SIZE_IN_BYTES as usize,
+                    tail_deswizzled_size(params, tail_start),
+                ),
+                core::cmp::Ordering::Greater => (0, 0),
+            };
+            tiled.saturating_sub(linear) * layer_count
+        })
+        .collect();
+
+    let sizes = surface_sizes(params);
+    let padded_bytes = sizes.tiled.saturating_sub(sizes.linear);
+    let percent = if sizes.tiled > 0 {
+        padded_bytes as f32 / sizes.tiled as f32 * 100.0
+    } else {
+        0.0
+    };
+
+    Ok(PaddingInfo {
+        padded_bytes,
+        percent,
+        per_mip,
+    })
+}
+
+/// Finds the byte ranges of the tiled data for `params` that are only used for padding.
+///
+/// Padding bytes are never read when untiling and are set to zero when tiling.
+/// Clearing these ranges normalizes tiled data from other tools before comparing or hashing files.
+/// This includes the padding to whole GOBs and blocks of GOBs for each mipmap,
+/// the unused space in the GOBs of a packed mip tail, and the alignment between array layers.
+/// Patching tools can safely zero or reuse these bytes without changing the untiled surface.
+///
+/// Ranges are sorted, do not overlap, and adjacent ranges are merged.
+/// The total length of the ranges is [PaddingInfo::padded_bytes] from [padding_overhead].
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{padded_ranges, SurfaceParams};
+
+// 8x8 R8G8B8A8 only uses the left 32 bytes of each row,
+// which are stored in the first half of the GOB.
+let params = SurfaceParams {
+    width: 8,
+    height: 8,
+    ..Default::default()
+};
+assert_eq!(vec![256..512], padded_ranges(&params));
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn padded_ranges(params: &SurfaceParams) -> Vec<core::ops::Range<usize>> {
+    let mut ranges: Vec<core::ops::Range<usize>> = Vec::new();
+    if is_empty(params) || validate_params(params).is_err() {
+        return ranges;
+    }
+
+    let mut add_padding = |range: core::ops::Range<usize>| match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    };
+
+    let layout = SurfaceLayout::new(&TegraBlockLinear, params);
+    for layer in 0..params.layer_count as usize {
+        let layer_offset = layer * layout.swizzled_layer_size;
+
+        for mip in layout.mips() {
+            let level = &mip.level;
+            let block_height = surface_mip_block_height(params, level.mip, level.height) as u32;
+            let block_depth = mip_block_depth(level.depth, block_depth(params.depth));
+            let row_size = level.width as usize * params.bytes_per_pixel as usize;
+
+            let width_in_gobs = row_size.div_ceil(GOB_WIDTH_IN_BYTES as usize);
+            let height_in_blocks =
+                div_round_up(level.height, block_height * GOB_HEIGHT_IN_BYTES) as usize;
+            let gobs_per_block = (block_height * block_depth) as usize;
+
+            // Invert the GOB ordering for blocks of GOBs in each row and slice.
+            for gob in 0..mip.swizzled_size / GOB_SIZE_IN_BYTES as usize {
+                let block = gob / gobs_per_block;
+                let gob_in_block = gob % gobs_per_block;
+                let gob_x = (block % width_in_gobs) * GOB_WIDTH_IN_BYTES as usize;
+                let block_y = (block / width_in_gobs) % height_in_blocks;
+                let block_z = block / width_in_gobs / height_in_blocks;
+                let gob_y = (block_y * block_height as usize
+                    + gob_in_block % block_height as usize)
+                    * GOB_HEIGHT_IN_BYTES as usize;
+                let z = block_z * block_depth as usize + gob_in_block / block_height as usize;
+
+                let gob_offset =
+                    layer_offset + mip.swizzled_offset + gob * GOB_SIZE_IN_BYTES as usize;
+                add_gob_padding(gob_offset, &mut add_padding, |x, y| {
+                    gob_x + x < row_size
+                        && gob_y + y < level.height as usize
+                        && z < level.depth as usize
+                });
+            }
+        }
+
+        // Each GOB of the tail contains mipmaps placed side by side.
+        let positions: Vec<_> = mip_tail_positions(params, layout.tail_start).collect();
+        for gob in 0..mip_tail_gob_count(params, layout.tail_start) {
+            let gob_offset =
+                layer_offset + layout.tail.swizzled_offset + gob * GOB_SIZE_IN_BYTES as usize;
+            add_gob_padding(gob_offset, &mut add_padding, |x, y| {
+                positions.iter().filter(|p| p.gob == gob).any(|p| {
+                    let (mip_width, mip_height, _) = mip_dimensions(params, p.mip);
+                    let row_size = (mip_width * params.bytes_per_pixel) as usize;
+                    x >= p.x as usize && x < p.x as usize + row_size && y < mip_height as usize
+                })
+            });
+        }
+
+        let layer_end = layout.tail.swizzled_offset + layout.tail.swizzled_size;
+        if layer_end < layout.swizzled_layer_size {
+            add_padding(layer_offset + layer_end..layer_offset + layout.swizzled_layer_size);
+        }
+    }
+
+    ranges
+}
+
+// Add the bytes of the GOB at `gob_offset` that don't map to an x and y byte coordinate within the GOB.
+#[cfg(not(feature = "minimal"))]
+fn add_gob_padding(
+    gob_offset: usize,
+    add_padding: &mut impl FnMut(core::ops::Range<usize>),
+    is_mapped: impl Fn(usize, usize) -> bool,
+) {
+    // Each 16 byte sector of a GOB contains consecutive bytes from a single row.
+    for sector in 0..GOB_SIZE_IN_BYTES as usize / 16 {
+        let x = ((sector >> 4) & 1) * 32 + ((sector >> 1) & 1) * 16;
+        let y = ((sector >> 2) & 3) * 2 + (sector & 1);
+        let sector_offset = gob_offset + sector * 16;
+        for i in 0..16 {
+            if !is_mapped(x + i, y) {
+                add_padding(sector_offset + i..sector_offset + i + 1);
+            }
+        }
+    }
+}
+
+/// Hashes the untiled data for all the array layers and mipmaps in `source`
+/// without allocating the untiled surface.
+///
+/// The untiled bytes are written to `hasher` in the same order as the output of [SurfaceParams::deswizzle].
+/// Bytes are buffered and written in chunks of 256 bytes with a smaller final chunk.
+/// This produces the same hash as calling [core::hash::Hasher::write]
+/// for each 256 byte chunk of the untiled data,
+/// so the results do not depend on the tiled layout or how the hasher handles separate writes.
+///
+/// Returns [SwizzleError::NotEnoughMipData] if `source` does not have
+/// at least as many bytes as [SurfaceParams::swizzled_size].
+/// # Examples
+/**
+```rust
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+use tegra_swizzle::surface::{hash_deswizzled, BlockDim, SurfaceParams};
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 9,
+    ..Default::default()
+};
+# let tiled = vec![0u8; params.swizzled_size()];
+let mut hasher = DefaultHasher::new();
+hash_deswizzled(&tiled, &params, &mut hasher).unwrap();
+
+let mut expected = DefaultHasher::new();
+for chunk in params.deswizzle(&tiled).unwrap().chunks(256) {
+    expected.write(chunk);
+}
+assert_eq!(expected.finish(), hasher.finish());
+```
+ */
+pub fn hash_deswizzled<H: core::hash::Hasher>(
+    source: &[u8],
+    params: &SurfaceParams,
+    hasher: &mut H,
+) -> Result<()> {
+    if is_empty(params) {
+        return Ok(());
+    }
+
+    validate_params(params)?;
+
+    let layout = SurfaceLayout::new(&TegraBlockLinear, params);
+    surface_destination_size::<true>(&layout, source)?;
+
+    let mut writer = ChunkWriter {
+        hasher,
+        chunk: [0u8; 256],
+        len: 0,
+    };
+    for layer in 0..params.layer_count as usize {
+        let layer_offset = layer * layout.swizzled_layer_size;
+
+        for mip in layout.mips() {
+            let level = &mip.level;
+            let block_height = surface_mip_block_height(params, level.mip, level.height) as u32;
+            let block_depth = mip_block_depth(level.depth, block_depth(params.depth));
+            let row_size = level.width * params.bytes_per_pixel;
+
+            let width_in_gobs = div_round_up(row_size, GOB_WIDTH_IN_BYTES);
+            let slice_size = slice_size(block_height, block_depth, width_in_gobs, level.height);
+            let block_size_in_bytes = GOB_SIZE_IN_BYTES * block_height * block_depth;
+
+            for z in 0..level.depth {
+                let offset_z = gob_address_z(z, block_height, block_depth, slice_size as u32);
+                for y in 0..level.height {
+                    let offset_y = gob_address_y(
+                        y,
+                        GOB_HEIGHT_IN_BYTES * block_height,
+                        block_size_in_bytes,
+                        width_in_gobs,
+                    );
+                    let row_offset =
+                        layer_offset + mip.swizzled_offset + (offset_z + offset_y) as usize;
+
+                    // Each 16 bytes of a row are contiguous in the tiled data.
+                    for x in (0..row_size).step_by(16) {
+                        let offset = row_offset
+                            + (gob_address_x(x, block_size_in_bytes) + gob_offset(x, y)) as usize;
+                        let len = 16.min(row_size - x) as usize;
+                        writer.write(&source[offset..offset + len]);
+                    }
+                }
+            }
+        }
+
+        // Untile each GOB of the tail to find the rows for each mipmap.
+        let mut positions = mip_tail_positions(params, layout.tail_start).peekable();
+        for gob in 0..mip_tail_gob_count(params, layout.tail_start) {
+            let gob_offset =
+                layer_offset + layout.tail.swizzled_offset + gob * GOB_SIZE_IN_BYTES as usize;
+            let mut linear_gob = [0u8; GOB_SIZE_IN_BYTES as usize];
+            TegraBlockLinear.untile_mip(
+                &MIP_TAIL_SURFACE,
+                &MIP_TAIL_LEVEL,
+                &source[gob_offset..],
+                &mut linear_gob,
+            );
+
+            while let Some(position) = positions.next_if(|p| p.gob == gob) {
+                let (mip_width, mip_height, _) = mip_dimensions(params, position.mip);
+                let row_size = (mip_width * params.bytes_per_pixel) as usize;
+                for y in 0..mip_height as usize {
+                    let gob_row = y * GOB_WIDTH_IN_BYTES as usize + position.x as usize;
+                    writer.write(&linear_gob[gob_row..gob_row + row_size]);
+                }
+            }
+        }
+    }
+    writer.flush();
+
+    Ok(())
+}
+
+// Buffer writes to make the hash independent of the tiled layout.
+struct ChunkWriter<'a, H> {
+    hasher: &'a mut H,
+    chunk: [u8; 256],
+    len: usize,
+}
+
+impl<H: core::hash::Hasher> ChunkWriter<'_, H> {
+    fn write(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            let count = bytes.len().min(self.chunk.len() - self.len);
+            self.chunk[self.len..self.len + count].copy_from_slice(&bytes[..count]);
+            self.len += count;
+            bytes = &bytes[count..];
+
+            if self.len == self.chunk.len() {
+                self.flush();
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.len > 0 {
+            self.hasher.write(&self.chunk[..self.len]);
+            self.len = 0;
+        }
+    }
+}
+
+/// Copies the bytes for the texels at each `(x, y, z)` in `coords`
+/// from the base mipmap of the first array layer in `source` to `destination`.
+///
+/// This is much faster than untiling the entire surface when only a few texels are needed.
+/// The texels are read in order of their offset in `source` to improve locality
+/// but written to `destination` in the same order as `coords`.
+/// The coordinates are in pixels or blocks for compressed formats.
+///
+/// Returns [SwizzleError::InvalidCoordinate] with the first coordinate outside the base mipmap.
+/// Returns [SwizzleError::NotEnoughMipData] if `source` does not contain the base mipmap.
+/// Returns [SwizzleError::NotEnoughData] if `destination` has fewer than
+/// `coords.len() * bytes_per_pixel` bytes.
+/// Returns [SwizzleError::Unsupported] if the base mipmap is part of a packed mip tail.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{read_texels, BlockDim, SurfaceParams};
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::uncompressed(),
+    bytes_per_pixel: 4,
+    mipmap_count: 9,
+    ..Default::default()
+};
+# let tiled = vec![0u8; params.swizzled_size()];
+// Sample an 8x8 grid of RGBA texels.
+let coords: Vec<_> = (0..64).map(|i| (i % 8 * 32, i / 8 * 32, 0)).collect();
+let mut texels = vec![0u8; coords.len() * 4];
+read_texels(&tiled, &params, &coords, &mut texels).unwrap();
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn read_texels(
+    source: &[u8],
+    params: &SurfaceParams,
+    coords: &[(u32, u32, u32)],
+    destination: &mut [u8],
+) -> Result<()> {
+    if is_empty(params) || coords.is_empty() {
+        return Ok(());
+    }
+
+    validate_params(params)?;
+
+    if mip_tail_start(params) == 0 {
+        return Err(SwizzleError::Unsupported {
+            feature: "reading texels from a packed mip tail",
+        });
+    }
+
+    let level = mip_level(params, 0);
+    if let Some(&(x, y, z)) = coords
+        .iter()
+        .find(|(x, y, z)| *x >= level.width || *y >= level.height || *z >= level.depth)
+    {
+        return Err(SwizzleError::InvalidCoordinate {
+            x,
+            y,
+            z,
+            width: level.width,
+            height: level.height,
+            depth: level.depth,
+        });
+    }
+
+    let bytes_per_pixel = params.bytes_per_pixel as usize;
+    let block_height = surface_mip_block_height(params, 0, level.height);
+    let expected_size = swizzled_mip_size(
+        level.width,
+        level.height,
+        level.depth,
+        block_height,
+        params.bytes_per_pixel,
+    );
+    if source.len() < expected_size {
+        return Err(SwizzleError::NotEnoughMipData {
+            layer: 0,
+            mip: 0,
+            expected_size,
+            actual_size: source.len(),
+        });
+    }
+
+    let destination_size = coords.len() * bytes_per_pixel;
+    if destination.len() < destination_size {
+        return Err(SwizzleError::NotEnoughData {
+            expected_size: destination_size,
+            actual_size: destination.len(),
+        });
+    }
+
+    // Treat each texel as bytes_per_pixel many texels with 1 byte per pixel.
+    // This also handles formats like R8G8B8 that aren't contiguous in the tiled data.
+    let row_size = level.width * params.bytes_per_pixel;
+    let byte_offset = |x: u32, y: u32, z: u32| {
+        texel_offset(
+            x,
+            y,
+            z,
+            row_size,
+            level.height,
+            level.depth,
+            block_height,
+            1,
+        )
+    };
+
+    let mut texels: Vec<_> = coords
+        .iter()
+        .enumerate()
+        .map(|(i, &(x, y, z))| (byte_offset(x * params.bytes_per_pixel, y, z), i))
+        .collect();
+    texels.sort_unstable();
+
+    for (offset, i) in texels {
+        let (x, y, z) = coords[i];
+        let texel = &mut destination[i * bytes_per_pixel..(i + 1) * bytes_per_pixel];
+        if bytes_per_pixel.is_power_of_two() && bytes_per_pixel <= 16 {
+            // Texels never cross a 16 byte segment of a GOB row.
+            texel.copy_from_slice(&source[offset..offset + bytes_per_pixel]);
+        } else {
+            for (b, byte) in texel.iter_mut().enumerate() {
+                *byte = source[byte_offset(x * params.bytes_per_pixel + b as u32, y, z)];
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A precomputed layout for tiling or untiling many surfaces with the same [SurfaceParams].
+///
+/// Emulators often convert surfaces like render targets with identical parameters every frame.
+/// The plan validates the parameters and calculates the offsets, block heights,
+/// and GOB addresses for each mipmap once using a [BlockLinearPlan],
+/// so each conversion only needs to copy bytes.
+/// The output is the same as [SurfaceParams::swizzle_into] and [SurfaceParams::deswizzle_into].
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{SurfaceParams, SurfacePlan};
+
+// A 1280x720 RGBA render target.
+let params = SurfaceParams {
+    width: 1280,
+    height: 720,
+    bytes_per_pixel: 4,
+    ..Default::default()
+};
+let plan = SurfacePlan::new(&params)?;
+
+# let frames = vec![vec![0u8; plan.swizzled_size()]; 3];
+let mut untiled = vec![0u8; plan.deswizzled_size()];
+for tiled in &frames {
+    plan.deswizzle_into(tiled, &mut untiled)?;
+}
+# Ok::<(), tegra_swizzle::SwizzleError>(())
+```
+ */
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone)]
+pub struct SurfacePlan<'a> {
+    params: SurfaceParams<'a>,
+    layout: SurfaceLayout,
+    // The GOB traversal for each mipmap before the mip tail.
+    mips: Vec<BlockLinearPlan>,
+}
+
+#[cfg(not(feature = "minimal"))]
+impl<'a> SurfacePlan<'a> {
+    /// Calculates the layout for surfaces with the parameters in `params`.
+    ///
+    /// Returns the same errors as [SurfaceParams::swizzle] for invalid parameters.
+    pub fn new(params: &SurfaceParams<'a>) -> Result<Self> {
+        let params = if is_empty(params) {
+            // Empty surfaces don't have any data to convert.
+            SurfaceParams {
+                mipmap_count: 0,
+                layer_count: 0,
+                ..*params
+            }
+        } else {
+            validate_params(params)?;
+            *params
+        };
+
+        let layout = SurfaceLayout::new(&TegraBlockLinear, &params);
+        let block_depth = block_depth(params.depth);
+        let mips = layout
+            .mips()
+            .iter()
+            .map(|mip| {
+                let level = &mip.level;
+                BlockLinearPlan::with_block_depth(
+                    level.width,
+                    level.height,
+                    level.depth,
+                    surface_mip_block_height(&params, level.mip, level.height),
+                    mip_block_depth(level.depth, block_depth),
+                    params.bytes_per_pixel,
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            params,
+            layout,
+            mips,
+        })
+    }
+
+    /// Sets the order for visiting the GOBs of each mipmap.
+    /// The default is [AccessOrder::Linear].
+    ///
+    /// Mipmaps in a packed mip tail are small and always use the default order.
+    pub fn with_access_order(mut self, access_order: AccessOrder) -> Self {
+        self.mips = self
+            .mips
+            .into_iter()
+            .map(|plan| plan.with_access_order(access_order))
+            .collect();
+        self
+    }
+
+    /// The size in bytes of the tiled data. See [SurfaceParams::swizzled_size].
+    pub fn swizzled_size(&self) -> usize {
+        self.layout.swizzled_size()
+    }
+
+    /// The size in bytes of the untiled data. See [SurfaceParams::deswizzled_size].
+    pub fn deswizzled_size(&self) -> usize {
+        self.layout.deswizzled_size()
+    }
+
+    /// Tiles all the array layers and mipmaps in `source` into `destination`.
+    /// See [SurfaceParams::swizzle_into].
+    pub fn swizzle_into(&self, source: &[u8], destination: &mut [u8]) -> Result<()> {
+        let destination = self.check_sizes::<false>(source, destination)?;
+
+        // Padding bytes are not written while tiling, so clear any previous data.
+        destination.fill(0);
+
+        self.swizzle_layers::<false>(source, destination)
+    }
+
+    /// Untiles all the array layers and mipmaps in `source` into `destination`.
+    /// See [SurfaceParams::deswizzle_into].
+    pub fn deswizzle_into(&self, source: &[u8], destination: &mut [u8]) -> Result<()> {
+        let destination = self.check_sizes::<true>(source, destination)?;
+        self.swizzle_layers::<true>(source, destination)
+    }
+
+    // Return the part of the destination used for the output.
+    fn check_sizes<'d, const DESWIZZLE: bool>(
+        &self,
+        source: &[u8],
+        destination: &'d mut [u8],
+    ) -> Result<&'d mut [u8]> {
+        let output_size = surface_destination_size::<DESWIZZLE>(&self.layout, source)?;
+        if destination.len() < output_size {
+            return Err(SwizzleError::NotEnoughData {
+                expected_size: output_size,
+                actual_size: destination.len(),
+            });
+        }
+        Ok(&mut destination[..output_size])
+    }
+
+    fn swizzle_layers<const DESWIZZLE: bool>(
+        &self,
+        source: &[u8],
+        result: &mut [u8],
+    ) -> Result<()> {
+        let layout = &self.layout;
+        for layer in 0..self.params.layer_count as usize {
+            let swizzled_layer = layer * layout.swizzled_layer_size;
+            let deswizzled_layer = layer * layout.deswizzled_layer_size;
+
+            for (mip, plan) in layout.mips().iter().zip(&self.mips) {
+                let swizzled_offset = swizzled_layer + mip.swizzled_offset;
+                let deswizzled_offset = deswizzled_layer + mip.deswizzled_offset;
+                if DESWIZZLE {
+                    plan.deswizzle_into(
+                        &source[swizzled_offset..],
+                        &mut result[deswizzled_offset..],
+                    )?;
+                } else {
+                    plan.swizzle_into(
+                        &source[deswizzled_offset..],
+                        &mut result[swizzled_offset..],
+                    )?;
+                }
+            }
+
+            swizzle_layer_tail::<_, DESWIZZLE>(
+                &TegraBlockLinear,
+                &self.params,
+                layout,
+                layer,
+                source,
+                result,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The location of a single mipmap in the first array layer from [mip_layout].
+///
+/// Unlike [crate::gpu::TilingUniforms], the offsets and sizes are not limited to `u32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MipLayout {
+    /// The dimensions of the mipmap in blocks.
+    pub level: MipLevel,
+    /// The height of each block in GOBs.
+    pub block_height: BlockHeight,
+    /// The depth of each block in GOBs.
+    pub block_depth: u32,
+    /// The offset in bytes of the mipmap in the tiled data.
+    pub swizzled_offset: usize,
+    /// The size in bytes of the mipmap in the tiled data.
+    pub swizzled_size: usize,
+    /// The offset in bytes of the mipmap in the untiled data.
+    pub deswizzled_offset: usize,
+    /// The size in bytes of the mipmap in the untiled data.
+    pub deswizzled_size: usize,
+}
+
+/// Calculates the dimensions, block size, and location of mipmap `mip` in the first array layer.
+/// Add a multiple of [layer_stride_tiled] or [layer_stride_linear] for the mipmaps of other array layers.
+///
+/// Returns [SwizzleError::InvalidSurface] if `mip` is not less than [SurfaceParams::mipmap_count]
+/// or the parameters are invalid.
+/// Returns [SwizzleError::Unsupported] if the mipmap is part of a packed mip tail.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{mip_layout, BlockDim, SurfaceParams};
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 3,
+    ..Default::default()
+};
+let mip1 = mip_layout(&params, 1)?;
+assert_eq!(32, mip1.level.width);
+assert_eq!(65536, mip1.swizzled_offset);
+# Ok::<(), tegra_swizzle::SwizzleError>(())
+```
+ */
+pub fn mip_layout(params: &SurfaceParams, mip: u32) -> Result<MipLayout> {
+    if is_empty(params) || mip >= params.mipmap_count {
+        return Err(SwizzleError::InvalidSurface {
+            width: params.width,
+            height: params.height,
+            depth: params.depth,
+            bytes_per_pixel: params.bytes_per_pixel,
+            mipmap_count: params.mipmap_count,
+        });
+    }
+    validate_params(params)?;
+
+    let layout = SurfaceLayout::new(&TegraBlockLinear, params);
+    let sizes = layout
+        .mips()
+        .get(mip as usize)
+        .ok_or(SwizzleError::Unsupported {
+            feature: "layouts for mipmaps in a packed mip tail",
+        })?;
+
+    let level = sizes.level;
+    Ok(MipLayout {
+        level,
+        block_height: surface_mip_block_height(params, mip, level.height),
+        block_depth: mip_block_depth(level.depth, block_depth(params.depth)),
+        swizzled_offset: sizes.swizzled_offset,
+        swizzled_size: sizes.swizzled_size,
+        deswizzled_offset: sizes.deswizzled_offset,
+        deswizzled_size: sizes.deswizzled_size,
+    })
+}
+
+/// Which mipmaps of a surface use the optimized copies for complete GOBs.
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastPathInfo {
+    /// `true` for each mipmap where [is_fast_path] is `true`.
+    /// Mipmaps in a packed mip tail are always `false`.
+    pub per_mip: Vec<bool>,
+    /// The untiled size in bytes of the fast path mipmaps for all array layers.
+    pub fast_bytes: usize,
+    /// The untiled size in bytes of the surface. See [SurfaceParams::deswizzled_size].
+    pub total_bytes: usize,
+}
+
+/// Checks which mipmaps of `params` only contain complete GOBs using [is_fast_path].
+///
+/// Applications with a separate GPU implementation can use this to decide where to untile each surface.
+/// Surfaces with non power of two dimensions tend to have mipmaps with partially filled GOBs.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{fast_path_mips, BlockDim, SurfaceParams};
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 4,
+    ..Default::default()
+};
+// The 32x32 mipmap is 8x8 blocks or 128x8 bytes.
+// The 64x64 mipmap is 16x16 blocks or 256x16 bytes.
+let info = fast_path_mips(&params);
+assert_eq!(vec![true, true, true, true], info.per_mip);
+assert_eq!(info.total_bytes, info.fast_bytes);
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn fast_path_mips(params: &SurfaceParams) -> FastPathInfo {
+    if is_empty(params) {
+        return FastPathInfo {
+            per_mip: vec![false; params.mipmap_count as usize],
+            fast_bytes: 0,
+            total_bytes: 0,
+        };
+    }
+
+    let tail_start = mip_tail_start(params);
+
+    let mut fast_bytes = 0;
+    let per_mip = (0..params.mipmap_count)
+        .map(|mip| {
+            let (mip_width, mip_height, mip_depth) = mip_dimensions(params, mip);
+            let fast =
+                mip < tail_start && is_fast_path(mip_width, mip_height, params.bytes_per_pixel);
+            if fast {
+                fast_bytes +=
+                    deswizzled_mip_size(mip_width, mip_height, mip_depth, params.bytes_per_pixel);
+            }
+            fast
+        })
+        .collect();
+
+    FastPathInfo {
+        per_mip,
+        fast_bytes: fast_bytes * params.layer_count as usize,
+        total_bytes: params.deswizzled_size(),
+    }
+}
+
+// Surfaces that can be tiled or untiled without returning an error or empty output.
+fn is_valid(params: &SurfaceParams) -> bool {
+    !is_empty(params) && validate_params(params).is_ok()
+}
+
+/// Tiles all the array layers and mipmaps in `source` using the block linear algorithm
+/// to a combined vector with appropriate mipmap and layer alignment.
+///
+/// The `width`, `height`, and `depth` are in terms of blocks with the pixels per block defined by `block_dim`.
+/// Use a `block_height_mip0` of [None] to infer the block height from the specified dimensions.
+///
+/// Returns [SwizzleError::NotEnoughMipData] if `source` does not have
+/// at least as many bytes as the result of [deswizzled_surface_size].
+///
+/// # Examples
+///
+/// Compressed formats should still use pixel dimensions and set the appropriate block dimensions.
+///
+/// ```rust no_run
+/// use tegra_swizzle::surface::{BlockDim, swizzle_surface};
+/// # let deswizzled_surface = vec![0u8; 10];
+///
+/// // 16x16 BC7 cube map with 5 mipmaps.
+/// let surface = swizzle_surface(
+///     16,
+///     16,
+///     1,
+///     &deswizzled_surface,
+///     BlockDim::block_4x4(),
+///     None,
+///     16,
+///     5,
+///     6,
+/// );
+/// ```
+///
+/// Uncompressed formats use a 1x1x1 pixel block.
+///
+/// ```rust no_run
+/// // 128x128 R8G8B8A8 2D texture with no mipmaps.
+/// use tegra_swizzle::surface::{BlockDim, swizzle_surface};
+/// # let deswizzled_surface = vec![0u8; 10];
+/// let surface = swizzle_surface(
+///     128,
+///     128,
+///     1,
+///     &deswizzled_surface,
+///     BlockDim::uncompressed(),
+///     None,
+///     4,
+///     1,
+///     1,
+/// );
+///
+/// // 16x16x16 R8G8B8A8 3D texture with no mipmaps.
+/// let surface = swizzle_surface(
+///     16,
+///     16,
+///     16,
+///     &deswizzled_surface,
+///     BlockDim::uncompressed(),
+///     None,
+///     4,
+///     1,
+///     1,
+/// );
+/// ```
+#[cfg(not(feature = "minimal"))]
+#[deprecated(
+    since = "0.5.0",
+    note = "use SurfaceParams::swizzle instead, see the compat module"
+)]
+pub fn swizzle_surface(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    block_dim: BlockDim, // TODO: Use None to indicate uncompressed?
+    block_height_mip0: Option<BlockHeight>, // TODO: Make this optional in other functions as well?
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    layer_count: u32,
+) -> Result<Vec<u8>> {
+    let params = SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0,
+        block_height_heuristic: BlockHeightHeuristic::Trm,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count,
+        packed_mip_tail: false,
+        mip_block_heights: None,
+        layer_alignment: None,
+        max_surface_size: None,
+    };
+    params.swizzle(source)
+}
+
+// TODO: Find a way to simplify the parameters.
+/// Untiles all the array layers and mipmaps in `source` using the block linear algorithm
+/// to a new vector without any padding between layers or mipmaps.
+///
+/// The `width`, `height`, and `depth` are in terms of blocks with the pixels per block defined by `block_dim`.
+/// Use a `block_height_mip0` of [None] to infer the block height from the specified dimensions.
+///
+/// Returns [SwizzleError::NotEnoughMipData] if `source` does not have
+/// at least as many bytes as the result of [swizzled_surface_size].
+///
+/// # Examples
+///
+/// Compressed formats should still use pixel dimensions and set the appropriate block dimensions.
+///
+/// ```rust no_run
+/// use tegra_swizzle::surface::{BlockDim, deswizzle_surface};
+/// # let swizzled_surface = vec![0u8; 10];
+///
+/// // 16x16 BC7 cube map with 5 mipmaps.
+/// let surface = deswizzle_surface(
+///     16,
+///     16,
+///     1,
+///     &swizzled_surface,
+///     BlockDim::block_4x4(),
+///     None,
+///     16,
+///     5,
+///     6,
+/// );
+/// ```
+///
+/// Uncompressed formats use a 1x1x1 pixel block.
+///
+/// ```rust no_run
+/// use tegra_swizzle::surface::{BlockDim, deswizzle_surface};
+/// # let swizzled_surface = vec![0u8; 10];
+/// // 128x128 R8G8B8A8 2D texture with no mipmaps.
+/// let surface = deswizzle_surface(
+///     128,
+///     128,
+///     1,
+///     &swizzled_surface,
+///     BlockDim::uncompressed(),
+///     None,
+///     4,
+///     1,
+///     1,
+/// );
+///
+/// // 16x16x16 R8G8B8A8 3D texture with no mipmaps.
+/// let surface = deswizzle_surface(
+///     16,
+///     16,
+///     16,
+///     &swizzled_surface,
+///     BlockDim::uncompressed(),
+///     None,
+///     4,
+///     1,
+///     1,
+/// );
+/// ```
+#[cfg(not(feature = "minimal"))]
+#[deprecated(
+    since = "0.5.0",
+    note = "use SurfaceParams::deswizzle instead, see the compat module"
+)]
+pub fn deswizzle_surface(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    block_dim: BlockDim,
+    block_height_mip0: Option<BlockHeight>, // TODO: Make this optional in other functions as well?
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    layer_count: u32,
+) -> Result<Vec<u8>> {
+    let params = SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0,
+        block_height_heuristic: BlockHeightHeuristic::Trm,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count,
+        packed_mip_tail: false,
+        mip_block_heights: None,
+        layer_alignment: None,
+        max_surface_size: None,
+    };
+    params.deswizzle(source)
+}
+
+/// Tiles the array layers and mipmaps in `mips` using the block linear algorithm
+/// to a combined vector with appropriate mipmap and layer alignment.
+///
+/// This is equivalent to [swizzle_surface] but with a separate slice for each array layer and mipmap.
+/// This avoids combining the data into a single buffer if the mipmaps are already stored separately.
+/// The slices in `mips` are ordered by layer and then mipmap,
+/// so the data for layer `L` and mip `M` is at index `L * mipmap_count + M`.
+///
+/// Returns [SwizzleError::NotEnoughMipData] if `mips` does not contain a slice for every layer and mipmap
+/// or any of the slices is smaller than the untiled size of its mipmap.
+/// The sizes in the error are for the slice of that mipmap rather than the entire surface.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{BlockDim, swizzle_surface_from_mips};
+
+// 8x8 R8G8B8A8 2D texture with 4 mipmaps.
+let mip0 = vec![0u8; 8 * 8 * 4];
+let mip1 = vec![0u8; 4 * 4 * 4];
+let mip2 = vec![0u8; 2 * 2 * 4];
+let mip3 = vec![0u8; 4];
+
+let surface = swizzle_surface_from_mips(
+    8,
+    8,
+    1,
+    &[&mip0, &mip1, &mip2, &mip3],
+    BlockDim::uncompressed(),
+    None,
+    4,
+    4,
+    1,
+)
+.unwrap();
+```
+ */
+#[cfg(not(feature = "minimal"))]
+#[deprecated(
+    since = "0.5.0",
+    note = "use SurfaceParams::swizzle_from_mips instead, see the compat module"
+)]
+pub fn swizzle_surface_from_mips(
+    width: u32,
+    height: u32,
+    depth: u32,
+    mips: &[&[u8]],
+    block_dim: BlockDim,
+    block_height_mip0: Option<BlockHeight>,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    layer_count: u32,
+) -> Result<Vec<u8>> {
+    let params = SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0,
+        block_height_heuristic: BlockHeightHeuristic::Trm,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count,
+        packed_mip_tail: false,
+        mip_block_heights: None,
+        layer_alignment: None,
+        max_surface_size: None,
+    };
+    params.swizzle_from_mips(mips)
+}
+
+/// Tiles all the array layers and mipmaps in `source` and appends the tiled data to `output`.
+/// Returns the range of bytes in `output` containing the tiled data.
+///
+/// The tiled data starts at the next multiple of `alignment` bytes.
+/// Any bytes added before this offset are set to zero.
+/// This allows writing many tiled surfaces back to back into a single buffer
+/// without allocating a separate `Vec` for each surface.
+///
+/// Returns the same errors as [SurfaceParams::swizzle].
+/// The length of `output` is unchanged if an error occurs.
+/// # Examples
+/**
+```rust
+use core::num::NonZeroUsize;
+use tegra_swizzle::surface::{swizzle_surface_append, BlockDim, SurfaceParams};
+
+let params = SurfaceParams {
+    width: 16,
+    height: 16,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 5,
+    ..Default::default()
+};
+# let input = vec![0u8; params.deswizzled_size()];
+let alignment = NonZeroUsize::new(4096).unwrap();
+
+let mut archive = vec![0u8; 5];
+let range = swizzle_surface_append(&params, &input, alignment, &mut archive).unwrap();
+assert_eq!(4096..4096 + params.swizzled_size(), range);
+assert_eq!(range.end, archive.len());
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn swizzle_surface_append(
+    params: &SurfaceParams,
+    source: &[u8],
+    alignment: NonZeroUsize,
+    output: &mut Vec<u8>,
+) -> Result<core::ops::Range<usize>> {
+    let previous_len = output.len();
+    let start = previous_len.next_multiple_of(alignment.get());
+
+    let mut buffer = AppendBuffer { output, start };
+    match params.swizzle_to(source, &mut buffer) {
+        Ok(()) => Ok(start..output.len()),
+        Err(e) => {
+            output.truncate(previous_len);
+            Err(e)
+        }
+    }
+}
+
+// Write to the end of an existing Vec starting at an aligned offset.
+#[cfg(not(feature = "minimal"))]
+struct AppendBuffer<'a> {
+    output: &'a mut Vec<u8>,
+    start: usize,
+}
+
+#[cfg(not(feature = "minimal"))]
+impl DestBuffer for AppendBuffer<'_> {
+    fn buffer(&mut self, size: usize) -> Result<&mut [u8]> {
+        self.output.resize(self.start + size, 0);
+        Ok(&mut self.output[self.start..])
+    }
+}
+
+/// Tiles all the array layers and mipmaps in `source` using the block linear algorithm
+/// with the block height for each mipmap specified in `mip_block_heights`.
+///
+/// This is equivalent to [swizzle_surface] but without calculating the block height for each mipmap.
+/// Use this for formats that store the block height for every mipmap.
+///
+/// Returns [SwizzleError::NotEnoughBlockHeights] if `mip_block_heights`
+/// does not contain a block height for every mipmap.
+#[cfg(not(feature = "minimal"))]
+#[deprecated(
+    since = "0.5.0",
+    note = "use SurfaceParams::swizzle with mip_block_heights instead, see the compat module"
+)]
+pub fn swizzle_surface_with_block_heights(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    block_dim: BlockDim,
+    mip_block_heights: &[BlockHeight],
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    layer_count: u32,
+) -> Result<Vec<u8>> {
+    SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0: None,
+        block_height_heuristic: BlockHeightHeuristic::Trm,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count,
+        packed_mip_tail: false,
+        mip_block_heights: Some(mip_block_heights),
+        layer_alignment: None,
+        max_surface_size: None,
+    }
+    .swizzle(source)
+}
+
+/// Untiles all the array layers and mipmaps in `source` using the block linear algorithm
+/// with the block height for each mipmap specified in `mip_block_heights`.
+///
+/// This is equivalent to [deswizzle_surface] but without calculating the block height for each mipmap.
+/// Use this for formats that store the block height for every mipmap.
+///
+/// Returns [SwizzleError::NotEnoughBlockHeights] if `mip_block_heights`
+/// does not contain a block height for every mipmap.
+#[cfg(not(feature = "minimal"))]
+#[deprecated(
+    since = "0.5.0",
+    note = "use SurfaceParams::deswizzle with mip_block_heights instead, see the compat module"
+)]
+pub fn deswizzle_surface_with_block_heights(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    block_dim: BlockDim,
+    mip_block_heights: &[BlockHeight],
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    layer_count: u32,
+) -> Result<Vec<u8>> {
+    SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0: None,
+        block_height_heuristic: BlockHeightHeuristic::Trm,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count,
+        packed_mip_tail: false,
+        mip_block_heights: Some(mip_block_heights),
+        layer_alignment: None,
+        max_surface_size: None,
+    }
+    .deswizzle(source)
+}
+
+#[cfg(not(feature = "minimal"))]
+fn swizzle_from_mips_params<S: TilingScheme>(
+    scheme: &S,
+    params: &SurfaceParams,
+    mips: &[&[u8]],
+) -> Result<Vec<u8>> {
+    // Check for empty surfaces first to more reliably handle overflow.
+    if is_empty(params) {
+        return Ok(Vec::new());
+    }
+
+    validate_params(params)?;
+
+    // Validate the source lengths before attempting to allocate.
+    let mip_source = |layer: u32, mip: u32| -> Result<&[u8]> {
+        let level = mip_level(params, mip);
+        let expected_size = deswizzled_mip_size(
+            level.width,
+            level.height,
+            level.depth,
+            params.bytes_per_pixel,
+        );
+        let source = mips
+            .get(layer as usize * params.mipmap_count as usize + mip as usize)
+            .copied()
+            .unwrap_or_default();
+        if source.len() < expected_size {
+            Err(SwizzleError::NotEnoughMipData {
+                layer,
+                mip,
+                expected_size,
+                actual_size: source.len(),
+            })
+        } else {
+            Ok(&source[..expected_size])
+        }
+    };
+    for layer in 0..params.layer_count {
+        for mip in 0..params.mipmap_count {
+            mip_source(layer, mip)?;
+        }
+    }
+
+    let layout = SurfaceLayout::new(scheme, params);
+    let mut result = vec![0u8; layout.swizzled_size()];
+
+    for layer in 0..params.layer_count {
+        let swizzled_layer = layer as usize * layout.swizzled_layer_size;
+        for (mip, sizes) in layout.mips().iter().enumerate() {
+            scheme.tile_mip(
+                params,
+                &sizes.level,
+                mip_source(layer, mip as u32)?,
+                &mut result[swizzled_layer + sizes.swizzled_offset..],
+            );
+        }
+
+        if layout.tail_start < params.mipmap_count {
+            // Combine the mipmaps in the tail since they share GOBs.
+            let mut tail = Vec::new();
+            for mip in layout.tail_start..params.mipmap_count {
+                tail.extend_from_slice(mip_source(layer, mip)?);
+            }
+            swizzle_mip_tail::<_, false>(
+                scheme,
+                params,
+                layout.tail_start,
+                &tail,
+                &mut 0,
+                &mut result,
+                &mut (swizzled_layer + layout.tail.swizzled_offset),
+            )?;
+        }
+    }
+
+    Ok(result)
+}
+
+// Find each mipmap in the source and tile them in the usual layer major order.
+#[cfg(not(feature = "minimal"))]
+fn swizzle_mip_major<S: TilingScheme>(
+    scheme: &S,
+    params: &SurfaceParams,
+    source: &[u8],
+) -> Result<Vec<u8>> {
+    if is_empty(params) {
+        return Ok(Vec::new());
+    }
+
+    validate_params(params)?;
+
+    // Each mipmap has at least one byte, so the source length limits the allocation size.
+    let mut mip_major = Vec::new();
+    let mut offset = 0;
+    for mip in 0..params.mipmap_count {
+        let level = mip_level(params, mip);
+        let size = deswizzled_mip_size(
+            level.width,
+            level.height,
+            level.depth,
+            params.bytes_per_pixel,
+        );
+        for layer in 0..params.layer_count {
+            let mip_data =
+                source
+                    .get(offset..offset + size)
+                    .ok_or(SwizzleError::NotEnoughMipData {
+                        layer,
+                        mip,
+                        expected_size: offset + size,
+                        actual_size: source.len(),
+                    })?;
+            mip_major.push(mip_data);
+            offset += size;
+        }
+    }
+
+    let layer_count = params.layer_count as usize;
+    let mips: Vec<_> = (0..layer_count)
+        .flat_map(|layer| mip_major[layer..].iter().step_by(layer_count).copied())
+        .collect();
+
+    swizzle_from_mips_params(scheme, params, &mips)
+}
+
+#[cfg(not(feature = "minimal"))]
+fn swizzle_from_fn_params<
+    S: TilingScheme,
+    F: FnMut(u32, u32, &mut [u8]),
+    D: DestBuffer + ?Sized,
+>(
+    scheme: &S,
+    params: &SurfaceParams,
+    mut fill: F,
+    destination: &mut D,
+) -> Result<()> {
+    if is_empty(params) {
+        destination.buffer(0)?;
+        return Ok(());
+    }
+
+    validate_params(params)?;
+
+    let layout = SurfaceLayout::new(scheme, params);
+    let surface_size = layout.swizzled_size();
+    let result = &mut destination.buffer(surface_size)?[..surface_size];
+
+    // Padding bytes are not written while tiling, so clear any previous data.
+    result.fill(0);
+
+    // The packed mipmaps in the tail are tiled together.
+    let staging_size = layout
+        .mips()
+        .iter()
+        .map(|mip| mip.deswizzled_size)
+        .fold(layout.tail.deswizzled_size, usize::max);
+    let mut staging = vec![0u8; staging_size];
+
+    for layer in 0..params.layer_count {
+        let swizzled_layer = layer as usize * layout.swizzled_layer_size;
+        for mip in layout.mips() {
+            let untiled = &mut staging[..mip.deswizzled_size];
+            untiled.fill(0);
+            fill(layer, mip.level.mip, untiled);
+            scheme.tile_mip(
+                params,
+                &mip.level,
+                untiled,
+                &mut result[swizzled_layer + mip.swizzled_offset..],
+            );
+        }
+
+        if layout.tail_start < params.mipmap_count {
+            staging.fill(0);
+            let mut offset = 0;
+            for mip in layout.tail_start..params.mipmap_count {
+                let level = mip_level(params, mip);
+                let size = deswizzled_mip_size(
+                    level.width,
+                    level.height,
+                    level.depth,
+                    params.bytes_per_pixel,
+                );
+                fill(layer, mip, &mut staging[offset..offset + size]);
+                offset += size;
+            }
+            swizzle_mip_tail::<_, false>(
+                scheme,
+                params,
+                layout.tail_start,
+                &staging,
+                &mut 0,
+                result,
+                &mut (swizzled_layer + layout.tail.swizzled_offset),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts the tiled data in `source` to use a different block height
+/// without untiling the entire surface.
+///
+/// The `source` is tiled using the block heights in `params`.
+/// The output uses the same surface dimensions and `target_block_height_mip0`
+/// with the block heights for each mipmap calculated using [crate::mip_block_height].
+/// Tiling the untiled data with the target block height produces the same output.
+///
+/// This is faster than calling [SurfaceParams::deswizzle] and then [SurfaceParams::swizzle],
+/// since whole GOBs are copied without an intermediate linear buffer.
+///
+/// Returns [SwizzleError::NotEnoughMipData] if `source` does not have
+/// at least as many bytes as [SurfaceParams::swizzled_size].
+/// Returns [SwizzleError::AllocationTooLarge] if the output with the target block height
+/// is larger than [SurfaceParams::max_surface_size].
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{BlockHeight, surface::{retile, BlockDim, SurfaceParams}};
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::block_4x4(),
+    block_height_mip0: Some(BlockHeight::Sixteen),
+    bytes_per_pixel: 16,
+    mipmap_count: 9,
+    ..Default::default()
+};
+# let input = vec![0u8; params.swizzled_size()];
+let output = retile(&input, &params, Some(BlockHeight::Four)).unwrap();
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn retile(
+    source: &[u8],
+    params: &SurfaceParams,
+    target_block_height_mip0: Option<BlockHeight>,
+) -> Result<Vec<u8>> {
+    if is_empty(params) {
+        return Ok(Vec::new());
+    }
+
+    validate_params(params)?;
+
+    let target = SurfaceParams {
+        block_height_mip0: target_block_height_mip0,
+        mip_block_heights: None,
+        ..*params
+    };
+    // A larger block height can exceed the size limit for the surface.
+    validate_params(&target)?;
+
+    check_swizzled_source(params, source)?;
+
+    let mut result = vec![0u8; target.swizzled_size()];
+
+    let tail_start = mip_tail_start(params);
+    let tail_size = mip_tail_gob_count(params, tail_start) * GOB_SIZE_IN_BYTES as usize;
+
+    let mut src_offset = 0;
+    let mut dst_offset = 0;
+    for _ in 0..params.layer_count {
+        for mip in 0..tail_start {
+            let level = mip_level(params, mip);
+            let source_block_height = surface_mip_block_height(params, mip, level.height);
+            let target_block_height = surface_mip_block_height(&target, mip, level.height);
+
+            retile_inner(
+                level.width,
+                level.height,
+                level.depth,
+                &source[src_offset..],
+                &mut result[dst_offset..],
+                source_block_height,
+                target_block_height,
+                mip_block_depth(level.depth, block_depth(params.depth)),
+                params.bytes_per_pixel,
+            );
+
+            src_offset += swizzled_mip_size(
+                level.width,
+                level.height,
+                level.depth,
+                source_block_height,
+                params.bytes_per_pixel,
+            );
+            dst_offset += swizzled_mip_size(
+                level.width,
+                level.height,
+                level.depth,
+                target_block_height,
+                params.bytes_per_pixel,
+            );
+        }
+
+        // The packed mip tail does not depend on the block height.
+        result[dst_offset..dst_offset + tail_size]
+            .copy_from_slice(&source[src_offset..src_offset + tail_size]);
+        src_offset += tail_size;
+        dst_offset += tail_size;
+
+        // Align offsets between array layers.
+        if params.layer_count > 1 {
+            src_offset = surface_layer_size(params, src_offset);
+            dst_offset = surface_layer_size(&target, dst_offset);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Creates a tiled surface with `mipmap_count` mipmaps and `layer_count` array layers
+/// from the tiled data in `source` without untiling the entire surface.
+///
+/// The smallest mipmaps are removed if `mipmap_count` is less than the mipmap count in `params`.
+/// Additional array layers repeat the layers in `source`,
+/// so a single layer can be used to create all the faces of a cube map.
+/// The alignment between array layers is recalculated for the new surface.
+/// Tiling the untiled data with the new mipmap and layer count produces the same output.
+///
+/// Returns [SwizzleError::InvalidSurface] if `mipmap_count` is greater than the mipmap count in `params`
+/// or the resized surface is not valid like a 3D surface with more than one array layer.
+/// Returns [SwizzleError::NotEnoughMipData] if `source` does not have
+/// at least as many bytes as [SurfaceParams::swizzled_size].
+///
+/// The output size is not limited by the size of `source`.
+/// Set [SurfaceParams::max_surface_size] to return [SwizzleError::AllocationTooLarge]
+/// instead of allocating very large outputs for layer counts from untrusted input.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{resize_surface, BlockDim, SurfaceParams};
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 9,
+    layer_count: 6,
+    ..Default::default()
+};
+# let input = vec![0u8; params.swizzled_size()];
+// Keep only the base mip level for each layer.
+let output = resize_surface(&input, &params, 1, 6).unwrap();
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn resize_surface(
+    source: &[u8],
+    params: &SurfaceParams,
+    mipmap_count: u32,
+    layer_count: u32,
+) -> Result<Vec<u8>> {
+    let target = SurfaceParams {
+        mipmap_count,
+        layer_count,
+        ..*params
+    };
+
+    if mipmap_count > params.mipmap_count {
+        return Err(SwizzleError::InvalidSurface {
+            width: target.width,
+            height: target.height,
+            depth: target.depth,
+            bytes_per_pixel: target.bytes_per_pixel,
+            mipmap_count,
+        });
+    }
+
+    if is_empty(params) || is_empty(&target) {
+        return Ok(Vec::new());
+    }
+
+    validate_params(params)?;
+    validate_params(&target)?;
+
+    let expected_size = check_swizzled_source(params, source)?;
+
+    let source_layer_size = expected_size / params.layer_count as usize;
+    let target_size = target.swizzled_size();
+    let target_layer_size = target_size / layer_count as usize;
+
+    // Removing mipmaps never changes the position of the remaining mipmaps.
+    // The packed mip tail can only start at the same mipmap or be removed entirely.
+    let tail_start = mip_tail_start(&target);
+    let mips_size: usize = (0..tail_start)
+        .map(|mip| TegraBlockLinear.tiled_mip_size(params, &mip_level(params, mip)))
+        .sum();
+
+    let mut result = vec![0u8; target_size];
+    for (layer, target_layer) in result.chunks_exact_mut(target_layer_size).enumerate() {
+        let source_offset = layer % params.layer_count as usize * source_layer_size;
+        let source_layer = &source[source_offset..source_offset + source_layer_size];
+
+        target_layer[..mips_size].copy_from_slice(&source_layer[..mips_size]);
+
+        if tail_start < mipmap_count {
+            // Mipmaps may be removed from the end of the tail, so untile and tile the tail again.
+            let mut tail = vec![0u8; tail_deswizzled_size(params, tail_start)];
+            let mut source_offset = mips_size;
+            let mut target_offset = mips_size;
+            swizzle_mip_tail::<_, true>(
+                &TegraBlockLinear,
+                params,
+                tail_start,
+                source_layer,
+                &mut source_offset,
+                &mut tail,
+                &mut 0,
+            )?;
+            swizzle_mip_tail::<_, false>(
+                &TegraBlockLinear,
+                &target,
+                tail_start,
+                &tail,
+                &mut 0,
+                target_layer,
+                &mut target_offset,
+            )?;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Creates a tiled surface containing only the array layers in `layers`
+/// from the tiled data in `source` without untiling the surface.
+///
+/// The alignment between array layers is recalculated for the new surface.
+/// Tiling the untiled data for the selected layers produces the same output.
+///
+/// Returns [SwizzleError::InvalidLayerRange] if `layers` is not contained in the layers of `params`.
+/// Returns [SwizzleError::NotEnoughMipData] if `source` does not have
+/// at least as many bytes as [SurfaceParams::swizzled_size].
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{extract_layers, BlockDim, SurfaceParams};
+
+// An array of 4 BC7 cube maps.
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 9,
+    layer_count: 6 * 4,
+    ..Default::default()
+};
+# let input = vec![0u8; params.swizzled_size()];
+// Split the array into individual cube maps.
+for i in 0..4 {
+    let cube_map = extract_layers(&input, &params, i * 6..(i + 1) * 6).unwrap();
+}
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn extract_layers(
+    source: &[u8],
+    params: &SurfaceParams,
+    layers: core::ops::Range<u32>,
+) -> Result<Vec<u8>> {
+    if layers.start > layers.end || layers.end > params.layer_count {
+        return Err(SwizzleError::InvalidLayerRange {
+            start: layers.start,
+            end: layers.end,
+            layer_count: params.layer_count,
+        });
+    }
+
+    let target = SurfaceParams {
+        layer_count: layers.end - layers.start,
+        ..*params
+    };
+    if is_empty(params) || is_empty(&target) {
+        return Ok(Vec::new());
+    }
+
+    validate_params(params)?;
+
+    let expected_size = check_swizzled_source(params, source)?;
+
+    let source_layer_size = expected_size / params.layer_count as usize;
+    let target_size = target.swizzled_size();
+    let target_layer_size = target_size / target.layer_count as usize;
+
+    // The alignment padding only depends on the layer count, so each layer can be copied directly.
+    let unaligned_layer_size = SurfaceParams {
+        layer_count: 1,
+        ..*params
+    }
+    .swizzled_size();
+
+    let mut result = vec![0u8; target_size];
+    for (layer, target_layer) in layers.zip(result.chunks_exact_mut(target_layer_size)) {
+        let source_offset = layer as usize * source_layer_size;
+        target_layer[..unaligned_layer_size]
+            .copy_from_slice(&source[source_offset..source_offset + unaligned_layer_size]);
+    }
+
+    Ok(result)
+}
+
+/// The untiled data and missing mipmaps from [deswizzle_surface_lossy].
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LossySurface {
+    /// The untiled data for all array layers and mipmaps.
+    /// The bytes for missing mipmaps are set to zero.
+    pub data: Vec<u8>,
+    /// The mipmaps that were not completely contained in the tiled data
+    /// ordered by array layer and then mipmap.
+    pub missing: Vec<MissingMip>,
+}
+
+/// A mipmap of an array layer that was not untiled by [deswizzle_surface_lossy].
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MissingMip {
+    /// The array layer of the mipmap.
+    pub layer: u32,
+    /// The mipmap level starting from 0 for the base level.
+    pub mip: u32,
+    /// The offset in bytes of the mipmap in [LossySurface::data].
+    pub offset: usize,
+    /// The size in bytes of the mipmap in [LossySurface::data].
+    pub size: usize,
+}
+
+/// Untiles all the array layers and mipmaps in `source` that are completely contained in `source`.
+/// This is the same as [SurfaceParams::deswizzle] but for truncated or partially corrupted data.
+///
+/// Mipmaps that extend past the end of `source` are filled with zeros and listed in [LossySurface::missing].
+/// Mipmaps in a packed mip tail are stored together, so these mipmaps are either all untiled or all missing.
+///
+/// The output always has [SurfaceParams::deswizzled_size] many bytes even if `source` is much smaller.
+/// Check the dimensions from untrusted files before calling this function to avoid large allocations.
+///
+/// Returns [SwizzleError::InvalidSurface] if the surface dimensions would overflow.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{deswizzle_surface_lossy, BlockDim, SurfaceParams};
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 9,
+    layer_count: 6,
+    ..Default::default()
+};
+# let input = vec![0u8; params.swizzled_size()];
+// Only the first few layers made it into the file.
+let surface = deswizzle_surface_lossy(&input[..input.len() / 2], &params).unwrap();
+for missing in &surface.missing {
+    println!("Missing layer {} mip {}", missing.layer, missing.mip);
+}
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn deswizzle_surface_lossy(source: &[u8], params: &SurfaceParams) -> Result<LossySurface> {
+    if is_empty(params) {
+        return Ok(LossySurface {
+            data: Vec::new(),
+            missing: Vec::new(),
+        });
+    }
+
+    validate_params(params)?;
+
+    let scheme = TegraBlockLinear;
+    let layout = SurfaceLayout::new(&scheme, params);
+    let mut data = vec![0u8; layout.deswizzled_size()];
+    let mut missing = Vec::new();
+
+    for layer in 0..params.layer_count {
+        let swizzled_layer = layer as usize * layout.swizzled_layer_size;
+        let deswizzled_layer = layer as usize * layout.deswizzled_layer_size;
+
+        for sizes in layout.mips() {
+            let swizzled_offset = swizzled_layer + sizes.swizzled_offset;
+            let deswizzled_offset = deswizzled_layer + sizes.deswizzled_offset;
+            if swizzled_offset + sizes.swizzled_size <= source.len() {
+                scheme.untile_mip(
+                    params,
+                    &sizes.level,
+                    &source[swizzled_offset..],
+                    &mut data[deswizzled_offset..],
+                );
+            } else {
+                missing.push(MissingMip {
+                    layer,
+                    mip: sizes.level.mip,
+                    offset: deswizzled_offset,
+                    size: sizes.deswizzled_size,
+                });
+            }
+        }
+
+        if layout.tail_start < params.mipmap_count {
+            let mut swizzled_offset = swizzled_layer + layout.tail.swizzled_offset;
+            let mut deswizzled_offset = deswizzled_layer + layout.tail.deswizzled_offset;
+            if swizzled_offset + layout.tail.swizzled_size <= source.len() {
+                swizzle_mip_tail::<_, true>(
+                    &scheme,
+                    params,
+                    layout.tail_start,
+                    source,
+                    &mut swizzled_offset,
+                    &mut data,
+                    &mut deswizzled_offset,
+                )?;
+            } else {
+                for mip in layout.tail_start..params.mipmap_count {
+                    let level = mip_level(params, mip);
+                    let size =
+                        deswizzled_mip_size(level.width, level.height, 1, params.bytes_per_pixel);
+                    missing.push(MissingMip {
+                        layer,
+                        mip,
+                        offset: deswizzled_offset,
+                        size,
+                    });
+                    deswizzled_offset += size;
+                }
+            }
+        }
+    }
+
+    Ok(LossySurface { data, missing })
+}
+
+/// Untiles all the array layers and mipmaps in `source` using the tiled size of each mipmap in `mip_sizes`.
+/// This is the same as [SurfaceParams::deswizzle] but uses the mipmap sizes stored in the file
+/// instead of calculating the offset of each mipmap.
+///
+/// Many file formats store the tiled size of each mipmap in a header or footer.
+/// Each mipmap starts immediately after the previous mipmap using the sizes in `mip_sizes`.
+/// The array layers start after all the mipmaps with the same alignment between layers as [SurfaceParams::swizzle].
+/// Set [SurfaceParams::layer_alignment] to 1 for array layers that start immediately after the previous layer.
+///
+/// The mipmaps are still untiled using the dimensions and block heights from `params`.
+///
+/// Returns [SwizzleError::NotEnoughMipSizes] if `mip_sizes` does not contain a size for every mipmap.
+/// Returns [SwizzleError::NotEnoughMipData] if `source` does not contain the tiled data for every mipmap.
+/// Returns [SwizzleError::Unsupported] if the surface has a packed mip tail.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{deswizzle_with_mip_sizes, BlockDim, SurfaceParams};
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    block_dim: BlockDim::block_4x4(),
+    bytes_per_pixel: 16,
+    mipmap_count: 9,
+    ..Default::default()
+};
+// The tiled mipmap sizes from the file footer.
+let mip_sizes = [65536, 16384, 4096, 1024, 512, 512, 512, 512, 512];
+# let input = vec![0u8; mip_sizes.iter().sum()];
+let untiled = deswizzle_with_mip_sizes(&input, &params, &mip_sizes).unwrap();
+assert_eq!(params.deswizzled_size(), untiled.len());
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn deswizzle_with_mip_sizes(
+    source: &[u8],
+    params: &SurfaceParams,
+    mip_sizes: &[usize],
+) -> Result<Vec<u8>> {
+    if is_empty(params) {
+        return Ok(Vec::new());
+    }
+
+    validate_params(params)?;
+
+    let mip_sizes =
+        mip_sizes
+            .get(..params.mipmap_count as usize)
+            .ok_or(SwizzleError::NotEnoughMipSizes {
+                expected_count: params.mipmap_count,
+                actual_count: mip_sizes.len(),
+            })?;
+
+    if mip_tail_start(params) < params.mipmap_count {
+        return Err(SwizzleError::Unsupported {
+            feature: "packed mip tails with explicit mipmap sizes",
+        });
+    }
+
+    let scheme = TegraBlockLinear;
+    let layout = SurfaceLayout::new(&scheme, params);
+
+    // The sizes come from untrusted files, so avoid overflow for very large values.
+    let layer_size = mip_sizes.iter().fold(0usize, |a, b| a.saturating_add(*b));
+    let layer_stride = if params.layer_count > 1 && layer_size <= source.len() {
+        scheme.align_layer_size(params, layer_size)
+    } else {
+        layer_size
+    };
+
+    // Check the offsets for all mipmaps before allocating.
+    let mips = layout.mips();
+    let mip_offsets = || {
+        (0..params.layer_count).flat_map(move |layer| {
+            let layer_offset = (layer as usize).saturating_mul(layer_stride);
+            mips.iter()
+                .zip(mip_sizes)
+                .scan(layer_offset, move |offset, (sizes, mip_size)| {
+                    let swizzled_offset = *offset;
+                    *offset = offset.saturating_add(*mip_size);
+                    Some((layer, sizes, swizzled_offset))
+                })
+        })
+    };
+    for (layer, sizes, swizzled_offset) in mip_offsets() {
+        let expected_size = swizzled_offset.saturating_add(sizes.swizzled_size);
+        if source.len() < expected_size {
+            return Err(SwizzleError::NotEnoughMipData {
+                layer,
+                mip: sizes.level.mip,
+                expected_size,
+                actual_size: source.len(),
+            });
+        }
+    }
+
+    let mut data = vec![0u8; layout.deswizzled_size()];
+    for (layer, sizes, swizzled_offset) in mip_offsets() {
+        let deswizzled_offset =
+            layer as usize * layout.deswizzled_layer_size + sizes.deswizzled_offset;
+        scheme.untile_mip(
+            params,
+            &sizes.level,
+            &source[swizzled_offset..],
+            &mut data[deswizzled_offset..],
+        );
+    }
+
+    Ok(data)
+}
+
+#[cfg(not(feature = "minimal"))]
+fn swizzle_surface_params<S: TilingScheme, const DESWIZZLE: bool>(
+    scheme: &S,
+    params: &SurfaceParams,
+    source: &[u8],
+) -> Result<Vec<u8>> {
+    // Check for empty surfaces first to more reliably handle overflow.
+    if is_empty(params) {
+        return Ok(Vec::new());
+    }
+
+    validate_params(params)?;
+
+    // Validate the source length before attempting to allocate.
+    // This reduces potential out of memory panics.
+    // The same layout determines the size and the offsets, so don't reallocate later.
+    let layout = SurfaceLayout::new(scheme, params);
+    let surface_size = surface_destination_size::<DESWIZZLE>(&layout, source)?;
+    let mut result = vec![0u8; surface_size];
+
+    swizzle_surface_inner::<_, DESWIZZLE>(scheme, params, &layout, source, &mut result)?;
+
+    Ok(result)
+}
+
+// The total untiled size for progress callbacks.
+// Invalid surfaces return an error before reporting any progress,
+// so avoid calculating sizes that may overflow.
+fn progress_total(params: &SurfaceParams) -> usize {
+    if is_empty(params) || validate_params(params).is_err() {
+        0
+    } else {
+        params.deswizzled_size()
+    }
+}
+
+fn swizzle_surface_params_into<S: TilingScheme, D: DestBuffer + ?Sized, const DESWIZZLE: bool>(
+    scheme: &S,
+    params: &SurfaceParams,
+    source: &[u8],
+    destination: &mut D,
+) -> Result<()> {
+    if is_empty(params) {
+        destination.buffer(0)?;
+        return Ok(());
+    }
+
+    validate_params(params)?;
+
+    let layout = SurfaceLayout::new(scheme, params);
+    let surface_size = surface_destination_size::<DESWIZZLE>(&layout, source)?;
+    let destination = &mut destination.buffer(surface_size)?[..surface_size];
+
+    // Padding bytes are not written while tiling, so clear any previous data.
+    if !DESWIZZLE {
+        destination.fill(0);
+    }
+
+    swizzle_surface_inner::<_, DESWIZZLE>(scheme, params, &layout, source, destination)
+}
+
+fn swizzle_surface_inner<S: TilingScheme, const DESWIZZLE: bool>(
+    scheme: &S,
+    params: &SurfaceParams,
+    layout: &SurfaceLayout,
+    source: &[u8],
+    result: &mut [u8],
+) -> Result<()> {
+    let (tiled_len, linear_len) = if DESWIZZLE {
+        (source.len(), result.len())
+    } else {
+        (result.len(), source.len())
+    };
+
+    for layer in 0..params.layer_count as usize {
+        let swizzled_layer = layer * layout.swizzled_layer_size;
+        let deswizzled_layer = layer * layout.deswizzled_layer_size;
+
+        for mip in layout.mips() {
+            let swizzled_offset = swizzled_layer + mip.swizzled_offset;
+            let deswizzled_offset = deswizzled_layer + mip.deswizzled_offset;
+            check_internal(
+                swizzled_offset + mip.swizzled_size <= tiled_len
+                    && deswizzled_offset + mip.deswizzled_size <= linear_len,
+                "mipmap outside the surface",
+            )?;
+            if DESWIZZLE {
+                scheme.untile_mip(
+                    params,
+                    &mip.level,
+                    &source[swizzled_offset..],
+                    &mut result[deswizzled_offset..],
+                );
+            } else {
+                scheme.tile_mip(
+                    params,
+                    &mip.level,
+                    &source[deswizzled_offset..],
+                    &mut result[swizzled_offset..],
+                );
+            }
+        }
+
+        swizzle_layer_tail::<_, DESWIZZLE>(scheme, params, layout, layer, source, result)?;
+    }
+
+    Ok(())
+}
+
+// Copy the packed mip tail for an array layer if the surface has one.
+fn swizzle_layer_tail<S: TilingScheme, const DESWIZZLE: bool>(
+    scheme: &S,
+    params: &SurfaceParams,
+    layout: &SurfaceLayout,
+    layer: usize,
+    source: &[u8],
+    result: &mut [u8],
+) -> Result<()> {
+    if layout.tail_start < params.mipmap_count {
+        let mut swizzled_offset = layer * layout.swizzled_layer_size + layout.tail.swizzled_offset;
+        let mut deswizzled_offset =
+            layer * layout.deswizzled_layer_size + layout.tail.deswizzled_offset;
+        let (src_offset, dst_offset) = if DESWIZZLE {
+            (&mut swizzled_offset, &mut deswizzled_offset)
+        } else {
+            (&mut deswizzled_offset, &mut swizzled_offset)
+        };
+        swizzle_mip_tail::<_, DESWIZZLE>(
+            scheme,
+            params,
+            layout.tail_start,
+            source,
+            src_offset,
+            result,
+            dst_offset,
+        )?;
+    }
+    Ok(())
+}
+
+// Tiling returns early without an error once cancelled, so check the flag again.
+fn check_cancelled<T>(cancel: &AtomicBool, result: Result<T>) -> Result<T> {
+    match result {
+        Ok(_) if cancel.load(Ordering::Relaxed) => Err(SwizzleError::Cancelled),
+        result => result,
+    }
+}
+
+// Check that the tiled source contains every layer and mipmap of the surface.
+#[cfg(not(feature = "minimal"))]
+fn check_swizzled_source(params: &SurfaceParams, source: &[u8]) -> Result<usize> {
+    let layout = SurfaceLayout::new(&TegraBlockLinear, params);
+    let expected_size = layout.swizzled_size();
+    if source.len() < expected_size {
+        return Err(layout.not_enough_data(true, source.len()));
+    }
+    Ok(expected_size)
+}
+
+// Calculate the size of the output after checking that the source has enough data.
+fn surface_destination_size<const DESWIZZLE: bool>(
+    layout: &SurfaceLayout,
+    source: &[u8],
+) -> Result<usize> {
+    let swizzled_size = layout.swizzled_size();
+    let deswizzled_size = layout.deswizzled_size();
+    let (surface_size, expected_size) = if DESWIZZLE {
+        (deswizzled_size, swizzled_size)
+    } else {
+        (swizzled_size, deswizzled_size)
+    };
+
+    if source.len() < expected_size {
+        return Err(layout.not_enough_data(DESWIZZLE, source.len()));
+    }
+
+    Ok(surface_size)
+}
+
+// The offsets of each mipmap within an array layer.
+// Calculating the size and offsets from a single layout prevents them from disagreeing.
+#[derive(Debug, Clone)]
+pub(crate) struct SurfaceLayout {
+    // Surfaces have at most 32 mipmaps, so this doesn't need to allocate.
+    mips: [MipSizes; u32::BITS as usize],
+    tail_start: u32,
+    // The packed mipmaps starting from tail_start.
+    tail: MipSizes,
+    // The layer sizes including any alignment between layers.
+    pub(crate) swizzled_layer_size: usize,
+    pub(crate) deswizzled_layer_size: usize,
+    layer_count: u32,
+}
+
+impl SurfaceLayout {
+    pub(crate) fn new<S: TilingScheme>(scheme: &S, params: &SurfaceParams) -> Self {
+        let tail_start = mip_tail_start(params);
+
+        // The mipmaps are the same for each layer, so only calculate the sizes once.
+        let mut mips = [MipSizes::default(); u32::BITS as usize];
+        let mut swizzled_offset = 0;
+        let mut deswizzled_offset = 0;
+        for (mip, sizes) in mips[..tail_start as usize].iter_mut().enumerate() {
+            let level = mip_level(params, mip as u32);
+            *sizes = MipSizes {
+                swizzled_offset,
+                deswizzled_offset,
+                swizzled_size: scheme.tiled_mip_size(params, &level),
+                deswizzled_size: deswizzled_mip_size(
+                    level.width,
+                    level.height,
+                    level.depth,
+                    params.bytes_per_pixel,
+                ),
+                level,
+            };
+            swizzled_offset += sizes.swizzled_size;
+            deswizzled_offset += sizes.deswizzled_size;
+        }
+
+        let tail = MipSizes {
+            swizzled_offset,
+            deswizzled_offset,
+            swizzled_size: mip_tail_gob_count(params, tail_start)
+                * scheme.tiled_mip_size(&MIP_TAIL_SURFACE, &MIP_TAIL_LEVEL),
+            deswizzled_size: tail_deswizzled_size(params, tail_start),
+            level: MipLevel::default(),
+        };
+
+        let swizzled_layer_size = tail.swizzled_offset + tail.swizzled_size;
+        let deswizzled_layer_size = tail.deswizzled_offset + tail.deswizzled_size;
+
+        Self {
+            mips,
+            tail_start,
+            tail,
+            // We only need alignment between layers.
+            swizzled_layer_size: if params.layer_count > 1 {
+                scheme.align_layer_size(params, swizzled_layer_size)
+            } else {
+                swizzled_layer_size
+            },
+            deswizzled_layer_size,
+            layer_count: params.layer_count,
+        }
+    }
+
+    pub(crate) fn mips(&self) -> &[MipSizes] {
+        &self.mips[..self.tail_start as usize]
+    }
+
+    fn swizzled_size(&self) -> usize {
+        self.swizzled_layer_size * self.layer_count as usize
+    }
+
+    fn deswizzled_size(&self) -> usize {
+        self.deswizzled_layer_size * self.layer_count as usize
+    }
+
+    // Find the first layer and mipmap that ends after the available data.
+    fn not_enough_data(&self, swizzled: bool, actual_size: usize) -> SwizzleError {
+        let (layer_size, total_size) = if swizzled {
+            (self.swizzled_layer_size, self.swizzled_size())
+        } else {
+            (self.deswizzled_layer_size, self.deswizzled_size())
+        };
+
+        let tail = Some(&self.tail).filter(|t| t.swizzled_size > 0);
+        for layer in 0..self.layer_count {
+            for (mip, sizes) in self.mips().iter().chain(tail).enumerate() {
+                let (offset, size) = if swizzled {
+                    (sizes.swizzled_offset, sizes.swizzled_size)
+                } else {
+                    (sizes.deswizzled_offset, sizes.deswizzled_size)
+                };
+                let expected_size = layer as usize * layer_size + offset + size;
+                if actual_size < expected_size {
+                    return SwizzleError::NotEnoughMipData {
+                        layer,
+                        mip: mip as u32,
+                        expected_size,
+                        actual_size,
+                    };
+                }
+            }
+        }
+
+        // The data is only missing the padding after the last mipmap.
+        SwizzleError::NotEnoughMipData {
+            layer: self.layer_count.saturating_sub(1),
+            mip: self.tail_start.saturating_sub(tail.is_none() as u32),
+            expected_size: total_size,
+            actual_size,
+        }
+    }
+}
+
+// The mip dimensions in blocks.
+const fn mip_dimensions(params: &SurfaceParams, mip: u32) -> (u32, u32, u32) {
+    let mip_width = max_u32(
+        div_round_up(params.width >> mip, params.block_dim.width.get()),
+        1,
+    );
+    let mip_height = max_u32(
+        div_round_up(params.height >> mip, params.block_dim.height.get()),
+        1,
+    );
+    let mip_depth = max_u32(
+        div_round_up(params.depth >> mip, params.block_dim.depth.get()),
+        1,
+    );
+    (mip_width, mip_height, mip_depth)
+}
+
+pub(crate) fn mip_level(params: &SurfaceParams, mip: u32) -> MipLevel {
+    let (width, height, depth) = mip_dimensions(params, mip);
+    MipLevel {
+        mip,
+        width,
+        height,
+        depth,
+    }
+}
+
+// core::cmp::max is not const.
+const fn max_u32(a: u32, b: u32) -> u32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+pub(crate) const fn block_height_mip0(params: &SurfaceParams) -> BlockHeight {
+    // The block height can be inferred if not specified.
+    // TODO: Enforce a block height of 1 for depth textures elsewhere?
+    if params.depth == 1 {
+        match (params.block_height_mip0, params.mip_block_heights) {
+            (Some(block_height), _) => block_height,
+            (None, Some([block_height, ..])) => *block_height,
+            (None, _) => params
+                .block_height_heuristic
+                .block_height_mip0(div_round_up(params.height, params.block_dim.height.get())),
+        }
+    } else {
+        BlockHeight::One
+    }
+}
+
+// Use the explicit layer alignment if present or calculate it from the base mip level.
+pub(crate) const fn surface_layer_size(params: &SurfaceParams, layer_size: usize) -> usize {
+    match params.layer_alignment {
+        Some(alignment) => layer_size.next_multiple_of(alignment.get()),
+        None => align_layer_size(
+            layer_size,
+            div_round_up(params.height, params.block_dim.height.get()),
+            params.depth,
+            block_height_mip0(params),
+            1,
+        ),
+    }
+}
+
+// Use the explicit block height if present or calculate it from the base mip level.
+pub(crate) const fn surface_mip_block_height(
+    params: &SurfaceParams,
+    mip: u32,
+    mip_height: u32,
+) -> BlockHeight {
+    if let Some(block_heights) = params.mip_block_heights {
+        if (mip as usize) < block_heights.len() {
+            return block_heights[mip as usize];
+        }
+    }
+    mip_block_height(mip_height, block_height_mip0(params))
+}
+
+// Find the first mip that fits within a single GOB.
+// All remaining mipmaps will be even smaller and also fit within a GOB.
+const fn mip_tail_start(params: &SurfaceParams) -> u32 {
+    if !params.packed_mip_tail || params.depth != 1 {
+        return params.mipmap_count;
+    }
+
+    let mut mip = 0;
+    while mip < params.mipmap_count {
+        let (mip_width, mip_height, _) = mip_dimensions(params, mip);
+        if mip_width as u64 * params.bytes_per_pixel as u64 <= GOB_WIDTH_IN_BYTES as u64
+            && mip_height <= GOB_HEIGHT_IN_BYTES
+        {
+            return mip;
+        }
+        mip += 1;
+    }
+    params.mipmap_count
+}
+
+// Each GOB of the packed mip tail is tiled like a 64x8 surface with 1 byte per pixel.
+const MIP_TAIL_SURFACE: SurfaceParams<'static> = SurfaceParams {
+    width: GOB_WIDTH_IN_BYTES,
+    height: GOB_HEIGHT_IN_BYTES,
+    depth: 1,
+    block_dim: BlockDim::uncompressed(),
+    block_height_mip0: Some(BlockHeight::One),
+    block_height_heuristic: BlockHeightHeuristic::Trm,
+    bytes_per_pixel: 1,
+    mipmap_count: 1,
+    layer_count: 1,
+    packed_mip_tail: false,
+    mip_block_heights: None,
+    layer_alignment: None,
+    max_surface_size: None,
+};
+
+const MIP_TAIL_LEVEL: MipLevel = MipLevel {
+    mip: 0,
+    width: GOB_WIDTH_IN_BYTES,
+    height: GOB_HEIGHT_IN_BYTES,
+    depth: 1,
+};
+
+// The location of a mipmap in the packed mip tail.
+struct TailPosition {
+    mip: u32,
+    gob: usize,
+    x: u32,
+}
+
+// Mipmaps in the tail are placed left to right within each 64x8 byte GOB.
+// A mipmap that doesn't fit in the remaining width of the GOB starts a new GOB.
+const fn next_tail_position(params: &SurfaceParams, mip: u32, gob: usize, x: u32) -> TailPosition {
+    let (mip_width, _, _) = mip_dimensions(params, mip);
+    if x + mip_width * params.bytes_per_pixel > GOB_WIDTH_IN_BYTES {
+        TailPosition {
+            mip,
+            gob: gob + 1,
+            x: 0,
+        }
+    } else {
+        TailPosition { mip, gob, x }
+    }
+}
+
+fn mip_tail_positions<'a>(
+    params: &'a SurfaceParams,
+    tail_start: u32,
+) -> impl Iterator<Item = TailPosition> + 'a {
+    let mut gob = 0;
+    let mut x = 0;
+    (tail_start..params.mipmap_count).map(move |mip| {
+        let position = next_tail_position(params, mip, gob, x);
+        let (mip_width, _, _) = mip_dimensions(params, mip);
+        gob = position.gob;
+        x = position.x + mip_width * params.bytes_per_pixel;
+        position
+    })
+}
+
+const fn mip_tail_gob_count(params: &SurfaceParams, tail_start: u32) -> usize {
+    if tail_start >= params.mipmap_count {
+        return 0;
+    }
+
+    let mut gob = 0;
+    let mut x = 0;
+    let mut mip = tail_start;
+    while mip < params.mipmap_count {
+        let position = next_tail_position(params, mip, gob, x);
+        let (mip_width, _, _) = mip_dimensions(params, mip);
+        gob = position.gob;
+        x = position.x + mip_width * params.bytes_per_pixel;
+        mip += 1;
+    }
+    gob + 1
+}
+
+fn tail_deswizzled_size(params: &SurfaceParams, tail_start: u32) -> usize {
+    (tail_start..params.mipmap_count)
+        .map(|mip| {
+            let (mip_width, mip_height, _) = mip_dimensions(params, mip);
+            deswizzled_mip_size(mip_width, mip_height, 1, params.bytes_per_pixel)
+        })
+        .sum()
+}
+
+fn swizzle_mip_tail<S: TilingScheme, const DESWIZZLE: bool>(
+    scheme: &S,
+    params: &SurfaceParams,
+    tail_start: u32,
+    source: &[u8],
+    src_offset: &mut usize,
+    dst: &mut [u8],
+    dst_offset: &mut usize,
+) -> Result<()> {
+    let gob_count = mip_tail_gob_count(params, tail_start);
+    let gob_size = scheme.tiled_mip_size(&MIP_TAIL_SURFACE, &MIP_TAIL_LEVEL);
+    let swizzled_size = gob_count * gob_size;
+    let deswizzled_size = tail_deswizzled_size(params, tail_start);
+
+    // Callers check the source length first to return the layer and mipmap in the error.
+    let input_size = if DESWIZZLE {
+        swizzled_size
+    } else {
+        deswizzled_size
+    };
+    check_internal(
+        *src_offset + input_size <= source.len(),
+        "mipmap tail outside the source",
+    )?;
+    let output_size = if DESWIZZLE {
+        deswizzled_size
+    } else {
+        swizzled_size
+    };
+    check_internal(
+        *dst_offset + output_size <= dst.len(),
+        "mipmap tail outside the surface",
+    )?;
+
+    let (tiled_offset, mut linear_offset) = if DESWIZZLE {
+        (*src_offset, *dst_offset)
+    } else {
+        (*dst_offset, *src_offset)
+    };
+
+    // Each tail GOB is tiled as a 64x8 byte surface.
+    // The mipmaps are copied to and from their regions of the untiled GOB.
+    let mut positions = mip_tail_positions(params, tail_start).peekable();
+    for gob in 0..gob_count {
+        let gob_offset = tiled_offset + gob * gob_size;
+        let mut linear_gob = [0u8; GOB_SIZE_IN_BYTES as usize];
+
+        if DESWIZZLE {
+            scheme.untile_mip(
+                &MIP_TAIL_SURFACE,
+                &MIP_TAIL_LEVEL,
+                &source[gob_offset..],
+                &mut linear_gob,
+            );
+        }
+
+        while let Some(position) = positions.next_if(|p| p.gob == gob) {
+            let (mip_width, mip_height, _) = mip_dimensions(params, position.mip);
+            let row_size = (mip_width * params.bytes_per_pixel) as usize;
+            for y in 0..mip_height as usize {
+                let gob_row = y * GOB_WIDTH_IN_BYTES as usize + position.x as usize;
+                let linear_row = linear_offset + y * row_size;
+                if DESWIZZLE {
+                    dst[linear_row..linear_row + row_size]
+                        .copy_from_slice(&linear_gob[gob_row..gob_row + row_size]);
+                } else {
+                    linear_gob[gob_row..gob_row + row_size]
+                        .copy_from_slice(&source[linear_row..linear_row + row_size]);
+                }
+            }
+            linear_offset += row_size * mip_height as usize;
+        }
+
+        if !DESWIZZLE {
+            scheme.tile_mip(
+                &MIP_TAIL_SURFACE,
+                &MIP_TAIL_LEVEL,
+                &linear_gob,
+                &mut dst[gob_offset..],
+            );
+        }
+    }
+
+    if DESWIZZLE {
+        *src_offset += swizzled_size;
+        *dst_offset += deswizzled_size;
+    } else {
+        *src_offset += deswizzled_size;
+        *dst_offset += swizzled_size;
+    };
+
+    Ok(())
+}
+
+pub(crate) const fn is_empty(params: &SurfaceParams) -> bool {
+    params.width == 0
+        || params.height == 0
+        || params.depth == 0
+        || params.bytes_per_pixel == 0
+        || params.mipmap_count == 0
+        || params.layer_count == 0
+}
+
+/// Suspicious surface parameters that can still be tiled or untiled.
+/// These usually indicate that the parameters were read incorrectly.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum Warning {
+    /// There are more mipmaps than needed to reach a 1x1x1 mipmap.
+    MipmapCountExceedsChain {
+        mipmap_count: u32,
+        max_mipmap_count: u32,
+    },
+
+    /// The block height is larger than the block height inferred from the height.
+    /// Block heights larger than necessary waste memory on padding.
+    BlockHeightLargerThanExpected {
+        block_height: BlockHeight,
+        expected: BlockHeight,
+    },
+
+    /// The bytes per pixel does not match any common uncompressed or compressed formats.
+    UncommonBytesPerPixel { bytes_per_pixel: u32 },
+}
+
+impl core::fmt::Display for Warning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Warning::MipmapCountExceedsChain {
+                mipmap_count,
+                max_mipmap_count,
+            } => write!(
+                f,
+                "The mipmap count {mipmap_count} exceeds the {max_mipmap_count} mipmaps in a full mipmap chain."
+            ),
+            Warning::BlockHeightLargerThanExpected {
+                block_height,
+                expected,
+            } => write!(
+                f,
+                "The block height {} is larger than the expected block height {}.",
+                *block_height as u32, *expected as u32
+            ),
+            Warning::UncommonBytesPerPixel { bytes_per_pixel } => write!(
+                f,
+                "The bytes per pixel {bytes_per_pixel} does not match any common formats."
+            ),
+        }
+    }
+}
+
+/// Checks `params` for errors and suspicious but valid parameters.
+///
+/// Returns any errors that would occur when tiling or untiling with `params`.
+/// Otherwise, returns a list of [Warning] for parameters that are valid but likely incorrect.
+/// This allows applications to warn users before starting a long conversion.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::{validate, SurfaceParams, Warning};
+
+let params = SurfaceParams {
+    width: 16,
+    height: 16,
+    mipmap_count: 10,
+    ..Default::default()
+};
+assert_eq!(
+    vec![Warning::MipmapCountExceedsChain {
+        mipmap_count: 10,
+        max_mipmap_count: 5
+    }],
+    validate(&params).unwrap()
+);
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn validate(params: &SurfaceParams) -> Result<Vec<Warning>> {
+    validate_params(params)?;
+
+    let mut warnings = Vec::new();
+
+    // Each mipmap halves the dimensions until reaching 1x1x1.
+    let max_dimension = params.width.max(params.height).max(params.depth).max(1);
+    let max_mipmap_count = max_dimension.ilog2() + 1;
+    if params.mipmap_count > max_mipmap_count {
+        warnings.push(Warning::MipmapCountExceedsChain {
+            mipmap_count: params.mipmap_count,
+            max_mipmap_count,
+        });
+    }
+
+    // 3D surfaces always use a block height of 1.
+    if let (Some(block_height), 1) = (params.block_height_mip0, params.depth) {
+        let expected = params
+            .block_height_heuristic
+            .block_height_mip0(div_round_up(params.height, params.block_dim.height.get()));
+        if block_height as u32 > expected as u32 {
+            warnings.push(Warning::BlockHeightLargerThanExpected {
+                block_height,
+                expected,
+            });
+        }
+    }
+
+    // Check the sizes for common uncompressed formats and BCN or ASTC blocks.
+    if !matches!(params.bytes_per_pixel, 1 | 2 | 4 | 8 | 12 | 16) {
+        warnings.push(Warning::UncommonBytesPerPixel {
+            bytes_per_pixel: params.bytes_per_pixel,
+        });
+    }
+
+    Ok(warnings)
+}
+
+pub(crate) fn validate_params(params: &SurfaceParams) -> Result<()> {
+    validate_surface(
+        params.width,
+        params.height,
+        params.depth,
+        params.bytes_per_pixel,
+        params.mipmap_count,
+    )?;
+
+    // The Tegra X1 does not support arrays of 3D textures.
+    // Reject these surfaces instead of guessing at the alignment between layers.
+    if params.depth > 1 && params.layer_count > 1 {
+        return Err(SwizzleError::InvalidSurface {
+            width: params.width,
+            height: params.height,
+            depth: params.depth,
+            bytes_per_pixel: params.bytes_per_pixel,
+            mipmap_count: params.mipmap_count,
+        });
+    }
+
+    // Large explicit alignments can overflow the aligned layer size or the total size.
+    if let (Some(alignment), true) = (params.layer_alignment, params.layer_count > 1) {
+        let layer_size = layer_stride_tiled(&SurfaceParams {
+            layer_count: 1,
+            ..*params
+        });
+        if layer_size
+            .checked_next_multiple_of(alignment.get())
+            .and_then(|size| size.checked_mul(params.layer_count as usize))
+            .is_none()
+        {
+            return Err(SwizzleError::InvalidSurface {
+                width: params.width,
+                height: params.height,
+                depth: params.depth,
+                bytes_per_pixel: params.bytes_per_pixel,
+                mipmap_count: params.mipmap_count,
+            });
+        }
+    }
+
+    // The Tegra X1 does not support compressed formats with 3D blocks like 3D ASTC.
+    // The depth of the GOB blocks is inferred separately from the surface depth.
+    if params.block_dim.depth.get() != 1 {
+        return Err(SwizzleError::InvalidBlockDim {
+            width: params.block_dim.width.get(),
+            height: params.block_dim.height.get(),
+            depth: params.block_dim.depth.get(),
+        });
+    }
+
+    if let Some(block_heights) = params.mip_block_heights {
+        if block_heights.len() < params.mipmap_count as usize {
+            return Err(SwizzleError::NotEnoughBlockHeights {
+                expected_count: params.mipmap_count,
+                actual_count: block_heights.len(),
+            });
+        }
+    }
+
+    // Check the size last since the size calculations assume valid parameters.
+    if let Some(max_size) = params.max_surface_size {
+        let size = surface_sizes(params).tiled;
+        if size > max_size {
+            return Err(SwizzleError::AllocationTooLarge { size, max_size });
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_surface(
+    width: u32,
+    height: u32,
+    depth: u32,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+) -> Result<()> {
+    // Check dimensions to prevent overflow.
+    if width
+        .checked_mul(height)
+        .and_then(|u| u.checked_mul(depth))
+        .and_then(|u| u.checked_mul(bytes_per_pixel))
+        .is_none()
+        || width.checked_mul(bytes_per_pixel).is_none()
+        || depth.checked_add(depth / 2).is_none()
+        || mipmap_count > u32::BITS
+    {
+        Err(SwizzleError::InvalidSurface {
+            width,
+            height,
+            depth,
+            bytes_per_pixel,
+            mipmap_count,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Calculates the size in bytes for the tiled data for the given surface.
+/// Compare with [deswizzled_surface_size].
+///
+/// Dimensions should be in pixels.
+///
+/// Use a `block_height_mip0` of [None] to infer the block height from the specified dimensions.
+///
+/// # Examples
+/// The size can be calculated at compile time for known surface parameters.
+/**
+```rust
+use tegra_swizzle::surface::{BlockDim, swizzled_surface_size};
+
+// 16x16 BC7 cube map with 5 mipmaps.
+const SIZE: usize = swizzled_surface_size(16, 16, 1, BlockDim::block_4x4(), None, 16, 5, 6);
+assert_eq!(15360, SIZE);
+```
+ */
+#[deprecated(
+    since = "0.5.0",
+    note = "use SurfaceParams::swizzled_size instead, see the compat module"
+)]
+pub const fn swizzled_surface_size(
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_dim: BlockDim, // TODO: Use None to indicate uncompressed?
+    block_height_mip0: Option<BlockHeight>, // TODO: Make this optional in other functions as well?
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    layer_count: u32,
+) -> usize {
+    SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0,
+        block_height_heuristic: BlockHeightHeuristic::Trm,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count,
+        packed_mip_tail: false,
+        mip_block_heights: None,
+        layer_alignment: None,
+        max_surface_size: None,
+    }
+    .swizzled_size()
+}
+
+/// Calculates the size in bytes for the tiled data for the given surface
+/// with the block height for each mipmap specified in `mip_block_heights`.
+/// Compare with [swizzled_surface_size].
+///
+/// Dimensions should be in pixels.
+///
+/// Mipmaps without an entry in `mip_block_heights` use [mip_block_height].
+#[deprecated(
+    since = "0.5.0",
+    note = "use SurfaceParams::swizzled_size with mip_block_heights instead, see the compat module"
+)]
+pub const fn swizzled_surface_size_with_block_heights(
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_dim: BlockDim,
+    mip_block_heights: &[BlockHeight],
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    layer_count: u32,
+) -> usize {
+    SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0: None,
+        block_height_heuristic: BlockHeightHeuristic::Trm,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count,
+        packed_mip_tail: false,
+        mip_block_heights: Some(mip_block_heights),
+        layer_alignment: None,
+        max_surface_size: None,
+    }
+    .swizzled_size()
+}
+
+/// Calculates the size in bytes for the untiled or linear data for the given surface.
+/// Compare with [swizzled_surface_size].
+///
+/// Dimensions should be in pixels.
+///
+/// # Examples
+/// The size can be calculated at compile time for known surface parameters.
+/**
+```rust
+use tegra_swizzle::surface::{BlockDim, deswizzled_surface_size};
+
+// 16x16 BC7 cube map with 5 mipmaps.
+const SIZE: usize = deswizzled_surface_size(16, 16, 1, BlockDim::block_4x4(), 16, 5, 6);
+assert_eq!(2208, SIZE);
+```
+ */
+#[deprecated(
+    since = "0.5.0",
+    note = "use SurfaceParams::deswizzled_size instead, see the compat module"
+)]
+pub const fn deswizzled_surface_size(
+    width: u32,
+    height: u32,
+    depth: u32,
+    block_dim: BlockDim,
+    bytes_per_pixel: u32,
+    mipmap_count: u32,
+    layer_count: u32,
+) -> usize {
+    SurfaceParams {
+        width,
+        height,
+        depth,
+        block_dim,
+        block_height_mip0: None,
+        block_height_heuristic: BlockHeightHeuristic::Trm,
+        bytes_per_pixel,
+        mipmap_count,
+        layer_count,
+        packed_mip_tail: false,
+        mip_block_heights: None,
+        layer_alignment: None,
+        max_surface_size: None,
+    }
+    .deswizzled_size()
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct MipSizes {
+    pub(crate) level: MipLevel,
+    pub(crate) swizzled_offset: usize,
+    pub(crate) deswizzled_offset: usize,
+    pub(crate) swizzled_size: usize,
+    pub(crate) deswizzled_size: usize,
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+#[allow(deprecated)]
+mod tests {
+    use super::*;
+    use crate::fixtures::read_fixture;
+
+    // Use helper functions to shorten the test cases.
+    fn swizzle_length(
+        width: u32,
+        height: u32,
+        source_length: usize,
+        is_compressed: bool,
+        bpp: u32,
+        mipmap_count: u32,
+        layer_count: u32,
+    ) -> usize {
+        swizzle_length_3d(
+            width,
+            height,
+            1,
+            source_length,
+            is_compressed,
+            bpp,
+            mipmap_count,
+            layer_count,
+        )
+    }
+
+    fn deswizzle_length(
+        width: u32,
+        height: u32,
+        source_length: usize,
+        is_compressed: bool,
+        bpp: u32,
+        mipmap_count: u32,
+        layer_count: u32,
+    ) -> usize {
+        deswizzle_length_3d(
+            width,
+            height,
+            1,
+            source_length,
+            is_compressed,
+            bpp,
+            mipmap_count,
+            layer_count,
+        )
+    }
+
+    fn swizzle_length_3d(
+        width: u32,
+        height: u32,
+        depth: u32,
+        source_length: usize,
+        is_compressed: bool,
+        bpp: u32,
+        mipmap_count: u32,
+        layer_count: u32,
+    ) -> usize {
+        swizzle_surface(
+            width,
+            height,
+            depth,
+            &vec![0u8; source_length],
+            if is_compressed {
+                BlockDim::block_4x4()
+            } else {
+                BlockDim::uncompressed()
+            },
+            None,
+            bpp,
+            mipmap_count,
+            layer_count,
+        )
+        .unwrap()
+        .len()
+    }
+
+    fn deswizzle_length_3d(
+        width: u32,
+        height: u32,
+        depth: u32,
+        source_length: usize,
+        is_compressed: bool,
+        bpp: u32,
+        mipmap_count: u32,
+        layer_count: u32,
+    ) -> usize {
+        deswizzle_surface(
+            width,
+            height,
+            depth,
+            &vec![0u8; source_length],
+            if is_compressed {
+                BlockDim::block_4x4()
+            } else {
+                BlockDim::uncompressed()
+            },
+            None,
+            bpp,
+            mipmap_count,
+            layer_count,
+        )
+        .unwrap()
+        .len()
+    }
+
+    // Expected swizzled sizes are taken from the nutexb footer.
+    // Expected deswizzled sizes are the product of the mipmap size sum and the layer count.
+    // TODO: Calculate more accurate deswizzled sizes?
+    // TODO: Add a CSV of nutexb sizes.
+    // TODO: Clean up the existing documentation/data dumps.
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_surface_arrays_no_mipmaps_length() {
+        assert_eq!(6144, swizzle_length(16, 16, 6144, false, 4, 1, 6));
+        assert_eq!(3072, swizzle_length(16, 16, 768, true, 8, 1, 6));
+        assert_eq!(
+            25165824,
+            swizzle_length(2048, 2048, 25165824, true, 16, 1, 6)
+        );
+        assert_eq!(1572864, swizzle_length(256, 256, 1572864, false, 4, 1, 6));
+        assert_eq!(98304, swizzle_length(64, 64, 98304, false, 4, 1, 6));
+        assert_eq!(98304, swizzle_length(64, 64, 98304, false, 4, 1, 6));
+        assert_eq!(393216, swizzle_length(64, 64, 393216, false, 16, 1, 6));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_surface_arrays_mipmaps_length() {
+        assert_eq!(147456, swizzle_length(128, 128, 131232, true, 16, 8, 6));
+        assert_eq!(15360, swizzle_length(16, 16, 2208, true, 16, 5, 6));
+        assert_eq!(540672, swizzle_length(256, 256, 524448, true, 16, 9, 6));
+        assert_eq!(1204224, swizzle_length(288, 288, 664512, true, 16, 9, 6));
+        assert_eq!(2113536, swizzle_length(512, 512, 2097312, true, 16, 10, 6));
+        assert_eq!(49152, swizzle_length(64, 64, 32928, true, 16, 7, 6));
+    }
+
+    #[test]
+    fn swizzle_surface_3d_length() {
+        assert_eq!(
+            16384,
+            swizzle_length_3d(16, 16, 16, 16 * 16 * 16 * 4, false, 4, 1, 1)
+        );
+        assert_eq!(
+            368640,
+            swizzle_length_3d(33, 33, 33, 33 * 33 * 33 * 4, false, 4, 1, 1)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_surface_nutexb_length() {
+        // Sizes and parameters taken from Smash Ultimate nutexb files.
+        // The deswizzled size is estimated as the product of the mip sizes sum and array count.
+        // The swizzled size is taken from the footer.
+        assert_eq!(12800, swizzle_length(100, 100, 6864, true, 8, 7, 1));
+        assert_eq!(360960, swizzle_length(1028, 256, 351376, true, 16, 11, 1));
+        assert_eq!(24064, swizzle_length(128, 32, 21852, false, 4, 8, 1));
+        assert_eq!(
+            2099712,
+            swizzle_length(1536, 1024, 2097184, true, 16, 11, 1)
+        );
+        assert_eq!(35328, swizzle_length(180, 180, 21992, true, 8, 8, 1));
+        assert_eq!(
+            4546048,
+            swizzle_length(2048, 1344, 3670320, true, 16, 12, 1)
+        );
+        assert_eq!(17920, swizzle_length(256, 32, 11024, true, 16, 9, 1));
+        assert_eq!(58368, swizzle_length(320, 128, 54672, true, 16, 9, 1));
+        assert_eq!(125440, swizzle_length(340, 340, 77840, true, 8, 9, 1));
+        assert_eq!(147968, swizzle_length(400, 400, 106864, true, 8, 9, 1));
+        assert_eq!(2048, swizzle_length(4, 24, 384, false, 4, 1, 1));
+        assert_eq!(351744, swizzle_length(512, 384, 262192, true, 16, 10, 1));
+        assert_eq!(440832, swizzle_length(640, 640, 273120, true, 8, 10, 1));
+        assert_eq!(26624, swizzle_length(64, 512, 21896, true, 8, 10, 1));
+        assert_eq!(280064, swizzle_length(800, 400, 213576, true, 8, 10, 1));
+        assert_eq!(
+            16777216,
+            swizzle_length(8192, 2048, 16777216, true, 16, 1, 1)
+        );
+    }
+
+    #[test]
+    fn swizzle_surface_potential_overflow_length() {
+        assert_eq!(0, swizzle_length_3d(u32::MAX, 0, 0, 0, false, 4, 1, 1));
+        assert_eq!(0, swizzle_length_3d(0, u32::MAX, 0, 0, false, 4, 1, 1));
+        assert_eq!(0, swizzle_length_3d(0, 0, u32::MAX, 0, false, 4, 1, 1));
+        assert_eq!(
+            0,
+            swizzle_length_3d(u32::MAX, u32::MAX, u32::MAX, 0, false, 0, 1, 1)
+        );
+        assert_eq!(
+            0,
+            swizzle_length_3d(u32::MAX, u32::MAX, u32::MAX, 0, false, 1, 0, 1)
+        );
+        assert_eq!(
+            0,
+            swizzle_length_3d(u32::MAX, u32::MAX, u32::MAX, 0, false, 1, 1, 0)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn deswizzle_surface_nutexb_length() {
+        // Sizes and parameters taken from Smash Ultimate nutexb files.
+        // The deswizzled size is estimated as the product of the mip sizes sum and layer count.
+        // The swizzled size is taken from the footer.
+        assert_eq!(6864, deswizzle_length(100, 100, 12800, true, 8, 7, 1));
+        assert_eq!(351376, deswizzle_length(1028, 256, 360960, true, 16, 11, 1));
+        assert_eq!(21852, deswizzle_length(128, 32, 24064, false, 4, 8, 1));
+        assert_eq!(
+            2097184,
+            deswizzle_length(1536, 1024, 2099712, true, 16, 11, 1)
+        );
+        assert_eq!(21992, deswizzle_length(180, 180, 35328, true, 8, 8, 1));
+        assert_eq!(
+            3670320,
+            deswizzle_length(2048, 1344, 4546048, true, 16, 12, 1)
+        );
+        assert_eq!(11024, deswizzle_length(256, 32, 17920, true, 16, 9, 1));
+        assert_eq!(54672, deswizzle_length(320, 128, 58368, true, 16, 9, 1));
+        assert_eq!(77840, deswizzle_length(340, 340, 125440, true, 8, 9, 1));
+        assert_eq!(106864, deswizzle_length(400, 400, 147968, true, 8, 9, 1));
+        assert_eq!(384, deswizzle_length(4, 24, 2048, false, 4, 1, 1));
+        assert_eq!(262192, deswizzle_length(512, 384, 351744, true, 16, 10, 1));
+        assert_eq!(273120, deswizzle_length(640, 640, 440832, true, 8, 10, 1));
+        assert_eq!(21896, deswizzle_length(64, 512, 26624, true, 8, 10, 1));
+        assert_eq!(213576, deswizzle_length(800, 400, 280064, true, 8, 10, 1));
+        assert_eq!(
+            16777216,
+            deswizzle_length(8192, 2048, 16777216, true, 16, 1, 1)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn deswizzle_surface_arrays_no_mipmaps_length() {
+        assert_eq!(6144, deswizzle_length(16, 16, 6144, false, 4, 1, 6));
+        assert_eq!(768, deswizzle_length(16, 16, 3072, true, 8, 1, 6));
+        assert_eq!(
+            25165824,
+            deswizzle_length(2048, 2048, 25165824, true, 16, 1, 6)
+        );
+        assert_eq!(1572864, deswizzle_length(256, 256, 1572864, false, 4, 1, 6));
+        assert_eq!(98304, deswizzle_length(64, 64, 98304, false, 4, 1, 6));
+        assert_eq!(98304, deswizzle_length(64, 64, 98304, false, 4, 1, 6));
+        assert_eq!(393216, deswizzle_length(64, 64, 393216, false, 16, 1, 6));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn deswizzle_surface_arrays_mipmaps_length() {
+        assert_eq!(131232, deswizzle_length(128, 128, 147456, true, 16, 8, 6));
+        assert_eq!(2208, deswizzle_length(16, 16, 15360, true, 16, 5, 6));
+        assert_eq!(524448, deswizzle_length(256, 256, 540672, true, 16, 9, 6));
+        assert_eq!(664512, deswizzle_length(288, 288, 1204224, true, 16, 9, 6));
+        assert_eq!(
+            2097312,
+            deswizzle_length(512, 512, 2113536, true, 16, 10, 6)
+        );
+        assert_eq!(32928, deswizzle_length(64, 64, 49152, true, 16, 7, 6));
+    }
+
+    #[test]
+    fn deswizzle_surface_potential_overflow_length() {
+        assert_eq!(0, deswizzle_length(u32::MAX, 0, 0, false, 4, 1, 6));
+        assert_eq!(0, deswizzle_length(0, u32::MAX, 0, false, 4, 1, 6));
+        assert_eq!(0, deswizzle_length(u32::MAX, u32::MAX, 0, false, 0, 1, 6));
+        assert_eq!(0, deswizzle_length(u32::MAX, u32::MAX, 0, false, 4, 0, 6));
+        assert_eq!(0, deswizzle_length(u32::MAX, u32::MAX, 0, false, 4, 1, 0));
+    }
+
+    #[test]
+    fn swizzle_surface_not_enough_data() {
+        let input = [0, 0, 0, 0];
+        let result = swizzle_surface(16, 16, 16, &input, BlockDim::uncompressed(), None, 4, 1, 1);
+        assert_eq!(
+            result,
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 0,
+                mip: 0,
+                expected_size: 16384,
+                actual_size: 4
+            })
+        );
+    }
+
+    #[test]
+    fn deswizzle_surface_not_enough_mip_data() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            block_dim: BlockDim::uncompressed(),
+            bytes_per_pixel: 4,
+            mipmap_count: 3,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let size = params.swizzled_size();
+        let input = vec![0u8; size];
+
+        // The expected size includes the previous layers and mipmaps.
+        assert_eq!(
+            params.deswizzle(&input[..size / 2 + 20000]),
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 1,
+                mip: 1,
+                expected_size: size / 2 + 16384 + 4096,
+                actual_size: size / 2 + 20000
+            })
+        );
+        // The untiled size is used when tiling.
+        assert_eq!(
+            params.swizzle(&input[..100]),
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 0,
+                mip: 0,
+                expected_size: 16384,
+                actual_size: 100
+            })
+        );
+    }
+
+    #[test]
+    fn deswizzle_surface_not_enough_mip_tail_data() {
+        let params = SurfaceParams {
+            width: 32,
+            height: 32,
+            block_dim: BlockDim::uncompressed(),
+            block_height_mip0: Some(BlockHeight::One),
+            bytes_per_pixel: 4,
+            mipmap_count: 6,
+            packed_mip_tail: true,
+            ..Default::default()
+        };
+        let size = params.swizzled_size();
+        let input = vec![0u8; size];
+
+        // The packed mipmaps starting from mip 2 share the last GOB.
+        assert_eq!(
+            params.deswizzle(&input[..size - 1]),
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 0,
+                mip: 2,
+                expected_size: size,
+                actual_size: size - 1
+            })
+        );
+    }
+
+    #[test]
+    fn deswizzle_surface_not_enough_data() {
+        let input = [0, 0, 0, 0];
+        let result = deswizzle_surface(4, 4, 1, &input, BlockDim::uncompressed(), None, 4, 1, 1);
+        assert_eq!(
+            result,
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 0,
+                mip: 0,
+                expected_size: 512,
+                actual_size: 4
+            })
+        );
+    }
+
+    #[test]
+    fn swizzle_surface_potential_out_of_memory() {
+        // Test a large 3D texture that likely won't fit in memory.
+        // The input is clearly too small, so this should error instead of panic.
+        let input = [0, 0, 0, 0];
+        let result = swizzle_surface(
+            65535,
+            65535,
+            65535,
+            &input,
+            BlockDim::uncompressed(),
+            None,
+            4,
+            1,
+            1,
+        );
+        assert_eq!(
+            result,
+            Err(SwizzleError::InvalidSurface {
+                width: 65535,
+                height: 65535,
+                depth: 65535,
+                bytes_per_pixel: 4,
+                mipmap_count: 1
+            })
+        );
+    }
+
+    #[test]
+    fn deswizzle_surface_potential_out_of_memory() {
+        // Test a large 3D texture that likely won't fit in memory.
+        // The input is clearly too small, so this should error instead of panic.
+        let input = [0, 0, 0, 0];
+        let result = deswizzle_surface(
+            65535,
+            65535,
+            65535,
+            &input,
+            BlockDim::uncompressed(),
+            None,
+            4,
+            1,
+            1,
+        );
+        assert_eq!(
+            result,
+            Err(SwizzleError::InvalidSurface {
+                width: 65535,
+                height: 65535,
+                depth: 65535,
+                bytes_per_pixel: 4,
+                mipmap_count: 1
+            })
+        );
+    }
+
+    #[test]
+    fn swizzle_invalid_mipmaps() {
+        // A 32-bit integer dimension can only have 32 mipmaps.
+        let input = [0; 4];
+        let result = swizzle_surface(1, 1, 1, &input, BlockDim::uncompressed(), None, 4, 33, 1);
+        assert_eq!(
+            result,
+            Err(SwizzleError::InvalidSurface {
+                width: 1,
+                height: 1,
+                depth: 1,
+                bytes_per_pixel: 4,
+                mipmap_count: 33,
+            })
+        );
+    }
+
+    #[test]
+    fn deswizzle_surface_invalid_mipmaps() {
+        // A 32-bit integer dimension can only have 32 mipmaps.
+        let input = [0; 4];
+        let result = deswizzle_surface(1, 1, 1, &input, BlockDim::uncompressed(), None, 4, 33, 1);
+        assert_eq!(
+            result,
+            Err(SwizzleError::InvalidSurface {
+                width: 1,
+                height: 1,
+                depth: 1,
+                bytes_per_pixel: 4,
+                mipmap_count: 33,
+            })
+        );
+    }
+
+    #[test]
+    fn swizzle_deswizzle_3d_array() {
+        // The Tegra X1 does not support arrays of 3D textures.
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            depth: 16,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let expected = Err(SwizzleError::InvalidSurface {
+            width: 16,
+            height: 16,
+            depth: 16,
+            bytes_per_pixel: 4,
+            mipmap_count: 1,
+        });
+        let input = vec![0u8; params.swizzled_size()];
+        assert_eq!(expected, params.swizzle(&input));
+        assert_eq!(expected, params.deswizzle(&input));
+        assert_eq!(expected.map(|_| Vec::new()), validate(&params));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_surface_rgba_16_16_16() {
+        let input = &read_fixture("16_16_16_rgba.bin");
+        let expected = &read_fixture("16_16_16_rgba_tiled.bin");
+        let actual =
+            swizzle_surface(16, 16, 16, input, BlockDim::uncompressed(), None, 4, 1, 1).unwrap();
+        assert_eq!(expected, &actual[..]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn deswizzle_surface_rgba_16_16_16() {
+        let input = &read_fixture("16_16_16_rgba_tiled.bin");
+        let expected = &read_fixture("16_16_16_rgba.bin");
+        let actual =
+            deswizzle_surface(16, 16, 16, input, BlockDim::uncompressed(), None, 4, 1, 1).unwrap();
+        assert_eq!(expected, &actual[..]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_surface_rgba_33_33_33() {
+        let input = &read_fixture("33_33_33_rgba.bin");
+        let expected = &read_fixture("33_33_33_rgba_tiled.bin");
+        let actual =
+            swizzle_surface(33, 33, 33, input, BlockDim::uncompressed(), None, 4, 1, 1).unwrap();
+        assert!(expected == &actual[..]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn deswizzle_surface_rgba_33_33_33() {
+        let input = &read_fixture("33_33_33_rgba_tiled.bin");
+        let expected = &read_fixture("33_33_33_rgba.bin");
+        let actual =
+            deswizzle_surface(33, 33, 33, input, BlockDim::uncompressed(), None, 4, 1, 1).unwrap();
+        assert!(expected == &actual[..]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_surface_rgba_16_16_16_mipmaps() {
+        // The base level of a 3D mip chain should match the single mip capture.
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            depth: 16,
+            bytes_per_pixel: 4,
+            mipmap_count: 5,
+            ..Default::default()
+        };
+        // 16x16x16, 8x8x8, 4x4x4, 2x2x2, and 1x1x1 with a GOB depth of 16, 8, 4, 2, and 1.
+        assert_eq!(16384 + 4096 + 2048 + 1024 + 512, params.swizzled_size());
+
+        let mip0 = &read_fixture("16_16_16_rgba.bin");
+        let mut input = vec![0u8; params.deswizzled_size()];
+        input[..mip0.len()].copy_from_slice(mip0);
+        for (i, b) in input[mip0.len()..].iter_mut().enumerate() {
+            *b = i as u8;
+        }
+
+        let tiled = params.swizzle(&input).unwrap();
+        let expected = &read_fixture("16_16_16_rgba_tiled.bin");
+        assert!(expected == &tiled[..expected.len()]);
+
+        assert_eq!(input, params.deswizzle(&tiled).unwrap());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn deswizzle_surface_rgba_33_33_33_mipmaps() {
+        // Mip 1 is 16x16x16 and still uses the GOB depth of 16 from the base level.
+        let params = SurfaceParams {
+            width: 33,
+            height: 33,
+            depth: 33,
+            bytes_per_pixel: 4,
+            mipmap_count: 2,
+            ..Default::default()
+        };
+        let mip0 = &read_fixture("33_33_33_rgba_tiled.bin");
+        let mip1 = &read_fixture("16_16_16_rgba_tiled.bin");
+        assert_eq!(mip0.len() + mip1.len(), params.swizzled_size());
+
+        let input = [&mip0[..], &mip1[..]].concat();
+        let actual = params.deswizzle(&input).unwrap();
+
+        let expected = [
+            &read_fixture("33_33_33_rgba.bin")[..],
+            &read_fixture("16_16_16_rgba.bin")[..],
+        ]
+        .concat();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn swizzle_deswizzle_surface_block_height_32() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 512,
+            bytes_per_pixel: 4,
+            mipmap_count: 4,
+            layer_count: 2,
+            block_height_heuristic: BlockHeightHeuristic::TrmExtended,
+            ..Default::default()
+        };
+        assert_eq!(BlockHeight::ThirtyTwo, block_height_mip0(&params));
+
+        // Each layer has 131072 + 32768 + 8192 + 4096 bytes aligned to a multiple of 16384 bytes.
+        assert_eq!(2 * 180224, params.swizzled_size());
+
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let tiled = params.swizzle(&input).unwrap();
+        assert_eq!(input, params.deswizzle(&tiled).unwrap());
+
+        // The base level uses a block height of 32 instead of 16.
+        let mip0 = crate::swizzle::swizzle_block_linear(
+            64,
+            512,
+            1,
+            &input[..131072],
+            BlockHeight::ThirtyTwo,
+            4,
+        )
+        .unwrap();
+        assert_eq!(mip0, &tiled[..131072]);
+    }
+
+    #[test]
+    fn surface_block_depth_not_one() {
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            depth: 16,
+            block_dim: BlockDim::new(4, 4, 4).unwrap(),
+            bytes_per_pixel: 16,
+            mipmap_count: 1,
+            ..Default::default()
+        };
+        let expected = Err(SwizzleError::InvalidBlockDim {
+            width: 4,
+            height: 4,
+            depth: 4,
+        });
+        let input = vec![0u8; 4096];
+        assert_eq!(expected, params.swizzle(&input));
+        assert_eq!(expected, params.deswizzle(&input));
+
+        let mut output = vec![0u8; 4096];
+        assert_eq!(
+            expected,
+            params
+                .deswizzle_into(&input, &mut output)
+                .map(|_| Vec::new())
+        );
+    }
+
+    #[test]
+    fn block_dim_try_from() {
+        assert_eq!(Ok(BlockDim::uncompressed()), BlockDim::try_from((1, 1)));
+        assert_eq!(
+            Ok(BlockDim::new(12, 10, 1).unwrap()),
+            BlockDim::try_from((12, 10))
+        );
+        assert_eq!(
+            Ok(BlockDim::new(6, 6, 6).unwrap()),
+            BlockDim::try_from((6, 6, 6))
+        );
+        assert_eq!(
+            Err(SwizzleError::InvalidBlockDim {
+                width: 0,
+                height: 4,
+                depth: 1
+            }),
+            BlockDim::try_from((0, 4))
+        );
+        assert_eq!(
+            Err(SwizzleError::InvalidBlockDim {
+                width: 4,
+                height: 4,
+                depth: 17
+            }),
+            BlockDim::try_from((4, 4, 17))
+        );
+        assert_eq!(Some(16), BlockDim::new(16, 16, 16).map(|b| b.depth.get()));
+    }
+
+    #[test]
+    fn extract_layers_cube_map_array() {
+        // Each layer has 86016 bytes aligned to 90112 bytes.
+        let params = SurfaceParams {
+            width: 64,
+            height: 200,
+            bytes_per_pixel: 4,
+            mipmap_count: 3,
+            layer_count: 12,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let tiled = params.swizzle(&input).unwrap();
+
+        let layer_size = params.deswizzled_size() / 12;
+        for (layers, expected_size) in [(6..12, 6 * 90112), (0..1, 86016), (5..7, 2 * 90112)] {
+            let target = SurfaceParams {
+                layer_count: layers.end - layers.start,
+                ..params
+            };
+            let expected = target
+                .swizzle(
+                    &input[layers.start as usize * layer_size..layers.end as usize * layer_size],
+                )
+                .unwrap();
+
+            let actual = extract_layers(&tiled, &params, layers).unwrap();
+            assert_eq!(expected_size, actual.len());
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn extract_layers_invalid_range() {
+        let params = SurfaceParams {
+            width: 4,
+            height: 4,
+            layer_count: 6,
+            ..Default::default()
+        };
+        let input = vec![0u8; params.swizzled_size()];
+        assert_eq!(
+            Err(SwizzleError::InvalidLayerRange {
+                start: 4,
+                end: 7,
+                layer_count: 6
+            }),
+            extract_layers(&input, &params, 4..7)
+        );
+        assert_eq!(Ok(Vec::new()), extract_layers(&input, &params, 2..2));
+        assert_eq!(
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 1,
+                mip: 0,
+                expected_size: 1024,
+                actual_size: 512
+            }),
+            extract_layers(&input[..512], &params, 0..1)
+        );
+    }
+
+    #[test]
+    fn deswizzle_surface_lossy_complete() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 7,
+            layer_count: 6,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.swizzled_size()).map(|i| i as u8).collect();
+        let surface = deswizzle_surface_lossy(&input, &params).unwrap();
+        assert_eq!(params.deswizzle(&input).unwrap(), surface.data);
+        assert!(surface.missing.is_empty());
+    }
+
+    #[test]
+    fn deswizzle_surface_lossy_missing_layers() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 3,
+            layer_count: 4,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.swizzled_size()).map(|i| i as u8).collect();
+        let expected = params.deswizzle(&input).unwrap();
+
+        // Keep the first layer and the base mip level of the second layer.
+        let layer_size = params.swizzled_size() / 4;
+        let surface = deswizzle_surface_lossy(&input[..layer_size + 4096], &params).unwrap();
+
+        let deswizzled_layer_size = 4096 + 1024 + 256;
+        assert_eq!(
+            &expected[..deswizzled_layer_size + 4096],
+            &surface.data[..deswizzled_layer_size + 4096]
+        );
+        assert!(surface.data[deswizzled_layer_size + 4096..]
+            .iter()
+            .all(|b| *b == 0));
+
+        assert_eq!(8, surface.missing.len());
+        assert_eq!(
+            MissingMip {
+                layer: 1,
+                mip: 1,
+                offset: deswizzled_layer_size + 4096,
+                size: 1024
+            },
+            surface.missing[0]
+        );
+        assert_eq!(
+            MissingMip {
+                layer: 3,
+                mip: 2,
+                offset: deswizzled_layer_size * 4 - 256,
+                size: 256
+            },
+            surface.missing[7]
+        );
+    }
+
+    #[test]
+    fn deswizzle_surface_lossy_missing_mip_tail() {
+        let params = SurfaceParams {
+            width: 32,
+            height: 32,
+            block_dim: BlockDim::uncompressed(),
+            block_height_mip0: Some(BlockHeight::One),
+            bytes_per_pixel: 4,
+            mipmap_count: 6,
+            packed_mip_tail: true,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.swizzled_size()).map(|i| i as u8).collect();
+        let expected = params.deswizzle(&input).unwrap();
+
+        // The packed mipmaps starting from mip 2 share the last GOB.
+        let surface = deswizzle_surface_lossy(&input[..input.len() - 1], &params).unwrap();
+        assert_eq!(&expected[..4096 + 1024], &surface.data[..4096 + 1024]);
+        assert_eq!(
+            vec![(2, 5120, 256), (3, 5376, 64), (4, 5440, 16), (5, 5456, 4)],
+            surface
+                .missing
+                .iter()
+                .map(|m| (m.mip, m.offset, m.size))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    fn check_read_texels(params: &SurfaceParams) {
+        let tiled: Vec<_> = (0..params.swizzled_size())
+            .map(|i| (i * 7 % 251) as u8)
+            .collect();
+        let linear = params.deswizzle(&tiled).unwrap();
+
+        let level = mip_level(params, 0);
+        let bpp = params.bytes_per_pixel as usize;
+        let coords: Vec<_> = (0..200u32)
+            .map(|i| {
+                (
+                    i * 37 % level.width,
+                    i * 53 % level.height,
+                    i * 11 % level.depth,
+                )
+            })
+            .collect();
+
+        let mut texels = vec![0u8; coords.len() * bpp];
+        read_texels(&tiled, params, &coords, &mut texels).unwrap();
+        for (i, (x, y, z)) in coords.into_iter().enumerate() {
+            let offset = ((z * level.height + y) * level.width + x) as usize * bpp;
+            assert_eq!(
+                &linear[offset..offset + bpp],
+                &texels[i * bpp..(i + 1) * bpp],
+                "{:?}",
+                (x, y, z)
+            );
+        }
+    }
+
+    #[test]
+    fn read_texels_match_deswizzle() {
+        check_read_texels(&SurfaceParams {
+            width: 256,
+            height: 256,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 9,
+            layer_count: 6,
+            ..Default::default()
+        });
+        check_read_texels(&SurfaceParams {
+            width: 33,
+            height: 65,
+            bytes_per_pixel: 3,
+            mipmap_count: 2,
+            ..Default::default()
+        });
+        check_read_texels(&SurfaceParams {
+            width: 16,
+            height: 16,
+            depth: 16,
+            bytes_per_pixel: 4,
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn read_texels_invalid() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            bytes_per_pixel: 4,
+            ..Default::default()
+        };
+        let tiled = vec![0u8; params.swizzled_size()];
+        assert_eq!(
+            Err(SwizzleError::InvalidCoordinate {
+                x: 64,
+                y: 3,
+                z: 0,
+                width: 64,
+                height: 64,
+                depth: 1
+            }),
+            read_texels(&tiled, &params, &[(0, 0, 0), (64, 3, 0)], &mut [0u8; 8])
+        );
+        assert_eq!(
+            Err(SwizzleError::InvalidCoordinate {
+                x: 1,
+                y: 2,
+                z: 1,
+                width: 64,
+                height: 64,
+                depth: 1
+            }),
+            read_texels(&tiled, &params, &[(1, 2, 1)], &mut [0u8; 4])
+        );
+        assert_eq!(
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 0,
+                mip: 0,
+                expected_size: params.swizzled_size(),
+                actual_size: 512
+            }),
+            read_texels(&tiled[..512], &params, &[(0, 0, 0)], &mut [0u8; 4])
+        );
+        assert_eq!(
+            Err(SwizzleError::NotEnoughData {
+                expected_size: 8,
+                actual_size: 4
+            }),
+            read_texels(&tiled, &params, &[(0, 0, 0), (1, 0, 0)], &mut [0u8; 4])
+        );
+    }
+
+    #[test]
+    fn surface_plan_matches_params() {
+        let mip_block_heights = [BlockHeight::One, BlockHeight::Four, BlockHeight::Two];
+        for params in [
+            SurfaceParams {
+                width: 33,
+                height: 65,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 5,
+                layer_count: 6,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 16,
+                height: 16,
+                depth: 16,
+                bytes_per_pixel: 4,
+                mipmap_count: 5,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 64,
+                height: 64,
+                bytes_per_pixel: 4,
+                mipmap_count: 7,
+                layer_count: 2,
+                packed_mip_tail: true,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 128,
+                height: 128,
+                bytes_per_pixel: 4,
+                mipmap_count: 3,
+                mip_block_heights: Some(&mip_block_heights),
+                ..Default::default()
+            },
+        ] {
+            for access_order in [AccessOrder::Linear, AccessOrder::Tiled] {
+                let plan = SurfacePlan::new(&params)
+                    .unwrap()
+                    .with_access_order(access_order);
+                assert_eq!(params.swizzled_size(), plan.swizzled_size());
+                assert_eq!(params.deswizzled_size(), plan.deswizzled_size());
+
+                let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+                // Tiling should overwrite padding bytes from previous data.
+                let mut tiled = vec![0xFFu8; plan.swizzled_size()];
+                plan.swizzle_into(&input, &mut tiled).unwrap();
+                assert_eq!(params.swizzle(&input).unwrap(), tiled);
+
+                let mut untiled = vec![0u8; plan.deswizzled_size()];
+                plan.deswizzle_into(&tiled, &mut untiled).unwrap();
+                assert_eq!(input, untiled);
+            }
+        }
+    }
+
+    #[test]
+    fn surface_plan_invalid() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            bytes_per_pixel: 4,
+            mipmap_count: 2,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let plan = SurfacePlan::new(&params).unwrap();
+        let tiled = vec![0u8; plan.swizzled_size()];
+        assert_eq!(
+            Err(params.deswizzle(&tiled[..512]).unwrap_err()),
+            plan.deswizzle_into(&tiled[..512], &mut vec![0u8; plan.deswizzled_size()])
+        );
+        assert_eq!(
+            Err(SwizzleError::NotEnoughData {
+                expected_size: plan.deswizzled_size(),
+                actual_size: 4
+            }),
+            plan.deswizzle_into(&tiled, &mut [0u8; 4])
+        );
+        // Empty surfaces have no output like SurfaceParams::swizzle.
+        let empty = SurfacePlan::new(&SurfaceParams { width: 0, ..params }).unwrap();
+        assert_eq!(0, empty.swizzled_size());
+        assert_eq!(0, empty.deswizzled_size());
+        assert_eq!(Ok(()), empty.deswizzle_into(&[], &mut []));
+
+        assert!(matches!(
+            SurfacePlan::new(&SurfaceParams { depth: 4, ..params }),
+            Err(SwizzleError::InvalidSurface { .. })
+        ));
+    }
+
+    #[test]
+    fn deswizzle_with_mip_sizes_matches_deswizzle() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 7,
+            layer_count: 6,
+            ..Default::default()
+        };
+        let mip_sizes: Vec<_> = SurfaceLayout::new(&TegraBlockLinear, &params)
+            .mips()
+            .iter()
+            .map(|m| m.swizzled_size)
+            .collect();
+
+        let input: Vec<_> = (0..params.swizzled_size()).map(|i| i as u8).collect();
+        assert_eq!(
+            params.deswizzle(&input),
+            deswizzle_with_mip_sizes(&input, &params, &mip_sizes)
+        );
+    }
+
+    #[test]
+    fn deswizzle_with_mip_sizes_padded_mipmaps() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            bytes_per_pixel: 4,
+            mipmap_count: 3,
+            layer_count: 2,
+            layer_alignment: NonZeroUsize::new(1),
+            ..Default::default()
+        };
+        let untiled: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let tiled = params.swizzle(&untiled).unwrap();
+
+        // Each mipmap is padded to 0x4000 bytes in the file.
+        let mut input = vec![0u8; 0x4000 * 6];
+        let layout = SurfaceLayout::new(&TegraBlockLinear, &params);
+        for layer in 0..2 {
+            for (i, mip) in layout.mips().iter().enumerate() {
+                let offset = layer * layout.swizzled_layer_size + mip.swizzled_offset;
+                let padded_offset = (layer * 3 + i) * 0x4000;
+                input[padded_offset..padded_offset + mip.swizzled_size]
+                    .copy_from_slice(&tiled[offset..offset + mip.swizzled_size]);
+            }
+        }
+
+        assert_eq!(
+            Ok(untiled),
+            deswizzle_with_mip_sizes(&input, &params, &[0x4000; 3])
+        );
+    }
+
+    #[test]
+    fn deswizzle_with_mip_sizes_errors() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            bytes_per_pixel: 4,
+            mipmap_count: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(SwizzleError::NotEnoughMipSizes {
+                expected_count: 3,
+                actual_count: 2
+            }),
+            deswizzle_with_mip_sizes(&[0u8; 0x10000], &params, &[0x4000; 2])
+        );
+        assert_eq!(
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 0,
+                mip: 2,
+                expected_size: 0x8000 + 1024,
+                actual_size: 0x8000
+            }),
+            deswizzle_with_mip_sizes(&[0u8; 0x8000], &params, &[0x4000; 3])
+        );
+        assert_eq!(
+            Err(SwizzleError::Unsupported {
+                feature: "packed mip tails with explicit mipmap sizes"
+            }),
+            deswizzle_with_mip_sizes(
+                &[0u8; 0x10000],
+                &SurfaceParams {
+                    packed_mip_tail: true,
+                    mipmap_count: 7,
+                    ..params
+                },
+                &[0x4000; 7]
+            )
+        );
+    }
+
+    #[test]
+    fn surface_layout_matches_surface_sizes() {
+        // The const size calculations should agree with the offsets used for tiling.
+        for (width, height) in [(1, 1), (16, 16), (36, 100), (300, 17), (512, 512)] {
+            for mipmap_count in [1, 3, 10] {
+                for layer_count in [1, 2, 6] {
+                    for packed_mip_tail in [false, true] {
+                        let params = SurfaceParams {
+                            width,
+                            height,
+                            block_dim: BlockDim::block_4x4(),
+                            bytes_per_pixel: 16,
+                            mipmap_count,
+                            layer_count,
+                            packed_mip_tail,
+                            ..Default::default()
+                        };
+                        let layout = SurfaceLayout::new(&TegraBlockLinear, &params);
+                        let sizes = surface_sizes(&params);
+                        assert_eq!(sizes.tiled, layout.swizzled_size());
+                        assert_eq!(sizes.linear, layout.deswizzled_size());
+                        assert_eq!(params.swizzled_size(), layout.swizzled_size());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn swizzled_size_packed_mip_tail() {
+        // Mips 1 to 4 all fit within the same GOB.
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            mipmap_count: 5,
+            ..Default::default()
+        };
+        assert_eq!(3072, params.swizzled_size());
+        assert_eq!(
+            1536,
+            SurfaceParams {
+                packed_mip_tail: true,
+                ..params
+            }
+            .swizzled_size()
+        );
+    }
+
+    #[test]
+    fn swizzled_size_packed_mip_tail_multiple_gobs() {
+        // Mip 2 fills an entire GOB, so mips 3 and 4 use a second GOB.
+        let params = SurfaceParams {
+            width: 64,
+            height: 32,
+            bytes_per_pixel: 4,
+            mipmap_count: 5,
+            packed_mip_tail: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            swizzled_mip_size(64, 32, 1, BlockHeight::Four, 4)
+                + swizzled_mip_size(32, 16, 1, BlockHeight::Two, 4)
+                + 2 * 512,
+            params.swizzled_size()
+        );
+    }
+
+    #[test]
+    fn partial_mip_chain_block_heights() {
+        // The block height for mipmap 2 is One rather than the inferred Eight.
+        let params = SurfaceParams {
+            width: 256,
+            height: 256,
+            block_height_mip0: Some(BlockHeight::One),
+            bytes_per_pixel: 4,
+            mipmap_count: 9,
+            ..Default::default()
+        };
+        let resident = params.partial_mip_chain(2).unwrap();
+        assert_eq!(
+            (64, 64, 7),
+            (resident.width, resident.height, resident.mipmap_count)
+        );
+        assert_eq!(Some(BlockHeight::One), resident.block_height_mip0);
+
+        // The stored mipmaps should match the end of the full mipmap chain.
+        let skipped = SurfaceParams {
+            mipmap_count: 2,
+            ..params
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let tiled = params.swizzle(&input).unwrap();
+        assert_eq!(
+            tiled[skipped.swizzled_size()..],
+            resident
+                .swizzle(&input[skipped.deswizzled_size()..])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn partial_mip_chain_explicit_block_heights() {
+        let block_heights = [BlockHeight::Four, BlockHeight::Two, BlockHeight::One];
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            mipmap_count: 3,
+            mip_block_heights: Some(&block_heights),
+            ..Default::default()
+        };
+        let resident = params.partial_mip_chain(1).unwrap();
+        assert_eq!(Some(BlockHeight::Two), resident.block_height_mip0);
+        assert_eq!(Some(&block_heights[1..]), resident.mip_block_heights);
+    }
+
+    #[test]
+    fn partial_mip_chain_empty_or_unsupported() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            mipmap_count: 3,
+            ..Default::default()
+        };
+        assert_eq!(Ok(params), params.partial_mip_chain(0));
+        assert_eq!(0, params.partial_mip_chain(3).unwrap().swizzled_size());
+        assert_eq!(0, params.partial_mip_chain(40).unwrap().deswizzled_size());
+
+        let params = SurfaceParams {
+            depth: 16,
+            ..params
+        };
+        assert_eq!(
+            Err(SwizzleError::Unsupported {
+                feature: "partial mipmap chains for 3D surfaces"
+            }),
+            params.partial_mip_chain(1)
+        );
+    }
+
+    #[test]
+    fn deswizzle_verified_ignores_padding() {
+        let params = SurfaceParams {
+            width: 5,
+            height: 3,
+            bytes_per_pixel: 4,
+            mipmap_count: 2,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let mut input: Vec<_> = (0..params.swizzled_size()).map(|i| i as u8).collect();
+        let expected = params.deswizzle(&input).unwrap();
+        assert_eq!(expected, params.deswizzle_verified(&input).unwrap());
+
+        // The last byte of each GOB is outside the 20x3 byte region.
+        input[511] = 0xFF;
+        assert_eq!(expected, params.deswizzle_verified(&input).unwrap());
+    }
+
+    #[test]
+    fn deswizzle_verified_mismatch() {
+        // Drop the last byte of each mipmap when untiling.
+        struct Lossy;
+
+        impl TilingScheme for Lossy {
+            fn tiled_mip_size(&self, surface: &SurfaceParams, level: &MipLevel) -> usize {
+                (level.width * level.height * level.depth * surface.bytes_per_pixel) as usize
+            }
+
+            fn tile_mip(
+                &self,
+                surface: &SurfaceParams,
+                level: &MipLevel,
+                source: &[u8],
+                destination: &mut [u8],
+            ) {
+                let size = self.tiled_mip_size(surface, level);
+                destination[..size].copy_from_slice(&source[..size]);
+            }
+
+            fn untile_mip(
+                &self,
+                surface: &SurfaceParams,
+                level: &MipLevel,
+                source: &[u8],
+                destination: &mut [u8],
+            ) {
+                let size = self.tiled_mip_size(surface, level) - 1;
+                destination[..size].copy_from_slice(&source[..size]);
+            }
+        }
+
+        let params = SurfaceParams {
+            width: 4,
+            height: 4,
+            bytes_per_pixel: 1,
+            mipmap_count: 2,
+            ..Default::default()
+        };
+        let input = vec![1u8; params.swizzled_size_with(&Lossy)];
+        assert_eq!(
+            Err(SwizzleError::VerificationFailed { offset: 15 }),
+            params.deswizzle_verified_with(&Lossy, &input)
+        );
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn mip_layout_larger_than_4gb() {
+        // This surface is too large for the u32 offsets in TilingUniforms.
+        let params = SurfaceParams {
+            width: 8192,
+            height: 8192,
+            bytes_per_pixel: 16,
+            mipmap_count: 2,
+            layer_count: 6,
+            ..Default::default()
+        };
+        let mips = [
+            mip_layout(&params, 0).unwrap(),
+            mip_layout(&params, 1).unwrap(),
+        ];
+        assert_eq!(
+            [
+                MipLayout {
+                    level: mip_level(&params, 0),
+                    block_height: BlockHeight::Sixteen,
+                    block_depth: 1,
+                    swizzled_offset: 0,
+                    swizzled_size: 1 << 30,
+                    deswizzled_offset: 0,
+                    deswizzled_size: 1 << 30,
+                },
+                MipLayout {
+                    level: mip_level(&params, 1),
+                    block_height: BlockHeight::Sixteen,
+                    block_depth: 1,
+                    swizzled_offset: 1 << 30,
+                    swizzled_size: 1 << 28,
+                    deswizzled_offset: 1 << 30,
+                    deswizzled_size: 1 << 28,
+                },
+            ],
+            mips
+        );
+        assert_eq!(6 * layer_stride_tiled(&params), params.swizzled_size());
+        assert!(mip_layout(&params, 2).is_err());
+        assert!(mip_layout(
+            &SurfaceParams {
+                mipmap_count: 40,
+                ..params
+            },
+            0
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn swizzled_size_layer_alignment() {
+        let params = SurfaceParams {
+            width: 256,
+            height: 256,
+            bytes_per_pixel: 4,
+            mipmap_count: 9,
+            layer_count: 2,
+            ..Default::default()
+        };
+        // The calculated alignment uses the block height of 16 for the base mip level.
+        assert_eq!(2 * 352256, params.swizzled_size());
+
+        let params = SurfaceParams {
+            layer_alignment: NonZeroUsize::new(0x400),
+            ..params
+        };
+        assert_eq!(2 * 351232, params.swizzled_size());
+        assert_eq!(2 * 351232, surface_sizes(&params).tiled);
+        assert_eq!(2 * 351232, params.swizzled_size_with(&TegraBlockLinear));
+
+        let params = SurfaceParams {
+            layer_alignment: NonZeroUsize::new(0x800),
+            mipmap_count: 1,
+            width: 16,
+            height: 16,
+            ..params
+        };
+        assert_eq!(2 * 2048, params.swizzled_size());
+    }
+
+    #[test]
+    fn swizzle_deswizzle_layer_alignment() {
+        let params = SurfaceParams {
+            width: 256,
+            height: 256,
+            bytes_per_pixel: 4,
+            mipmap_count: 9,
+            layer_count: 2,
+            layer_alignment: NonZeroUsize::new(0x400),
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let swizzled = params.swizzle(&input).unwrap();
+        assert_eq!(params.swizzled_size(), swizzled.len());
+
+        // The second layer starts at the next multiple of the layer alignment.
+        let layer = SurfaceParams {
+            layer_count: 1,
+            ..params
+        };
+        let layer_size = layer.deswizzled_size();
+        let expected = layer.swizzle(&input[layer_size..]).unwrap();
+        assert_eq!(&expected[..], &swizzled[351232..]);
+
+        assert_eq!(input, params.deswizzle(&swizzled).unwrap());
+    }
+
+    #[test]
+    fn layer_alignment_overflow() {
+        let params = SurfaceParams {
+            width: 4,
+            height: 4,
+            bytes_per_pixel: 4,
+            layer_count: 2,
+            layer_alignment: NonZeroUsize::new(1 << (usize::BITS - 1)),
+            ..Default::default()
+        };
+        let error = SwizzleError::InvalidSurface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            bytes_per_pixel: 4,
+            mipmap_count: 1,
+        };
+        assert_eq!(Err(error.clone()), params.swizzle(&[0u8; 64]));
+        assert_eq!(Err(error.clone()), params.deswizzle(&[0u8; 512]));
+        assert_eq!(
+            Err(error),
+            deswizzle_with_mip_sizes(&[0u8; 512], &params, &[512])
+        );
+    }
+
+    #[test]
+    fn max_surface_size() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            bytes_per_pixel: 4,
+            mipmap_count: 2,
+            layer_count: 2,
+            max_surface_size: Some(0x4000),
+            ..Default::default()
+        };
+        let error = SwizzleError::AllocationTooLarge {
+            size: params.swizzled_size(),
+            max_size: 0x4000,
+        };
+        let untiled = vec![0u8; params.deswizzled_size()];
+        let tiled = vec![0u8; params.swizzled_size()];
+        assert_eq!(Err(error.clone()), params.swizzle(&untiled));
+        assert_eq!(Err(error.clone()), params.deswizzle(&tiled));
+        assert_eq!(
+            Err(error.clone()),
+            params.swizzle_ordered(&untiled, InputOrdering::MipMajor)
+        );
+        assert_eq!(
+            Err(error.clone()),
+            deswizzle_with_mip_sizes(&tiled, &params, &[0x4000, 0x1000])
+        );
+        #[cfg(feature = "bytemuck")]
+        assert_eq!(Err(error.clone()), params.deswizzle_as::<u32>(&tiled));
+        #[cfg(feature = "allocator-api2")]
+        assert_eq!(
+            Err(error.clone()),
+            params
+                .swizzle_in(&untiled, allocator_api2::alloc::Global)
+                .map(|_| ())
+        );
+
+        // Surfaces up to the limit are unaffected.
+        let limited = SurfaceParams {
+            max_surface_size: Some(params.swizzled_size()),
+            ..params
+        };
+        let unlimited = SurfaceParams {
+            max_surface_size: None,
+            ..params
+        };
+        assert_eq!(unlimited.swizzle(&untiled), limited.swizzle(&untiled));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_deswizzle_packed_mip_tail() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        // BC7 cube map with a full mip chain.
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 7,
+            layer_count: 6,
+            packed_mip_tail: true,
+            ..Default::default()
+        };
+
+        let seed = [13u8; 32];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let input: Vec<_> = (0..params.deswizzled_size())
+            .map(|_| rng.gen_range::<u8, _>(0..=255))
+            .collect();
+
+        let swizzled = params.swizzle(&input).unwrap();
+        assert_eq!(params.swizzled_size(), swizzled.len());
+        assert!(
+            swizzled.len()
+                < SurfaceParams {
+                    packed_mip_tail: false,
+                    ..params
+                }
+                .swizzled_size()
+        );
+
+        let deswizzled = params.deswizzle(&swizzled).unwrap();
+        assert_eq!(input, deswizzled);
+    }
+
+    #[test]
+    fn packed_mip_tail_3d_unchanged() {
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            depth: 16,
+            mipmap_count: 5,
+            ..Default::default()
+        };
+        assert_eq!(
+            params.swizzled_size(),
+            SurfaceParams {
+                packed_mip_tail: true,
+                ..params
+            }
+            .swizzled_size()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_surface_from_mips_matches_combined() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        for packed_mip_tail in [false, true] {
+            let params = SurfaceParams {
+                width: 128,
+                height: 128,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 8,
+                layer_count: 6,
+                packed_mip_tail,
+                ..Default::default()
+            };
+
+            let seed = [13u8; 32];
+            let mut rng: StdRng = SeedableRng::from_seed(seed);
+            let input: Vec<_> = (0..params.deswizzled_size())
+                .map(|_| rng.gen_range::<u8, _>(0..=255))
+                .collect();
+
+            // Split the combined input into separate slices for each layer and mipmap.
+            let mut mips = Vec::new();
+            let mut offset = 0;
+            for _ in 0..params.layer_count {
+                for mip in 0..params.mipmap_count {
+                    let level = mip_level(&params, mip);
+                    let size = deswizzled_mip_size(level.width, level.height, 1, 16);
+                    mips.push(&input[offset..offset + size]);
+                    offset += size;
+                }
+            }
+
+            assert_eq!(
+                params.swizzle(&input).unwrap(),
+                params.swizzle_from_mips(&mips).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    fn deswizzle_as_matches_deswizzle() {
+        let params = SurfaceParams {
+            width: 33,
+            height: 65,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 4,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.swizzled_size()).map(|i| i as u8).collect();
+        let expected = params.deswizzle(&input).unwrap();
+
+        let blocks: Vec<[u8; 16]> = params.deswizzle_as(&input).unwrap();
+        assert_eq!(expected, bytemuck::cast_slice::<_, u8>(&blocks));
+        let words: Vec<u32> = params.deswizzle_as(&input).unwrap();
+        assert_eq!(expected, bytemuck::cast_slice::<_, u8>(&words));
+
+        assert_eq!(
+            Err(SwizzleError::InvalidElementSize {
+                bytes_per_pixel: 16,
+                element_size: 32
+            }),
+            params.deswizzle_as::<[u8; 32]>(&input)
+        );
+        assert!(matches!(
+            params.deswizzle_as::<u32>(&input[..16]),
+            Err(SwizzleError::NotEnoughMipData { .. })
+        ));
+
+        let empty = SurfaceParams { width: 0, ..params };
+        assert_eq!(Ok(Vec::new()), empty.deswizzle_as::<u32>(&[]));
+    }
+
+    #[test]
+    #[cfg(all(feature = "bytemuck", target_pointer_width = "64"))]
+    fn deswizzle_as_huge_surface_small_source() {
+        // The output would be 64 TiB, so check the source before allocating.
+        let params = SurfaceParams {
+            width: 4096,
+            height: 4096,
+            bytes_per_pixel: 4,
+            layer_count: 1 << 20,
+            ..Default::default()
+        };
+        assert!(matches!(
+            params.deswizzle_as::<u32>(&[0u8; 16]),
+            Err(SwizzleError::NotEnoughMipData { .. })
+        ));
+    }
+
+    #[test]
+    fn swizzle_ordered_mip_major() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 48,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 6,
+            layer_count: 3,
+            packed_mip_tail: true,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let expected = params.swizzle(&input).unwrap();
+
+        // Rearrange the layer major input to mip major.
+        let mip_sizes: Vec<_> = (0..params.mipmap_count)
+            .map(|mip| {
+                let level = mip_level(&params, mip);
+                deswizzled_mip_size(level.width, level.height, 1, 16)
+            })
+            .collect();
+        let layer_size: usize = mip_sizes.iter().sum();
+        let mut mip_major = Vec::new();
+        for mip in 0..params.mipmap_count as usize {
+            let mip_offset: usize = mip_sizes[..mip].iter().sum();
+            for layer in 0..params.layer_count as usize {
+                let offset = layer * layer_size + mip_offset;
+                mip_major.extend_from_slice(&input[offset..offset + mip_sizes[mip]]);
+            }
+        }
+
+        assert_eq!(
+            Ok(expected.clone()),
+            params.swizzle_ordered(&mip_major, InputOrdering::MipMajor)
+        );
+        assert_eq!(
+            Ok(expected),
+            params.swizzle_ordered(&input, InputOrdering::LayerMajor)
+        );
+
+        // The data for the second layer of the base mipmap is incomplete.
+        assert_eq!(
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 1,
+                mip: 0,
+                expected_size: mip_sizes[0] * 2,
+                actual_size: mip_sizes[0] + 1
+            }),
+            params.swizzle_ordered(&mip_major[..mip_sizes[0] + 1], InputOrdering::MipMajor)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_from_fn_matches_swizzle() {
+        for params in [
+            SurfaceParams {
+                width: 128,
+                height: 128,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 8,
+                layer_count: 6,
+                packed_mip_tail: true,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 33,
+                height: 33,
+                depth: 33,
+                bytes_per_pixel: 4,
+                mipmap_count: 4,
+                ..Default::default()
+            },
+        ] {
+            let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+
+            // The mipmaps should be requested in the order of the untiled surface.
+            let mut offset = 0;
+            let mut calls = Vec::new();
+            let tiled = params
+                .swizzle_from_fn(|layer, mip, untiled| {
+                    assert!(untiled.iter().all(|b| *b == 0));
+                    untiled.copy_from_slice(&input[offset..offset + untiled.len()]);
+                    offset += untiled.len();
+                    calls.push((layer, mip));
+                })
+                .unwrap();
+
+            assert_eq!(params.swizzle(&input).unwrap(), tiled);
+            assert_eq!(input.len(), offset);
+            let expected_calls: Vec<_> = (0..params.layer_count)
+                .flat_map(|layer| (0..params.mipmap_count).map(move |mip| (layer, mip)))
+                .collect();
+            assert_eq!(expected_calls, calls);
+        }
+    }
+
+    #[test]
+    fn swizzle_from_fn_to_invalid() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            bytes_per_pixel: 4,
+            mipmap_count: 2,
+            ..Default::default()
+        };
+        let mut output = vec![1u8; 4];
+        assert_eq!(
+            Err(SwizzleError::NotEnoughData {
+                expected_size: params.swizzled_size(),
+                actual_size: 4
+            }),
+            params.swizzle_from_fn_to(
+                |_, _, _| panic!("no mipmaps should be filled"),
+                &mut output[..]
+            )
+        );
+
+        // Empty surfaces have no mipmaps to fill.
+        let empty = SurfaceParams { width: 0, ..params };
+        params
+            .swizzle_from_fn_to(|_, _, _| (), &mut output)
+            .unwrap();
+        assert_eq!(params.swizzled_size(), output.len());
+        empty
+            .swizzle_from_fn_to(|_, _, _| panic!("no mipmaps should be filled"), &mut output)
+            .unwrap();
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn cancellable_matches_swizzle_deswizzle() {
+        let cancel = AtomicBool::new(false);
+        for params in [
+            SurfaceParams {
+                width: 300,
+                height: 300,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 9,
+                layer_count: 6,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 100,
+                height: 37,
+                bytes_per_pixel: 4,
+                mipmap_count: 7,
+                packed_mip_tail: true,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 33,
+                height: 33,
+                depth: 33,
+                bytes_per_pixel: 4,
+                mipmap_count: 4,
+                ..Default::default()
+            },
+        ] {
+            let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+            let swizzled = params.swizzle(&input).unwrap();
+            assert_eq!(
+                swizzled,
+                params.swizzle_cancellable(&input, &cancel).unwrap()
+            );
+            assert_eq!(
+                input,
+                params.deswizzle_cancellable(&swizzled, &cancel).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn cancellable_cancelled() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            bytes_per_pixel: 4,
+            mipmap_count: 7,
+            ..Default::default()
+        };
+        let cancel = AtomicBool::new(true);
+        let input = vec![0u8; params.swizzled_size()];
+        let mut output = vec![0u8; params.swizzled_size()];
+        assert_eq!(
+            Err(SwizzleError::Cancelled),
+            params.deswizzle_cancellable(&input, &cancel)
+        );
+        assert_eq!(
+            Err(SwizzleError::Cancelled),
+            params.swizzle_into_cancellable(&input, &mut output, &cancel)
+        );
+        // Other errors are still reported.
+        assert!(matches!(
+            params.deswizzle_into_cancellable(&input[..4], &mut output, &cancel),
+            Err(SwizzleError::NotEnoughMipData { .. })
+        ));
     }
 
-    layer_size * layer_count as usize
-}
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn progress_matches_swizzle_deswizzle() {
+        for params in [
+            SurfaceParams {
+                width: 300,
+                height: 300,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 9,
+                layer_count: 6,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 100,
+                height: 37,
+                bytes_per_pixel: 4,
+                mipmap_count: 7,
+                packed_mip_tail: true,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 33,
+                height: 33,
+                depth: 33,
+                bytes_per_pixel: 4,
+                mipmap_count: 4,
+                ..Default::default()
+            },
+        ] {
+            let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+            let swizzled = params.swizzle(&input).unwrap();
 
-fn swizzle_mipmap<const DESWIZZLE: bool>(
-    with: u32,
-    height: u32,
-    depth: u32,
-    block_height: BlockHeight,
-    block_depth: u32,
-    bytes_per_pixel: u32,
-    source: &[u8],
-    src_offset: &mut usize,
-    dst: &mut [u8],
-    dst_offset: &mut usize,
-) -> Result<(), SwizzleError> {
-    let swizzled_size = swizzled_mip_size(with, height, depth, block_height, bytes_per_pixel);
-    let deswizzled_size = deswizzled_mip_size(with, height, depth, bytes_per_pixel);
+            let mut reports = Vec::new();
+            let untiled = params
+                .deswizzle_with_progress(&swizzled, |done, total| reports.push((done, total)))
+                .unwrap();
+            assert_eq!(input, untiled);
 
-    // Make sure the source has enough space.
-    if DESWIZZLE && source.len() < *src_offset + swizzled_size {
-        return Err(SwizzleError::NotEnoughData {
-            expected_size: swizzled_size,
-            actual_size: source.len(),
-        });
-    }
+            // Progress increases up to the total.
+            // 2D mipmaps report each row of blocks, and 3D mipmaps report each mipmap.
+            // Mipmaps in the packed mip tail are reported together.
+            let total = params.deswizzled_size();
+            if params.depth > 1 {
+                assert_eq!(params.mipmap_count as usize, reports.len());
+            } else if !params.packed_mip_tail {
+                assert!(reports.len() > params.mipmap_count as usize * params.layer_count as usize);
+            }
+            assert!(reports.windows(2).all(|w| w[0].0 < w[1].0));
+            assert!(reports.iter().all(|r| r.1 == total));
+            assert_eq!(Some(&(total, total)), reports.last());
 
-    if !DESWIZZLE && source.len() < *src_offset + deswizzled_size {
-        return Err(SwizzleError::NotEnoughData {
-            expected_size: deswizzled_size,
-            actual_size: source.len(),
-        });
+            let mut last = (0, 0);
+            let tiled = params
+                .swizzle_with_progress(&input, |done, total| last = (done, total))
+                .unwrap();
+            assert_eq!(swizzled, tiled);
+            assert_eq!((total, total), last);
+        }
     }
 
-    // Tile or untile the data and move to the next section.
-    swizzle_inner::<DESWIZZLE>(
-        with,
-        height,
-        depth,
-        &source[*src_offset..],
-        &mut dst[*dst_offset..],
-        block_height,
-        block_depth,
-        bytes_per_pixel,
-    );
+    #[test]
+    fn progress_invalid() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            bytes_per_pixel: 4,
+            mipmap_count: 7,
+            ..Default::default()
+        };
+        let mut output = vec![0u8; params.deswizzled_size()];
+        assert!(matches!(
+            params.deswizzle_into_with_progress(&[0u8; 4], &mut output, |_, _| panic!(
+                "progress should not be reported"
+            )),
+            Err(SwizzleError::NotEnoughMipData { .. })
+        ));
 
-    if DESWIZZLE {
-        *src_offset += swizzled_size;
-        *dst_offset += deswizzled_size;
-    } else {
-        *src_offset += deswizzled_size;
-        *dst_offset += swizzled_size;
-    };
+        let invalid = SurfaceParams {
+            width: u32::MAX,
+            height: u32::MAX,
+            ..params
+        };
+        assert!(invalid
+            .swizzle_into_with_progress(&[], &mut output, |_, _| panic!(
+                "progress should not be reported"
+            ))
+            .is_err());
+    }
 
-    Ok(())
-}
+    #[test]
+    fn swizzle_surface_from_mips_missing_mip() {
+        let mip0 = [0u8; 8 * 8 * 4];
+        let result =
+            swizzle_surface_from_mips(8, 8, 1, &[&mip0], BlockDim::uncompressed(), None, 4, 2, 1);
+        assert_eq!(
+            result,
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 0,
+                mip: 1,
+                expected_size: 64,
+                actual_size: 0
+            })
+        );
+    }
 
-#[cfg(test)]
-mod tests {
-    use core::u32;
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_deswizzle_explicit_block_heights() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
 
-    use super::*;
+        let seed = [13u8; 32];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let size = deswizzled_surface_size(64, 64, 1, BlockDim::uncompressed(), 4, 3, 2);
+        let input: Vec<_> = (0..size).map(|_| rng.gen_range::<u8, _>(0..=255)).collect();
 
-    // Use helper functions to shorten the test cases.
-    fn swizzle_length(
-        width: u32,
-        height: u32,
-        source_length: usize,
-        is_compressed: bool,
-        bpp: u32,
-        mipmap_count: u32,
-        layer_count: u32,
-    ) -> usize {
-        swizzle_length_3d(
-            width,
-            height,
+        // The inferred block heights would be 8, 4, 2.
+        let block_heights = [BlockHeight::Sixteen, BlockHeight::One, BlockHeight::Four];
+
+        let swizzled = swizzle_surface_with_block_heights(
+            64,
+            64,
             1,
-            source_length,
-            is_compressed,
-            bpp,
-            mipmap_count,
-            layer_count,
+            &input,
+            BlockDim::uncompressed(),
+            &block_heights,
+            4,
+            3,
+            2,
         )
-    }
+        .unwrap();
+        assert_eq!(
+            swizzled_surface_size_with_block_heights(
+                64,
+                64,
+                1,
+                BlockDim::uncompressed(),
+                &block_heights,
+                4,
+                3,
+                2
+            ),
+            swizzled.len()
+        );
 
-    fn deswizzle_length(
-        width: u32,
-        height: u32,
-        source_length: usize,
-        is_compressed: bool,
-        bpp: u32,
-        mipmap_count: u32,
-        layer_count: u32,
-    ) -> usize {
-        deswizzle_length_3d(
-            width,
-            height,
+        // Each mipmap should be tiled with its own block height.
+        let mip0 = &input[..64 * 64 * 4];
+        let mip1 = &input[64 * 64 * 4..64 * 64 * 4 + 32 * 32 * 4];
+        assert_eq!(
+            crate::swizzle::swizzle_block_linear(64, 64, 1, mip0, BlockHeight::Sixteen, 4).unwrap(),
+            &swizzled[..swizzled_mip_size(64, 64, 1, BlockHeight::Sixteen, 4)]
+        );
+        let mip1_offset = swizzled_mip_size(64, 64, 1, BlockHeight::Sixteen, 4);
+        assert_eq!(
+            crate::swizzle::swizzle_block_linear(32, 32, 1, mip1, BlockHeight::One, 4).unwrap(),
+            &swizzled[mip1_offset..mip1_offset + swizzled_mip_size(32, 32, 1, BlockHeight::One, 4)]
+        );
+
+        let deswizzled = deswizzle_surface_with_block_heights(
+            64,
+            64,
             1,
-            source_length,
-            is_compressed,
-            bpp,
-            mipmap_count,
-            layer_count,
+            &swizzled,
+            BlockDim::uncompressed(),
+            &block_heights,
+            4,
+            3,
+            2,
         )
+        .unwrap();
+        assert_eq!(input, deswizzled);
     }
 
-    fn swizzle_length_3d(
-        width: u32,
-        height: u32,
-        depth: u32,
-        source_length: usize,
-        is_compressed: bool,
-        bpp: u32,
-        mipmap_count: u32,
-        layer_count: u32,
-    ) -> usize {
-        swizzle_surface(
-            width,
-            height,
-            depth,
-            &vec![0u8; source_length],
-            if is_compressed {
-                BlockDim::block_4x4()
-            } else {
-                BlockDim::uncompressed()
-            },
-            None,
-            bpp,
-            mipmap_count,
-            layer_count,
-        )
-        .unwrap()
-        .len()
+    #[test]
+    fn swizzle_surface_not_enough_block_heights() {
+        let input = [0u8; 64];
+        let result = swizzle_surface_with_block_heights(
+            4,
+            4,
+            1,
+            &input,
+            BlockDim::uncompressed(),
+            &[BlockHeight::One],
+            4,
+            2,
+            1,
+        );
+        assert_eq!(
+            result,
+            Err(SwizzleError::NotEnoughBlockHeights {
+                expected_count: 2,
+                actual_count: 1
+            })
+        );
     }
 
-    fn deswizzle_length_3d(
-        width: u32,
-        height: u32,
-        depth: u32,
-        source_length: usize,
-        is_compressed: bool,
-        bpp: u32,
-        mipmap_count: u32,
-        layer_count: u32,
-    ) -> usize {
-        deswizzle_surface(
-            width,
-            height,
-            depth,
-            &vec![0u8; source_length],
-            if is_compressed {
-                BlockDim::block_4x4()
-            } else {
-                BlockDim::uncompressed()
-            },
-            None,
-            bpp,
-            mipmap_count,
-            layer_count,
-        )
-        .unwrap()
-        .len()
+    #[test]
+    fn swizzle_deswizzle_into_matches_vec() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 7,
+            layer_count: 6,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let expected = params.swizzle(&input).unwrap();
+
+        // Previous data in the destination should not affect the output.
+        let mut swizzled = vec![0xFFu8; expected.len()];
+        params.swizzle_into(&input, &mut swizzled).unwrap();
+        assert_eq!(expected, swizzled);
+
+        let mut deswizzled = vec![0xFFu8; input.len()];
+        params.deswizzle_into(&swizzled, &mut deswizzled).unwrap();
+        assert_eq!(input, deswizzled);
     }
 
-    // Expected swizzled sizes are taken from the nutexb footer.
-    // Expected deswizzled sizes are the product of the mipmap size sum and the layer count.
-    // TODO: Calculate more accurate deswizzled sizes?
-    // TODO: Add a CSV of nutexb sizes.
-    // TODO: Clean up the existing documentation/data dumps.
     #[test]
-    fn swizzle_surface_arrays_no_mipmaps_length() {
-        assert_eq!(6144, swizzle_length(16, 16, 6144, false, 4, 1, 6));
-        assert_eq!(3072, swizzle_length(16, 16, 768, true, 8, 1, 6));
+    fn deswizzle_into_destination_too_small() {
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            ..Default::default()
+        };
+        let result = params.deswizzle_into(&[0u8; 4096], &mut [0u8; 1023]);
         assert_eq!(
-            25165824,
-            swizzle_length(2048, 2048, 25165824, true, 16, 1, 6)
+            result,
+            Err(SwizzleError::NotEnoughData {
+                expected_size: 1024,
+                actual_size: 1023
+            })
         );
-        assert_eq!(1572864, swizzle_length(256, 256, 1572864, false, 4, 1, 6));
-        assert_eq!(98304, swizzle_length(64, 64, 98304, false, 4, 1, 6));
-        assert_eq!(98304, swizzle_length(64, 64, 98304, false, 4, 1, 6));
-        assert_eq!(393216, swizzle_length(64, 64, 393216, false, 16, 1, 6));
     }
 
     #[test]
-    fn swizzle_surface_arrays_mipmaps_length() {
-        assert_eq!(147456, swizzle_length(128, 128, 131232, true, 16, 8, 6));
-        assert_eq!(15360, swizzle_length(16, 16, 2208, true, 16, 5, 6));
-        assert_eq!(540672, swizzle_length(256, 256, 524448, true, 16, 9, 6));
-        assert_eq!(1204224, swizzle_length(288, 288, 664512, true, 16, 9, 6));
-        assert_eq!(2113536, swizzle_length(512, 512, 2097312, true, 16, 10, 6));
-        assert_eq!(49152, swizzle_length(64, 64, 32928, true, 16, 7, 6));
+    #[cfg_attr(miri, ignore)]
+    fn retile_matches_swizzle() {
+        let params = SurfaceParams {
+            width: 300,
+            height: 200,
+            block_dim: BlockDim::block_4x4(),
+            block_height_mip0: Some(BlockHeight::Sixteen),
+            bytes_per_pixel: 16,
+            mipmap_count: 9,
+            layer_count: 6,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let swizzled = params.swizzle(&input).unwrap();
+
+        for target_block_height in [None, Some(BlockHeight::One), Some(BlockHeight::ThirtyTwo)] {
+            let target = SurfaceParams {
+                block_height_mip0: target_block_height,
+                ..params
+            };
+            let expected = target.swizzle(&input).unwrap();
+            assert_eq!(
+                expected,
+                retile(&swizzled, &params, target_block_height).unwrap()
+            );
+        }
     }
 
     #[test]
-    fn swizzle_surface_3d_length() {
-        assert_eq!(
-            16384,
-            swizzle_length_3d(16, 16, 16, 16 * 16 * 16 * 4, false, 4, 1, 1)
-        );
+    fn retile_packed_mip_tail() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            block_height_mip0: Some(BlockHeight::Two),
+            mipmap_count: 7,
+            packed_mip_tail: true,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let swizzled = params.swizzle(&input).unwrap();
+
+        let target = SurfaceParams {
+            block_height_mip0: Some(BlockHeight::Eight),
+            ..params
+        };
         assert_eq!(
-            368640,
-            swizzle_length_3d(33, 33, 33, 33 * 33 * 33 * 4, false, 4, 1, 1)
+            target.swizzle(&input).unwrap(),
+            retile(&swizzled, &params, Some(BlockHeight::Eight)).unwrap()
         );
     }
 
     #[test]
-    fn swizzle_surface_nutexb_length() {
-        // Sizes and parameters taken from Smash Ultimate nutexb files.
-        // The deswizzled size is estimated as the product of the mip sizes sum and array count.
-        // The swizzled size is taken from the footer.
-        assert_eq!(12800, swizzle_length(100, 100, 6864, true, 8, 7, 1));
-        assert_eq!(360960, swizzle_length(1028, 256, 351376, true, 16, 11, 1));
-        assert_eq!(24064, swizzle_length(128, 32, 21852, false, 4, 8, 1));
+    fn retile_not_enough_data() {
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            block_height_mip0: Some(BlockHeight::Two),
+            ..Default::default()
+        };
         assert_eq!(
-            2099712,
-            swizzle_length(1536, 1024, 2097184, true, 16, 11, 1)
+            retile(&[0u8; 512], &params, Some(BlockHeight::One)),
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 0,
+                mip: 0,
+                expected_size: 1024,
+                actual_size: 512
+            })
         );
-        assert_eq!(35328, swizzle_length(180, 180, 21992, true, 8, 8, 1));
+    }
+
+    #[test]
+    fn retile_max_surface_size() {
+        // 264 rows is 33 GOBs tall, so a block height of 32 pads the surface to 64 GOBs.
+        let params = SurfaceParams {
+            width: 16,
+            height: 264,
+            block_height_mip0: Some(BlockHeight::One),
+            max_surface_size: Some(16896),
+            ..Default::default()
+        };
+        let input = vec![0u8; 16896];
         assert_eq!(
-            4546048,
-            swizzle_length(2048, 1344, 3670320, true, 16, 12, 1)
+            retile(&input, &params, Some(BlockHeight::ThirtyTwo)),
+            Err(SwizzleError::AllocationTooLarge {
+                size: 32768,
+                max_size: 16896
+            })
         );
-        assert_eq!(17920, swizzle_length(256, 32, 11024, true, 16, 9, 1));
-        assert_eq!(58368, swizzle_length(320, 128, 54672, true, 16, 9, 1));
-        assert_eq!(125440, swizzle_length(340, 340, 77840, true, 8, 9, 1));
-        assert_eq!(147968, swizzle_length(400, 400, 106864, true, 8, 9, 1));
-        assert_eq!(2048, swizzle_length(4, 24, 384, false, 4, 1, 1));
-        assert_eq!(351744, swizzle_length(512, 384, 262192, true, 16, 10, 1));
-        assert_eq!(440832, swizzle_length(640, 640, 273120, true, 8, 10, 1));
-        assert_eq!(26624, swizzle_length(64, 512, 21896, true, 8, 10, 1));
-        assert_eq!(280064, swizzle_length(800, 400, 213576, true, 8, 10, 1));
         assert_eq!(
-            16777216,
-            swizzle_length(8192, 2048, 16777216, true, 16, 1, 1)
+            Ok(input.clone()),
+            retile(&input, &params, Some(BlockHeight::One))
         );
     }
 
     #[test]
-    fn swizzle_surface_potential_overflow_length() {
-        assert_eq!(0, swizzle_length_3d(u32::MAX, 0, 0, 0, false, 4, 1, 1));
-        assert_eq!(0, swizzle_length_3d(0, u32::MAX, 0, 0, false, 4, 1, 1));
-        assert_eq!(0, swizzle_length_3d(0, 0, u32::MAX, 0, false, 4, 1, 1));
-        assert_eq!(
-            0,
-            swizzle_length_3d(u32::MAX, u32::MAX, u32::MAX, 0, false, 0, 1, 1)
-        );
+    #[cfg_attr(miri, ignore)]
+    fn resize_surface_matches_swizzle() {
+        let params = SurfaceParams {
+            width: 128,
+            height: 96,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 6,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let swizzled = params.swizzle(&input).unwrap();
+
+        for (mipmap_count, layer_count) in [(1, 1), (3, 2), (6, 1), (2, 6)] {
+            let target = SurfaceParams {
+                mipmap_count,
+                layer_count,
+                ..params
+            };
+            // Copy the mipmaps to create the expected untiled data.
+            let source_layer_size = params.deswizzled_size() / 2;
+            let target_layer_size = target.deswizzled_size() / layer_count as usize;
+            let target_input: Vec<_> = (0..layer_count as usize)
+                .flat_map(|layer| {
+                    let start = layer % 2 * source_layer_size;
+                    input[start..start + target_layer_size].iter().copied()
+                })
+                .collect();
+
+            assert_eq!(
+                target.swizzle(&target_input).unwrap(),
+                resize_surface(&swizzled, &params, mipmap_count, layer_count).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn resize_surface_packed_mip_tail() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            mipmap_count: 7,
+            packed_mip_tail: true,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let swizzled = params.swizzle(&input).unwrap();
+
+        // Remove mipmaps from the tail.
+        let target = SurfaceParams {
+            mipmap_count: 5,
+            ..params
+        };
         assert_eq!(
-            0,
-            swizzle_length_3d(u32::MAX, u32::MAX, u32::MAX, 0, false, 1, 0, 1)
+            target.swizzle(&input[..target.deswizzled_size()]).unwrap(),
+            resize_surface(&swizzled, &params, 5, 1).unwrap()
         );
+    }
+
+    #[test]
+    fn resize_surface_more_mipmaps() {
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            ..Default::default()
+        };
         assert_eq!(
-            0,
-            swizzle_length_3d(u32::MAX, u32::MAX, u32::MAX, 0, false, 1, 1, 0)
+            resize_surface(&[0u8; 1024], &params, 2, 1),
+            Err(SwizzleError::InvalidSurface {
+                width: 16,
+                height: 16,
+                depth: 1,
+                bytes_per_pixel: 4,
+                mipmap_count: 2
+            })
         );
     }
 
     #[test]
-    fn deswizzle_surface_nutexb_length() {
-        // Sizes and parameters taken from Smash Ultimate nutexb files.
-        // The deswizzled size is estimated as the product of the mip sizes sum and layer count.
-        // The swizzled size is taken from the footer.
-        assert_eq!(6864, deswizzle_length(100, 100, 12800, true, 8, 7, 1));
-        assert_eq!(351376, deswizzle_length(1028, 256, 360960, true, 16, 11, 1));
-        assert_eq!(21852, deswizzle_length(128, 32, 24064, false, 4, 8, 1));
+    fn resize_surface_invalid_target() {
+        // The Tegra X1 does not support arrays of 3D textures.
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            depth: 16,
+            ..Default::default()
+        };
+        let input = vec![0u8; params.swizzled_size()];
+        let error = SwizzleError::InvalidSurface {
+            width: 16,
+            height: 16,
+            depth: 16,
+            bytes_per_pixel: 4,
+            mipmap_count: 1,
+        };
+        assert_eq!(Err(error.clone()), resize_surface(&input, &params, 1, 6));
+        // This would be a 70 TB allocation without checking the target.
+        assert_eq!(Err(error), resize_surface(&input, &params, 1, u32::MAX));
+
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            max_surface_size: Some(0x10000),
+            ..Default::default()
+        };
+        let input = vec![0u8; params.swizzled_size()];
         assert_eq!(
-            2097184,
-            deswizzle_length(1536, 1024, 2099712, true, 16, 11, 1)
+            Err(SwizzleError::AllocationTooLarge {
+                size: 0x100000,
+                max_size: 0x10000
+            }),
+            resize_surface(&input, &params, 1, 64)
         );
-        assert_eq!(21992, deswizzle_length(180, 180, 35328, true, 8, 8, 1));
         assert_eq!(
-            3670320,
-            deswizzle_length(2048, 1344, 4546048, true, 16, 12, 1)
+            0x10000,
+            resize_surface(&input, &params, 1, 4).unwrap().len()
         );
-        assert_eq!(11024, deswizzle_length(256, 32, 17920, true, 16, 9, 1));
-        assert_eq!(54672, deswizzle_length(320, 128, 58368, true, 16, 9, 1));
-        assert_eq!(77840, deswizzle_length(340, 340, 125440, true, 8, 9, 1));
-        assert_eq!(106864, deswizzle_length(400, 400, 147968, true, 8, 9, 1));
-        assert_eq!(384, deswizzle_length(4, 24, 2048, false, 4, 1, 1));
-        assert_eq!(262192, deswizzle_length(512, 384, 351744, true, 16, 10, 1));
-        assert_eq!(273120, deswizzle_length(640, 640, 440832, true, 8, 10, 1));
-        assert_eq!(21896, deswizzle_length(64, 512, 26624, true, 8, 10, 1));
-        assert_eq!(213576, deswizzle_length(800, 400, 280064, true, 8, 10, 1));
+    }
+
+    #[test]
+    fn max_sizes_layer_alignment() {
+        let cube = SurfaceParams {
+            width: 16,
+            height: 16,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 5,
+            layer_count: 6,
+            ..Default::default()
+        };
+        let texture = SurfaceParams {
+            width: 32,
+            height: 32,
+            ..Default::default()
+        };
+        let invalid = SurfaceParams {
+            width: u32::MAX,
+            height: u32::MAX,
+            ..Default::default()
+        };
+        let surfaces = [texture, cube, invalid];
+
+        // The cube map is smaller untiled but larger tiled due to layer alignment.
+        assert_eq!(15360, max_swizzled_size(&surfaces));
+        assert_eq!(4096, max_deswizzled_size(&surfaces));
+    }
+
+    #[test]
+    fn max_sizes_empty() {
+        assert_eq!(0, max_swizzled_size(&[]));
+        assert_eq!(0, max_deswizzled_size(Vec::<SurfaceParams>::new().iter()));
+    }
+
+    #[test]
+    fn swizzle_legacy_block_height_heuristic() {
+        let params = SurfaceParams {
+            width: 40,
+            height: 40,
+            mipmap_count: 3,
+            block_height_heuristic: BlockHeightHeuristic::LegacyNutexb,
+            ..Default::default()
+        };
+        let explicit = SurfaceParams {
+            block_height_mip0: Some(BlockHeight::Eight),
+            ..params
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+
+        assert_eq!(explicit.swizzled_size(), params.swizzled_size());
+        assert_eq!(explicit.swizzle(&input), params.swizzle(&input));
+    }
+
+    #[test]
+    fn swizzle_surface_append_multiple_surfaces() {
+        let params = [
+            SurfaceParams {
+                width: 64,
+                height: 64,
+                mipmap_count: 3,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 16,
+                height: 16,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                layer_count: 6,
+                ..Default::default()
+            },
+        ];
+        let alignment = NonZeroUsize::new(0x1000).unwrap();
+
+        let mut output = vec![1u8; 3];
+        let mut ranges = Vec::new();
+        for p in &params {
+            let input: Vec<_> = (0..p.deswizzled_size()).map(|i| i as u8).collect();
+            let range = swizzle_surface_append(p, &input, alignment, &mut output).unwrap();
+            assert_eq!(p.swizzle(&input).unwrap(), output[range.clone()]);
+            ranges.push(range);
+        }
+
         assert_eq!(
-            16777216,
-            deswizzle_length(8192, 2048, 16777216, true, 16, 1, 1)
+            vec![0x1000..0x1000 + 0x5400, 0x7000..0x7000 + 6 * 512],
+            ranges
         );
+        assert_eq!(0x7000 + 6 * 512, output.len());
+        assert_eq!(&[1, 1, 1], &output[..3]);
+        assert!(output[3..0x1000].iter().all(|b| *b == 0));
     }
 
     #[test]
-    fn deswizzle_surface_arrays_no_mipmaps_length() {
-        assert_eq!(6144, deswizzle_length(16, 16, 6144, false, 4, 1, 6));
-        assert_eq!(768, deswizzle_length(16, 16, 3072, true, 8, 1, 6));
-        assert_eq!(
-            25165824,
-            deswizzle_length(2048, 2048, 25165824, true, 16, 1, 6)
+    fn swizzle_surface_append_error() {
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            ..Default::default()
+        };
+        let mut output = vec![1u8; 3];
+        let result = swizzle_surface_append(
+            &params,
+            &[0u8; 4],
+            NonZeroUsize::new(16).unwrap(),
+            &mut output,
         );
-        assert_eq!(1572864, deswizzle_length(256, 256, 1572864, false, 4, 1, 6));
-        assert_eq!(98304, deswizzle_length(64, 64, 98304, false, 4, 1, 6));
-        assert_eq!(98304, deswizzle_length(64, 64, 98304, false, 4, 1, 6));
-        assert_eq!(393216, deswizzle_length(64, 64, 393216, false, 16, 1, 6));
+        assert!(matches!(result, Err(SwizzleError::NotEnoughMipData { .. })));
+        assert_eq!(vec![1u8; 3], output);
     }
 
     #[test]
-    fn deswizzle_surface_arrays_mipmaps_length() {
-        assert_eq!(131232, deswizzle_length(128, 128, 147456, true, 16, 8, 6));
-        assert_eq!(2208, deswizzle_length(16, 16, 15360, true, 16, 5, 6));
-        assert_eq!(524448, deswizzle_length(256, 256, 540672, true, 16, 9, 6));
-        assert_eq!(664512, deswizzle_length(288, 288, 1204224, true, 16, 9, 6));
+    fn swizzle_surface_append_empty() {
+        let params = SurfaceParams {
+            width: 0,
+            ..Default::default()
+        };
+        let mut output = vec![1u8; 3];
+        let range =
+            swizzle_surface_append(&params, &[], NonZeroUsize::new(16).unwrap(), &mut output)
+                .unwrap();
+        assert_eq!(16..16, range);
+        assert_eq!(16, output.len());
+    }
+
+    #[test]
+    fn fast_path_mips_npot() {
+        // 100x100 is 400x100 bytes, so every mipmap has partial GOBs.
+        let params = SurfaceParams {
+            width: 100,
+            height: 100,
+            mipmap_count: 3,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let info = fast_path_mips(&params);
+        assert_eq!(vec![false, false, false], info.per_mip);
+        assert_eq!(0, info.fast_bytes);
+        assert_eq!(params.deswizzled_size(), info.total_bytes);
+    }
+
+    #[test]
+    fn fast_path_mips_small_mipmaps() {
+        // The 16x8 mipmap is 64x8 bytes, but the smaller mipmaps fill less than a GOB.
+        let params = SurfaceParams {
+            width: 64,
+            height: 32,
+            mipmap_count: 4,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let info = fast_path_mips(&params);
+        assert_eq!(vec![true, true, true, false], info.per_mip);
+        assert_eq!(2 * (64 * 32 + 32 * 16 + 16 * 8) * 4, info.fast_bytes);
+        assert_eq!(params.deswizzled_size(), info.total_bytes);
+    }
+
+    #[test]
+    fn fast_path_mips_packed_mip_tail() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            mipmap_count: 7,
+            packed_mip_tail: true,
+            ..Default::default()
+        };
+        let info = fast_path_mips(&params);
         assert_eq!(
-            2097312,
-            deswizzle_length(512, 512, 2113536, true, 16, 10, 6)
+            vec![true, true, true, false, false, false, false],
+            info.per_mip
         );
-        assert_eq!(32928, deswizzle_length(64, 64, 49152, true, 16, 7, 6));
     }
 
     #[test]
-    fn deswizzle_surface_potential_overflow_length() {
-        assert_eq!(0, deswizzle_length(u32::MAX, 0, 0, false, 4, 1, 6));
-        assert_eq!(0, deswizzle_length(0, u32::MAX, 0, false, 4, 1, 6));
-        assert_eq!(0, deswizzle_length(u32::MAX, u32::MAX, 0, false, 0, 1, 6));
-        assert_eq!(0, deswizzle_length(u32::MAX, u32::MAX, 0, false, 4, 0, 6));
-        assert_eq!(0, deswizzle_length(u32::MAX, u32::MAX, 0, false, 4, 1, 0));
+    fn layer_strides_alignment() {
+        // 256x256 RGBA with 9 mipmaps uses 352256 bytes for each aligned layer.
+        let params = SurfaceParams {
+            width: 256,
+            height: 256,
+            mipmap_count: 9,
+            layer_count: 2,
+            ..Default::default()
+        };
+        assert_eq!(352256, layer_stride_tiled(&params));
+        assert_eq!(349524, layer_stride_linear(&params));
+
+        let params = SurfaceParams {
+            layer_alignment: NonZeroUsize::new(0x400),
+            ..params
+        };
+        assert_eq!(351232, layer_stride_tiled(&params));
+
+        // A single layer is not aligned.
+        let params = SurfaceParams {
+            layer_count: 1,
+            layer_alignment: None,
+            ..params
+        };
+        assert_eq!(params.swizzled_size(), layer_stride_tiled(&params));
+        assert_eq!(params.deswizzled_size(), layer_stride_linear(&params));
+    }
+
+    // Record each write to check the chunks and not just the final hash.
+    #[derive(Default)]
+    struct RecordingHasher(Vec<Vec<u8>>);
+
+    impl core::hash::Hasher for RecordingHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.push(bytes.to_vec());
+        }
     }
 
     #[test]
-    fn swizzle_surface_not_enough_data() {
-        let input = [0, 0, 0, 0];
-        let result = swizzle_surface(16, 16, 16, &input, BlockDim::uncompressed(), None, 4, 1, 1);
+    fn hash_deswizzled_matches_deswizzle() {
+        for params in [
+            SurfaceParams {
+                width: 300,
+                height: 200,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 9,
+                layer_count: 6,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 33,
+                height: 33,
+                depth: 33,
+                mipmap_count: 6,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 100,
+                height: 75,
+                bytes_per_pixel: 3,
+                mipmap_count: 7,
+                layer_count: 2,
+                packed_mip_tail: true,
+                ..Default::default()
+            },
+        ] {
+            let tiled: Vec<_> = (0..params.swizzled_size())
+                .map(|i| (i * 7 % 251) as u8)
+                .collect();
+            let untiled = params.deswizzle(&tiled).unwrap();
+
+            let mut hasher = RecordingHasher::default();
+            hash_deswizzled(&tiled, &params, &mut hasher).unwrap();
+            let expected: Vec<_> = untiled.chunks(256).map(|c| c.to_vec()).collect();
+            assert!(expected == hasher.0, "{:?}", params);
+        }
+    }
+
+    #[test]
+    fn hash_deswizzled_not_enough_data() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            mipmap_count: 2,
+            ..Default::default()
+        };
+        let mut hasher = RecordingHasher::default();
         assert_eq!(
-            result,
-            Err(SwizzleError::NotEnoughData {
-                expected_size: 16384,
-                actual_size: 4
-            })
+            Err(SwizzleError::NotEnoughMipData {
+                layer: 0,
+                mip: 1,
+                expected_size: params.swizzled_size(),
+                actual_size: 16384
+            }),
+            hash_deswizzled(&[0u8; 16384], &params, &mut hasher)
         );
+        assert!(hasher.0.is_empty());
     }
 
-    #[test]
-    fn deswizzle_surface_not_enough_data() {
-        let input = [0, 0, 0, 0];
-        let result = deswizzle_surface(4, 4, 1, &input, BlockDim::uncompressed(), None, 4, 1, 1);
+    // Tiling data without any zeros should only leave zeros in the padding.
+    fn check_padded_ranges(params: &SurfaceParams) {
+        let ranges = padded_ranges(params);
+        let input = vec![0xFFu8; params.deswizzled_size()];
+        let tiled = params.swizzle(&input).unwrap();
+
+        let mut is_padding = vec![false; tiled.len()];
+        for range in &ranges {
+            is_padding[range.clone()].fill(true);
+        }
+        for (i, (byte, is_padding)) in tiled.iter().zip(is_padding).enumerate() {
+            assert_eq!(*byte == 0, is_padding, "{params:?} byte {i}");
+        }
+
+        assert!(ranges.windows(2).all(|r| r[0].end < r[1].start));
         assert_eq!(
-            result,
-            Err(SwizzleError::NotEnoughData {
-                expected_size: 512,
-                actual_size: 4
-            })
+            padding_overhead(params).unwrap().padded_bytes,
+            ranges.iter().map(|r| r.len()).sum::<usize>()
         );
     }
 
     #[test]
-    fn swizzle_surface_potential_out_of_memory() {
-        // Test a large 3D texture that likely won't fit in memory.
-        // The input is clearly too small, so this should error instead of panic.
-        let input = [0, 0, 0, 0];
-        let result = swizzle_surface(
-            65535,
-            65535,
-            65535,
+    fn padded_ranges_match_swizzle() {
+        for params in [
+            SurfaceParams {
+                width: 300,
+                height: 200,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 9,
+                layer_count: 6,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 33,
+                height: 33,
+                depth: 33,
+                mipmap_count: 6,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 100,
+                height: 75,
+                bytes_per_pixel: 3,
+                mipmap_count: 7,
+                layer_count: 2,
+                packed_mip_tail: true,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 64,
+                height: 64,
+                mipmap_count: 7,
+                layer_count: 3,
+                layer_alignment: NonZeroUsize::new(0x8000),
+                ..Default::default()
+            },
+        ] {
+            check_padded_ranges(&params);
+        }
+    }
+
+    #[test]
+    fn reused_buffers_have_zero_padding() {
+        let params = SurfaceParams {
+            width: 100,
+            height: 75,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 7,
+            layer_count: 3,
+            packed_mip_tail: true,
+            layer_alignment: NonZeroUsize::new(0x8000),
+            ..Default::default()
+        };
+        let input = vec![0xFFu8; params.deswizzled_size()];
+        let expected = params.swizzle(&input).unwrap();
+        let size = expected.len();
+        assert!(padded_ranges(&params)
+            .into_iter()
+            .all(|r| expected[r].iter().all(|b| *b == 0)));
+
+        // Stale data from a previous surface should never appear in the padding.
+        let stale = || vec![0xABu8; size + 16];
+
+        let mut output = stale();
+        params.swizzle_into(&input, &mut output).unwrap();
+        assert_eq!(expected, output[..size]);
+
+        let mut output = stale();
+        params.swizzle_to(&input, &mut output).unwrap();
+        assert_eq!(expected, output);
+
+        let mut output = stale();
+        let cancel = AtomicBool::new(false);
+        params
+            .swizzle_into_cancellable(&input, &mut output, &cancel)
+            .unwrap();
+        assert_eq!(expected, output[..size]);
+
+        let mut output = stale();
+        let plan = SurfacePlan::new(&params).unwrap();
+        plan.swizzle_into(&input, &mut output).unwrap();
+        assert_eq!(expected, output[..size]);
+
+        let mut output = stale();
+        let range = swizzle_surface_append(
+            &params,
             &input,
-            BlockDim::uncompressed(),
-            None,
-            4,
-            1,
-            1,
+            NonZeroUsize::new(4096).unwrap(),
+            &mut output,
+        )
+        .unwrap();
+        assert!(output[size + 16..range.start].iter().all(|b| *b == 0));
+        assert_eq!(expected, output[range]);
+
+        // The gap between the planes is also padding.
+        let planar = crate::planar::PlanarParams {
+            chroma_offset: Some(0x1000),
+            ..crate::planar::PlanarParams::nv12(64, 30)
+        };
+        let input = vec![0xFFu8; planar.deswizzled_size()];
+        let expected = planar.swizzle(&input).unwrap();
+        assert!(expected[2048..0x1000].iter().all(|b| *b == 0));
+
+        let mut output = vec![0xABu8; planar.swizzled_size()];
+        planar.swizzle_into(&input, &mut output).unwrap();
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn padded_ranges_no_padding() {
+        let params = SurfaceParams {
+            width: 256,
+            height: 256,
+            ..Default::default()
+        };
+        assert!(padded_ranges(&params).is_empty());
+
+        let params = SurfaceParams { width: 0, ..params };
+        assert!(padded_ranges(&params).is_empty());
+    }
+
+    #[test]
+    fn sub_byte_formats() {
+        // 33x8 with 4 bits per pixel pads each row to 17 bytes.
+        let (block_dim, bytes_per_pixel) = BlockDim::from_bits_per_pixel(4).unwrap();
+        let params = SurfaceParams {
+            width: 33,
+            height: 8,
+            block_dim,
+            bytes_per_pixel,
+            ..Default::default()
+        };
+        assert_eq!(17 * 8, params.deswizzled_size());
+        assert_eq!(512, params.swizzled_size());
+
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let expected = SurfaceParams {
+            width: 17,
+            block_dim: BlockDim::uncompressed(),
+            ..params
+        }
+        .swizzle(&input)
+        .unwrap();
+        assert_eq!(expected, params.swizzle(&input).unwrap());
+
+        let (block_dim, bytes_per_pixel) = BlockDim::from_bits_per_pixel(1).unwrap();
+        assert_eq!((8, 1), (block_dim.width.get(), bytes_per_pixel));
+    }
+
+    #[test]
+    fn sub_byte_formats_invalid() {
+        for bits_per_pixel in [0, 3, 6, 12] {
+            assert_eq!(
+                Err(SwizzleError::InvalidBitsPerPixel { bits_per_pixel }),
+                BlockDim::from_bits_per_pixel(bits_per_pixel)
+            );
+        }
+    }
+
+    #[test]
+    fn layer_alignment_compressed_block_height() {
+        // The layer alignment should use the height in blocks rather than pixels.
+        // 109 pixels is 28 blocks, which only needs a block height of 4 for alignment.
+        let params = SurfaceParams {
+            width: 57,
+            height: 109,
+            block_dim: BlockDim::block_4x4(),
+            block_height_mip0: Some(BlockHeight::ThirtyTwo),
+            bytes_per_pixel: 2,
+            mipmap_count: 7,
+            layer_count: 6,
+            ..Default::default()
+        };
+        assert_eq!(6144, layer_stride_tiled(&params));
+        assert_eq!(36864, params.swizzled_size());
+    }
+
+    #[test]
+    fn padding_overhead_bc7_mipmaps() {
+        // 300x200 is 75x50 blocks for the base mip level.
+        let params = SurfaceParams {
+            width: 300,
+            height: 200,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 3,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let padding = padding_overhead(&params).unwrap();
+        assert_eq!(
+            params.swizzled_size() - params.deswizzled_size(),
+            padding.padded_bytes
         );
         assert_eq!(
-            result,
-            Err(SwizzleError::InvalidSurface {
-                width: 65535,
-                height: 65535,
-                depth: 65535,
-                bytes_per_pixel: 4,
-                mipmap_count: 1
-            })
+            padding.padded_bytes as f32 / params.swizzled_size() as f32 * 100.0,
+            padding.percent
         );
-    }
 
-    #[test]
-    fn deswizzle_surface_potential_out_of_memory() {
-        // Test a large 3D texture that likely won't fit in memory.
-        // The input is clearly too small, so this should error instead of panic.
-        let input = [0, 0, 0, 0];
-        let result = deswizzle_surface(
-            65535,
-            65535,
-            65535,
-            &input,
-            BlockDim::uncompressed(),
-            None,
-            4,
-            1,
-            1,
+        // Each mipmap is padded to whole GOBs in x and whole blocks in y.
+        assert_eq!(
+            vec![
+                2 * (1216 * 64 - 1200 * 50),
+                2 * (640 * 32 - 608 * 25),
+                2 * (320 * 16 - 304 * 13)
+            ],
+            padding.per_mip
         );
+        // The remaining padding aligns the second layer.
         assert_eq!(
-            result,
-            Err(SwizzleError::InvalidSurface {
-                width: 65535,
-                height: 65535,
-                depth: 65535,
-                bytes_per_pixel: 4,
-                mipmap_count: 1
-            })
+            6144,
+            padding.padded_bytes - padding.per_mip.iter().sum::<usize>()
         );
     }
 
     #[test]
-    fn swizzle_invalid_mipmaps() {
-        // A 32-bit integer dimension can only have 32 mipmaps.
-        let input = [0; 4];
-        let result = swizzle_surface(1, 1, 1, &input, BlockDim::uncompressed(), None, 4, 33, 1);
+    fn padding_overhead_packed_mip_tail() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            mipmap_count: 7,
+            packed_mip_tail: true,
+            ..Default::default()
+        };
+        let padding = padding_overhead(&params).unwrap();
         assert_eq!(
-            result,
-            Err(SwizzleError::InvalidSurface {
-                width: 1,
-                height: 1,
-                depth: 1,
-                bytes_per_pixel: 4,
-                mipmap_count: 33,
-            })
+            params.swizzled_size() - params.deswizzled_size(),
+            padding.padded_bytes
         );
+        assert_eq!(padding.padded_bytes, padding.per_mip.iter().sum::<usize>());
+
+        // The 8x8 and smaller mipmaps share a single GOB.
+        assert_eq!(vec![0, 0, 0, 512 - 340, 0, 0, 0], padding.per_mip);
     }
 
     #[test]
-    fn deswizzle_surface_invalid_mipmaps() {
-        // A 32-bit integer dimension can only have 32 mipmaps.
-        let input = [0; 4];
-        let result = deswizzle_surface(1, 1, 1, &input, BlockDim::uncompressed(), None, 4, 33, 1);
-        assert_eq!(
-            result,
-            Err(SwizzleError::InvalidSurface {
-                width: 1,
-                height: 1,
-                depth: 1,
-                bytes_per_pixel: 4,
-                mipmap_count: 33,
-            })
-        );
+    fn padding_overhead_empty() {
+        let padding = padding_overhead(&SurfaceParams {
+            width: 0,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(0, padding.padded_bytes);
+        assert_eq!(0.0, padding.percent);
+        assert_eq!(vec![0], padding.per_mip);
     }
 
     #[test]
-    fn swizzle_surface_rgba_16_16_16() {
-        let input = include_bytes!("../block_linear/16_16_16_rgba.bin");
-        let expected = include_bytes!("../block_linear/16_16_16_rgba_tiled.bin");
-        let actual =
-            swizzle_surface(16, 16, 16, input, BlockDim::uncompressed(), None, 4, 1, 1).unwrap();
-        assert_eq!(expected, &actual[..]);
+    fn padding_overhead_invalid() {
+        let params = SurfaceParams {
+            width: u32::MAX,
+            height: u32::MAX,
+            ..Default::default()
+        };
+        assert!(matches!(
+            padding_overhead(&params),
+            Err(SwizzleError::InvalidSurface { .. })
+        ));
+
+        // Empty surfaces should not allocate space for billions of mipmaps.
+        let params = SurfaceParams {
+            width: 0,
+            mipmap_count: u32::MAX,
+            ..Default::default()
+        };
+        assert!(matches!(
+            padding_overhead(&params),
+            Err(SwizzleError::InvalidSurface { .. })
+        ));
     }
 
     #[test]
-    fn deswizzle_surface_rgba_16_16_16() {
-        let input = include_bytes!("../block_linear/16_16_16_rgba_tiled.bin");
-        let expected = include_bytes!("../block_linear/16_16_16_rgba.bin");
-        let actual =
-            deswizzle_surface(16, 16, 16, input, BlockDim::uncompressed(), None, 4, 1, 1).unwrap();
-        assert_eq!(expected, &actual[..]);
+    fn surface_sizes_match_separate_sizes() {
+        for params in [
+            SurfaceParams {
+                width: 300,
+                height: 200,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 9,
+                layer_count: 6,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 64,
+                height: 64,
+                mipmap_count: 7,
+                packed_mip_tail: true,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 33,
+                height: 33,
+                depth: 33,
+                mipmap_count: 4,
+                ..Default::default()
+            },
+        ] {
+            assert_eq!(
+                SurfaceSizes {
+                    tiled: params.swizzled_size(),
+                    linear: params.deswizzled_size()
+                },
+                surface_sizes(&params)
+            );
+        }
     }
 
     #[test]
-    fn swizzle_surface_rgba_33_33_33() {
-        let input = include_bytes!("../block_linear/33_33_33_rgba.bin");
-        let expected = include_bytes!("../block_linear/33_33_33_rgba_tiled.bin");
-        let actual =
-            swizzle_surface(33, 33, 33, input, BlockDim::uncompressed(), None, 4, 1, 1).unwrap();
-        assert!(expected == &actual[..]);
+    fn swizzle_deswizzle_surface_12_bytes_per_pixel() {
+        // R32G32B32 texels don't evenly divide the GOB width.
+        let params = SurfaceParams {
+            width: 75,
+            height: 50,
+            bytes_per_pixel: 12,
+            mipmap_count: 7,
+            layer_count: 3,
+            ..Default::default()
+        };
+        let input: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let swizzled = params.swizzle(&input).unwrap();
+        assert_eq!(params.swizzled_size(), swizzled.len());
+        assert_eq!(input, params.deswizzle(&swizzled).unwrap());
     }
 
     #[test]
-    fn deswizzle_surface_rgba_33_33_33() {
-        let input = include_bytes!("../block_linear/33_33_33_rgba_tiled.bin");
-        let expected = include_bytes!("../block_linear/33_33_33_rgba.bin");
-        let actual =
-            deswizzle_surface(33, 33, 33, input, BlockDim::uncompressed(), None, 4, 1, 1).unwrap();
-        assert!(expected == &actual[..]);
+    fn validate_warnings() {
+        let params = SurfaceParams {
+            width: 256,
+            height: 32,
+            block_dim: BlockDim::block_4x4(),
+            block_height_mip0: Some(BlockHeight::Sixteen),
+            bytes_per_pixel: 16,
+            mipmap_count: 9,
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                Warning::MipmapCountExceedsChain {
+                    mipmap_count: 10,
+                    max_mipmap_count: 9
+                },
+                Warning::BlockHeightLargerThanExpected {
+                    block_height: BlockHeight::Sixteen,
+                    expected: BlockHeight::One
+                },
+                Warning::UncommonBytesPerPixel { bytes_per_pixel: 3 }
+            ],
+            validate(&SurfaceParams {
+                mipmap_count: 10,
+                bytes_per_pixel: 3,
+                ..params
+            })
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn validate_no_warnings() {
+        let params = SurfaceParams {
+            width: 300,
+            height: 200,
+            block_dim: BlockDim::block_4x4(),
+            block_height_mip0: Some(BlockHeight::Eight),
+            bytes_per_pixel: 16,
+            mipmap_count: 9,
+            layer_count: 6,
+            ..Default::default()
+        };
+        assert_eq!(Ok(Vec::new()), validate(&params));
+    }
+
+    #[test]
+    fn validate_invalid_surface() {
+        let params = SurfaceParams {
+            mipmap_count: 33,
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate(&params),
+            Err(SwizzleError::InvalidSurface { .. })
+        ));
     }
 }
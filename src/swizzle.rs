@@ -3,11 +3,24 @@
 //! These functions are for advanced usages of tiling and untiling.
 //! Most texture formats should use the surface functions
 //! to handle mipmap and array layer alignment.
+//!
+//! The dimensions for most functions are in blocks rather than pixels.
+//! Compressed formats like BC7 need to divide the width and height in pixels by the block dimensions.
+//! The functions ending in `_px` like [swizzle_block_linear_px] take the dimensions in pixels
+//! and a `BlockDim` from the surface module and calculate the dimensions in blocks.
+//!
+//! A mipmap with a width, height, depth, or bytes per pixel of 0 is empty.
+//! The tiled and untiled sizes are both 0, and tiling or untiling an empty mipmap
+//! succeeds with an empty output without reading any data.
+#[cfg(feature = "surface")]
+use crate::surface::BlockDim;
 use crate::{
-    blockdepth::block_depth, div_round_up, height_in_blocks, width_in_gobs, BlockHeight,
+    blockdepth::block_depth, div_round_up, height_in_blocks, width_in_gobs, BlockHeight, Result,
     SwizzleError, GOB_HEIGHT_IN_BYTES, GOB_SIZE_IN_BYTES, GOB_WIDTH_IN_BYTES,
 };
+#[cfg(not(feature = "minimal"))]
 use alloc::{vec, vec::Vec};
+use core::sync::atomic::{AtomicU8, Ordering};
 
 /// Tiles the bytes from `source` using the block linear algorithm.
 ///
@@ -50,6 +63,7 @@ let output = swizzle_block_linear(
 );
 ```
  */
+#[cfg(not(feature = "minimal"))]
 pub fn swizzle_block_linear(
     width: u32,
     height: u32,
@@ -57,9 +71,135 @@ pub fn swizzle_block_linear(
     source: &[u8],
     block_height: BlockHeight,
     bytes_per_pixel: u32,
-) -> Result<Vec<u8>, SwizzleError> {
+) -> Result<Vec<u8>> {
+    let expected_size = deswizzled_mip_size(width, height, depth, bytes_per_pixel);
+    if source.len() < expected_size {
+        return Err(SwizzleError::NotEnoughData {
+            actual_size: source.len(),
+            expected_size,
+        });
+    }
+
     let mut destination =
         vec![0u8; swizzled_mip_size(width, height, depth, block_height, bytes_per_pixel)];
+    swizzle_block_linear_into(
+        width,
+        height,
+        depth,
+        source,
+        &mut destination,
+        block_height,
+        bytes_per_pixel,
+    )?;
+    Ok(destination)
+}
+
+/// Tiles the bytes from `source` into `destination` using the block linear algorithm.
+/// This avoids allocating a new buffer when tiling many surfaces of the same size.
+///
+/// Returns [SwizzleError::NotEnoughData] if `source` does not have
+/// at least as many bytes as the result of [deswizzled_mip_size]
+/// or if `destination` does not have at least as many bytes as the result of [swizzled_mip_size].
+/// Only the first [swizzled_mip_size] many bytes of `destination` are modified.
+pub fn swizzle_block_linear_into(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> Result<()> {
+    swizzle_block_linear_gob_pitch_into(
+        width,
+        height,
+        depth,
+        source,
+        destination,
+        block_height,
+        bytes_per_pixel,
+        width_in_gobs(width, bytes_per_pixel),
+    )
+}
+
+/// Tiles the bytes from `source` into `destination` using `width_in_gobs` many GOBs for each row of blocks.
+/// This is the same as [swizzle_block_linear_into] with a larger width in GOBs.
+///
+/// Some render targets use more GOBs for each row than the minimum needed for the width.
+/// Use [swizzled_mip_size_gob_pitch] to calculate the size of the tiled data.
+///
+/// Returns [SwizzleError::InvalidRowPitch] if `width_in_gobs` is smaller than the width of the mipmap in GOBs.
+/// Returns [SwizzleError::NotEnoughData] if `source` does not have
+/// at least as many bytes as the result of [deswizzled_mip_size]
+/// or if `destination` does not have at least as many bytes as the result of [swizzled_mip_size_gob_pitch].
+pub fn swizzle_block_linear_gob_pitch_into(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+    width_in_gobs: u32,
+) -> Result<()> {
+    swizzle_gob_pitch_into(
+        width,
+        height,
+        depth,
+        source,
+        destination,
+        block_height,
+        bytes_per_pixel,
+        width_in_gobs,
+        gob_copy(),
+    )
+}
+
+/// Tiles the bytes from `source` into `destination` like [swizzle_block_linear_into]
+/// but assembles each GOB in a 512 byte buffer before writing the entire GOB at once.
+///
+/// This avoids small scattered writes when tiling directly into
+/// write-combined memory like a mapped GPU buffer.
+/// The output is identical to [swizzle_block_linear_into].
+/// Use [swizzle_block_linear_into] for ordinary memory since the extra copy is slightly slower.
+///
+/// Returns [SwizzleError::NotEnoughData] if `source` does not have
+/// at least as many bytes as the result of [deswizzled_mip_size]
+/// or if `destination` does not have at least as many bytes as the result of [swizzled_mip_size].
+pub fn swizzle_block_linear_buffered_into(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> Result<()> {
+    swizzle_gob_pitch_into(
+        width,
+        height,
+        depth,
+        source,
+        destination,
+        block_height,
+        bytes_per_pixel,
+        width_in_gobs(width, bytes_per_pixel),
+        GobCopy::Buffered,
+    )
+}
+
+fn swizzle_gob_pitch_into(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+    width_in_gobs: u32,
+    gob_copy: GobCopy,
+) -> Result<()> {
+    check_width_in_gobs(width, bytes_per_pixel, width_in_gobs)?;
 
     let expected_size = deswizzled_mip_size(width, height, depth, bytes_per_pixel);
     if source.len() < expected_size {
@@ -69,18 +209,114 @@ pub fn swizzle_block_linear(
         });
     }
 
+    let destination_size = swizzled_mip_size_gob_pitch(width_in_gobs, height, depth, block_height);
+    if destination.len() < destination_size {
+        return Err(SwizzleError::NotEnoughData {
+            actual_size: destination.len(),
+            expected_size: destination_size,
+        });
+    }
+
+    // Padding bytes are not written while tiling, so clear any previous data.
+    destination[..destination_size].fill(0);
+
     // TODO: This should be a parameter since it varies by mipmap?
     let block_depth = block_depth(depth);
 
-    swizzle_inner::<false>(
+    swizzle_inner_strided::<false>(
         width,
         height,
         depth,
         source,
-        &mut destination,
+        destination,
         block_height,
         block_depth,
         bytes_per_pixel,
+        width as usize * bytes_per_pixel as usize,
+        width_in_gobs,
+        gob_copy,
+    );
+    Ok(())
+}
+
+/// Tiles the bytes from `source` using the block linear algorithm
+/// where each row of `source` starts `source_row_pitch` many bytes after the previous row.
+///
+/// This allows tiling a region of a larger image without first copying the region to a separate buffer.
+/// The start of `source` should be the first byte of the region.
+/// Depth slices are `source_row_pitch * height` many bytes apart.
+///
+/// Returns [SwizzleError::InvalidRowPitch] if `source_row_pitch` is smaller than `width * bytes_per_pixel`.
+/// Returns [SwizzleError::NotEnoughData] if `source` does not contain every row of the region.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{BlockHeight, swizzle::swizzle_block_linear_strided};
+
+// Tile the 64x64 region starting at pixel (128, 32) of a 512x512 R8G8B8A8 image.
+let image = vec![0u8; 512 * 512 * 4];
+let row_pitch = 512 * 4;
+let start = 32 * row_pitch + 128 * 4;
+
+let output = swizzle_block_linear_strided(
+    64,
+    64,
+    1,
+    &image[start..],
+    row_pitch,
+    BlockHeight::Eight,
+    4,
+);
+```
+ */
+#[cfg(not(feature = "minimal"))]
+pub fn swizzle_block_linear_strided(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    source_row_pitch: usize,
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> Result<Vec<u8>> {
+    let row_size = width as usize * bytes_per_pixel as usize;
+    if source_row_pitch < row_size {
+        return Err(SwizzleError::InvalidRowPitch {
+            row_pitch: source_row_pitch,
+            min_row_pitch: row_size,
+        });
+    }
+
+    // The last row does not need any padding bytes.
+    // Empty rows don't read any data regardless of the row pitch.
+    let row_count = height as usize * depth as usize;
+    let expected_size = match (row_count, row_size) {
+        (0, _) | (_, 0) => 0,
+        (n, _) => (n - 1) * source_row_pitch + row_size,
+    };
+    if source.len() < expected_size {
+        return Err(SwizzleError::NotEnoughData {
+            actual_size: source.len(),
+            expected_size,
+        });
+    }
+
+    let mut destination =
+        vec![0u8; swizzled_mip_size(width, height, depth, block_height, bytes_per_pixel)];
+
+    swizzle_inner_strided::<false>(
+        width,
+        height,
+        depth,
+        source,
+        &mut destination,
+        block_height,
+        block_depth(depth),
+        bytes_per_pixel,
+        source_row_pitch,
+        width_in_gobs(width, bytes_per_pixel),
+        gob_copy(),
     );
     Ok(destination)
 }
@@ -126,6 +362,7 @@ let output = deswizzle_block_linear(
 );
 ```
  */
+#[cfg(not(feature = "minimal"))]
 pub fn deswizzle_block_linear(
     width: u32,
     height: u32,
@@ -133,10 +370,91 @@ pub fn deswizzle_block_linear(
     source: &[u8],
     block_height: BlockHeight,
     bytes_per_pixel: u32,
-) -> Result<Vec<u8>, SwizzleError> {
+) -> Result<Vec<u8>> {
+    let expected_size = swizzled_mip_size(width, height, depth, block_height, bytes_per_pixel);
+    if source.len() < expected_size {
+        return Err(SwizzleError::NotEnoughData {
+            actual_size: source.len(),
+            expected_size,
+        });
+    }
+
     let mut destination = vec![0u8; deswizzled_mip_size(width, height, depth, bytes_per_pixel)];
+    deswizzle_block_linear_into(
+        width,
+        height,
+        depth,
+        source,
+        &mut destination,
+        block_height,
+        bytes_per_pixel,
+    )?;
+    Ok(destination)
+}
 
-    let expected_size = swizzled_mip_size(width, height, depth, block_height, bytes_per_pixel);
+/// Untiles the bytes from `source` into `destination` using the block linear algorithm.
+/// This avoids allocating a new buffer when untiling many surfaces of the same size.
+///
+/// Returns [SwizzleError::NotEnoughData] if `source` does not have
+/// at least as many bytes as the result of [swizzled_mip_size]
+/// or if `destination` does not have at least as many bytes as the result of [deswizzled_mip_size].
+/// Only the first [deswizzled_mip_size] many bytes of `destination` are modified.
+pub fn deswizzle_block_linear_into(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> Result<()> {
+    deswizzle_block_linear_gob_pitch_into(
+        width,
+        height,
+        depth,
+        source,
+        destination,
+        block_height,
+        bytes_per_pixel,
+        width_in_gobs(width, bytes_per_pixel),
+    )
+}
+
+/// Untiles the bytes from `source` into `destination` using `width_in_gobs` many GOBs for each row of blocks.
+/// This is the same as [deswizzle_block_linear_into] with a larger width in GOBs.
+///
+/// Some render targets use more GOBs for each row than the minimum needed for the width.
+/// The extra GOBs on the right of each row of blocks are skipped.
+///
+/// Returns [SwizzleError::InvalidRowPitch] if `width_in_gobs` is smaller than the width of the mipmap in GOBs.
+/// Returns [SwizzleError::NotEnoughData] if `source` does not have
+/// at least as many bytes as the result of [swizzled_mip_size_gob_pitch]
+/// or if `destination` does not have at least as many bytes as the result of [deswizzled_mip_size].
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{BlockHeight, swizzle::{deswizzle_block_linear_gob_pitch_into, swizzled_mip_size_gob_pitch}};
+
+// A 100x100 R8G8B8A8 render target needs 7 GOBs for each row but was allocated with 8 GOBs.
+# let input = vec![0u8; swizzled_mip_size_gob_pitch(8, 100, 1, BlockHeight::Sixteen)];
+let mut output = vec![0u8; 100 * 100 * 4];
+deswizzle_block_linear_gob_pitch_into(100, 100, 1, &input, &mut output, BlockHeight::Sixteen, 4, 8).unwrap();
+```
+ */
+pub fn deswizzle_block_linear_gob_pitch_into(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+    width_in_gobs: u32,
+) -> Result<()> {
+    check_width_in_gobs(width, bytes_per_pixel, width_in_gobs)?;
+
+    let expected_size = swizzled_mip_size_gob_pitch(width_in_gobs, height, depth, block_height);
     if source.len() < expected_size {
         return Err(SwizzleError::NotEnoughData {
             actual_size: source.len(),
@@ -144,22 +462,534 @@ pub fn deswizzle_block_linear(
         });
     }
 
+    let destination_size = deswizzled_mip_size(width, height, depth, bytes_per_pixel);
+    if destination.len() < destination_size {
+        return Err(SwizzleError::NotEnoughData {
+            actual_size: destination.len(),
+            expected_size: destination_size,
+        });
+    }
+
     // TODO: This should be a parameter since it varies by mipmap?
     let block_depth = block_depth(depth);
 
-    swizzle_inner::<true>(
+    swizzle_inner_strided::<true>(
         width,
         height,
         depth,
         source,
-        &mut destination,
+        destination,
         block_height,
         block_depth,
         bytes_per_pixel,
+        width as usize * bytes_per_pixel as usize,
+        width_in_gobs,
+        gob_copy(),
     );
-    Ok(destination)
+    Ok(())
+}
+
+fn check_width_in_gobs(width: u32, bytes_per_pixel: u32, width_in_gobs: u32) -> Result<()> {
+    let min_width_in_gobs = crate::width_in_gobs(width, bytes_per_pixel);
+    if width_in_gobs < min_width_in_gobs {
+        Err(SwizzleError::InvalidRowPitch {
+            row_pitch: width_in_gobs as usize * GOB_WIDTH_IN_BYTES as usize,
+            min_row_pitch: min_width_in_gobs as usize * GOB_WIDTH_IN_BYTES as usize,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// A rectangular region of a mipmap.
+///
+/// The coordinates and dimensions use the same units as the mipmap width and height.
+/// For compressed formats like BC7, this is the number of blocks rather than pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    /// The horizontal offset of the left edge.
+    pub x: u32,
+    /// The vertical offset of the top edge.
+    pub y: u32,
+    /// The horizontal size starting from `x`.
+    pub width: u32,
+    /// The vertical size starting from `y`.
+    pub height: u32,
+}
+
+impl Region {
+    /// Calculates the size in bytes for the untiled data for this region.
+    ///
+    /// Returns [SwizzleError::InvalidRegion] if the size overflows `usize`.
+    pub const fn deswizzled_size(&self, bytes_per_pixel: u32) -> Result<usize> {
+        if let Some(row_size) = (self.width as usize).checked_mul(bytes_per_pixel as usize) {
+            if let Some(size) = row_size.checked_mul(self.height as usize) {
+                return Ok(size);
+            }
+        }
+        Err(self.invalid())
+    }
+
+    const fn invalid(&self) -> SwizzleError {
+        SwizzleError::InvalidRegion {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+        }
+    }
 }
 
+/// Untiles multiple regions from `source` using the block linear algorithm.
+/// Each [Region] is untiled into the corresponding destination buffer in row-major order.
+///
+/// The tiled data is only traversed once for all regions.
+/// This is faster than untiling the entire mipmap when extracting
+/// a small number of sprites or glyphs from a texture atlas.
+///
+/// Returns [SwizzleError::NotEnoughData] if `source` does not have
+/// at least as many bytes as the result of [swizzled_mip_size]
+/// or if a destination does not have at least as many bytes as [Region::deswizzled_size].
+/// Returns [SwizzleError::InvalidRegion] if a region is not contained in the mipmap
+/// or the size in bytes of a region overflows.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{BlockHeight, swizzle::{deswizzle_block_linear_regions, swizzled_mip_size, Region}};
+
+# let input = vec![0u8; swizzled_mip_size(256, 256, 1, BlockHeight::Sixteen, 4)];
+let glyph = Region { x: 0, y: 0, width: 16, height: 32 };
+let sprite = Region { x: 100, y: 64, width: 48, height: 48 };
+
+let mut glyph_data = vec![0u8; glyph.deswizzled_size(4)?];
+let mut sprite_data = vec![0u8; sprite.deswizzled_size(4)?];
+
+deswizzle_block_linear_regions(
+    256,
+    256,
+    &input,
+    BlockHeight::Sixteen,
+    4,
+    &mut [(glyph, &mut glyph_data), (sprite, &mut sprite_data)],
+)?;
+# Ok::<(), tegra_swizzle::SwizzleError>(())
+```
+ */
+pub fn deswizzle_block_linear_regions(
+    width: u32,
+    height: u32,
+    source: &[u8],
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+    regions: &mut [(Region, &mut [u8])],
+) -> Result<()> {
+    let expected_size = swizzled_mip_size(width, height, 1, block_height, bytes_per_pixel);
+    if source.len() < expected_size {
+        return Err(SwizzleError::NotEnoughData {
+            actual_size: source.len(),
+            expected_size,
+        });
+    }
+
+    for (region, destination) in regions.iter() {
+        if !region_in_bounds(region, width, height) {
+            return Err(region.invalid());
+        }
+
+        let expected_size = region.deswizzled_size(bytes_per_pixel)?;
+        if destination.len() < expected_size {
+            return Err(SwizzleError::NotEnoughData {
+                actual_size: destination.len(),
+                expected_size,
+            });
+        }
+    }
+
+    // Only visit the GOBs that overlap the bounding box of all regions.
+    let non_empty = || regions.iter().filter(|(r, _)| r.width > 0 && r.height > 0);
+    let (Some(min_x), Some(min_y), Some(max_x), Some(max_y)) = (
+        non_empty().map(|(r, _)| r.x * bytes_per_pixel).min(),
+        non_empty().map(|(r, _)| r.y).min(),
+        non_empty()
+            .map(|(r, _)| (r.x + r.width) * bytes_per_pixel)
+            .max(),
+        non_empty().map(|(r, _)| r.y + r.height).max(),
+    ) else {
+        return Ok(());
+    };
+
+    let block_height = block_height as u32;
+    let width_in_gobs = width_in_gobs(width, bytes_per_pixel);
+    let block_size_in_bytes = GOB_SIZE_IN_BYTES * block_height;
+    let block_height_in_bytes = GOB_HEIGHT_IN_BYTES * block_height;
+
+    let start_y = min_y - min_y % GOB_HEIGHT_IN_BYTES;
+    let start_x = min_x - min_x % GOB_WIDTH_IN_BYTES;
+    for y0 in (start_y..max_y).step_by(GOB_HEIGHT_IN_BYTES as usize) {
+        let offset_y = gob_address_y(
+            y0,
+            block_height_in_bytes,
+            block_size_in_bytes,
+            width_in_gobs,
+        );
+
+        for x0 in (start_x..max_x).step_by(GOB_WIDTH_IN_BYTES as usize) {
+            // The GOB address is shared by all regions overlapping this GOB.
+            let gob_address = offset_y as usize + gob_address_x(x0, block_size_in_bytes) as usize;
+
+            for (region, destination) in regions.iter_mut() {
+                deswizzle_gob_region(
+                    destination,
+                    source,
+                    region,
+                    x0,
+                    y0,
+                    bytes_per_pixel,
+                    gob_address,
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn region_in_bounds(region: &Region, width: u32, height: u32) -> bool {
+    match (
+        region.x.checked_add(region.width),
+        region.y.checked_add(region.height),
+    ) {
+        (Some(right), Some(bottom)) => right <= width && bottom <= height,
+        _ => false,
+    }
+}
+
+// Copy the bytes from the GOB at (x0, y0) that are contained in the region.
+fn deswizzle_gob_region(
+    destination: &mut [u8],
+    source: &[u8],
+    region: &Region,
+    x0: u32,
+    y0: u32,
+    bytes_per_pixel: u32,
+    gob_address: usize,
+) {
+    let region_x = region.x * bytes_per_pixel;
+    let region_row_size = region.width * bytes_per_pixel;
+
+    let start_x = x0.max(region_x);
+    let end_x = (x0 + GOB_WIDTH_IN_BYTES).min(region_x + region_row_size);
+    let start_y = y0.max(region.y);
+    let end_y = (y0 + GOB_HEIGHT_IN_BYTES).min(region.y + region.height);
+
+    for y in start_y..end_y {
+        // Each 16 byte segment of a GOB row is contiguous in the tiled data.
+        let mut x = start_x;
+        while x < end_x {
+            let segment_end = (x - x % 16 + 16).min(end_x);
+            let len = (segment_end - x) as usize;
+
+            let swizzled_offset = gob_address + gob_offset(x - x0, y - y0) as usize;
+            let linear_offset = ((y - region.y) * region_row_size + (x - region_x)) as usize;
+            destination[linear_offset..linear_offset + len]
+                .copy_from_slice(&source[swizzled_offset..swizzled_offset + len]);
+
+            x = segment_end;
+        }
+    }
+}
+
+/// A precomputed traversal of the GOBs for a mipmap with fixed dimensions.
+///
+/// Texture arrays often store many mipmaps with identical dimensions.
+/// The plan calculates the tiled and untiled address of each GOB once,
+/// so tiling or untiling each mipmap only needs to copy bytes.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{BlockHeight, swizzle::BlockLinearPlan};
+
+// 64 layers of 256x256 BC7 with a single mipmap.
+let plan = BlockLinearPlan::new(256 / 4, 256 / 4, 1, BlockHeight::Eight, 16);
+# let source = vec![0u8; plan.swizzled_size() * 64];
+let mut destination = vec![0u8; plan.deswizzled_size() * 64];
+
+for (tiled, untiled) in source
+    .chunks_exact(plan.swizzled_size())
+    .zip(destination.chunks_exact_mut(plan.deswizzled_size()))
+{
+    plan.deswizzle_into(tiled, untiled).unwrap();
+}
+```
+ */
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockLinearPlan {
+    row_size: usize,
+    swizzled_size: usize,
+    deswizzled_size: usize,
+    complete_gobs: Vec<CompleteGob>,
+    spans: Vec<Span>,
+    access_order: AccessOrder,
+}
+
+/// The order for visiting the GOBs of a [BlockLinearPlan] or [crate::surface::SurfacePlan].
+///
+/// Both orders produce identical results and only differ in performance.
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AccessOrder {
+    /// Visit GOBs one row of GOBs at a time to keep accesses to the untiled rows sequential.
+    #[default]
+    Linear,
+    /// Visit GOBs in the order they are stored in the tiled data.
+    ///
+    /// Untiling reads the tiled data strictly sequentially and writes rows scattered across the untiled data.
+    /// Tiling writes the tiled data strictly sequentially.
+    /// This helps when the tiled data is a memory mapped file on a slow disk or network share,
+    /// since each page is only accessed once and in order, which works well with readahead.
+    ///
+    /// For data already in memory, [AccessOrder::Linear] is usually faster
+    /// since each block touches `8 * block_height` untiled rows.
+    /// The `access_order` benchmark with data in memory was within 30% for 256x256 and 1000x1000 RGBA
+    /// and 1.3-5x slower for 4096x4096 and 16384x256 RGBA.
+    Tiled,
+}
+
+// A complete 64x8 GOB that can use the optimized copies.
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CompleteGob {
+    gob_address: usize,
+    linear_offset: usize,
+}
+
+// Partially filled GOBs are split into contiguous runs of up to 16 bytes.
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Span {
+    swizzled_offset: usize,
+    linear_offset: usize,
+    len: usize,
+}
+
+#[cfg(not(feature = "minimal"))]
+impl BlockLinearPlan {
+    /// Calculates the GOB addresses for a mipmap with the given dimensions.
+    /// The parameters are the same as [swizzle_block_linear] and [deswizzle_block_linear].
+    pub fn new(
+        width: u32,
+        height: u32,
+        depth: u32,
+        block_height: BlockHeight,
+        bytes_per_pixel: u32,
+    ) -> Self {
+        Self::with_block_depth(
+            width,
+            height,
+            depth,
+            block_height,
+            block_depth(depth),
+            bytes_per_pixel,
+        )
+    }
+
+    // Mipmaps of 3D surfaces use a block depth based on the base mip level.
+    pub(crate) fn with_block_depth(
+        width: u32,
+        height: u32,
+        depth: u32,
+        block_height: BlockHeight,
+        block_depth: u32,
+        bytes_per_pixel: u32,
+    ) -> Self {
+        let swizzled_size = swizzled_mip_size(width, height, depth, block_height, bytes_per_pixel);
+        let deswizzled_size = deswizzled_mip_size(width, height, depth, bytes_per_pixel);
+
+        let block_height = block_height as u32;
+        let width_in_gobs = width_in_gobs(width, bytes_per_pixel);
+        let slice_size = slice_size(block_height, block_depth, width_in_gobs, height);
+        let block_size_in_bytes = GOB_SIZE_IN_BYTES * block_height * block_depth;
+        let block_height_in_bytes = GOB_HEIGHT_IN_BYTES * block_height;
+        let row_size = width * bytes_per_pixel;
+
+        let mut complete_gobs = Vec::new();
+        let mut spans = Vec::new();
+        for z0 in 0..depth {
+            let offset_z = gob_address_z(z0, block_height, block_depth, slice_size as u32);
+            for y0 in (0..height).step_by(GOB_HEIGHT_IN_BYTES as usize) {
+                let offset_y = gob_address_y(
+                    y0,
+                    block_height_in_bytes,
+                    block_size_in_bytes,
+                    width_in_gobs,
+                );
+                for x0 in (0..row_size).step_by(GOB_WIDTH_IN_BYTES as usize) {
+                    let offset_x = gob_address_x(x0, block_size_in_bytes);
+                    let gob_address = offset_z as usize + offset_y as usize + offset_x as usize;
+
+                    if x0 + GOB_WIDTH_IN_BYTES <= row_size && y0 + GOB_HEIGHT_IN_BYTES <= height {
+                        complete_gobs.push(CompleteGob {
+                            gob_address,
+                            linear_offset: linear_offset(x0, y0, z0, height, row_size as usize),
+                        });
+                    } else {
+                        let end_x = GOB_WIDTH_IN_BYTES.min(row_size - x0);
+                        let end_y = GOB_HEIGHT_IN_BYTES.min(height - y0);
+                        for y in 0..end_y {
+                            for x in (0..end_x).step_by(16) {
+                                spans.push(Span {
+                                    swizzled_offset: gob_address + gob_offset(x, y) as usize,
+                                    linear_offset: linear_offset(
+                                        x0 + x,
+                                        y0 + y,
+                                        z0,
+                                        height,
+                                        row_size as usize,
+                                    ),
+                                    len: 16.min(end_x - x) as usize,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            row_size: row_size as usize,
+            swizzled_size,
+            deswizzled_size,
+            complete_gobs,
+            spans,
+            access_order: AccessOrder::Linear,
+        }
+    }
+
+    /// Sets the order for visiting GOBs when tiling or untiling with this plan.
+    /// The default is [AccessOrder::Linear].
+    pub fn with_access_order(mut self, access_order: AccessOrder) -> Self {
+        match access_order {
+            AccessOrder::Linear => {
+                self.complete_gobs.sort_by_key(|g| g.linear_offset);
+                self.spans.sort_by_key(|s| s.linear_offset);
+            }
+            AccessOrder::Tiled => {
+                self.complete_gobs.sort_by_key(|g| g.gob_address);
+                self.spans.sort_by_key(|s| s.swizzled_offset);
+            }
+        }
+        self.access_order = access_order;
+        self
+    }
+
+    /// The size in bytes of the tiled data. See [swizzled_mip_size].
+    pub fn swizzled_size(&self) -> usize {
+        self.swizzled_size
+    }
+
+    /// The size in bytes of the untiled data. See [deswizzled_mip_size].
+    pub fn deswizzled_size(&self) -> usize {
+        self.deswizzled_size
+    }
+
+    /// Tiles the bytes from `source` into `destination`.
+    /// See [swizzle_block_linear_into].
+    pub fn swizzle_into(&self, source: &[u8], destination: &mut [u8]) -> Result<()> {
+        check_size(source, self.deswizzled_size)?;
+        check_size(destination, self.swizzled_size)?;
+
+        // Padding bytes are not written while tiling, so clear any previous data.
+        destination[..self.swizzled_size].fill(0);
+
+        self.visit_gobs(
+            destination,
+            |destination, gob| {
+                swizzle_complete_gob(
+                    &mut destination[gob.gob_address..],
+                    &source[gob.linear_offset..],
+                    self.row_size,
+                )
+            },
+            |destination, span| {
+                destination[span.swizzled_offset..span.swizzled_offset + span.len]
+                    .copy_from_slice(&source[span.linear_offset..span.linear_offset + span.len])
+            },
+        );
+        Ok(())
+    }
+
+    /// Untiles the bytes from `source` into `destination`.
+    /// See [deswizzle_block_linear_into].
+    pub fn deswizzle_into(&self, source: &[u8], destination: &mut [u8]) -> Result<()> {
+        check_size(source, self.swizzled_size)?;
+        check_size(destination, self.deswizzled_size)?;
+
+        self.visit_gobs(
+            destination,
+            |destination, gob| {
+                deswizzle_complete_gob(
+                    &mut destination[gob.linear_offset..],
+                    &source[gob.gob_address..],
+                    self.row_size,
+                )
+            },
+            |destination, span| {
+                destination[span.linear_offset..span.linear_offset + span.len]
+                    .copy_from_slice(&source[span.swizzled_offset..span.swizzled_offset + span.len])
+            },
+        );
+        Ok(())
+    }
+
+    fn visit_gobs(
+        &self,
+        destination: &mut [u8],
+        gob_fn: impl Fn(&mut [u8], &CompleteGob),
+        span_fn: impl Fn(&mut [u8], &Span),
+    ) {
+        match self.access_order {
+            AccessOrder::Linear => {
+                for gob in &self.complete_gobs {
+                    gob_fn(destination, gob);
+                }
+                for span in &self.spans {
+                    span_fn(destination, span);
+                }
+            }
+            AccessOrder::Tiled => {
+                // Merge the complete GOBs and spans sorted by their tiled offsets.
+                let mut spans = self.spans.iter().peekable();
+                for gob in &self.complete_gobs {
+                    while let Some(span) = spans.next_if(|s| s.swizzled_offset < gob.gob_address) {
+                        span_fn(destination, span);
+                    }
+                    gob_fn(destination, gob);
+                }
+                for span in spans {
+                    span_fn(destination, span);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+fn check_size(data: &[u8], expected_size: usize) -> Result<()> {
+    if data.len() < expected_size {
+        Err(SwizzleError::NotEnoughData {
+            actual_size: data.len(),
+            expected_size,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+// Used by the surface functions.
+#[cfg(feature = "surface")]
 pub(crate) fn swizzle_inner<const DESWIZZLE: bool>(
     width: u32,
     height: u32,
@@ -170,9 +1000,130 @@ pub(crate) fn swizzle_inner<const DESWIZZLE: bool>(
     block_depth: u32,
     bytes_per_pixel: u32,
 ) {
-    let block_height = block_height as u32;
-    let width_in_gobs = width_in_gobs(width, bytes_per_pixel);
+    swizzle_inner_strided::<DESWIZZLE>(
+        width,
+        height,
+        depth,
+        source,
+        destination,
+        block_height,
+        block_depth,
+        bytes_per_pixel,
+        width as usize * bytes_per_pixel as usize,
+        width_in_gobs(width, bytes_per_pixel),
+        gob_copy(),
+    )
+}
+
+/// The implementation for copying the bytes within each GOB.
+///
+/// All implementations produce identical results and only differ in performance.
+/// The optimized row copies only apply to GOBs that are completely filled.
+/// Surfaces narrower than 64 bytes or shorter than 8 rows always copy one texel at a time.
+/// Texels of 4, 8, or 16 bytes are copied as a single move and other sizes one byte at a time.
+///
+/// The row copies were faster for every surface with at least one complete GOB in the gob_copy benchmark.
+/// Untiling a 64x64 RGBA surface took 0.23 µs with row copies and 4.1 µs copying texels.
+/// Surfaces without complete GOBs like 4x4 RGBA took about 0.03 µs with either implementation.
+///
+/// This is only intended for benchmarks and tests and may change without a major version.
+#[doc(hidden)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GobCopy {
+    /// Select the implementation for each mipmap based on the dimensions.
+    #[default]
+    Auto,
+    /// Copy complete GOBs using optimized row copies and partially filled GOBs one texel at a time.
+    Rows,
+    /// Copy all GOBs one texel at a time.
+    Bytes,
+    /// The same as [GobCopy::Auto] but tiling assembles each GOB in a 512 byte buffer
+    /// and writes the entire GOB to the destination at once.
+    ///
+    /// This avoids small scattered writes when tiling directly into
+    /// write-combined memory like a mapped GPU buffer.
+    /// Padding bytes in partially filled GOBs are written as zeros.
+    /// Untiling is the same as [GobCopy::Auto].
+    /// Use [swizzle_block_linear_buffered_into] to enable this for a single call.
+    Buffered,
+}
+
+static GOB_COPY: AtomicU8 = AtomicU8::new(GobCopy::Auto as u8);
+
+/// Overrides the implementation selected for all tiling and untiling functions.
+///
+/// This applies to all threads and is only intended for benchmarks.
+/// Tests should pass the implementation to the internal functions instead
+/// to avoid changing the behavior of tests running in parallel.
+#[doc(hidden)]
+pub fn set_gob_copy(gob_copy: GobCopy) {
+    GOB_COPY.store(gob_copy as u8, Ordering::Relaxed);
+}
+
+/// The implementation set by [set_gob_copy].
+#[doc(hidden)]
+pub fn gob_copy() -> GobCopy {
+    match GOB_COPY.load(Ordering::Relaxed) {
+        1 => GobCopy::Rows,
+        2 => GobCopy::Bytes,
+        3 => GobCopy::Buffered,
+        _ => GobCopy::Auto,
+    }
+}
+
+fn use_gob_rows(gob_copy: GobCopy, width: u32, height: u32, bytes_per_pixel: u32) -> bool {
+    match gob_copy {
+        // Skip checking each GOB if the mipmap is too small for any complete GOBs.
+        GobCopy::Auto | GobCopy::Buffered => {
+            width * bytes_per_pixel >= GOB_WIDTH_IN_BYTES && height >= GOB_HEIGHT_IN_BYTES
+        }
+        GobCopy::Rows => true,
+        GobCopy::Bytes => false,
+    }
+}
+
+/// Returns `true` if every GOB of the mipmap is completely filled
+/// and can use the optimized row copies.
+///
+/// This requires the width in bytes to be a multiple of 64 and the height to be a multiple of 8.
+/// Mipmaps with partially filled GOBs along the right or bottom edge
+/// copy the texels in those GOBs individually, which is much slower.
+/// Untiling a 64x64 RGBA surface took 0.23 µs with row copies and 4.1 µs copying texels in the gob_copy benchmark.
+/// The width and height should be in blocks for compressed formats.
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::swizzle::is_fast_path;
+
+assert!(is_fast_path(256, 256, 4));
+// BC7 has 4x4 pixel blocks that each take up 16 bytes.
+assert!(is_fast_path(256 / 4, 256 / 4, 16));
+// 100 * 4 = 400 bytes is not a multiple of 64.
+assert!(!is_fast_path(100, 64, 4));
+```
+ */
+pub const fn is_fast_path(width: u32, height: u32, bytes_per_pixel: u32) -> bool {
+    let width_in_bytes = width as u64 * bytes_per_pixel as u64;
+    width_in_bytes % GOB_WIDTH_IN_BYTES as u64 == 0 && height % GOB_HEIGHT_IN_BYTES == 0
+}
 
+// The linear data uses row_pitch many bytes for each row instead of width * bytes_per_pixel.
+// The tiled data uses width_in_gobs many GOBs for each row of blocks.
+fn swizzle_inner_strided<const DESWIZZLE: bool>(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    block_height: BlockHeight,
+    block_depth: u32,
+    bytes_per_pixel: u32,
+    row_pitch: usize,
+    width_in_gobs: u32,
+    gob_copy: GobCopy,
+) {
+    let block_height = block_height as u32;
     let slice_size = slice_size(block_height, block_depth, width_in_gobs, height);
 
     // Blocks are always one GOB wide.
@@ -181,12 +1132,19 @@ pub(crate) fn swizzle_inner<const DESWIZZLE: bool>(
     let block_size_in_bytes = GOB_SIZE_IN_BYTES * block_width * block_height * block_depth;
     let block_height_in_bytes = GOB_HEIGHT_IN_BYTES * block_height;
 
+    let gob_rows = use_gob_rows(gob_copy, width, height, bytes_per_pixel);
+    let buffered = !DESWIZZLE && gob_copy == GobCopy::Buffered;
+
     // Tiling is defined as a mapping from byte coordinates x,y,z -> x',y',z'.
     // We step a GOB of bytes at a time to optimize the inner loop with SIMD loads/stores.
     // GOBs always use the same tiling patterns, so we can optimize tiling complete 64x8 GOBs.
-    // The partially filled GOBs along the right and bottom edge use a slower per byte implementation.
-    for z0 in 0..depth {
-        let offset_z = gob_address_z(z0, block_height, block_depth, slice_size as u32);
+    // The partially filled GOBs along the right and bottom edge use a slower per texel implementation.
+    // Visiting whole rows of GOBs keeps reads and writes to the linear rows sequential.
+    // Processing a few GOB columns at a time was slower even for very wide surfaces.
+    // Step by a block of slices in z to avoid calculating the z address for each slice.
+    for z_block in (0..depth).step_by(block_depth as usize) {
+        let offset_z_block = gob_address_z(z_block, block_height, block_depth, slice_size as u32);
+        let z_end = depth.min(z_block + block_depth);
 
         // Step by a GOB of bytes in y.
         for y0 in (0..height).step_by(GOB_HEIGHT_IN_BYTES as usize) {
@@ -197,40 +1155,18 @@ pub(crate) fn swizzle_inner<const DESWIZZLE: bool>(
                 width_in_gobs,
             );
 
-            // Step by a GOB of bytes in x.
-            // The bytes per pixel converts pixel coordinates to byte coordinates.
-            // This assumes BCN formats pass in their width and height in number of blocks rather than pixels.
-            for x0 in (0..(width * bytes_per_pixel)).step_by(GOB_WIDTH_IN_BYTES as usize) {
-                let offset_x = gob_address_x(x0, block_size_in_bytes);
-
-                let gob_address = offset_z as usize + offset_y as usize + offset_x as usize;
-
-                // Check if we can use the fast path.
-                if x0 + GOB_WIDTH_IN_BYTES < width * bytes_per_pixel
-                    && y0 + GOB_HEIGHT_IN_BYTES < height
-                {
-                    let linear_offset = (z0 * width * height * bytes_per_pixel)
-                        + (y0 * width * bytes_per_pixel)
-                        + x0;
-
-                    // Use optimized code to reassign bytes.
-                    if DESWIZZLE {
-                        deswizzle_complete_gob(
-                            &mut destination[linear_offset as usize..],
-                            &source[gob_address..],
-                            width as usize * bytes_per_pixel as usize,
-                        );
-                    } else {
-                        swizzle_complete_gob(
-                            &mut destination[gob_address..],
-                            &source[linear_offset as usize..],
-                            width as usize * bytes_per_pixel as usize,
-                        );
-                    }
-                } else {
-                    // There may be a row and column with partially filled GOBs.
-                    // Fall back to a slow implementation that iterates over each byte.
-                    swizzle_deswizzle_gob::<DESWIZZLE>(
+            // The slices in a block are stored next to each other in the tiled data.
+            for z0 in z_block..z_end {
+                let offset_z = offset_z_block + (z0 - z_block) * GOB_SIZE_IN_BYTES * block_height;
+
+                // Step by a GOB of bytes in x.
+                // The bytes per pixel converts pixel coordinates to byte coordinates.
+                // This assumes BCN formats pass in their width and height in number of blocks rather than pixels.
+                for x0 in (0..(width * bytes_per_pixel)).step_by(GOB_WIDTH_IN_BYTES as usize) {
+                    let offset_x = gob_address_x(x0, block_size_in_bytes);
+
+                    let gob_address = offset_z as usize + offset_y as usize + offset_x as usize;
+                    copy_gob::<DESWIZZLE>(
                         destination,
                         source,
                         x0,
@@ -239,7 +1175,10 @@ pub(crate) fn swizzle_inner<const DESWIZZLE: bool>(
                         width,
                         height,
                         bytes_per_pixel,
+                        row_pitch,
                         gob_address,
+                        gob_rows,
+                        buffered,
                     );
                 }
             }
@@ -247,6 +1186,134 @@ pub(crate) fn swizzle_inner<const DESWIZZLE: bool>(
     }
 }
 
+// Tile or untile the GOB starting at byte coordinates x0, y0, z0.
+fn copy_gob<const DESWIZZLE: bool>(
+    destination: &mut [u8],
+    source: &[u8],
+    x0: u32,
+    y0: u32,
+    z0: u32,
+    width: u32,
+    height: u32,
+    bytes_per_pixel: u32,
+    row_pitch: usize,
+    gob_address: usize,
+    gob_rows: bool,
+    buffered: bool,
+) {
+    let is_complete = gob_rows
+        && x0 + GOB_WIDTH_IN_BYTES <= width * bytes_per_pixel
+        && y0 + GOB_HEIGHT_IN_BYTES <= height;
+
+    if buffered {
+        // Write the entire GOB at once even if it is only partially filled.
+        let mut gob = [0u8; GOB_SIZE_IN_BYTES as usize];
+        if is_complete {
+            let linear_offset = linear_offset(x0, y0, z0, height, row_pitch);
+            swizzle_complete_gob(&mut gob, &source[linear_offset..], row_pitch);
+        } else {
+            swizzle_deswizzle_gob::<false>(
+                &mut gob,
+                source,
+                x0,
+                y0,
+                z0,
+                width,
+                height,
+                bytes_per_pixel,
+                row_pitch,
+                0,
+            );
+        }
+        destination[gob_address..gob_address + gob.len()].copy_from_slice(&gob);
+        return;
+    }
+
+    // Check if we can use the fast path.
+    if is_complete {
+        let linear_offset = linear_offset(x0, y0, z0, height, row_pitch);
+
+        // Use optimized code to reassign bytes.
+        if DESWIZZLE {
+            deswizzle_complete_gob(
+                &mut destination[linear_offset..],
+                &source[gob_address..],
+                row_pitch,
+            );
+        } else {
+            swizzle_complete_gob(
+                &mut destination[gob_address..],
+                &source[linear_offset..],
+                row_pitch,
+            );
+        }
+    } else {
+        // There may be a row and column with partially filled GOBs.
+        // Fall back to a slow implementation that iterates over each texel.
+        swizzle_deswizzle_gob::<DESWIZZLE>(
+            destination,
+            source,
+            x0,
+            y0,
+            z0,
+            width,
+            height,
+            bytes_per_pixel,
+            row_pitch,
+            gob_address,
+        );
+    }
+}
+
+// Convert tiled data between block heights by copying whole GOBs.
+// The bytes within each GOB do not depend on the block height.
+#[cfg(all(feature = "surface", not(feature = "minimal")))]
+pub(crate) fn retile_inner(
+    width: u32,
+    height: u32,
+    depth: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    source_block_height: BlockHeight,
+    destination_block_height: BlockHeight,
+    block_depth: u32,
+    bytes_per_pixel: u32,
+) {
+    let width_in_gobs = width_in_gobs(width, bytes_per_pixel);
+    let height_in_gobs = div_round_up(height, GOB_HEIGHT_IN_BYTES);
+
+    let gob_address = |block_height: BlockHeight| {
+        let block_height = block_height as u32;
+        let slice_size = slice_size(block_height, block_depth, width_in_gobs, height) as u32;
+        let block_size_in_bytes = GOB_SIZE_IN_BYTES * block_height * block_depth;
+        let block_height_in_bytes = GOB_HEIGHT_IN_BYTES * block_height;
+
+        move |x: u32, y: u32, z: u32| {
+            gob_address_z(z, block_height, block_depth, slice_size) as usize
+                + gob_address_y(
+                    y * GOB_HEIGHT_IN_BYTES,
+                    block_height_in_bytes,
+                    block_size_in_bytes,
+                    width_in_gobs,
+                ) as usize
+                + gob_address_x(x * GOB_WIDTH_IN_BYTES, block_size_in_bytes) as usize
+        }
+    };
+    let source_address = gob_address(source_block_height);
+    let destination_address = gob_address(destination_block_height);
+
+    let gob_size = GOB_SIZE_IN_BYTES as usize;
+    for z in 0..depth {
+        for y in 0..height_in_gobs {
+            for x in 0..width_in_gobs {
+                let src = source_address(x, y, z);
+                let dst = destination_address(x, y, z);
+                destination[dst..dst + gob_size].copy_from_slice(&source[src..src + gob_size]);
+            }
+        }
+    }
+}
+
 fn swizzle_deswizzle_gob<const DESWIZZLE: bool>(
     destination: &mut [u8],
     source: &[u8],
@@ -256,37 +1323,116 @@ fn swizzle_deswizzle_gob<const DESWIZZLE: bool>(
     width: u32,
     height: u32,
     bytes_per_pixel: u32,
+    row_pitch: usize,
     gob_address: usize,
 ) {
-    for y in 0..GOB_HEIGHT_IN_BYTES {
-        for x in 0..GOB_WIDTH_IN_BYTES {
-            if y0 + y < height && x0 + x < width * bytes_per_pixel {
-                let swizzled_offset = gob_address + gob_offset(x, y) as usize;
-                let linear_offset = (z0 * width * height * bytes_per_pixel)
-                    + ((y0 + y) * width * bytes_per_pixel)
-                    + x0
-                    + x;
-
-                // Swap the addresses for tiling vs untiling.
-                if DESWIZZLE {
-                    destination[linear_offset as usize] = source[swizzled_offset];
-                } else {
-                    destination[swizzled_offset] = source[linear_offset as usize];
-                }
+    // Each 16 byte segment of a GOB row is contiguous in the tiled data.
+    // Texels that evenly divide 16 bytes can be moved as a single copy.
+    match bytes_per_pixel {
+        4 => swizzle_deswizzle_gob_texels::<DESWIZZLE, 4>(
+            destination,
+            source,
+            x0,
+            y0,
+            z0,
+            width * bytes_per_pixel,
+            height,
+            row_pitch,
+            gob_address,
+        ),
+        8 => swizzle_deswizzle_gob_texels::<DESWIZZLE, 8>(
+            destination,
+            source,
+            x0,
+            y0,
+            z0,
+            width * bytes_per_pixel,
+            height,
+            row_pitch,
+            gob_address,
+        ),
+        16 => swizzle_deswizzle_gob_texels::<DESWIZZLE, 16>(
+            destination,
+            source,
+            x0,
+            y0,
+            z0,
+            width * bytes_per_pixel,
+            height,
+            row_pitch,
+            gob_address,
+        ),
+        _ => swizzle_deswizzle_gob_texels::<DESWIZZLE, 1>(
+            destination,
+            source,
+            x0,
+            y0,
+            z0,
+            width * bytes_per_pixel,
+            height,
+            row_pitch,
+            gob_address,
+        ),
+    }
+}
+
+// Copy N bytes at a time for the part of the GOB inside the surface.
+// The width in bytes and GOB x coordinate must be multiples of N.
+fn swizzle_deswizzle_gob_texels<const DESWIZZLE: bool, const N: usize>(
+    destination: &mut [u8],
+    source: &[u8],
+    x0: u32,
+    y0: u32,
+    z0: u32,
+    width_in_bytes: u32,
+    height: u32,
+    row_pitch: usize,
+    gob_address: usize,
+) {
+    let gob_width = (width_in_bytes - x0).min(GOB_WIDTH_IN_BYTES);
+    let gob_height = (height - y0).min(GOB_HEIGHT_IN_BYTES);
+
+    for y in 0..gob_height {
+        for x in (0..gob_width).step_by(N) {
+            let swizzled_offset = gob_address + gob_offset(x, y) as usize;
+            let linear_offset = linear_offset(x0 + x, y0 + y, z0, height, row_pitch);
+
+            // Swap the addresses for tiling vs untiling.
+            if DESWIZZLE {
+                destination[linear_offset..linear_offset + N]
+                    .copy_from_slice(&source[swizzled_offset..swizzled_offset + N]);
+            } else {
+                destination[swizzled_offset..swizzled_offset + N]
+                    .copy_from_slice(&source[linear_offset..linear_offset + N]);
             }
         }
     }
 }
 
+// The offset for the byte coordinates (x, y, z) in the untiled data.
+fn linear_offset(x: u32, y: u32, z: u32, height: u32, row_pitch: usize) -> usize {
+    (z as usize * height as usize + y as usize) * row_pitch + x as usize
+}
+
 // The gob address and slice size functions are ported from Ryujinx Emulator.
 // https://github.com/Ryujinx/Ryujinx/blob/master/Ryujinx.Graphics.Texture/BlockLinearLayout.cs
 // License MIT: https://github.com/Ryujinx/Ryujinx/blob/master/LICENSE.txt.
-fn slice_size(block_height: u32, block_depth: u32, width_in_gobs: u32, height: u32) -> usize {
+pub(crate) const fn slice_size(
+    block_height: u32,
+    block_depth: u32,
+    width_in_gobs: u32,
+    height: u32,
+) -> usize {
     let rob_size = GOB_SIZE_IN_BYTES * block_height * block_depth * width_in_gobs;
     div_round_up(height, block_height * GOB_HEIGHT_IN_BYTES) as usize * rob_size as usize
 }
 
-fn gob_address_z(z: u32, block_height: u32, block_depth: u32, slice_size: u32) -> u32 {
+pub(crate) const fn gob_address_z(
+    z: u32,
+    block_height: u32,
+    block_depth: u32,
+    slice_size: u32,
+) -> u32 {
     // Each "column" of blocks has block_depth many blocks.
     // A 16x16x16 RGBA8 3d texture has the following untiled GOB indices.
     //  0, 16,
@@ -297,7 +1443,7 @@ fn gob_address_z(z: u32, block_height: u32, block_depth: u32, slice_size: u32) -
     (z / block_depth * slice_size) + ((z & (block_depth - 1)) * GOB_SIZE_IN_BYTES * block_height)
 }
 
-fn gob_address_y(
+pub(crate) const fn gob_address_y(
     y: u32,
     block_height_in_bytes: u32,
     block_size_in_bytes: u32,
@@ -309,14 +1455,14 @@ fn gob_address_y(
 }
 
 // Code for offset_x and offset_y adapted from examples in the Tegra TRM v1.3 page 1217.
-fn gob_address_x(x: u32, block_size_in_bytes: u32) -> u32 {
+pub(crate) const fn gob_address_x(x: u32, block_size_in_bytes: u32) -> u32 {
     let block_x = x / GOB_WIDTH_IN_BYTES;
     block_x * block_size_in_bytes
 }
 
 // Code taken from examples in Tegra TRM v1.3 page 1218.
 // Return the offset within the GOB for the byte at location (x, y).
-fn gob_offset(x: u32, y: u32) -> u32 {
+pub(crate) const fn gob_offset(x: u32, y: u32) -> u32 {
     // TODO: Optimize this?
     // TODO: Describe the pattern here?
     ((x % 64) / 32) * 256 + ((y % 8) / 2) * 64 + ((x % 32) / 16) * 32 + (y % 2) * 16 + (x % 16)
@@ -331,6 +1477,7 @@ const GOB_ROW_OFFSETS: [usize; GOB_HEIGHT_IN_BYTES as usize] = [0, 16, 64, 80, 1
 // An optimized version of the gob_offset for an entire GOB worth of bytes.
 // The tiled GOB is a contiguous region of 512 bytes.
 // The untiled GOB is a 64x8 2D region of memory, so we need to account for the pitch.
+// The copies only operate on bytes, so the results do not depend on the target's endianness.
 fn deswizzle_complete_gob(dst: &mut [u8], src: &[u8], row_size_in_bytes: usize) {
     // Hard code each of the GOB_HEIGHT many rows.
     // This allows the compiler to optimize the copies with SIMD instructions.
@@ -413,14 +1560,30 @@ pub const fn swizzled_mip_size(
     bytes_per_pixel: u32,
 ) -> usize {
     // Assume each block is 1 GOB wide.
-    let width_in_gobs = width_in_gobs(width, bytes_per_pixel) as usize;
+    swizzled_mip_size_gob_pitch(
+        width_in_gobs(width, bytes_per_pixel),
+        height,
+        depth,
+        block_height,
+    )
+}
+
+/// Calculates the size in bytes for the tiled data with `width_in_gobs` many GOBs for each row of blocks.
+/// See [swizzle_block_linear_gob_pitch_into] and [deswizzle_block_linear_gob_pitch_into].
+pub const fn swizzled_mip_size_gob_pitch(
+    width_in_gobs: u32,
+    height: u32,
+    depth: u32,
+    block_height: BlockHeight,
+) -> usize {
+    let width_in_gobs = width_in_gobs as usize;
 
     let height_in_blocks = height_in_blocks(height, block_height as u32);
     let height_in_gobs = height_in_blocks as usize * block_height as usize;
 
-    let depth_in_gobs = depth.next_multiple_of(block_depth(depth));
+    let depth_in_gobs = (depth as usize).next_multiple_of(block_depth(depth) as usize);
 
-    let num_gobs = width_in_gobs * height_in_gobs * depth_in_gobs as usize;
+    let num_gobs = width_in_gobs * height_in_gobs * depth_in_gobs;
     num_gobs * GOB_SIZE_IN_BYTES as usize
 }
 
@@ -461,13 +1624,485 @@ pub const fn deswizzled_mip_size(
     width as usize * height as usize * depth as usize * bytes_per_pixel as usize
 }
 
-#[cfg(test)]
+/// Tiles the bytes from `source` for a mipmap with dimensions in pixels.
+///
+/// This is the same as [swizzle_block_linear] with the dimensions
+/// divided by the dimensions of `block_dim` and rounded up.
+/// The `block_height` should still be calculated from the height in blocks.
+///
+/// Returns [SwizzleError::NotEnoughData] if `source` does not have
+/// at least as many bytes as the result of [deswizzled_mip_size_px].
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{block_height_mip0, div_round_up};
+use tegra_swizzle::surface::BlockDim;
+use tegra_swizzle::swizzle::{deswizzled_mip_size_px, swizzle_block_linear_px};
+
+// BC7 has 4x4 pixel blocks that each take up 16 bytes.
+let width = 512;
+let height = 512;
+let block_height = block_height_mip0(div_round_up(height, 4));
+# let input = vec![0u8; deswizzled_mip_size_px(width, height, 1, BlockDim::block_4x4(), 16)];
+let output = swizzle_block_linear_px(
+    width,
+    height,
+    1,
+    &input,
+    BlockDim::block_4x4(),
+    block_height,
+    16,
+);
+```
+ */
+#[cfg(all(feature = "surface", not(feature = "minimal")))]
+pub fn swizzle_block_linear_px(
+    width_px: u32,
+    height_px: u32,
+    depth_px: u32,
+    source: &[u8],
+    block_dim: BlockDim,
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> Result<Vec<u8>> {
+    let (width, height, depth) = dimensions_in_blocks(width_px, height_px, depth_px, block_dim);
+    swizzle_block_linear(width, height, depth, source, block_height, bytes_per_pixel)
+}
+
+/// Untiles the bytes from `source` for a mipmap with dimensions in pixels.
+///
+/// This is the same as [deswizzle_block_linear] with the dimensions
+/// divided by the dimensions of `block_dim` and rounded up.
+/// The `block_height` should still be calculated from the height in blocks.
+///
+/// Returns [SwizzleError::NotEnoughData] if `source` does not have
+/// at least as many bytes as the result of [swizzled_mip_size_px].
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{block_height_mip0, div_round_up};
+use tegra_swizzle::surface::BlockDim;
+use tegra_swizzle::swizzle::{deswizzle_block_linear_px, swizzled_mip_size_px};
+
+// BC7 has 4x4 pixel blocks that each take up 16 bytes.
+let width = 512;
+let height = 512;
+let block_height = block_height_mip0(div_round_up(height, 4));
+# let input = vec![0u8; swizzled_mip_size_px(width, height, 1, BlockDim::block_4x4(), block_height, 16)];
+let output = deswizzle_block_linear_px(
+    width,
+    height,
+    1,
+    &input,
+    BlockDim::block_4x4(),
+    block_height,
+    16,
+);
+```
+ */
+#[cfg(all(feature = "surface", not(feature = "minimal")))]
+pub fn deswizzle_block_linear_px(
+    width_px: u32,
+    height_px: u32,
+    depth_px: u32,
+    source: &[u8],
+    block_dim: BlockDim,
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> Result<Vec<u8>> {
+    let (width, height, depth) = dimensions_in_blocks(width_px, height_px, depth_px, block_dim);
+    deswizzle_block_linear(width, height, depth, source, block_height, bytes_per_pixel)
+}
+
+/// Calculates the size in bytes for the tiled data for a mipmap with dimensions in pixels.
+/// This is the same as [swizzled_mip_size] with the dimensions in blocks.
+#[cfg(feature = "surface")]
+pub const fn swizzled_mip_size_px(
+    width_px: u32,
+    height_px: u32,
+    depth_px: u32,
+    block_dim: BlockDim,
+    block_height: BlockHeight,
+    bytes_per_pixel: u32,
+) -> usize {
+    let (width, height, depth) = dimensions_in_blocks(width_px, height_px, depth_px, block_dim);
+    swizzled_mip_size(width, height, depth, block_height, bytes_per_pixel)
+}
+
+/// Calculates the size in bytes for the untiled data for a mipmap with dimensions in pixels.
+/// This is the same as [deswizzled_mip_size] with the dimensions in blocks.
+#[cfg(feature = "surface")]
+pub const fn deswizzled_mip_size_px(
+    width_px: u32,
+    height_px: u32,
+    depth_px: u32,
+    block_dim: BlockDim,
+    bytes_per_pixel: u32,
+) -> usize {
+    let (width, height, depth) = dimensions_in_blocks(width_px, height_px, depth_px, block_dim);
+    deswizzled_mip_size(width, height, depth, bytes_per_pixel)
+}
+
+#[cfg(feature = "surface")]
+const fn dimensions_in_blocks(
+    width_px: u32,
+    height_px: u32,
+    depth_px: u32,
+    block_dim: BlockDim,
+) -> (u32, u32, u32) {
+    (
+        div_round_up(width_px, block_dim.width.get()),
+        div_round_up(height_px, block_dim.height.get()),
+        div_round_up(depth_px, block_dim.depth.get()),
+    )
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
 mod tests {
     use super::*;
+    use crate::fixtures::{load_fixtures, read_fixture, write_fixture, Fixture};
 
     use rand::{rngs::StdRng, Rng, SeedableRng};
 
     #[test]
+    #[cfg(feature = "surface")]
+    fn mip_functions_px() {
+        // 33x17 pixels is 9x5 blocks for BC7.
+        let block_dim = BlockDim::block_4x4();
+        let block_height = BlockHeight::One;
+        assert_eq!(
+            swizzled_mip_size(9, 5, 1, block_height, 16),
+            swizzled_mip_size_px(33, 17, 1, block_dim, block_height, 16)
+        );
+        assert_eq!(720, deswizzled_mip_size_px(33, 17, 1, block_dim, 16));
+
+        let input: Vec<_> = (0..720).map(|i| i as u8).collect();
+        let tiled =
+            swizzle_block_linear_px(33, 17, 1, &input, block_dim, block_height, 16).unwrap();
+        assert_eq!(
+            swizzle_block_linear(9, 5, 1, &input, block_height, 16).unwrap(),
+            tiled
+        );
+        assert_eq!(
+            input,
+            deswizzle_block_linear_px(33, 17, 1, &tiled, block_dim, block_height, 16).unwrap()
+        );
+    }
+
+    #[test]
+    fn fast_path_mipmaps() {
+        assert!(is_fast_path(16, 8, 4));
+        assert!(is_fast_path(64, 64, 1));
+        assert!(is_fast_path(64, 64, 3));
+        assert!(!is_fast_path(63, 64, 1));
+        assert!(!is_fast_path(16, 12, 4));
+        assert!(!is_fast_path(4, 4, 4));
+        assert!(is_fast_path(u32::MAX, 8, 64));
+    }
+
+    #[test]
+    fn empty_mipmaps() {
+        for (width, height, depth, bytes_per_pixel) in [
+            (0, 16, 1, 4),
+            (16, 0, 1, 4),
+            (16, 16, 0, 4),
+            (16, 16, 1, 0),
+            (0, 0, 0, 0),
+        ] {
+            assert_eq!(
+                0,
+                swizzled_mip_size(width, height, depth, BlockHeight::Two, bytes_per_pixel)
+            );
+            assert_eq!(
+                0,
+                deswizzled_mip_size(width, height, depth, bytes_per_pixel)
+            );
+
+            assert_eq!(
+                Ok(Vec::new()),
+                swizzle_block_linear(width, height, depth, &[], BlockHeight::Two, bytes_per_pixel)
+            );
+            assert_eq!(
+                Ok(Vec::new()),
+                deswizzle_block_linear(
+                    width,
+                    height,
+                    depth,
+                    &[],
+                    BlockHeight::Two,
+                    bytes_per_pixel
+                )
+            );
+            assert_eq!(
+                Ok(()),
+                swizzle_block_linear_into(
+                    width,
+                    height,
+                    depth,
+                    &[],
+                    &mut [],
+                    BlockHeight::Two,
+                    bytes_per_pixel
+                )
+            );
+            assert_eq!(
+                Ok(()),
+                deswizzle_block_linear_into(
+                    width,
+                    height,
+                    depth,
+                    &[],
+                    &mut [],
+                    BlockHeight::Two,
+                    bytes_per_pixel
+                )
+            );
+            assert_eq!(
+                Ok(Vec::new()),
+                swizzle_block_linear_strided(
+                    width,
+                    height,
+                    depth,
+                    &[],
+                    64,
+                    BlockHeight::Two,
+                    bytes_per_pixel
+                )
+            );
+
+            let plan =
+                BlockLinearPlan::new(width, height, depth, BlockHeight::Two, bytes_per_pixel);
+            assert_eq!(0, plan.swizzled_size());
+            assert_eq!(Ok(()), plan.deswizzle_into(&[], &mut []));
+        }
+    }
+
+    #[test]
+    fn empty_mipmap_does_not_modify_destination() {
+        let mut destination = [1u8; 4];
+        deswizzle_block_linear_into(0, 16, 1, &[], &mut destination, BlockHeight::One, 4).unwrap();
+        assert_eq!([1u8; 4], destination);
+    }
+
+    #[test]
+    fn deswizzle_gob_pitch_matches_wider_surface() {
+        // A 100x20 RGBA surface needs 7 GOBs for each row.
+        // Tiling with 8 GOBs is the same as tiling a 128x20 surface.
+        let wide: Vec<_> = (0..128 * 20 * 4).map(|i| i as u8).collect();
+        let tiled = swizzle_block_linear(128, 20, 1, &wide, BlockHeight::Two, 4).unwrap();
+        assert_eq!(
+            tiled.len(),
+            swizzled_mip_size_gob_pitch(8, 20, 1, BlockHeight::Two)
+        );
+
+        let mut untiled = vec![0u8; 100 * 20 * 4];
+        deswizzle_block_linear_gob_pitch_into(
+            100,
+            20,
+            1,
+            &tiled,
+            &mut untiled,
+            BlockHeight::Two,
+            4,
+            8,
+        )
+        .unwrap();
+        for (row, wide_row) in untiled.chunks(400).zip(wide.chunks(512)) {
+            assert_eq!(row, &wide_row[..400]);
+        }
+
+        // The extra GOBs are padding when tiling.
+        let mut retiled = vec![0u8; tiled.len()];
+        swizzle_block_linear_gob_pitch_into(
+            100,
+            20,
+            1,
+            &untiled,
+            &mut retiled,
+            BlockHeight::Two,
+            4,
+            8,
+        )
+        .unwrap();
+        let rewide = deswizzle_block_linear(128, 20, 1, &retiled, BlockHeight::Two, 4).unwrap();
+        for (row, wide_row) in untiled.chunks(400).zip(rewide.chunks(512)) {
+            assert_eq!(row, &wide_row[..400]);
+            assert!(wide_row[400..].iter().all(|b| *b == 0));
+        }
+    }
+
+    #[test]
+    fn edge_gob_texel_copies_match_bytes() {
+        // Tiling only depends on the width in bytes,
+        // so larger texels should match tiling with one byte per pixel.
+        for bytes_per_pixel in [4, 8, 16] {
+            for (width, height) in [(1, 1), (5, 3), (17, 9), (100, 100)] {
+                let input: Vec<_> = (0..deswizzled_mip_size(width, height, 1, bytes_per_pixel))
+                    .map(|i| i as u8)
+                    .collect();
+
+                let tiled = swizzle_block_linear(
+                    width,
+                    height,
+                    1,
+                    &input,
+                    BlockHeight::Two,
+                    bytes_per_pixel,
+                )
+                .unwrap();
+                let expected = swizzle_block_linear(
+                    width * bytes_per_pixel,
+                    height,
+                    1,
+                    &input,
+                    BlockHeight::Two,
+                    1,
+                )
+                .unwrap();
+                assert_eq!(expected, tiled);
+
+                let untiled = deswizzle_block_linear(
+                    width,
+                    height,
+                    1,
+                    &tiled,
+                    BlockHeight::Two,
+                    bytes_per_pixel,
+                )
+                .unwrap();
+                assert_eq!(input, untiled);
+            }
+        }
+    }
+
+    #[test]
+    fn gob_copy_implementations_match() {
+        // Include surfaces where the last row and column of GOBs are completely filled.
+        for (width, height, depth) in [
+            (16, 8, 1),
+            (17, 9, 1),
+            (32, 16, 1),
+            (64, 64, 1),
+            (16, 16, 16),
+            (300, 200, 1),
+            (33, 17, 5),
+        ] {
+            let block_height = BlockHeight::Two;
+            let input: Vec<_> = (0..deswizzled_mip_size(width, height, depth, 4))
+                .map(|i| i as u8)
+                .collect();
+
+            // Pass the implementation explicitly since other tests run in parallel.
+            let expected =
+                swizzle_block_linear(width, height, depth, &input, block_height, 4).unwrap();
+            for method in [
+                GobCopy::Bytes,
+                GobCopy::Rows,
+                GobCopy::Auto,
+                GobCopy::Buffered,
+            ] {
+                let mut tiled = vec![0u8; expected.len()];
+                swizzle_inner_strided::<false>(
+                    width,
+                    height,
+                    depth,
+                    &input,
+                    &mut tiled,
+                    block_height,
+                    block_depth(depth),
+                    4,
+                    width as usize * 4,
+                    width_in_gobs(width, 4),
+                    method,
+                );
+                assert_eq!(expected, tiled);
+
+                let mut untiled = vec![0u8; input.len()];
+                swizzle_inner_strided::<true>(
+                    width,
+                    height,
+                    depth,
+                    &tiled,
+                    &mut untiled,
+                    block_height,
+                    block_depth(depth),
+                    4,
+                    width as usize * 4,
+                    width_in_gobs(width, 4),
+                    method,
+                );
+                assert_eq!(input, untiled);
+            }
+        }
+    }
+
+    #[test]
+    fn swizzle_buffered_matches_swizzle() {
+        for (width, height, depth) in [(4, 4, 1), (17, 9, 1), (64, 64, 1), (33, 17, 5)] {
+            let block_height = BlockHeight::Two;
+            let input: Vec<_> = (0..deswizzled_mip_size(width, height, depth, 4))
+                .map(|i| i as u8)
+                .collect();
+            let expected =
+                swizzle_block_linear(width, height, depth, &input, block_height, 4).unwrap();
+
+            // Padding bytes from previous data should still be cleared.
+            let mut tiled = vec![0xFFu8; expected.len()];
+            swizzle_block_linear_buffered_into(
+                width,
+                height,
+                depth,
+                &input,
+                &mut tiled,
+                block_height,
+                4,
+            )
+            .unwrap();
+            assert_eq!(expected, tiled);
+        }
+
+        assert_eq!(
+            Err(SwizzleError::NotEnoughData {
+                expected_size: 512,
+                actual_size: 4
+            }),
+            swizzle_block_linear_buffered_into(
+                4,
+                4,
+                1,
+                &[0u8; 64],
+                &mut [0u8; 4],
+                BlockHeight::One,
+                4
+            )
+        );
+    }
+
+    #[test]
+    fn swizzle_gob_pitch_too_small() {
+        let mut destination = vec![0u8; 4096];
+        assert_eq!(
+            Err(SwizzleError::InvalidRowPitch {
+                row_pitch: 384,
+                min_row_pitch: 448
+            }),
+            swizzle_block_linear_gob_pitch_into(
+                100,
+                8,
+                1,
+                &[0u8; 3200],
+                &mut destination,
+                BlockHeight::One,
+                4,
+                6
+            )
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
     fn swizzle_deswizzle_bytes_per_pixel() {
         let width = 312;
         let height = 575;
@@ -497,6 +2132,234 @@ mod tests {
         assert_eq!(input, deswizzled);
     }
 
+    #[test]
+    fn complete_gob_matches_gob_offset() {
+        // The fast path should match the per byte path on all targets.
+        let row_size = 80;
+        let linear: Vec<_> = (0..row_size * 8).map(|i| i as u8).collect();
+
+        let mut expected = [0u8; GOB_SIZE_IN_BYTES as usize];
+        for y in 0..GOB_HEIGHT_IN_BYTES {
+            for x in 0..GOB_WIDTH_IN_BYTES {
+                expected[gob_offset(x, y) as usize] = linear[(y * row_size + x) as usize];
+            }
+        }
+
+        let mut tiled = [0u8; GOB_SIZE_IN_BYTES as usize];
+        swizzle_complete_gob(&mut tiled, &linear, row_size as usize);
+        assert_eq!(expected, tiled);
+
+        let mut untiled = vec![0u8; linear.len()];
+        deswizzle_complete_gob(&mut untiled, &tiled, row_size as usize);
+        for y in 0..GOB_HEIGHT_IN_BYTES as usize {
+            let row = y * row_size as usize;
+            assert_eq!(&linear[row..row + 64], &untiled[row..row + 64]);
+        }
+    }
+
+    #[test]
+    fn swizzle_strided_region() {
+        let image_width = 300;
+        let image_height = 40;
+        let row_pitch = image_width * 12;
+        let image: Vec<_> = (0..row_pitch * image_height).map(|i| i as u8).collect();
+
+        // Copy the 100x30 region at (7, 5) to a packed buffer for comparison.
+        let (x, y, width, height) = (7, 5, 100, 30);
+        let region: Vec<_> = image
+            .chunks_exact(row_pitch)
+            .skip(y)
+            .take(height)
+            .flat_map(|row| row[x * 12..(x + width) * 12].iter().copied())
+            .collect();
+        let expected = swizzle_block_linear(
+            width as u32,
+            height as u32,
+            1,
+            &region,
+            BlockHeight::Four,
+            12,
+        )
+        .unwrap();
+
+        let actual = swizzle_block_linear_strided(
+            width as u32,
+            height as u32,
+            1,
+            &image[y * row_pitch + x * 12..],
+            row_pitch,
+            BlockHeight::Four,
+            12,
+        )
+        .unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn swizzle_strided_invalid_row_pitch() {
+        let result = swizzle_block_linear_strided(16, 16, 1, &[0u8; 1024], 63, BlockHeight::One, 4);
+        assert_eq!(
+            result,
+            Err(SwizzleError::InvalidRowPitch {
+                row_pitch: 63,
+                min_row_pitch: 64
+            })
+        );
+    }
+
+    // Tile each byte separately without using any optimized copies.
+    fn swizzle_reference(
+        width: u32,
+        height: u32,
+        source: &[u8],
+        block_height: BlockHeight,
+        bytes_per_pixel: u32,
+    ) -> Vec<u8> {
+        let block_height = block_height as u32;
+        let width_in_gobs = width_in_gobs(width, bytes_per_pixel);
+        let block_size_in_bytes = GOB_SIZE_IN_BYTES * block_height;
+
+        let mut destination = vec![
+            0u8;
+            swizzled_mip_size(
+                width,
+                height,
+                1,
+                BlockHeight::new(block_height).unwrap(),
+                bytes_per_pixel
+            )
+        ];
+        for y in 0..height {
+            for x in 0..width * bytes_per_pixel {
+                let address = gob_address_y(
+                    y,
+                    GOB_HEIGHT_IN_BYTES * block_height,
+                    block_size_in_bytes,
+                    width_in_gobs,
+                ) + gob_address_x(x, block_size_in_bytes)
+                    + gob_offset(x, y);
+                destination[address as usize] = source[(y * width * bytes_per_pixel + x) as usize];
+            }
+        }
+        destination
+    }
+
+    fn check_texel_stride(bytes_per_pixel: u32) {
+        let seed = [13u8; 32];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+        // Rows of texels don't align to the 16 byte sectors of each GOB row.
+        for (width, height) in [(1, 1), (5, 3), (16, 16), (21, 37), (100, 100), (129, 65)] {
+            for block_height in [BlockHeight::One, BlockHeight::Four, BlockHeight::Sixteen] {
+                let input: Vec<_> = (0..deswizzled_mip_size(width, height, 1, bytes_per_pixel))
+                    .map(|_| rng.gen_range::<u8, _>(0..=255))
+                    .collect();
+
+                let swizzled =
+                    swizzle_block_linear(width, height, 1, &input, block_height, bytes_per_pixel)
+                        .unwrap();
+                assert_eq!(
+                    swizzle_reference(width, height, &input, block_height, bytes_per_pixel),
+                    swizzled
+                );
+
+                let deswizzled = deswizzle_block_linear(
+                    width,
+                    height,
+                    1,
+                    &swizzled,
+                    block_height,
+                    bytes_per_pixel,
+                )
+                .unwrap();
+                assert_eq!(input, deswizzled);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_deswizzle_6_bytes_per_pixel() {
+        // R16G16B16
+        check_texel_stride(6);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_deswizzle_12_bytes_per_pixel() {
+        // R32G32B32
+        check_texel_stride(12);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_deswizzle_24_bytes_per_pixel() {
+        // R64G64B64
+        check_texel_stride(24);
+    }
+
+    #[test]
+    fn swizzle_deswizzle_bc7_504_156() {
+        // BC7 504x156 is 126x39 blocks or 2016 bytes per row.
+        // Rows end halfway through a GOB, and the last row of blocks only partially fills its GOBs.
+        let (width, height) = (504 / 4, 156 / 4);
+        assert_eq!(32, width_in_gobs(width, 16));
+
+        let input: Vec<_> = (0..deswizzled_mip_size(width, height, 1, 16))
+            .map(|i| (i % 251) as u8)
+            .collect();
+        for block_height in [BlockHeight::Four, BlockHeight::Eight] {
+            let swizzled =
+                swizzle_block_linear(width, height, 1, &input, block_height, 16).unwrap();
+            assert_eq!(
+                swizzle_reference(width, height, &input, block_height, 16),
+                swizzled
+            );
+            assert_eq!(
+                input,
+                deswizzle_block_linear(width, height, 1, &swizzled, block_height, 16).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn swizzled_mip_size_block_height_32() {
+        // Each block is 32 GOBs or 256 bytes tall.
+        assert_eq!(
+            65536,
+            swizzled_mip_size(64, 256, 1, BlockHeight::ThirtyTwo, 4)
+        );
+        assert_eq!(
+            131072,
+            swizzled_mip_size(64, 257, 1, BlockHeight::ThirtyTwo, 4)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn swizzle_deswizzle_block_height_32() {
+        let seed = [13u8; 32];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+        for (width, height) in [(16, 16), (100, 300), (64, 512)] {
+            let input: Vec<_> = (0..deswizzled_mip_size(width, height, 1, 4))
+                .map(|_| rng.gen_range::<u8, _>(0..=255))
+                .collect();
+
+            let swizzled =
+                swizzle_block_linear(width, height, 1, &input, BlockHeight::ThirtyTwo, 4).unwrap();
+            assert_eq!(
+                swizzle_reference(width, height, &input, BlockHeight::ThirtyTwo, 4),
+                swizzled
+            );
+
+            let deswizzled =
+                deswizzle_block_linear(width, height, 1, &swizzled, BlockHeight::ThirtyTwo, 4)
+                    .unwrap();
+            assert_eq!(input, deswizzled);
+        }
+    }
+
     #[test]
     fn swizzle_empty() {
         let result = swizzle_block_linear(32, 32, 1, &[], BlockHeight::Sixteen, 4);
@@ -554,9 +2417,10 @@ mod tests {
     }
 
     #[test]
+    #[cfg_attr(miri, ignore)]
     fn swizzle_deswizzle_bc7_64_64() {
         // Test an even size.
-        let swizzled = include_bytes!("../block_linear/64_bc7_tiled.bin");
+        let swizzled = &read_fixture("64_bc7_tiled.bin");
         let deswizzled =
             deswizzle_block_linear(64 / 4, 64 / 4, 1, swizzled, BlockHeight::Two, 16).unwrap();
 
@@ -565,100 +2429,275 @@ mod tests {
         assert_eq!(swizzled, &new_swizzled[..]);
     }
 
-    #[test]
-    fn deswizzle_bc7_64_64() {
-        let input = include_bytes!("../block_linear/64_bc7_tiled.bin");
-        let expected = include_bytes!("../block_linear/64_bc7.bin");
-        let actual =
-            deswizzle_block_linear(64 / 4, 64 / 4, 1, input, BlockHeight::Two, 16).unwrap();
+    // Each fixture has an untiled file NAME.bin and a tiled file NAME_tiled.bin
+    // compressed as NAME.bin.zst and NAME_tiled.bin.zst.
+    // Tiled dumps may contain extra padding bytes after the tiled data.
+    fn check_fixture(fixture: &Fixture, linear: &[u8], tiled: &[u8]) -> Result<()> {
+        let (width, height, depth, block_height) = fixture.params();
+        let bpp = fixture.bytes_per_pixel;
+
+        let untiled = deswizzle_block_linear(width, height, depth, tiled, block_height, bpp)?;
+        assert!(
+            untiled == linear,
+            "{} does not match the untiled data",
+            fixture.name
+        );
 
-        assert_eq!(expected, &actual[..]);
+        let retiled = swizzle_block_linear(width, height, depth, linear, block_height, bpp)?;
+        let untiled = deswizzle_block_linear(width, height, depth, &retiled, block_height, bpp)?;
+        assert!(untiled == linear, "{} does not round trip", fixture.name);
+        Ok(())
     }
 
     #[test]
-    fn deswizzle_bc1_128_128() {
-        let input = include_bytes!("../block_linear/128_bc1_tiled.bin");
-        let expected = include_bytes!("../block_linear/128_bc1.bin");
-        let actual =
-            deswizzle_block_linear(128 / 4, 128 / 4, 1, input, BlockHeight::Four, 8).unwrap();
-
-        assert_eq!(expected, &actual[..]);
+    #[cfg_attr(miri, ignore)]
+    fn block_linear_fixtures() {
+        for fixture in load_fixtures() {
+            let linear = read_fixture(&std::format!("{}.bin", fixture.name));
+            let tiled = read_fixture(&std::format!("{}_tiled.bin", fixture.name));
+            check_fixture(&fixture, &linear, &tiled).unwrap();
+        }
     }
 
+    // Copy new or updated fixtures from a folder of dumps after checking each fixture.
+    // Add an entry to block_linear/fixtures.json for each new fixture first and then run
+    // TEGRA_SWIZZLE_DUMP_DIR=path/to/dumps cargo test regenerate_fixtures -- --ignored
     #[test]
-    fn deswizzle_bc3_128_128() {
-        let input = include_bytes!("../block_linear/128_bc3_tiled.bin");
-        let expected = include_bytes!("../block_linear/128_bc3.bin");
-        let actual =
-            deswizzle_block_linear(128 / 4, 128 / 4, 1, input, BlockHeight::Four, 16).unwrap();
-
-        assert_eq!(expected, &actual[..]);
+    #[ignore]
+    fn regenerate_fixtures() {
+        let dump_dir = std::path::PathBuf::from(std::env::var("TEGRA_SWIZZLE_DUMP_DIR").unwrap());
+        for fixture in load_fixtures() {
+            let linear_name = std::format!("{}.bin", fixture.name);
+            let tiled_name = std::format!("{}_tiled.bin", fixture.name);
+            if let (Ok(linear), Ok(tiled)) = (
+                std::fs::read(dump_dir.join(&linear_name)),
+                std::fs::read(dump_dir.join(&tiled_name)),
+            ) {
+                check_fixture(&fixture, &linear, &tiled).unwrap();
+                write_fixture(&linear_name, &linear);
+                write_fixture(&tiled_name, &tiled);
+                std::println!("updated {}", fixture.name);
+            }
+        }
     }
 
     #[test]
-    fn deswizzle_rgba_f32_128_128() {
-        let input = include_bytes!("../block_linear/128_rgbaf32_tiled.bin");
-        let expected = include_bytes!("../block_linear/128_rgbaf32.bin");
-        let actual = deswizzle_block_linear(128, 128, 1, input, BlockHeight::Sixteen, 16).unwrap();
-
-        assert_eq!(expected, &actual[..]);
+    #[cfg_attr(miri, ignore)]
+    fn deswizzle_into_reuse_destination() {
+        let input = &read_fixture("64_bc7_tiled.bin");
+        let expected = &read_fixture("64_bc7.bin");
+
+        // Extra bytes at the end should not be modified.
+        let mut destination = vec![1u8; 64 * 64 + 16];
+        deswizzle_block_linear_into(16, 16, 1, input, &mut destination, BlockHeight::Two, 16)
+            .unwrap();
+        assert_eq!(expected, &destination[..64 * 64]);
+        assert_eq!(&[1u8; 16], &destination[64 * 64..]);
+
+        let mut swizzled = vec![1u8; input.len()];
+        swizzle_block_linear_into(16, 16, 1, expected, &mut swizzled, BlockHeight::Two, 16)
+            .unwrap();
+        assert_eq!(input, &swizzled[..]);
     }
 
     #[test]
-    fn deswizzle_bc7_128_128() {
-        let input = include_bytes!("../block_linear/128_bc7_tiled.bin");
-        let expected = include_bytes!("../block_linear/128_bc7.bin");
-        let actual =
-            deswizzle_block_linear(128 / 4, 128 / 4, 1, input, BlockHeight::Four, 16).unwrap();
-
-        assert_eq!(expected, &actual[..]);
+    fn swizzle_into_destination_too_small() {
+        let result = swizzle_block_linear_into(
+            16,
+            16,
+            1,
+            &[0u8; 64 * 64],
+            &mut [0u8; 512],
+            BlockHeight::Two,
+            16,
+        );
+        assert_eq!(
+            result,
+            Err(SwizzleError::NotEnoughData {
+                actual_size: 512,
+                expected_size: 4096
+            })
+        );
     }
 
     #[test]
-    fn deswizzle_bc7_256_256() {
-        let input = include_bytes!("../block_linear/256_bc7_tiled.bin");
-        let expected = include_bytes!("../block_linear/256_bc7.bin");
-        let actual =
-            deswizzle_block_linear(256 / 4, 256 / 4, 1, input, BlockHeight::Eight, 16).unwrap();
+    fn plan_matches_swizzle_deswizzle() {
+        for (width, height, depth, block_height) in [
+            (64, 64, 1, BlockHeight::Two),
+            (100, 75, 1, BlockHeight::Eight),
+            (33, 33, 33, BlockHeight::One),
+        ] {
+            for access_order in [AccessOrder::Tiled, AccessOrder::Linear] {
+                let plan = BlockLinearPlan::new(width, height, depth, block_height, 4)
+                    .with_access_order(access_order);
+                let input: Vec<_> = (0..plan.deswizzled_size()).map(|i| i as u8).collect();
+
+                let expected =
+                    swizzle_block_linear(width, height, depth, &input, block_height, 4).unwrap();
+                let mut swizzled = vec![0xFFu8; plan.swizzled_size()];
+                plan.swizzle_into(&input, &mut swizzled).unwrap();
+                assert_eq!(expected, swizzled);
+
+                let mut deswizzled = vec![0u8; plan.deswizzled_size()];
+                plan.deswizzle_into(&swizzled, &mut deswizzled).unwrap();
+                assert_eq!(input, deswizzled);
+            }
+        }
+    }
 
-        assert_eq!(expected, &actual[..]);
+    #[test]
+    fn plan_tiled_access_order_sequential() {
+        let plan = BlockLinearPlan::new(100, 75, 1, BlockHeight::Eight, 4)
+            .with_access_order(AccessOrder::Tiled);
+        let offsets = core::cell::RefCell::new(Vec::new());
+        plan.visit_gobs(
+            &mut [],
+            |_, gob| offsets.borrow_mut().push(gob.gob_address),
+            |_, span| offsets.borrow_mut().push(span.swizzled_offset),
+        );
+        let offsets = offsets.into_inner();
+        assert_eq!(plan.complete_gobs.len() + plan.spans.len(), offsets.len());
+        assert!(offsets.windows(2).all(|w| w[0] < w[1]));
     }
 
     #[test]
-    fn deswizzle_bc7_320_320() {
-        let input = include_bytes!("../block_linear/320_bc7_tiled.bin");
-        let expected = include_bytes!("../block_linear/320_bc7.bin");
-        let actual =
-            deswizzle_block_linear(320 / 4, 320 / 4, 1, input, BlockHeight::Eight, 16).unwrap();
+    fn plan_exact_multiple_gobs() {
+        // 512x512 BC7 is 128x128 blocks or 32x16 GOBs.
+        // The last GOB column and row should use the complete GOB copies.
+        let plan = BlockLinearPlan::new(512 / 4, 512 / 4, 1, BlockHeight::Sixteen, 16);
+        assert_eq!(32 * 16, plan.complete_gobs.len());
+        assert!(plan.spans.is_empty());
+        assert!(is_fast_path(512 / 4, 512 / 4, 16));
+
+        // Only the GOBs in the last column are partially filled.
+        let plan = BlockLinearPlan::new(516 / 4, 512 / 4, 1, BlockHeight::Sixteen, 16);
+        assert_eq!(32 * 16, plan.complete_gobs.len());
+        assert_eq!(16 * 8, plan.spans.len());
+    }
 
-        assert_eq!(expected, &actual[..]);
+    #[test]
+    fn plan_not_enough_data() {
+        let plan = BlockLinearPlan::new(16, 16, 1, BlockHeight::Two, 4);
+        assert_eq!(
+            plan.deswizzle_into(&[0u8; 512], &mut [0u8; 1024]),
+            Err(SwizzleError::NotEnoughData {
+                actual_size: 512,
+                expected_size: 1024
+            })
+        );
     }
 
     #[test]
-    fn deswizzle_bc7_512_512() {
-        let input = include_bytes!("../block_linear/512_bc7_tiled.bin");
-        let expected = include_bytes!("../block_linear/512_bc7.bin");
-        let actual =
-            deswizzle_block_linear(512 / 4, 512 / 4, 1, input, BlockHeight::Sixteen, 16).unwrap();
+    #[cfg_attr(miri, ignore)]
+    fn deswizzle_regions_match_mipmap() {
+        let width = 200;
+        let height = 150;
+        let block_height = BlockHeight::Eight;
+
+        let seed = [13u8; 32];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let input: Vec<_> = (0..swizzled_mip_size(width, height, 1, block_height, 4))
+            .map(|_| rng.gen_range::<u8, _>(0..=255))
+            .collect();
+        let expected = deswizzle_block_linear(width, height, 1, &input, block_height, 4).unwrap();
+
+        // Include overlapping regions and regions that don't align to GOBs.
+        let regions = [
+            Region {
+                x: 0,
+                y: 0,
+                width: 200,
+                height: 150,
+            },
+            Region {
+                x: 3,
+                y: 5,
+                width: 17,
+                height: 9,
+            },
+            Region {
+                x: 10,
+                y: 7,
+                width: 30,
+                height: 100,
+            },
+            Region {
+                x: 199,
+                y: 149,
+                width: 1,
+                height: 1,
+            },
+            Region {
+                x: 50,
+                y: 50,
+                width: 0,
+                height: 10,
+            },
+        ];
+        let mut outputs: Vec<_> = regions
+            .iter()
+            .map(|r| vec![0u8; r.deswizzled_size(4).unwrap()])
+            .collect();
+        let mut pairs: Vec<_> = regions
+            .iter()
+            .copied()
+            .zip(outputs.iter_mut().map(|o| o.as_mut_slice()))
+            .collect();
+        deswizzle_block_linear_regions(width, height, &input, block_height, 4, &mut pairs).unwrap();
 
-        assert_eq!(expected, &actual[..]);
+        for (region, output) in regions.iter().zip(&outputs) {
+            let row_size = (region.width * 4) as usize;
+            for (i, row) in output.chunks_exact(row_size.max(1)).enumerate() {
+                let start = ((region.y as usize + i) * width as usize + region.x as usize) * 4;
+                assert_eq!(&expected[start..start + row_size], row);
+            }
+        }
     }
 
     #[test]
-    fn deswizzle_bc7_1024_1024() {
-        let input = include_bytes!("../block_linear/1024_bc7_tiled.bin");
-        let expected = include_bytes!("../block_linear/1024_bc7.bin");
-        let actual =
-            deswizzle_block_linear(1024 / 4, 1024 / 4, 1, input, BlockHeight::Sixteen, 16).unwrap();
-
-        assert_eq!(expected, &actual[..]);
+    fn deswizzle_regions_out_of_bounds() {
+        let region = Region {
+            x: 8,
+            y: 0,
+            width: 9,
+            height: 4,
+        };
+        let mut output = vec![0u8; region.deswizzled_size(4).unwrap()];
+        let result = deswizzle_block_linear_regions(
+            16,
+            16,
+            &[0u8; 4096],
+            BlockHeight::One,
+            4,
+            &mut [(region, &mut output)],
+        );
+        assert_eq!(
+            result,
+            Err(SwizzleError::InvalidRegion {
+                x: 8,
+                y: 0,
+                width: 9,
+                height: 4
+            })
+        );
     }
 
     #[test]
-    fn deswizzle_rgba_16_16_16() {
-        let input = include_bytes!("../block_linear/16_16_16_rgba_tiled.bin");
-        let expected = include_bytes!("../block_linear/16_16_16_rgba.bin");
-        let actual = deswizzle_block_linear(16, 16, 16, input, BlockHeight::One, 4).unwrap();
-        assert_eq!(expected, &actual[..]);
+    fn region_size_overflow() {
+        let region = Region {
+            x: 1,
+            y: 2,
+            width: u32::MAX,
+            height: u32::MAX,
+        };
+        assert_eq!(
+            Err(SwizzleError::InvalidRegion {
+                x: 1,
+                y: 2,
+                width: u32::MAX,
+                height: u32::MAX
+            }),
+            region.deswizzled_size(u32::MAX)
+        );
     }
 }
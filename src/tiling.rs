@@ -0,0 +1,693 @@
+//! Traits for tiling algorithms that share the same surface layout.
+//!
+//! A [TilingScheme] only needs to define how to tile and untile a single mipmap.
+//! Iterating over array layers and mipmaps is handled by the surface functions
+//! like [SurfaceParams::swizzle_with] and [SurfaceParams::deswizzle_with].
+//! This allows implementing other memory layouts without duplicating the surface logic.
+//!
+//! The surface methods like [SurfaceParams::swizzle] use [TegraBlockLinear].
+use core::{
+    cell::{Cell, RefCell},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+#[cfg(not(feature = "minimal"))]
+use alloc::vec;
+#[cfg(not(feature = "minimal"))]
+use core::convert::TryInto;
+
+use crate::{
+    blockdepth::{block_depth, mip_block_depth},
+    surface::{surface_layer_size, surface_mip_block_height, SurfaceParams},
+    swizzle::{swizzle_inner, swizzled_mip_size},
+    GOB_HEIGHT_IN_BYTES,
+};
+#[cfg(not(feature = "minimal"))]
+use crate::{swizzle::gob_offset, Result, SwizzleError, GOB_SIZE_IN_BYTES, GOB_WIDTH_IN_BYTES};
+
+/// The dimensions of a single mipmap of a surface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MipLevel {
+    /// The index of the mipmap starting from 0 for the base level.
+    pub mip: u32,
+    /// The width of the mipmap in blocks.
+    pub width: u32,
+    /// The height of the mipmap in blocks.
+    pub height: u32,
+    /// The depth of the mipmap in blocks.
+    pub depth: u32,
+}
+
+/// A memory tiling algorithm for the mipmaps of a surface.
+pub trait TilingScheme {
+    /// Calculates the size in bytes for the tiled data for `level`.
+    fn tiled_mip_size(&self, surface: &SurfaceParams, level: &MipLevel) -> usize;
+
+    /// Tiles the bytes from `source` to `destination`.
+    ///
+    /// `source` will have at least as many bytes as the untiled size of `level`,
+    /// and `destination` will have at least as many bytes as the result of [TilingScheme::tiled_mip_size].
+    fn tile_mip(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    );
+
+    /// Untiles the bytes from `source` to `destination`.
+    ///
+    /// `source` will have at least as many bytes as the result of [TilingScheme::tiled_mip_size],
+    /// and `destination` will have at least as many bytes as the untiled size of `level`.
+    fn untile_mip(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    );
+
+    /// Calculates the aligned size in bytes for a tiled array layer with size `layer_size`.
+    /// This is only applied for surfaces with more than one array layer.
+    ///
+    /// The default implementation does not add any alignment.
+    fn align_layer_size(&self, surface: &SurfaceParams, layer_size: usize) -> usize {
+        let _ = surface;
+        layer_size
+    }
+}
+
+/// The block linear memory layout for the Tegra X1.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TegraBlockLinear;
+
+impl TegraBlockLinear {
+    fn mip_block_depth(surface: &SurfaceParams, level: &MipLevel) -> u32 {
+        // TODO: Don't assume block_depth is 1?
+        mip_block_depth(level.depth, block_depth(surface.depth))
+    }
+}
+
+impl TilingScheme for TegraBlockLinear {
+    fn tiled_mip_size(&self, surface: &SurfaceParams, level: &MipLevel) -> usize {
+        swizzled_mip_size(
+            level.width,
+            level.height,
+            level.depth,
+            surface_mip_block_height(surface, level.mip, level.height),
+            surface.bytes_per_pixel,
+        )
+    }
+
+    fn tile_mip(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    ) {
+        swizzle_inner::<false>(
+            level.width,
+            level.height,
+            level.depth,
+            source,
+            destination,
+            surface_mip_block_height(surface, level.mip, level.height),
+            Self::mip_block_depth(surface, level),
+            surface.bytes_per_pixel,
+        );
+    }
+
+    fn untile_mip(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    ) {
+        swizzle_inner::<true>(
+            level.width,
+            level.height,
+            level.depth,
+            source,
+            destination,
+            surface_mip_block_height(surface, level.mip, level.height),
+            Self::mip_block_depth(surface, level),
+            surface.bytes_per_pixel,
+        );
+    }
+
+    fn align_layer_size(&self, surface: &SurfaceParams, layer_size: usize) -> usize {
+        surface_layer_size(surface, layer_size)
+    }
+}
+
+// Block linear tiling one row of blocks at a time for 2D mipmaps.
+// `next_row` is called with the untiled bytes finished so far for the mipmap
+// before each row of blocks and once after the last row.
+// Tiling stops early if `next_row` returns false.
+fn swizzle_block_rows<const DESWIZZLE: bool>(
+    surface: &SurfaceParams,
+    level: &MipLevel,
+    source: &[u8],
+    destination: &mut [u8],
+    mut next_row: impl FnMut(usize) -> bool,
+) {
+    let block_height = surface_mip_block_height(surface, level.mip, level.height);
+    let row_size = level.width as usize * surface.bytes_per_pixel as usize;
+
+    // 3D mipmaps interleave slices within each block, so only check once.
+    if level.depth != 1 {
+        if next_row(0) {
+            swizzle_inner::<DESWIZZLE>(
+                level.width,
+                level.height,
+                level.depth,
+                source,
+                destination,
+                block_height,
+                TegraBlockLinear::mip_block_depth(surface, level),
+                surface.bytes_per_pixel,
+            );
+            next_row(row_size * level.height as usize * level.depth as usize);
+        }
+        return;
+    }
+
+    // Each row of blocks is tiled the same as a mipmap with the height of a single block.
+    let rows = block_height as u32 * GOB_HEIGHT_IN_BYTES;
+    let tiled_size = swizzled_mip_size(level.width, rows, 1, block_height, surface.bytes_per_pixel);
+    let linear_size = row_size * rows as usize;
+
+    for (i, y) in (0..level.height).step_by(rows as usize).enumerate() {
+        if !next_row(row_size * y as usize) {
+            return;
+        }
+
+        let (source_offset, destination_offset) = if DESWIZZLE {
+            (i * tiled_size, i * linear_size)
+        } else {
+            (i * linear_size, i * tiled_size)
+        };
+        swizzle_inner::<DESWIZZLE>(
+            level.width,
+            rows.min(level.height - y),
+            1,
+            &source[source_offset..],
+            &mut destination[destination_offset..],
+            block_height,
+            1,
+            surface.bytes_per_pixel,
+        );
+    }
+    next_row(row_size * level.height as usize);
+}
+
+// Block linear tiling that stops early once the flag is set.
+// The surface functions check the flag again to report the cancellation.
+pub(crate) struct CancellableBlockLinear<'a>(pub &'a AtomicBool);
+
+impl CancellableBlockLinear<'_> {
+    fn swizzle_rows<const DESWIZZLE: bool>(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    ) {
+        swizzle_block_rows::<DESWIZZLE>(surface, level, source, destination, |_| {
+            !self.0.load(Ordering::Relaxed)
+        });
+    }
+}
+
+impl TilingScheme for CancellableBlockLinear<'_> {
+    fn tiled_mip_size(&self, surface: &SurfaceParams, level: &MipLevel) -> usize {
+        TegraBlockLinear.tiled_mip_size(surface, level)
+    }
+
+    fn tile_mip(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    ) {
+        self.swizzle_rows::<false>(surface, level, source, destination);
+    }
+
+    fn untile_mip(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    ) {
+        self.swizzle_rows::<true>(surface, level, source, destination);
+    }
+
+    fn align_layer_size(&self, surface: &SurfaceParams, layer_size: usize) -> usize {
+        TegraBlockLinear.align_layer_size(surface, layer_size)
+    }
+}
+
+// Block linear tiling that reports the untiled bytes finished after each row of blocks.
+// Mipmaps in a packed mip tail are tiled together, so the count is capped at the total.
+pub(crate) struct ProgressBlockLinear<F> {
+    progress: RefCell<F>,
+    done: Cell<usize>,
+    total: usize,
+}
+
+impl<F: FnMut(usize, usize)> ProgressBlockLinear<F> {
+    pub(crate) fn new(progress: F, total: usize) -> Self {
+        Self {
+            progress: RefCell::new(progress),
+            done: Cell::new(0),
+            total,
+        }
+    }
+
+    // Reports the total once after tiling finishes successfully.
+    pub(crate) fn finish(&self) {
+        if self.done.get() < self.total || self.total == 0 {
+            self.done.set(self.total);
+            (self.progress.borrow_mut())(self.total, self.total);
+        }
+    }
+
+    fn swizzle_rows<const DESWIZZLE: bool>(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    ) {
+        let start = self.done.get();
+        swizzle_block_rows::<DESWIZZLE>(surface, level, source, destination, |finished| {
+            let done = start.saturating_add(finished).min(self.total);
+            if done > self.done.get() {
+                self.done.set(done);
+                (self.progress.borrow_mut())(done, self.total);
+            }
+            true
+        });
+    }
+}
+
+impl<F: FnMut(usize, usize)> TilingScheme for ProgressBlockLinear<F> {
+    fn tiled_mip_size(&self, surface: &SurfaceParams, level: &MipLevel) -> usize {
+        TegraBlockLinear.tiled_mip_size(surface, level)
+    }
+
+    fn tile_mip(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    ) {
+        self.swizzle_rows::<false>(surface, level, source, destination);
+    }
+
+    fn untile_mip(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    ) {
+        self.swizzle_rows::<true>(surface, level, source, destination);
+    }
+
+    fn align_layer_size(&self, surface: &SurfaceParams, layer_size: usize) -> usize {
+        TegraBlockLinear.align_layer_size(surface, layer_size)
+    }
+}
+
+/// Block linear tiling with a custom order for the 512 bytes within each GOB.
+///
+/// This is intended for experimenting with formats that may use a different
+/// byte or sector order within GOBs than the Tegra X1.
+/// The GOB addresses and sizes are the same as [TegraBlockLinear].
+/// The default uses the standard Tegra X1 GOB layout and matches [TegraBlockLinear].
+///
+/// Tiling and untiling are slower than [TegraBlockLinear],
+/// and untiling allocates a copy of the tiled data for each mipmap.
+/// # Examples
+/**
+```rust
+use tegra_swizzle::surface::SurfaceParams;
+use tegra_swizzle::tiling::RemappedBlockLinear;
+
+// Swap the two 256 byte halves of each GOB.
+let mut offsets = RemappedBlockLinear::default().gob_offsets();
+for offset in &mut offsets {
+    *offset ^= 256;
+}
+let scheme = RemappedBlockLinear::new(&offsets)?;
+
+let params = SurfaceParams {
+    width: 256,
+    height: 256,
+    bytes_per_pixel: 4,
+    ..Default::default()
+};
+# let input = vec![0u8; params.deswizzled_size()];
+let tiled = params.swizzle_with(&scheme, &input)?;
+let untiled = params.deswizzle_with(&scheme, &tiled)?;
+# assert_eq!(input, untiled);
+# Ok::<(), tegra_swizzle::SwizzleError>(())
+```
+ */
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemappedBlockLinear {
+    // The custom offset for each standard offset within a GOB.
+    remap: [u16; GOB_SIZE_IN_BYTES as usize],
+}
+
+#[cfg(not(feature = "minimal"))]
+impl RemappedBlockLinear {
+    /// Creates a scheme that stores the byte at `x`, `y` within each 64x8 byte GOB
+    /// at offset `gob_offsets[y * 64 + x]` within the tiled GOB.
+    ///
+    /// Returns [SwizzleError::InvalidGobOffsets] if `gob_offsets` is not a permutation
+    /// of the offsets from 0 to 511.
+    pub fn new(gob_offsets: &[u16; GOB_SIZE_IN_BYTES as usize]) -> Result<Self> {
+        let mut used = [false; GOB_SIZE_IN_BYTES as usize];
+        let mut remap = [0u16; GOB_SIZE_IN_BYTES as usize];
+        for (i, &offset) in gob_offsets.iter().enumerate() {
+            match used.get_mut(offset as usize) {
+                Some(used) if !*used => *used = true,
+                _ => return Err(SwizzleError::InvalidGobOffsets { index: i, offset }),
+            }
+
+            let (x, y) = (i as u32 % GOB_WIDTH_IN_BYTES, i as u32 / GOB_WIDTH_IN_BYTES);
+            remap[gob_offset(x, y) as usize] = offset;
+        }
+        Ok(Self { remap })
+    }
+
+    /// The offset within the tiled GOB for each byte of the 64x8 byte GOB in row-major order.
+    pub fn gob_offsets(&self) -> [u16; GOB_SIZE_IN_BYTES as usize] {
+        let mut offsets = [0u16; GOB_SIZE_IN_BYTES as usize];
+        for (i, offset) in offsets.iter_mut().enumerate() {
+            let (x, y) = (i as u32 % GOB_WIDTH_IN_BYTES, i as u32 / GOB_WIDTH_IN_BYTES);
+            *offset = self.remap[gob_offset(x, y) as usize];
+        }
+        offsets
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+impl Default for RemappedBlockLinear {
+    fn default() -> Self {
+        let mut remap = [0u16; GOB_SIZE_IN_BYTES as usize];
+        for (i, offset) in remap.iter_mut().enumerate() {
+            *offset = i as u16;
+        }
+        Self { remap }
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+impl TilingScheme for RemappedBlockLinear {
+    fn tiled_mip_size(&self, surface: &SurfaceParams, level: &MipLevel) -> usize {
+        TegraBlockLinear.tiled_mip_size(surface, level)
+    }
+
+    fn tile_mip(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    ) {
+        // Move the bytes of each GOB from the standard offsets to the custom offsets.
+        let size = self.tiled_mip_size(surface, level);
+        TegraBlockLinear.tile_mip(surface, level, source, destination);
+        for gob in destination[..size].chunks_exact_mut(GOB_SIZE_IN_BYTES as usize) {
+            let standard: [u8; GOB_SIZE_IN_BYTES as usize] = gob.try_into().unwrap();
+            for (&value, &offset) in standard.iter().zip(&self.remap) {
+                gob[offset as usize] = value;
+            }
+        }
+    }
+
+    fn untile_mip(
+        &self,
+        surface: &SurfaceParams,
+        level: &MipLevel,
+        source: &[u8],
+        destination: &mut [u8],
+    ) {
+        // Move the bytes of each GOB back to the standard offsets before untiling.
+        let size = self.tiled_mip_size(surface, level);
+        let mut standard = vec![0u8; size];
+        for (standard, gob) in standard
+            .chunks_exact_mut(GOB_SIZE_IN_BYTES as usize)
+            .zip(source[..size].chunks_exact(GOB_SIZE_IN_BYTES as usize))
+        {
+            for (value, &offset) in standard.iter_mut().zip(&self.remap) {
+                *value = gob[offset as usize];
+            }
+        }
+        TegraBlockLinear.untile_mip(surface, level, &standard, destination);
+    }
+
+    fn align_layer_size(&self, surface: &SurfaceParams, layer_size: usize) -> usize {
+        TegraBlockLinear.align_layer_size(surface, layer_size)
+    }
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::{surface::BlockDim, SwizzleError};
+    use alloc::vec::Vec;
+
+    // Store tiled mipmaps in reverse byte order to test the surface logic.
+    struct Reversed;
+
+    impl TilingScheme for Reversed {
+        fn tiled_mip_size(&self, surface: &SurfaceParams, level: &MipLevel) -> usize {
+            (level.width * level.height * level.depth * surface.bytes_per_pixel) as usize
+        }
+
+        fn tile_mip(
+            &self,
+            surface: &SurfaceParams,
+            level: &MipLevel,
+            source: &[u8],
+            destination: &mut [u8],
+        ) {
+            let size = self.tiled_mip_size(surface, level);
+            for (d, s) in destination[..size]
+                .iter_mut()
+                .zip(source[..size].iter().rev())
+            {
+                *d = *s;
+            }
+        }
+
+        fn untile_mip(
+            &self,
+            surface: &SurfaceParams,
+            level: &MipLevel,
+            source: &[u8],
+            destination: &mut [u8],
+        ) {
+            self.tile_mip(surface, level, source, destination);
+        }
+
+        fn align_layer_size(&self, _surface: &SurfaceParams, layer_size: usize) -> usize {
+            layer_size.next_multiple_of(256)
+        }
+    }
+
+    #[test]
+    fn block_linear_matches_surface_size() {
+        let params = SurfaceParams {
+            width: 288,
+            height: 288,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 9,
+            layer_count: 6,
+            ..Default::default()
+        };
+        assert_eq!(
+            params.swizzled_size(),
+            params.swizzled_size_with(&TegraBlockLinear)
+        );
+    }
+
+    #[test]
+    fn custom_scheme_layers_mipmaps() {
+        let params = SurfaceParams {
+            width: 4,
+            height: 4,
+            bytes_per_pixel: 1,
+            mipmap_count: 3,
+            layer_count: 2,
+            ..Default::default()
+        };
+        // Each layer has 16 + 4 + 1 bytes aligned to 256 bytes.
+        assert_eq!(512, params.swizzled_size_with(&Reversed));
+
+        let input: Vec<u8> = (0..params.deswizzled_size() as u8).collect();
+        let tiled = params.swizzle_with(&Reversed, &input).unwrap();
+        assert_eq!(&[15, 14, 13], &tiled[..3]);
+        assert_eq!(&[36, 35], &tiled[256..258]);
+
+        let untiled = params.deswizzle_with(&Reversed, &tiled).unwrap();
+        assert_eq!(input, untiled);
+    }
+
+    // Aligning to a smaller size than the layer is a bug in the scheme.
+    struct Overlapping;
+
+    impl TilingScheme for Overlapping {
+        fn tiled_mip_size(&self, surface: &SurfaceParams, level: &MipLevel) -> usize {
+            Reversed.tiled_mip_size(surface, level)
+        }
+
+        fn tile_mip(
+            &self,
+            surface: &SurfaceParams,
+            level: &MipLevel,
+            source: &[u8],
+            destination: &mut [u8],
+        ) {
+            Reversed.tile_mip(surface, level, source, destination);
+        }
+
+        fn untile_mip(
+            &self,
+            surface: &SurfaceParams,
+            level: &MipLevel,
+            source: &[u8],
+            destination: &mut [u8],
+        ) {
+            Reversed.untile_mip(surface, level, source, destination);
+        }
+
+        fn align_layer_size(&self, _surface: &SurfaceParams, layer_size: usize) -> usize {
+            layer_size / 2
+        }
+    }
+
+    #[test]
+    #[cfg_attr(
+        feature = "panic-on-internal-error",
+        should_panic(expected = "mipmap outside the surface")
+    )]
+    fn custom_scheme_overlapping_layers() {
+        let params = SurfaceParams {
+            width: 4,
+            height: 4,
+            bytes_per_pixel: 1,
+            layer_count: 2,
+            ..Default::default()
+        };
+        let input = [0u8; 32];
+        assert_eq!(
+            Err(SwizzleError::Internal {
+                check: "mipmap outside the surface"
+            }),
+            params.swizzle_with(&Overlapping, &input)
+        );
+    }
+
+    fn remap_params() -> [SurfaceParams<'static>; 3] {
+        [
+            SurfaceParams {
+                width: 300,
+                height: 200,
+                bytes_per_pixel: 4,
+                mipmap_count: 5,
+                layer_count: 2,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 33,
+                height: 17,
+                depth: 16,
+                bytes_per_pixel: 4,
+                mipmap_count: 3,
+                ..Default::default()
+            },
+            SurfaceParams {
+                width: 256,
+                height: 256,
+                block_dim: BlockDim::block_4x4(),
+                bytes_per_pixel: 16,
+                mipmap_count: 9,
+                packed_mip_tail: true,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn remapped_default_matches_block_linear() {
+        let scheme = RemappedBlockLinear::default();
+        for params in remap_params() {
+            let input: Vec<_> = (0..params.deswizzled_size())
+                .map(|i| (i % 251) as u8)
+                .collect();
+            let tiled = params.swizzle(&input).unwrap();
+            assert_eq!(tiled, params.swizzle_with(&scheme, &input).unwrap());
+            assert_eq!(input, params.deswizzle_with(&scheme, &tiled).unwrap());
+        }
+    }
+
+    #[test]
+    fn remapped_custom_offsets() {
+        // Reverse the bytes within each GOB.
+        let mut offsets = RemappedBlockLinear::default().gob_offsets();
+        for offset in &mut offsets {
+            *offset = 511 - *offset;
+        }
+        let scheme = RemappedBlockLinear::new(&offsets).unwrap();
+        assert_eq!(offsets, scheme.gob_offsets());
+
+        for params in remap_params() {
+            let input: Vec<_> = (0..params.deswizzled_size())
+                .map(|i| (i % 251) as u8)
+                .collect();
+            let expected: Vec<_> = params
+                .swizzle(&input)
+                .unwrap()
+                .chunks_exact(512)
+                .flat_map(|gob| gob.iter().rev().copied())
+                .collect();
+            let tiled = params.swizzle_with(&scheme, &input).unwrap();
+            assert_eq!(expected, tiled);
+            assert_eq!(input, params.deswizzle_with(&scheme, &tiled).unwrap());
+        }
+    }
+
+    #[test]
+    fn remapped_offsets_not_permutation() {
+        let mut offsets = RemappedBlockLinear::default().gob_offsets();
+        offsets[5] = offsets[3];
+        assert_eq!(
+            Err(SwizzleError::InvalidGobOffsets {
+                index: 5,
+                offset: offsets[3]
+            }),
+            RemappedBlockLinear::new(&offsets)
+        );
+
+        offsets[5] = 512;
+        assert_eq!(
+            Err(SwizzleError::InvalidGobOffsets {
+                index: 5,
+                offset: 512
+            }),
+            RemappedBlockLinear::new(&offsets)
+        );
+    }
+}
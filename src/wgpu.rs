@@ -0,0 +1,267 @@
+//! Untiling surfaces into the buffer layout used for texture uploads in wgpu.
+//!
+//! wgpu requires each row of a buffer to texture copy to start at a multiple of
+//! [wgpu_types::COPY_BYTES_PER_ROW_ALIGNMENT] bytes.
+//! The untiled data from [SurfaceParams::deswizzle] is tightly packed,
+//! so the rows for each mipmap and array layer are padded to the required alignment.
+use alloc::vec::Vec;
+
+use wgpu_types::{Extent3d, TexelCopyBufferLayout, COPY_BYTES_PER_ROW_ALIGNMENT};
+
+use crate::{
+    surface::{mip_level, SurfaceParams},
+    Result,
+};
+
+/// The location of a single mipmap and array layer in the data from [deswizzle_for_upload].
+#[derive(Debug, Clone, Copy)]
+pub struct TextureCopy {
+    /// The array layer of the texture.
+    /// This is always `0` for 3D textures.
+    pub layer: u32,
+    /// The mipmap level of the texture.
+    pub mip: u32,
+    /// The location of the mipmap in the buffer.
+    pub layout: TexelCopyBufferLayout,
+    /// The size in pixels of the region to copy.
+    /// Compressed formats are rounded up to whole blocks.
+    pub size: Extent3d,
+}
+
+/// Untiles all the array layers and mipmaps in `source` into the layout expected by wgpu for texture uploads.
+/// Each copy can be passed directly to `Queue::write_texture` or `CommandEncoder::copy_buffer_to_texture`
+/// with [TextureCopy::layer] as the z origin and [TextureCopy::mip] as the mip level.
+///
+/// Each row uses a multiple of [wgpu_types::COPY_BYTES_PER_ROW_ALIGNMENT] bytes.
+/// Each copy starts at an offset that is also a multiple of this alignment.
+/// The copies are ordered by array layer and then by mipmap.
+///
+/// Returns the same errors as [SurfaceParams::deswizzle].
+///
+/// # Examples
+/**
+```rust
+use tegra_swizzle::{surface::{BlockDim, SurfaceParams}, wgpu::deswizzle_for_upload};
+
+let params = SurfaceParams {
+    width: 100,
+    height: 100,
+    block_dim: BlockDim::uncompressed(),
+    bytes_per_pixel: 4,
+    mipmap_count: 1,
+    ..Default::default()
+};
+# let input_file = vec![0u8; params.swizzled_size()];
+let (data, copies) = deswizzle_for_upload(&input_file, &params).unwrap();
+assert_eq!(Some(512), copies[0].layout.bytes_per_row);
+assert_eq!(Some(100), copies[0].layout.rows_per_image);
+assert_eq!(512 * 100, data.len());
+```
+ */
+pub fn deswizzle_for_upload(
+    source: &[u8],
+    params: &SurfaceParams,
+) -> Result<(Vec<u8>, Vec<TextureCopy>)> {
+    let untiled = params.deswizzle(source)?;
+
+    let alignment = COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+
+    let mut data = Vec::new();
+    let mut copies = Vec::new();
+    let mut src_offset = 0;
+    for layer in 0..params.layer_count {
+        for mip in 0..params.mipmap_count {
+            let level = mip_level(params, mip);
+
+            // Compressed formats use rows of blocks rather than rows of pixels.
+            let row_size = level.width as usize * params.bytes_per_pixel as usize;
+            let bytes_per_row = row_size.next_multiple_of(alignment);
+            let rows = level.height as usize * level.depth as usize;
+
+            let offset = data.len();
+            data.resize(offset + bytes_per_row * rows, 0);
+            for (dst, src) in data[offset..]
+                .chunks_exact_mut(bytes_per_row)
+                .zip(untiled[src_offset..src_offset + row_size * rows].chunks_exact(row_size))
+            {
+                dst[..row_size].copy_from_slice(src);
+            }
+            src_offset += row_size * rows;
+
+            copies.push(TextureCopy {
+                layer,
+                mip,
+                layout: TexelCopyBufferLayout {
+                    offset: offset as u64,
+                    bytes_per_row: Some(bytes_per_row as u32),
+                    rows_per_image: Some(level.height),
+                },
+                size: Extent3d {
+                    width: level.width * params.block_dim.width.get(),
+                    height: level.height * params.block_dim.height.get(),
+                    depth_or_array_layers: level.depth * params.block_dim.depth.get(),
+                },
+            });
+        }
+    }
+
+    Ok((data, copies))
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::{surface::BlockDim, SwizzleError};
+    use alloc::vec;
+
+    fn tiled_data(params: &SurfaceParams) -> Vec<u8> {
+        (0..params.swizzled_size()).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn upload_rgba_mipmaps() {
+        let params = SurfaceParams {
+            width: 100,
+            height: 60,
+            block_dim: BlockDim::uncompressed(),
+            bytes_per_pixel: 4,
+            mipmap_count: 3,
+            ..Default::default()
+        };
+        let source = tiled_data(&params);
+        let (data, copies) = deswizzle_for_upload(&source, &params).unwrap();
+
+        assert_eq!(
+            vec![
+                (
+                    0,
+                    512,
+                    60,
+                    Extent3d {
+                        width: 100,
+                        height: 60,
+                        depth_or_array_layers: 1
+                    }
+                ),
+                (
+                    30720,
+                    256,
+                    30,
+                    Extent3d {
+                        width: 50,
+                        height: 30,
+                        depth_or_array_layers: 1
+                    }
+                ),
+                (
+                    38400,
+                    256,
+                    15,
+                    Extent3d {
+                        width: 25,
+                        height: 15,
+                        depth_or_array_layers: 1
+                    }
+                ),
+            ],
+            copies
+                .iter()
+                .map(|c| (
+                    c.layout.offset,
+                    c.layout.bytes_per_row.unwrap(),
+                    c.layout.rows_per_image.unwrap(),
+                    c.size
+                ))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(38400 + 256 * 15, data.len());
+
+        // The rows should match the tightly packed data.
+        let untiled = params.deswizzle(&source).unwrap();
+        assert_eq!(&untiled[..400], &data[..400]);
+        assert_eq!(&untiled[400..800], &data[512..912]);
+        assert_eq!(&untiled[24000..24200], &data[30720..30920]);
+        assert_eq!(&untiled[24000 + 6000..24000 + 6100], &data[38400..38500]);
+    }
+
+    #[test]
+    fn upload_bc7_array_layers() {
+        let params = SurfaceParams {
+            width: 64,
+            height: 64,
+            block_dim: BlockDim::block_4x4(),
+            bytes_per_pixel: 16,
+            mipmap_count: 2,
+            layer_count: 6,
+            ..Default::default()
+        };
+        let source = tiled_data(&params);
+        let (data, copies) = deswizzle_for_upload(&source, &params).unwrap();
+
+        assert_eq!(12, copies.len());
+        for (i, copy) in copies.iter().enumerate() {
+            assert_eq!(i as u32 / 2, copy.layer);
+            assert_eq!(i as u32 % 2, copy.mip);
+            assert_eq!(0, copy.layout.offset % 256);
+            assert_eq!(Some(256), copy.layout.bytes_per_row);
+        }
+        assert_eq!(
+            Extent3d {
+                width: 32,
+                height: 32,
+                depth_or_array_layers: 1
+            },
+            copies[1].size
+        );
+        assert_eq!(Some(8), copies[1].layout.rows_per_image);
+
+        // The second layer starts after the 16 and 8 block rows of the first layer.
+        let untiled = params.deswizzle(&source).unwrap();
+        assert_eq!(256 * 24, copies[2].layout.offset);
+        assert_eq!(&untiled[5120..5376], &data[6144..6400]);
+    }
+
+    #[test]
+    fn upload_3d() {
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            depth: 16,
+            block_dim: BlockDim::uncompressed(),
+            bytes_per_pixel: 4,
+            mipmap_count: 1,
+            ..Default::default()
+        };
+        let source = tiled_data(&params);
+        let (data, copies) = deswizzle_for_upload(&source, &params).unwrap();
+
+        assert_eq!(
+            Extent3d {
+                width: 16,
+                height: 16,
+                depth_or_array_layers: 16
+            },
+            copies[0].size
+        );
+        assert_eq!(256 * 16 * 16, data.len());
+
+        let untiled = params.deswizzle(&source).unwrap();
+        assert_eq!(&untiled[64 * 17..64 * 18], &data[256 * 17..256 * 17 + 64]);
+    }
+
+    #[test]
+    fn upload_not_enough_data() {
+        let params = SurfaceParams {
+            width: 16,
+            height: 16,
+            block_dim: BlockDim::uncompressed(),
+            bytes_per_pixel: 4,
+            mipmap_count: 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            deswizzle_for_upload(&[0u8; 4], &params),
+            Err(SwizzleError::NotEnoughMipData { .. })
+        ));
+    }
+}
@@ -0,0 +1,48 @@
+// Untiles an in-memory BC7 surface using the WebAssembly module from wasm_example.
+// cargo build -p wasm_example --release --target wasm32-unknown-unknown
+// node wasm_example/run.mjs
+import { readFile } from "node:fs/promises";
+import assert from "node:assert/strict";
+
+const wasmPath = new URL(
+  "../target/wasm32-unknown-unknown/release/wasm_example.wasm",
+  import.meta.url
+);
+const { instance } = await WebAssembly.instantiate(await readFile(wasmPath));
+const wasm = instance.exports;
+
+// A 256x256 BC7 texture with a full mipmap chain.
+const [width, height, mipmapCount] = [256, 256, 9];
+const tiledSize = wasm.bc7_swizzled_size(width, height, mipmapCount);
+const untiledSize = wasm.bc7_deswizzled_size(width, height, mipmapCount);
+
+const linearPtr = wasm.alloc(untiledSize);
+const tiledPtr = wasm.alloc(tiledSize);
+const untiledPtr = wasm.alloc(untiledSize);
+
+// Allocating can grow the memory, so only create views after allocating.
+const bytes = (ptr, len) => new Uint8Array(wasm.memory.buffer, ptr, len);
+const linear = bytes(linearPtr, untiledSize);
+for (let i = 0; i < linear.length; i++) {
+  linear[i] = i % 251;
+}
+
+// Tile the data first to have an in-memory surface to untile.
+assert.ok(
+  wasm.swizzle_bc7(width, height, mipmapCount, linearPtr, untiledSize, tiledPtr, tiledSize)
+);
+assert.ok(
+  wasm.deswizzle_bc7(width, height, mipmapCount, tiledPtr, tiledSize, untiledPtr, untiledSize)
+);
+assert.deepEqual(bytes(untiledPtr, untiledSize), bytes(linearPtr, untiledSize));
+
+// Invalid buffer sizes return false instead of trapping.
+assert.equal(
+  wasm.deswizzle_bc7(width, height, mipmapCount, tiledPtr, 16, untiledPtr, untiledSize),
+  0
+);
+
+wasm.dealloc(linearPtr, untiledSize);
+wasm.dealloc(tiledPtr, tiledSize);
+wasm.dealloc(untiledPtr, untiledSize);
+console.log(`Untiled ${tiledSize} bytes of BC7 data to ${untiledSize} bytes`);
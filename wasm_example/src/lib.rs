@@ -0,0 +1,141 @@
+//! Tiling and untiling BC7 surfaces from JavaScript using WebAssembly.
+//!
+//! The exported functions only use numbers and pointers into the WebAssembly memory,
+//! so the module can be loaded without any generated bindings.
+//! JavaScript allocates buffers with [alloc], writes the input bytes,
+//! and reads the output after calling [deswizzle_bc7] or [swizzle_bc7].
+//!
+//! Build the module and run the example with
+//! `cargo build -p wasm_example --release --target wasm32-unknown-unknown`
+//! and `node wasm_example/run.mjs`.
+use tegra_swizzle::surface::{BlockDim, SurfaceParams};
+
+fn bc7_params(width: u32, height: u32, mipmap_count: u32) -> SurfaceParams<'static> {
+    SurfaceParams {
+        width,
+        height,
+        block_dim: BlockDim::block_4x4(),
+        bytes_per_pixel: 16,
+        mipmap_count,
+        ..Default::default()
+    }
+}
+
+/// Allocates a zeroed buffer with `len` bytes that lives until freed with [dealloc].
+#[no_mangle]
+pub extern "C" fn alloc(len: usize) -> *mut u8 {
+    let mut buffer = vec![0u8; len].into_boxed_slice();
+    let ptr = buffer.as_mut_ptr();
+    std::mem::forget(buffer);
+    ptr
+}
+
+/// Frees a buffer created with [alloc].
+///
+/// # Safety
+/// `ptr` and `len` must be the values from a previous call to [alloc].
+#[no_mangle]
+pub unsafe extern "C" fn dealloc(ptr: *mut u8, len: usize) {
+    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)));
+}
+
+/// The size in bytes of the tiled data for a BC7 surface with dimensions in pixels.
+#[no_mangle]
+pub extern "C" fn bc7_swizzled_size(width: u32, height: u32, mipmap_count: u32) -> usize {
+    bc7_params(width, height, mipmap_count).swizzled_size()
+}
+
+/// The size in bytes of the untiled data for a BC7 surface with dimensions in pixels.
+#[no_mangle]
+pub extern "C" fn bc7_deswizzled_size(width: u32, height: u32, mipmap_count: u32) -> usize {
+    bc7_params(width, height, mipmap_count).deswizzled_size()
+}
+
+/// Untiles the BC7 surface in `source` into `destination`.
+/// Returns `false` if the buffers are too small or the surface is invalid.
+///
+/// # Safety
+/// `source` and `destination` must be valid for `source_len` and `destination_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn deswizzle_bc7(
+    width: u32,
+    height: u32,
+    mipmap_count: u32,
+    source: *const u8,
+    source_len: usize,
+    destination: *mut u8,
+    destination_len: usize,
+) -> bool {
+    let source = std::slice::from_raw_parts(source, source_len);
+    let destination = std::slice::from_raw_parts_mut(destination, destination_len);
+    bc7_params(width, height, mipmap_count)
+        .deswizzle_into(source, destination)
+        .is_ok()
+}
+
+/// Tiles the BC7 surface in `source` into `destination`.
+/// Returns `false` if the buffers are too small or the surface is invalid.
+///
+/// # Safety
+/// `source` and `destination` must be valid for `source_len` and `destination_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn swizzle_bc7(
+    width: u32,
+    height: u32,
+    mipmap_count: u32,
+    source: *const u8,
+    source_len: usize,
+    destination: *mut u8,
+    destination_len: usize,
+) -> bool {
+    let source = std::slice::from_raw_parts(source, source_len);
+    let destination = std::slice::from_raw_parts_mut(destination, destination_len);
+    bc7_params(width, height, mipmap_count)
+        .swizzle_into(source, destination)
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bc7_round_trip() {
+        let (width, height, mipmap_count) = (300, 200, 9);
+        let params = bc7_params(width, height, mipmap_count);
+        let linear: Vec<_> = (0..params.deswizzled_size()).map(|i| i as u8).collect();
+        let mut tiled = vec![0u8; bc7_swizzled_size(width, height, mipmap_count)];
+        let mut untiled = vec![0u8; bc7_deswizzled_size(width, height, mipmap_count)];
+
+        unsafe {
+            assert!(swizzle_bc7(
+                width,
+                height,
+                mipmap_count,
+                linear.as_ptr(),
+                linear.len(),
+                tiled.as_mut_ptr(),
+                tiled.len()
+            ));
+            assert!(deswizzle_bc7(
+                width,
+                height,
+                mipmap_count,
+                tiled.as_ptr(),
+                tiled.len(),
+                untiled.as_mut_ptr(),
+                untiled.len()
+            ));
+            assert!(!deswizzle_bc7(
+                width,
+                height,
+                mipmap_count,
+                tiled.as_ptr(),
+                tiled.len() - 1,
+                untiled.as_mut_ptr(),
+                untiled.len()
+            ));
+        }
+        assert_eq!(linear, untiled);
+    }
+}
@@ -0,0 +1,209 @@
+//! Development tasks for the tegra_swizzle repository.
+//!
+//! Run `cargo xtask bench-compare` to run the regression benchmarks
+//! and compare the throughput to the stored baseline in `benches/baselines`.
+//! Use `--save` to replace the stored baseline with the new results.
+//! Baselines depend on the hardware, so only compare results from the same machine.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+const USAGE: &str =
+    "Usage: cargo xtask bench-compare [--baseline NAME] [--threshold PERCENT] [--save]
+
+Runs the regression benchmarks and fails if the throughput for any case
+is more than PERCENT lower than the baseline in benches/baselines/NAME.json.
+
+Options:
+    --baseline NAME      The name of the stored baseline (default: main)
+    --threshold PERCENT  The allowed decrease in throughput (default: 10)
+    --save               Save the results as the baseline instead of comparing";
+
+// The criterion benchmark group in benches/regression.rs.
+const BENCH: &str = "regression";
+
+struct Options {
+    baseline: String,
+    threshold: f64,
+    save: bool,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("bench-compare") => parse_options(&args[1..]).and_then(|o| bench_compare(&o)),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        baseline: "main".to_string(),
+        threshold: 10.0,
+        save: false,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--baseline" => {
+                options.baseline = args.next().ok_or(USAGE)?.clone();
+            }
+            "--threshold" => {
+                options.threshold = args
+                    .next()
+                    .and_then(|t| t.parse().ok())
+                    .ok_or_else(|| format!("Expected a percentage for --threshold\n\n{USAGE}"))?;
+            }
+            "--save" => options.save = true,
+            _ => return Err(format!("Unrecognized argument {arg}\n\n{USAGE}")),
+        }
+    }
+    Ok(options)
+}
+
+fn bench_compare(options: &Options) -> Result<(), String> {
+    let root = workspace_root();
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let status = Command::new(cargo)
+        .args(["bench", "-p", "tegra_swizzle", "--bench", BENCH])
+        .current_dir(&root)
+        .status()
+        .map_err(|e| format!("Failed to run cargo bench: {e}"))?;
+    if !status.success() {
+        return Err("cargo bench failed".to_string());
+    }
+
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| root.join("target"));
+    let results = read_results(&target_dir.join("criterion").join(BENCH))?;
+
+    let baseline_path = root
+        .join("benches")
+        .join("baselines")
+        .join(format!("{}.json", options.baseline));
+    if options.save {
+        let json = serde_json::to_string_pretty(&results).map_err(|e| e.to_string())?;
+        std::fs::write(&baseline_path, json + "\n")
+            .map_err(|e| format!("Failed to write {baseline_path:?}: {e}"))?;
+        println!("Saved baseline {baseline_path:?}");
+        return Ok(());
+    }
+
+    let baseline = read_baseline(&baseline_path)?;
+    let regressions = compare(&baseline, &results, options.threshold);
+    if regressions.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Throughput decreased by more than {}% for {}",
+            options.threshold,
+            regressions.join(", ")
+        ))
+    }
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+// Criterion stores the mean time in nanoseconds for each benchmark
+// in a path like regression/deswizzle/bc7_512x512/new/estimates.json.
+fn read_results(group_dir: &Path) -> Result<BTreeMap<String, f64>, String> {
+    let mut results = BTreeMap::new();
+    for function in read_dirs(group_dir)? {
+        for case in read_dirs(&function)? {
+            let path = case.join("new").join("estimates.json");
+            if !path.exists() {
+                continue;
+            }
+            let json = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {path:?}: {e}"))?;
+            let estimates: serde_json::Value =
+                serde_json::from_str(&json).map_err(|e| format!("{path:?}: {e}"))?;
+            let mean_ns = estimates["mean"]["point_estimate"]
+                .as_f64()
+                .ok_or_else(|| format!("{path:?} does not contain a mean estimate"))?;
+
+            results.insert(
+                format!("{}/{}", file_name(&function), file_name(&case)),
+                mean_ns,
+            );
+        }
+    }
+    Ok(results)
+}
+
+fn read_dirs(path: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(path).map_err(|e| format!("Failed to read {path:?}: {e}"))?;
+    Ok(entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_dir() && file_name(p) != "report")
+        .collect())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap().to_string_lossy().into_owned()
+}
+
+fn read_baseline(path: &Path) -> Result<BTreeMap<String, f64>, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {path:?}: {e}\nSave a baseline first with --save."))?;
+    serde_json::from_str(&json).map_err(|e| format!("{path:?}: {e}"))
+}
+
+// Returns the names of the cases with a larger decrease in throughput than the threshold.
+fn compare(
+    baseline: &BTreeMap<String, f64>,
+    results: &BTreeMap<String, f64>,
+    threshold: f64,
+) -> Vec<String> {
+    let mut regressions = Vec::new();
+    for (name, &mean_ns) in results {
+        match baseline.get(name) {
+            Some(&baseline_ns) => {
+                // Each case processes the same number of bytes, so throughput scales with 1 / time.
+                let change = (baseline_ns / mean_ns - 1.0) * 100.0;
+                println!(
+                    "{name:<32} {:>12.1} µs {:>12.1} µs {change:>+8.1}%",
+                    baseline_ns / 1000.0,
+                    mean_ns / 1000.0
+                );
+                if change < -threshold {
+                    regressions.push(name.clone());
+                }
+            }
+            None => println!("{name:<32} no baseline"),
+        }
+    }
+    regressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_threshold() {
+        let baseline = BTreeMap::from([("a".to_string(), 100.0), ("b".to_string(), 100.0)]);
+        let results = BTreeMap::from([
+            ("a".to_string(), 105.0),
+            ("b".to_string(), 125.0),
+            ("c".to_string(), 1.0),
+        ]);
+        assert_eq!(vec!["b".to_string()], compare(&baseline, &results, 10.0));
+        assert!(compare(&baseline, &results, 25.0).is_empty());
+    }
+}